@@ -0,0 +1,56 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+/// Writes `contents` to `path`, creating its directory. The file is replaced at once,
+/// through a `.partial` file renamed over it, so that concurrent servers never read half
+/// of it.
+pub fn write(path: &Path, contents: impl AsRef<[u8]>) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+    let partial = path.with_extension("partial");
+    std::fs::write(&partial, contents)
+        .and_then(|()| std::fs::rename(&partial, path))
+        .with_context(|| format!("Failed to write {}", path.display()))
+}
+
+/// Reads the JSON at `path`, or the default value while no file was written there.
+pub fn read_json<T: DeserializeOwned + Default>(path: &Path) -> Result<T> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse {}", path.display())),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Writes `value` to `path` as pretty-printed JSON, replacing the file at once like
+/// [`write`].
+pub fn write_json<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<()> {
+    write(path, serde_json::to_string_pretty(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state/names.json");
+        assert!(read_json::<Vec<String>>(&path).unwrap().is_empty());
+
+        write_json(&path, &["a", "b"]).unwrap();
+        write_json(&path, &["c"]).unwrap();
+        assert_eq!(read_json::<Vec<String>>(&path).unwrap(), vec!["c"]);
+        assert!(!path.with_extension("partial").exists());
+
+        std::fs::write(&path, "not json").unwrap();
+        let err = read_json::<Vec<String>>(&path).unwrap_err();
+        assert!(err.to_string().starts_with("Failed to parse"));
+    }
+}
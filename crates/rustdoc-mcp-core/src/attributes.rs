@@ -0,0 +1,118 @@
+use rustdoc_types::{Attribute, Deprecation, Item};
+
+use crate::types::ItemAttribute;
+
+const MUST_USE_GUIDANCE: &str =
+    "Use the returned value; discarding it triggers an `unused_must_use` warning.";
+const NON_EXHAUSTIVE_GUIDANCE: &str = "May gain variants or fields in minor releases: add a wildcard `_` arm when matching and use `..` in patterns; it cannot be constructed with a literal outside its crate.";
+const TRACK_CALLER_GUIDANCE: &str =
+    "Panics are reported at the caller's location rather than inside this function.";
+const DEPRECATED_GUIDANCE: &str = "Avoid in new code; using it triggers a `deprecated` warning.";
+
+fn attribute(name: &str, note: Option<String>, guidance: &str) -> ItemAttribute {
+    ItemAttribute {
+        name: name.to_string(),
+        note,
+        guidance: guidance.to_string(),
+    }
+}
+
+/// The banner shown atop the docs of a deprecated item, e.g.
+/// "Deprecated since 1.0: use `g` instead".
+pub fn deprecation_banner(deprecation: &Deprecation) -> String {
+    match (&deprecation.since, &deprecation.note) {
+        (Some(since), Some(note)) => format!("Deprecated since {since}: {note}"),
+        (Some(since), None) => format!("Deprecated since {since}"),
+        (None, Some(note)) => format!("Deprecated: {note}"),
+        (None, None) => "Deprecated".to_string(),
+    }
+}
+
+/// Collects the attributes of an item that change how calling code must be written:
+/// `#[must_use]`, `#[track_caller]`, `#[deprecated]` and `#[non_exhaustive]`.
+pub fn correctness_attributes(item: &Item) -> Vec<ItemAttribute> {
+    let mut attributes = Vec::new();
+
+    for attr in &item.attrs {
+        match attr {
+            Attribute::MustUse { reason } => {
+                attributes.push(attribute("must_use", reason.clone(), MUST_USE_GUIDANCE));
+            }
+            Attribute::NonExhaustive => {
+                attributes.push(attribute("non_exhaustive", None, NON_EXHAUSTIVE_GUIDANCE));
+            }
+            // Attributes without a dedicated variant are rendered, e.g. `#[attr = TrackCaller]`.
+            Attribute::Other(text)
+                if text.contains("TrackCaller") || text.contains("track_caller") =>
+            {
+                attributes.push(attribute("track_caller", None, TRACK_CALLER_GUIDANCE));
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(deprecation) = &item.deprecation {
+        let note = Some(deprecation_banner(deprecation));
+        attributes.push(attribute("deprecated", note, DEPRECATED_GUIDANCE));
+    }
+
+    attributes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_types::{Deprecation, Id, ItemEnum, Visibility};
+    use std::collections::HashMap;
+
+    fn create_item(attrs: Vec<Attribute>, deprecation: Option<Deprecation>) -> Item {
+        Item {
+            id: Id(0),
+            crate_id: 0,
+            name: Some("f".to_string()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs,
+            deprecation,
+            inner: ItemEnum::ExternType,
+        }
+    }
+
+    #[test]
+    fn test_correctness_attributes() {
+        let item = create_item(
+            vec![
+                Attribute::MustUse {
+                    reason: Some("use it".to_string()),
+                },
+                Attribute::Other("#[attr = TrackCaller]".to_string()),
+                Attribute::Other("#[attr = Inline(Hint)]".to_string()),
+                Attribute::NonExhaustive,
+            ],
+            Some(Deprecation {
+                since: Some("1.0".to_string()),
+                note: Some("use g".to_string()),
+            }),
+        );
+
+        let attributes = correctness_attributes(&item);
+        let names: Vec<&str> = attributes.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["must_use", "track_caller", "non_exhaustive", "deprecated"]
+        );
+        assert_eq!(attributes[0].note.as_deref(), Some("use it"));
+        assert_eq!(
+            attributes[3].note.as_deref(),
+            Some("Deprecated since 1.0: use g")
+        );
+    }
+
+    #[test]
+    fn test_no_correctness_attributes() {
+        let item = create_item(vec![Attribute::AutomaticallyDerived], None);
+        assert!(correctness_attributes(&item).is_empty());
+    }
+}
@@ -0,0 +1,338 @@
+use rustdoc_types::{Attribute, Item};
+
+use crate::types::Availability;
+
+/// A `cfg` predicate as written in `#[doc(cfg(...))]`.
+#[derive(Debug, Clone, PartialEq)]
+enum Cfg {
+    Name(String),
+    NameValue(String, String),
+    All(Vec<Cfg>),
+    Any(Vec<Cfg>),
+    Not(Box<Cfg>),
+}
+
+#[derive(Debug, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Punct(char),
+}
+
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '(' | ')' | ',' | '=' => tokens.push(Token::Punct(c)),
+            '"' => {
+                let mut value = String::new();
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => value.push(chars.next()?),
+                        c => value.push(c),
+                    }
+                }
+                tokens.push(Token::Str(value));
+            }
+            c if c.is_alphanumeric() || c == '_' => {
+                let mut ident = c.to_string();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    ident.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Ident(ident));
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+fn parse_predicate(tokens: &[Token], pos: &mut usize) -> Option<Cfg> {
+    let Token::Ident(name) = tokens.get(*pos)? else {
+        return None;
+    };
+    *pos += 1;
+    match tokens.get(*pos) {
+        Some(Token::Punct('=')) => {
+            let Token::Str(value) = tokens.get(*pos + 1)? else {
+                return None;
+            };
+            *pos += 2;
+            Some(Cfg::NameValue(name.clone(), value.clone()))
+        }
+        Some(Token::Punct('(')) => {
+            *pos += 1;
+            let mut args = Vec::new();
+            while tokens.get(*pos) != Some(&Token::Punct(')')) {
+                args.push(parse_predicate(tokens, pos)?);
+                if tokens.get(*pos) == Some(&Token::Punct(',')) {
+                    *pos += 1;
+                }
+            }
+            *pos += 1;
+            match name.as_str() {
+                "all" => Some(Cfg::All(args)),
+                "any" => Some(Cfg::Any(args)),
+                "not" if args.len() == 1 => Some(Cfg::Not(Box::new(args.pop()?))),
+                _ => None,
+            }
+        }
+        _ => Some(Cfg::Name(name.clone())),
+    }
+}
+
+/// Parses a predicate like `all(unix, feature = "fs")`.
+fn parse_cfg(text: &str) -> Option<Cfg> {
+    let tokens = tokenize(text)?;
+    let mut pos = 0;
+    let cfg = parse_predicate(&tokens, &mut pos)?;
+    (pos == tokens.len()).then_some(cfg)
+}
+
+/// Names a single `cfg` option the way docs.rs does, e.g. `Unix` or ``crate feature `fs` ``.
+fn label(name: &str, value: Option<&str>) -> String {
+    let known = match (name, value) {
+        ("unix", None) => "Unix",
+        ("windows", None) => "Windows",
+        ("debug_assertions", None) => "debug-assertions enabled",
+        ("test", None) => "testing",
+        ("doc", None) => "documentation",
+        ("target_os" | "target_family", Some(value)) => match value {
+            "unix" => "Unix",
+            "windows" => "Windows",
+            "wasm" => "WebAssembly",
+            "linux" => "Linux",
+            "macos" => "macOS",
+            "ios" => "iOS",
+            "android" => "Android",
+            "freebsd" => "FreeBSD",
+            "netbsd" => "NetBSD",
+            "openbsd" => "OpenBSD",
+            "dragonfly" => "DragonFly BSD",
+            "wasi" => "WASI",
+            "fuchsia" => "Fuchsia",
+            "illumos" => "illumos",
+            "solaris" => "Solaris",
+            "none" => "bare-metal",
+            _ => value,
+        },
+        ("target_arch", Some(value)) => match value {
+            "x86_64" => "x86-64",
+            "aarch64" => "AArch64",
+            "arm" => "ARM",
+            "wasm32" => "WebAssembly",
+            "riscv64" => "RISC-V RV64",
+            _ => value,
+        },
+        ("target_env", Some(value)) => match value {
+            "gnu" => "GNU",
+            "msvc" => "MSVC",
+            "sgx" => "SGX",
+            _ => value,
+        },
+        ("feature", Some(value)) => return format!("crate feature `{value}`"),
+        ("target_pointer_width", Some(value)) => return format!("{value}-bit"),
+        ("target_endian", Some(value)) => return format!("{value}-endian"),
+        (name, Some(value)) => return format!("`{name}=\"{value}\"`"),
+        (name, None) => return format!("`{name}`"),
+    };
+    known.to_string()
+}
+
+/// Joins phrases like prose: `a and b`, or `a, b, and c`.
+fn join(parts: &[String], conjunction: &str) -> String {
+    match parts {
+        [] => String::new(),
+        [one] => one.clone(),
+        [a, b] => format!("{a} {conjunction} {b}"),
+        [init @ .., last] => format!("{}, {conjunction} {last}", init.join(", ")),
+    }
+}
+
+impl Cfg {
+    fn feature(&self) -> Option<&str> {
+        match self {
+            Cfg::NameValue(name, value) if name == "feature" => Some(value),
+            _ => None,
+        }
+    }
+
+    fn describe_nested(&self) -> String {
+        match self {
+            Cfg::All(cfgs) | Cfg::Any(cfgs) if cfgs.len() > 1 => format!("({})", self.describe()),
+            _ => self.describe(),
+        }
+    }
+
+    fn describe(&self) -> String {
+        match self {
+            Cfg::Name(name) => label(name, None),
+            Cfg::NameValue(name, value) => label(name, Some(value)),
+            Cfg::All(cfgs) | Cfg::Any(cfgs) => {
+                let conjunction = if matches!(self, Cfg::All(_)) {
+                    "and"
+                } else {
+                    "or"
+                };
+                let features: Option<Vec<String>> = cfgs
+                    .iter()
+                    .map(|cfg| Some(format!("`{}`", cfg.feature()?)))
+                    .collect();
+                match features {
+                    Some(features) if features.len() > 1 => {
+                        format!("crate features {}", join(&features, conjunction))
+                    }
+                    _ => {
+                        let parts: Vec<String> = cfgs.iter().map(Cfg::describe_nested).collect();
+                        join(&parts, conjunction)
+                    }
+                }
+            }
+            Cfg::Not(inner) => match inner.as_ref() {
+                Cfg::Name(_) | Cfg::NameValue(..) => format!("non-{}", inner.describe()),
+                Cfg::Any(cfgs) if cfgs.len() == 2 => {
+                    format!("neither {} nor {}", cfgs[0].describe(), cfgs[1].describe())
+                }
+                _ => format!("not ({})", inner.describe()),
+            },
+        }
+    }
+
+    /// Collects the crate features mentioned outside a `not(...)`.
+    fn features(&self, out: &mut Vec<String>) {
+        match self {
+            Cfg::All(cfgs) | Cfg::Any(cfgs) => cfgs.iter().for_each(|cfg| cfg.features(out)),
+            _ => out.extend(self.feature().map(str::to_string)),
+        }
+    }
+}
+
+/// Reads the `#[doc(cfg(...))]` attributes of an item, combining several with `all`.
+fn doc_cfg(item: &Item) -> Option<(String, Cfg)> {
+    let predicates: Vec<(&str, Cfg)> = item
+        .attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            Attribute::Other(text) => {
+                let inner = text
+                    .trim()
+                    .strip_prefix("#[doc(cfg(")?
+                    .strip_suffix("))]")?;
+                Some((inner, parse_cfg(inner)?))
+            }
+            _ => None,
+        })
+        .collect();
+    match predicates.len() {
+        0 => None,
+        1 => predicates
+            .into_iter()
+            .next()
+            .map(|(text, cfg)| (text.to_string(), cfg)),
+        _ => {
+            let texts: Vec<&str> = predicates.iter().map(|(text, _)| *text).collect();
+            let cfgs = predicates.into_iter().map(|(_, cfg)| cfg).collect();
+            Some((format!("all({})", texts.join(", ")), Cfg::All(cfgs)))
+        }
+    }
+}
+
+/// Describes where an item is available according to its `#[doc(cfg(...))]`, like the
+/// docs.rs banner "Available on crate feature `fs` only."
+pub fn availability(item: &Item) -> Option<Availability> {
+    let (cfg_text, cfg) = doc_cfg(item)?;
+    let mut features = Vec::new();
+    cfg.features(&mut features);
+    features.dedup();
+    Some(Availability {
+        cfg: cfg_text,
+        description: format!("Available on {} only.", cfg.describe()),
+        features,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_types::{Id, ItemEnum, Visibility};
+    use std::collections::HashMap;
+
+    fn create_item(attrs: Vec<&str>) -> Item {
+        Item {
+            id: Id(0),
+            crate_id: 0,
+            name: Some("f".to_string()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: attrs
+                .into_iter()
+                .map(|attr| Attribute::Other(attr.to_string()))
+                .collect(),
+            deprecation: None,
+            inner: ItemEnum::ExternType,
+        }
+    }
+
+    fn describe(cfg: &str) -> String {
+        parse_cfg(cfg).unwrap().describe()
+    }
+
+    #[test]
+    fn test_describe_cfg() {
+        assert_eq!(describe(r#"feature = "fs""#), "crate feature `fs`");
+        assert_eq!(describe("unix"), "Unix");
+        assert_eq!(
+            describe(r#"all(feature = "fs", feature = "rt")"#),
+            "crate features `fs` and `rt`"
+        );
+        assert_eq!(
+            describe(r#"all(unix, not(target_os = "macos"))"#),
+            "Unix and non-macOS"
+        );
+        assert_eq!(
+            describe(r#"all(feature = "net", any(unix, windows))"#),
+            "crate feature `net` and (Unix or Windows)"
+        );
+        assert_eq!(
+            describe("not(any(unix, windows))"),
+            "neither Unix nor Windows"
+        );
+        assert_eq!(describe(r#"target_pointer_width = "64""#), "64-bit");
+        assert_eq!(describe(r#"tokio_unstable"#), "`tokio_unstable`");
+        assert_eq!(parse_cfg("all(unix"), None);
+    }
+
+    #[test]
+    fn test_availability() {
+        let item = create_item(vec![
+            r#"#[attr = CfgTrace([NameValue { name: "feature", value: Some("serde") }])]"#,
+            r#"#[doc(cfg(feature = "serde"))]"#,
+        ]);
+        let info = availability(&item).unwrap();
+        assert_eq!(info.cfg, r#"feature = "serde""#);
+        assert_eq!(info.description, "Available on crate feature `serde` only.");
+        assert_eq!(info.features, vec!["serde"]);
+
+        let item = create_item(vec![
+            r#"#[doc(cfg(feature = "net"))]"#,
+            r#"#[doc(cfg(not(feature = "legacy")))]"#,
+        ]);
+        let info = availability(&item).unwrap();
+        assert_eq!(
+            info.description,
+            "Available on crate feature `net` and non-crate feature `legacy` only."
+        );
+        assert_eq!(info.features, vec!["net"]);
+
+        assert!(availability(&create_item(vec!["#[attr = Inline(Hint)]"])).is_none());
+    }
+}
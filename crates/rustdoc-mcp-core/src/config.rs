@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Deserializer};
+use tracing::info;
+
+use crate::stability::UnstableItems;
+
+/// Name of the config file looked up in the workspace root when `--config` is not given.
+pub const CONFIG_FILE_NAME: &str = "rustdoc-mcp.toml";
+
+/// Server configuration, read from a TOML file. Every setting has a default.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config {
+    pub search: SearchConfig,
+    pub resolve: ResolveConfig,
+    pub docs: DocsConfig,
+    pub crates: CratePolicy,
+    pub redact: RedactConfig,
+    pub output: OutputConfig,
+    pub http: HttpConfig,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SearchConfig {
+    /// Minimum Jaro-Winkler similarity for a fuzzy match.
+    pub fuzzy_threshold: f64,
+    /// Queries up to this many characters only match by prefix or substring,
+    /// since fuzzy matching them hits nearly every path.
+    pub short_query_length: usize,
+    /// Number of search results returned when the client does not pass a `limit`.
+    pub max_results: usize,
+    /// How unstable (nightly-only) items rank when the workspace does not use nightly.
+    pub unstable: UnstableItems,
+    /// How searches match and rank items.
+    pub backend: SearchBackend,
+}
+
+/// How `search_docs` matches and ranks items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchBackend {
+    /// Exact, prefix, substring and Jaro-Winkler matching of item paths.
+    #[default]
+    Fuzzy,
+    /// BM25 ranking over item paths, names and docs with a tantivy index kept in the
+    /// target directory. Needs a server built with the `tantivy` feature.
+    Tantivy,
+}
+
+impl Default for SearchConfig {
+    fn default() -> Self {
+        Self {
+            fuzzy_threshold: 0.8,
+            short_query_length: 3,
+            max_results: 20,
+            unstable: UnstableItems::Demote,
+            backend: SearchBackend::Fuzzy,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ResolveConfig {
+    /// How many `Deref` impls to follow when looking for methods, e.g. `String` -> `str`.
+    pub deref_depth: usize,
+}
+
+impl Default for ResolveConfig {
+    fn default() -> Self {
+        Self { deref_depth: 3 }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DocsConfig {
+    /// How many methods or traits `get_docs` lists per page of a type's implementations.
+    pub page_size: usize,
+    /// How long a request needing several crates waits for each to load before answering
+    /// without it. 0 waits for every crate.
+    pub load_timeout_secs: u64,
+    /// The toolchain docs are generated with, e.g. `nightly-2025-06-01` to keep emitting
+    /// the rustdoc JSON format version the server reads whatever the workspace uses.
+    pub toolchain: String,
+    /// Toolchains for particular crates, overriding `toolchain`.
+    pub toolchains: HashMap<String, String>,
+}
+
+impl Default for DocsConfig {
+    fn default() -> Self {
+        Self {
+            page_size: 50,
+            load_timeout_secs: 60,
+            toolchain: "nightly".to_string(),
+            toolchains: HashMap::new(),
+        }
+    }
+}
+
+impl DocsConfig {
+    /// The toolchain to generate the docs of `crate_name` with.
+    pub fn toolchain_for(&self, crate_name: &str) -> &str {
+        self.toolchains
+            .iter()
+            .find(|(name, _)| normalize(name) == normalize(crate_name))
+            .map_or(&self.toolchain, |(_, toolchain)| toolchain)
+    }
+}
+
+/// Which crates may be documented. Names may use `*` as a wildcard (e.g. `*-sys`), and
+/// hyphens and underscores are interchangeable.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CratePolicy {
+    /// When set, only these crates are loaded.
+    pub allow: Option<Vec<String>>,
+    /// Crates that are never loaded, even when allowed.
+    pub deny: Vec<String>,
+}
+
+impl CratePolicy {
+    /// Fails with a "crate excluded by policy" error for crates that may not be loaded.
+    pub fn check(&self, crate_name: &str) -> Result<()> {
+        let matches = |patterns: &[String]| {
+            patterns
+                .iter()
+                .any(|pattern| wildcard_match(&normalize(pattern), &normalize(crate_name)))
+        };
+        if matches(&self.deny) {
+            anyhow::bail!("Crate `{crate_name}` is excluded by policy (listed in `crates.deny`)");
+        }
+        if let Some(allow) = &self.allow
+            && !matches(allow)
+        {
+            anyhow::bail!("Crate `{crate_name}` is excluded by policy (not in `crates.allow`)");
+        }
+        Ok(())
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.replace('-', "_")
+}
+
+/// Matches `text` against `pattern`, where `*` matches any run of characters.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts: Vec<&str> = parts.collect();
+    let Some((last, middle)) = parts.split_last() else {
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+/// How tool responses are rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Markdown or JSON, as each tool produces it.
+    #[default]
+    Full,
+    /// Dense plain text: headings and list markers stripped, code blocks reduced to their
+    /// lines, and JSON flattened into `key: value` lines and pipe-delimited tables.
+    Compact,
+    /// Markdown split into its blocks as JSON (see [`crate::render::JsonRenderer`]), for
+    /// clients that lay out docs themselves. JSON responses are unchanged.
+    Json,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct OutputConfig {
+    /// Tool responses longer than this many bytes are truncated and can be continued with
+    /// a cursor. 0 disables the limit.
+    pub max_bytes: usize,
+    /// Format of tool responses when a call does not pass `format`.
+    pub format: OutputFormat,
+}
+
+impl Default for OutputConfig {
+    fn default() -> Self {
+        Self {
+            max_bytes: 100_000,
+            format: OutputFormat::Full,
+        }
+    }
+}
+
+/// Who may call the server when it is served over HTTP.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HttpConfig {
+    /// A token clients must send as `Authorization: Bearer <token>`. Without one, any
+    /// process that can reach the port may use the server.
+    pub token: Option<String>,
+    /// An environment variable holding the token instead, to keep it out of the file.
+    pub token_env: Option<String>,
+    /// Host names accepted in the `Host` header, so that pages of other sites cannot
+    /// reach the server by resolving their own name to 127.0.0.1 (DNS rebinding).
+    pub allowed_hosts: Vec<String>,
+    /// Origins browser pages may call the server from besides localhost ones, like
+    /// `https://tools.example.com`. Requests without an `Origin`, like those of editors
+    /// and agents, are not affected.
+    pub allowed_origins: Vec<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            token: None,
+            token_env: None,
+            allowed_hosts: vec![
+                "localhost".to_string(),
+                "127.0.0.1".to_string(),
+                "[::1]".to_string(),
+            ],
+            allowed_origins: Vec::new(),
+        }
+    }
+}
+
+impl HttpConfig {
+    /// The bearer token clients must send, if any; fails when `token_env` names a
+    /// variable that is not set.
+    pub fn token(&self) -> Result<Option<String>> {
+        match &self.token_env {
+            Some(var) => std::env::var(var)
+                .map(Some)
+                .with_context(|| format!("`http.token_env` names `{var}`, which is not set")),
+            None => Ok(self.token.clone()),
+        }
+    }
+}
+
+/// What to hide before anything leaves the server.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RedactConfig {
+    /// Regular expressions whose matches are replaced in all tool and resource output.
+    #[serde(deserialize_with = "deserialize_regexes")]
+    pub patterns: Vec<Regex>,
+    /// Item paths (with `*` wildcards) whose docs are withheld entirely.
+    pub paths: Vec<String>,
+    pub replacement: String,
+}
+
+impl Default for RedactConfig {
+    fn default() -> Self {
+        Self {
+            patterns: Vec::new(),
+            paths: Vec::new(),
+            replacement: "[REDACTED]".to_string(),
+        }
+    }
+}
+
+fn deserialize_regexes<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Regex>, D::Error> {
+    Vec::<String>::deserialize(deserializer)?
+        .iter()
+        .map(|pattern| Regex::new(pattern).map_err(serde::de::Error::custom))
+        .collect()
+}
+
+impl Config {
+    /// Loads the config from `path`, or from `rustdoc-mcp.toml` in the workspace root
+    /// if it exists. Falls back to the defaults otherwise.
+    pub fn load(path: Option<&Path>, workspace_root: &Path) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => {
+                let default_path = workspace_root.join(CONFIG_FILE_NAME);
+                if !default_path.exists() {
+                    return Ok(Self::default());
+                }
+                default_path
+            }
+        };
+
+        info!("Loading config from {:?}", path);
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        Self::parse(&content)
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let config: Self = toml::from_str(content).context("Failed to parse config file")?;
+        if config.search.backend == SearchBackend::Tantivy && !cfg!(feature = "tantivy") {
+            anyhow::bail!(
+                "`search.backend = \"tantivy\"` needs a server built with `--features tantivy`"
+            );
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_partial_config() {
+        let config = Config::parse("[search]\nfuzzy_threshold = 0.9\n").unwrap();
+        assert_eq!(config.search.fuzzy_threshold, 0.9);
+        assert_eq!(config.search.short_query_length, 3);
+        assert_eq!(config.search.max_results, 20);
+        assert_eq!(config.search.unstable, UnstableItems::Demote);
+        assert_eq!(config.search.backend, SearchBackend::Fuzzy);
+        assert_eq!(config.resolve.deref_depth, 3);
+        assert_eq!(config.docs.page_size, 50);
+        assert_eq!(config.docs.load_timeout_secs, 60);
+        assert_eq!(config.docs.toolchain_for("serde"), "nightly");
+        assert_eq!(config.output.max_bytes, 100_000);
+        assert_eq!(config.output.format, OutputFormat::Full);
+    }
+
+    #[test]
+    fn test_parse_search_backend() {
+        let parsed = Config::parse("[search]\nbackend = \"tantivy\"\n");
+        if cfg!(feature = "tantivy") {
+            assert_eq!(parsed.unwrap().search.backend, SearchBackend::Tantivy);
+        } else {
+            assert!(
+                parsed
+                    .unwrap_err()
+                    .to_string()
+                    .contains("--features tantivy")
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_output_format() {
+        let config = Config::parse("[output]\nformat = \"compact\"\n").unwrap();
+        assert_eq!(config.output.format, OutputFormat::Compact);
+        assert!(Config::parse("[output]\nformat = \"yaml\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_toolchains() {
+        let config = Config::parse(
+            "[docs]\ntoolchain = \"nightly-2025-06-01\"\n\n[docs.toolchains]\nold-crate = \"nightly-2024-01-01\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.docs.toolchain_for("serde"), "nightly-2025-06-01");
+        assert_eq!(config.docs.toolchain_for("old_crate"), "nightly-2024-01-01");
+    }
+
+    #[test]
+    fn test_parse_http() {
+        let config = Config::parse("").unwrap();
+        assert_eq!(config.http.token().unwrap(), None);
+        assert!(config.http.allowed_hosts.contains(&"localhost".to_string()));
+
+        let config = Config::parse(
+            "[http]\ntoken = \"secret\"\nallowed_origins = [\"https://tools.example.com\"]\n",
+        )
+        .unwrap();
+        assert_eq!(config.http.token().unwrap().as_deref(), Some("secret"));
+        let config =
+            Config::parse("[http]\ntoken_env = \"RUSTDOC_MCP_TEST_UNSET_TOKEN\"\n").unwrap();
+        assert!(config.http.token().is_err());
+    }
+
+    #[test]
+    fn test_parse_rejects_unknown_keys() {
+        assert!(Config::parse("[search]\nthreshold = 0.9\n").is_err());
+    }
+
+    #[test]
+    fn test_crate_policy() {
+        let config = Config::parse("[crates]\ndeny = [\"*-sys\", \"internal_secrets\"]\n").unwrap();
+        assert!(config.crates.check("tokio").is_ok());
+        assert!(config.crates.check("openssl-sys").is_err());
+        assert!(config.crates.check("internal-secrets").is_err());
+
+        let config =
+            Config::parse("[crates]\nallow = [\"tokio*\", \"serde\"]\ndeny = [\"tokio-sys\"]\n")
+                .unwrap();
+        assert!(config.crates.check("tokio_util").is_ok());
+        assert!(config.crates.check("serde").is_ok());
+        assert!(config.crates.check("serde_json").is_err());
+        let err = config.crates.check("tokio-sys").unwrap_err();
+        assert!(err.to_string().contains("excluded by policy"));
+    }
+
+    #[test]
+    fn test_parse_redact_patterns() {
+        let config = Config::parse("[redact]\npatterns = [\"sk_[a-z0-9]+\"]\n").unwrap();
+        assert!(config.redact.patterns[0].is_match("key: sk_abc123"));
+        assert_eq!(config.redact.replacement, "[REDACTED]");
+        assert!(Config::parse("[redact]\npatterns = [\"(unclosed\"]\n").is_err());
+    }
+
+    #[test]
+    fn test_load_missing_default_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = Config::load(None, dir.path()).unwrap();
+        assert_eq!(config.search.max_results, 20);
+    }
+}
@@ -0,0 +1,112 @@
+use cargo_metadata::{Package, TargetKind};
+
+use crate::headings::{extract_headings, truncate_section};
+use crate::types::CrateInfo;
+use crate::workspace::Workspace;
+
+/// README section titles that usually describe what a build needs from the system.
+const REQUIREMENT_KEYWORDS: [&str; 6] = [
+    "requirement",
+    "prerequisite",
+    "dependencies",
+    "system",
+    "build",
+    "install",
+];
+
+pub fn has_build_script(package: &Package) -> bool {
+    package
+        .targets
+        .iter()
+        .any(|t| t.is_kind(TargetKind::CustomBuild))
+}
+
+/// Returns the package whose build script failed, from cargo's
+/// "failed to run custom build command for `name vX.Y.Z`" error.
+pub fn failed_build_script_package(stderr: &str) -> Option<&str> {
+    let rest = stderr
+        .split("failed to run custom build command for `")
+        .nth(1)?;
+    let spec = rest.split('`').next()?;
+    spec.split_whitespace().next()
+}
+
+/// Picks the section of a README describing system requirements, if any.
+pub fn requirements_section(readme: &str) -> Option<String> {
+    extract_headings(readme)
+        .into_iter()
+        .find(|(_, title, section)| {
+            let title = title.to_lowercase();
+            !section.is_empty() && REQUIREMENT_KEYWORDS.iter().any(|k| title.contains(k))
+        })
+        .map(|(_, title, section)| truncate_section(&format!("{title}\n\n{section}")))
+}
+
+/// Explains a failed doc build caused by a build script: which package failed, what it
+/// links against and what its README says it needs.
+pub fn build_failure_hint(workspace: &Workspace, stderr: &str) -> Option<String> {
+    let name = failed_build_script_package(stderr)?;
+    let mut hint = format!(
+        "The build script of `{name}` failed. This usually means a system library or tool it needs is missing."
+    );
+
+    let package = workspace.find_package(name);
+    if let Some(links) = package.and_then(|p| p.links.as_deref()) {
+        hint.push_str(&format!(" It links against the native library `{links}`."));
+    }
+    if let Some(requirements) = package
+        .and_then(|p| p.readme())
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|readme| requirements_section(&readme))
+    {
+        hint.push_str("\n\nFrom its README:\n\n");
+        hint.push_str(&requirements);
+    }
+    Some(hint)
+}
+
+/// Summarizes a package's metadata, including whether building it runs a build script.
+pub fn crate_info(workspace: &Workspace, crate_name: &str) -> Option<CrateInfo> {
+    let package = workspace.find_package(crate_name)?;
+    Some(CrateInfo {
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        description: package.description.clone(),
+        license: package.license.clone(),
+        repository: package.repository.clone(),
+        edition: package.edition.as_str().to_string(),
+        directness: workspace.directness(crate_name).as_str().to_string(),
+        source: workspace.package_source(package).describe(),
+        has_lib: package
+            .targets
+            .iter()
+            .any(|t| t.is_lib() || t.is_proc_macro()),
+        has_build_script: has_build_script(package),
+        links: package.links.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failed_build_script_package() {
+        let stderr = "   Compiling openssl-sys v0.9.102\nerror: failed to run custom build command for `openssl-sys v0.9.102`\n\nCaused by:\n  process didn't exit successfully";
+        assert_eq!(failed_build_script_package(stderr), Some("openssl-sys"));
+        assert_eq!(
+            failed_build_script_package("error[E0425]: cannot find value"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_requirements_section() {
+        let readme = "# foo\n\nBindings.\n\n## Build requirements\n\nInstall `libfoo-dev`.\n\n## License\n\nMIT";
+        assert_eq!(
+            requirements_section(readme).as_deref(),
+            Some("Build requirements\n\nInstall `libfoo-dev`.")
+        );
+        assert_eq!(requirements_section("# foo\n\nNo sections."), None);
+    }
+}
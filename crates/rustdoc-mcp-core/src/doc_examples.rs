@@ -0,0 +1,147 @@
+use crate::types::DocExample;
+
+/// Fence attributes rustdoc accepts on Rust code blocks, besides `edition*` and `ignore-*`.
+const RUST_ATTRIBUTES: &[&str] = &[
+    "rust",
+    "no_run",
+    "ignore",
+    "should_panic",
+    "compile_fail",
+    "test_harness",
+    "standalone_crate",
+];
+
+/// Whether a fenced block with these attributes is Rust: like rustdoc, blocks without a
+/// language are, and so are those whose every attribute is one rustdoc knows.
+fn is_rust(attributes: &[&str]) -> bool {
+    attributes.iter().all(|attribute| {
+        RUST_ATTRIBUTES.contains(attribute)
+            || attribute.starts_with("edition")
+            || attribute.starts_with("ignore-")
+    })
+}
+
+/// Unhides a line of a doctest: `# use foo;` is `use foo;` and `##` escapes a literal `#`.
+fn unhidden(line: &str) -> &str {
+    let trimmed = line.trim_start();
+    if trimmed == "#" {
+        ""
+    } else if let Some(rest) = trimmed.strip_prefix("# ") {
+        rest
+    } else if trimmed.starts_with("##") {
+        &trimmed[1..]
+    } else {
+        line
+    }
+}
+
+fn example(
+    code: &[&str],
+    attributes: &[&str],
+    section: Option<&str>,
+    prose: &[&str],
+) -> DocExample {
+    let has = |name: &str| attributes.contains(&name);
+    DocExample {
+        code: code
+            .iter()
+            .map(|line| unhidden(line))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        no_run: has("no_run"),
+        ignore: has("ignore") || attributes.iter().any(|a| a.starts_with("ignore-")),
+        should_panic: has("should_panic"),
+        compile_fail: has("compile_fail"),
+        attributes: attributes
+            .iter()
+            .filter(|a| !RUST_ATTRIBUTES.contains(a))
+            .map(|a| a.to_string())
+            .collect(),
+        section: section.map(str::to_string),
+        prose: (!prose.is_empty()).then(|| prose.join(" ")),
+    }
+}
+
+/// Extracts the Rust code blocks of `docs` with their fence attributes, the heading they
+/// are under and the paragraph right before them. The code is what the doctest compiles,
+/// lines rustdoc hides included.
+pub fn extract_examples(docs: &str) -> Vec<DocExample> {
+    let mut examples = Vec::new();
+    let mut section: Option<&str> = None;
+    // The last paragraph, until the blank line after it is followed by more text.
+    let mut prose: Vec<&str> = Vec::new();
+    let mut paragraph_ended = false;
+    // The fence that opened the current code block, its attributes and its lines.
+    let mut fence: Option<&str> = None;
+    let mut attributes: Vec<&str> = Vec::new();
+    let mut code: Vec<&str> = Vec::new();
+
+    for line in docs.lines() {
+        let trimmed = line.trim();
+        if let Some(open) = fence {
+            let closes =
+                trimmed.len() >= open.len() && trimmed.chars().all(|c| open.starts_with(c));
+            if !closes {
+                code.push(line);
+                continue;
+            }
+            if is_rust(&attributes) {
+                examples.push(example(&code, &attributes, section, &prose));
+            }
+            fence = None;
+            prose.clear();
+            continue;
+        }
+
+        let run = |c: char| trimmed.chars().take_while(|x| *x == c).count();
+        let opening = [run('`'), run('~')].into_iter().find(|n| *n >= 3);
+        if let Some(len) = opening {
+            fence = Some(&trimmed[..len]);
+            attributes = trimmed[len..]
+                .split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|a| !a.is_empty())
+                .collect();
+            code.clear();
+        } else if let Some(heading) = trimmed.strip_prefix('#') {
+            section = Some(heading.trim_start_matches('#').trim());
+            prose.clear();
+        } else if trimmed.is_empty() {
+            paragraph_ended = true;
+        } else {
+            if paragraph_ended {
+                prose.clear();
+                paragraph_ended = false;
+            }
+            prose.push(trimmed);
+        }
+    }
+    examples
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_examples() {
+        let docs = "Greets people.\n\n# Examples\n\nGreeting someone by name:\n\n```\n# use demo::Greeter;\nlet greeter = Greeter::new(\"Hi\");\n## not hidden\n```\n\n```text\nHi, Ferris!\n```\n\n~~~no_run,edition2021\nserve();\n~~~\n";
+        let examples = extract_examples(docs);
+        assert_eq!(examples.len(), 2);
+
+        assert_eq!(
+            examples[0].code,
+            "use demo::Greeter;\nlet greeter = Greeter::new(\"Hi\");\n# not hidden"
+        );
+        assert_eq!(examples[0].section.as_deref(), Some("Examples"));
+        assert_eq!(
+            examples[0].prose.as_deref(),
+            Some("Greeting someone by name:")
+        );
+        assert!(!examples[0].no_run);
+
+        assert_eq!(examples[1].code, "serve();");
+        assert!(examples[1].no_run && !examples[1].ignore);
+        assert_eq!(examples[1].attributes, vec!["edition2021"]);
+        assert_eq!(examples[1].prose, None);
+    }
+}
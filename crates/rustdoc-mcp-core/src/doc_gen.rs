@@ -0,0 +1,209 @@
+use anyhow::{Context, Result};
+use fs2::FileExt;
+use serde::Deserialize;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tracing::{info, instrument, warn};
+
+use crate::json_cache::fingerprint_path;
+
+#[derive(Deserialize)]
+struct FormatVersion {
+    format_version: u32,
+}
+
+/// Explains a rustdoc JSON parse failure caused by a nightly toolchain emitting another
+/// format version than the one this server reads. Returns `None` when the versions match.
+pub fn format_mismatch_hint(json: &str) -> Option<String> {
+    let found = serde_json::from_str::<FormatVersion>(json)
+        .ok()?
+        .format_version;
+    let supported = rustdoc_types::FORMAT_VERSION;
+    if found == supported {
+        return None;
+    }
+    let advice = if found > supported {
+        "The nightly toolchain is newer than this server: update rustdoc-mcp-server, or pin an older nightly with `docs.toolchain`"
+    } else {
+        "The nightly toolchain is older than this server: run `rustup update nightly`"
+    };
+    Some(format!(
+        "The docs use rustdoc JSON format version {found}, but this server reads version {supported}. {advice}."
+    ))
+}
+
+pub struct DocGenerator;
+
+/// The packages cargo reports compiling or checking in its `stderr`, i.e. those it could
+/// not reuse from the build cache.
+fn rebuilt_packages(stderr: &str) -> Vec<&str> {
+    stderr
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim_start();
+            line.strip_prefix("Compiling ")
+                .or_else(|| line.strip_prefix("Checking "))
+                .or_else(|| line.strip_prefix("Documenting "))
+        })
+        .filter_map(|rest| rest.split_whitespace().next())
+        .collect()
+}
+
+impl DocGenerator {
+    /// Generates the rustdoc JSON of a package into `target_dir`, unless docs generated
+    /// from the same inputs (see [`fingerprint_path`]) are already there. With `build_dir`,
+    /// intermediate artifacts go there instead of into `target_dir`, so builds into a
+    /// separate target directory still reuse the dependencies compiled in the workspace's.
+    /// `toolchain` is the nightly to run, e.g. `nightly` or `nightly-2025-06-01`.
+    #[instrument(skip(cwd, target_dir, build_dir))]
+    pub async fn generate(
+        package_name: &str,
+        features: Option<&[String]>,
+        toolchain: &str,
+        cwd: &str,
+        target_dir: &Path,
+        build_dir: Option<&Path>,
+        fingerprint: Option<&str>,
+    ) -> Result<PathBuf> {
+        let json_path = target_dir
+            .join("doc")
+            .join(format!("{}.json", package_name.replace('-', "_")));
+        let lock_path = target_dir
+            .join("doc")
+            .join(format!("{}.lock", package_name.replace('-', "_")));
+
+        info!(?json_path, "Checking for existing documentation");
+
+        // Ensure doc dir exists
+        if let Some(parent) = json_path.parent() {
+            std::fs::create_dir_all(parent).ok();
+        }
+
+        let lock_file = File::create(&lock_path).context("Failed to create lock file")?;
+        lock_file.lock_exclusive().context("Failed to lock file")?;
+
+        let fingerprint_path = fingerprint_path(&json_path);
+        if json_path.exists() {
+            let stored = std::fs::read_to_string(&fingerprint_path).ok();
+            let changed = matches!(
+                (fingerprint, stored.as_deref()),
+                (Some(current), Some(stored)) if stored.trim() != current
+            );
+            if !changed {
+                // Docs without a fingerprint, e.g. reused from the user's own runs, are
+                // adopted as they are.
+                if let Some(current) = fingerprint
+                    && stored.is_none()
+                {
+                    std::fs::write(&fingerprint_path, current).ok();
+                }
+                info!("Documentation already exists, skipping generation");
+                lock_file.unlock().ok();
+                return Ok(json_path);
+            }
+            info!("Inputs changed since documentation was generated, regenerating");
+            std::fs::remove_file(&json_path).ok();
+        }
+
+        info!("Generating documentation for package: {}", package_name);
+        let mut cmd = Command::new("cargo");
+        cmd.current_dir(cwd)
+            .arg(format!("+{toolchain}"))
+            .arg("rustdoc")
+            .arg("-p")
+            .arg(package_name);
+
+        if let Some(features) = features {
+            cmd.arg("--no-default-features");
+            if !features.is_empty() {
+                cmd.arg("--features").arg(features.join(","));
+            }
+        }
+
+        cmd.arg("--target-dir").arg(target_dir);
+        if let Some(build_dir) = build_dir {
+            cmd.arg("--config").arg(format!(
+                "build.build-dir={:?}",
+                build_dir.display().to_string()
+            ));
+        }
+        let cache = build_dir.unwrap_or(target_dir).join("debug");
+        let had_cache = cache.is_dir();
+
+        cmd.arg("--lib")
+            .arg("--")
+            .arg("-Z")
+            .arg("unstable-options")
+            .arg("--output-format")
+            .arg("json");
+
+        let output = cmd
+            .output()
+            .await
+            .context("Failed to execute cargo rustdoc")?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!("cargo rustdoc failed: {}", stderr);
+            lock_file.unlock().ok();
+            anyhow::bail!("cargo rustdoc failed for {package_name}: {stderr}");
+        }
+
+        if !json_path.exists() {
+            lock_file.unlock().ok();
+            anyhow::bail!(
+                "Documentation generated but file not found at expected path: {}",
+                json_path.display()
+            );
+        }
+
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let rebuilt: Vec<&str> = rebuilt_packages(&stderr)
+            .into_iter()
+            .filter(|name| *name != package_name)
+            .collect();
+        match (had_cache, rebuilt.len()) {
+            (true, 0) => info!("Reused the build cache in {:?} for all dependencies", cache),
+            (true, n) => info!(
+                "Rebuilt {} packages despite the build cache in {:?} (built with another toolchain or flags?): {}",
+                n,
+                cache,
+                rebuilt.join(", ")
+            ),
+            (false, n) => info!("No build cache in {:?} yet, built {} packages", cache, n),
+        }
+        info!("Documentation generated successfully");
+        if let Some(fingerprint) = fingerprint {
+            std::fs::write(&fingerprint_path, fingerprint).ok();
+        }
+        lock_file.unlock().ok();
+        Ok(json_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_mismatch_hint() {
+        let supported = rustdoc_types::FORMAT_VERSION;
+        let json = format!(r#"{{"root": 0, "format_version": {supported}}}"#);
+        assert_eq!(format_mismatch_hint(&json), None);
+
+        let json = format!(r#"{{"format_version": {}, "root": 0}}"#, supported + 1);
+        let hint = format_mismatch_hint(&json).unwrap();
+        assert!(hint.contains(&format!("format version {}", supported + 1)));
+        assert!(hint.contains("update rustdoc-mcp-server"));
+
+        assert_eq!(format_mismatch_hint("not json"), None);
+    }
+
+    #[test]
+    fn test_rebuilt_packages() {
+        let stderr = "   Compiling libc v0.2.169\n    Checking serde v1.0.217\n Documenting demo v0.1.0 (/work/demo)\n    Finished `dev` profile [unoptimized + debuginfo] target(s) in 2.31s\n";
+        assert_eq!(rebuilt_packages(stderr), vec!["libc", "serde", "demo"]);
+        assert!(rebuilt_packages("    Finished `dev` profile\n").is_empty());
+    }
+}
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use rustdoc_types::{Crate, Id, ItemEnum, Type};
+
+/// The full path of a link target, like `demo::Greeter` or, for methods and other
+/// associated items, which rustdoc records no path for, `demo::Greeter::greet`.
+pub fn link_path(krate: &Crate, id: Id) -> Option<String> {
+    if let Some(summary) = krate.paths.get(&id) {
+        return Some(summary.path.join("::"));
+    }
+    let name = krate.index.get(&id)?.name.as_deref()?;
+    let owner = krate
+        .index
+        .iter()
+        .find_map(|(owner_id, item)| match &item.inner {
+            ItemEnum::Impl(impl_) if impl_.items.contains(&id) => match &impl_.for_ {
+                Type::ResolvedPath(path) => Some(path.id),
+                _ => None,
+            },
+            ItemEnum::Trait(trait_) if trait_.items.contains(&id) => Some(*owner_id),
+            _ => None,
+        })?;
+    let owner = krate.paths.get(&owner)?.path.join("::");
+    Some(format!("{owner}::{name}"))
+}
+
+/// Finds the `]` closing the bracket opened just before `start`, or the `)` closing a
+/// parenthesis, counting nested ones.
+fn closing(line: &str, start: usize, open: char, close: char) -> Option<usize> {
+    let mut depth = 0usize;
+    for (i, c) in line[start..].char_indices() {
+        if c == open {
+            depth += 1;
+        } else if c == close {
+            if depth == 0 {
+                return Some(start + i);
+            }
+            depth -= 1;
+        }
+    }
+    None
+}
+
+/// Rewrites the intra-doc links of a line whose targets `resolve` knows.
+fn resolve_line(line: &str, resolve: &impl Fn(&str) -> Option<String>) -> String {
+    // A reference definition, `[Config]: crate::Config`.
+    if let Some(rest) = line.strip_prefix('[')
+        && let Some(end) = rest.find("]:")
+        && let Some(path) = resolve(rest[end + 2..].trim())
+    {
+        return format!("[{}]: {path}", &rest[..end]);
+    }
+
+    let mut resolved = String::new();
+    let mut rest = line;
+    while let Some(open) = rest.find('[') {
+        resolved.push_str(&rest[..open]);
+        let Some(close) = closing(rest, open + 1, '[', ']') else {
+            rest = &rest[open..];
+            break;
+        };
+        let text = &rest[open + 1..close];
+        let after = &rest[close + 1..];
+        // `[text](target)`, `[text][target]` or just `[target]`.
+        let (target, end) = match after.chars().next() {
+            Some('(') => match closing(after, 1, '(', ')') {
+                Some(end) => (&after[1..end], close + 1 + end + 1),
+                None => (text, close + 1),
+            },
+            Some('[') => match closing(after, 1, '[', ']') {
+                Some(1) => (text, close + 3),
+                Some(end) => (&after[1..end], close + 1 + end + 1),
+                None => (text, close + 1),
+            },
+            _ => (text, close + 1),
+        };
+        match resolve(target) {
+            Some(path) => resolved.push_str(&format!("[{text}]({path})")),
+            None => resolved.push_str(&rest[open..end]),
+        }
+        rest = &rest[end..];
+    }
+    resolved.push_str(rest);
+    resolved
+}
+
+/// Rewrites the intra-doc links of `docs`, like ``[`Config`]`` or `[the config](Config)`,
+/// to point at the full paths of their targets, like ``[`Config`](demo::Config)``, using
+/// the `links` rustdoc resolved for the item. Links it could not resolve and code blocks
+/// are left as they are.
+pub fn resolve_doc_links(docs: &str, links: &HashMap<String, Id>, krate: &Crate) -> String {
+    if links.is_empty() {
+        return docs.to_string();
+    }
+    let resolve = |target: &str| link_path(krate, *links.get(target)?);
+    let mut in_code = false;
+    let lines: Vec<String> = docs
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_code = !in_code;
+            }
+            if in_code || trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                line.to_string()
+            } else {
+                resolve_line(line, &resolve)
+            }
+        })
+        .collect();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn krate() -> Crate {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        krate.paths.insert(
+            Id(1),
+            rustdoc_types::ItemSummary {
+                crate_id: 0,
+                path: vec!["demo".to_string(), "Config".to_string()],
+                kind: rustdoc_types::ItemKind::Struct,
+            },
+        );
+        krate
+    }
+
+    #[test]
+    fn test_resolve_doc_links() {
+        let links = HashMap::from([
+            ("`Config`".to_string(), Id(1)),
+            ("Config".to_string(), Id(1)),
+        ]);
+        let docs = "Loads a [`Config`], [the config](Config) or [one][Config]; see [`Missing`].\n\n```\nlet x = [Config];\n```\n[settings]: Config";
+        assert_eq!(
+            resolve_doc_links(docs, &links, &krate()),
+            "Loads a [`Config`](demo::Config), [the config](demo::Config) or [one](demo::Config); see [`Missing`].\n\n```\nlet x = [Config];\n```\n[settings]: demo::Config"
+        );
+    }
+}
@@ -0,0 +1,113 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tokio::process::Command;
+use tracing::info;
+
+/// Written into a checkout once `rev` is checked out, so an interrupted clone is redone.
+const DONE_MARKER: &str = ".rustdoc-mcp-checkout";
+
+/// Where the checkout of `url` at `rev` is kept: a directory per repository and revision
+/// under `target_dir`, named after the repository so it can be told apart.
+pub fn checkout_dir(target_dir: &Path, url: &str, rev: &str) -> PathBuf {
+    let mut hasher = DefaultHasher::new();
+    (url, rev).hash(&mut hasher);
+    let name = url
+        .trim_end_matches('/')
+        .trim_end_matches(".git")
+        .rsplit(['/', ':'])
+        .next()
+        .filter(|name| !name.is_empty())
+        .unwrap_or("repo");
+    target_dir
+        .join("rustdoc-mcp-git")
+        .join(format!("{name}-{:016x}", hasher.finish()))
+}
+
+/// Rejects URLs and revisions git would read as options.
+fn check_argument(kind: &str, value: &str) -> Result<()> {
+    if value.is_empty() || value.starts_with('-') || value.contains(char::is_whitespace) {
+        anyhow::bail!("Invalid git {kind}: `{value}`");
+    }
+    Ok(())
+}
+
+async fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .output()
+        .await
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Clones `url` into `dir` and checks out `rev` (a commit, tag or branch), unless an
+/// earlier call completed that already. A branch is checked out as it is when first
+/// asked for.
+pub async fn checkout(url: &str, rev: &str, dir: &Path) -> Result<()> {
+    check_argument("URL", url)?;
+    check_argument("revision", rev)?;
+    if dir.join(DONE_MARKER).exists() {
+        return Ok(());
+    }
+    if dir.exists() {
+        std::fs::remove_dir_all(dir)
+            .with_context(|| format!("Failed to remove {}", dir.display()))?;
+    }
+    std::fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+
+    info!("Checking out {} at {} into {:?}", url, rev, dir);
+    git(dir, &["init", "--quiet"]).await?;
+    git(dir, &["fetch", "--quiet", "--depth", "1", "--", url, rev]).await?;
+    git(dir, &["checkout", "--quiet", "--detach", "FETCH_HEAD"]).await?;
+    std::fs::write(dir.join(DONE_MARKER), format!("{url} {rev}\n"))
+        .with_context(|| format!("Failed to write {}", dir.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkout_dir() {
+        let target = Path::new("/work/target");
+        let dir = checkout_dir(target, "https://github.com/tokio-rs/tokio.git", "a1b2c3");
+        assert!(dir.starts_with("/work/target/rustdoc-mcp-git"));
+        let name = dir.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("tokio-"));
+        assert_ne!(
+            dir,
+            checkout_dir(target, "https://github.com/tokio-rs/tokio.git", "d4e5f6")
+        );
+        assert!(
+            checkout_dir(target, "git@github.com:serde-rs/serde", "main")
+                .file_name()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .starts_with("serde-")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_checkout_rejects_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let err = checkout("--upload-pack=touch /tmp/x", "main", dir.path())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid git URL"));
+        let err = checkout("https://example.com/repo.git", "-b", dir.path())
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Invalid git revision"));
+    }
+}
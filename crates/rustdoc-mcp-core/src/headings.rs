@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+
+use rustdoc_types::{Crate, Id};
+use schemars::JsonSchema;
+use serde::Serialize;
+use strsim::jaro_winkler;
+
+/// Longest section text returned for a heading match.
+const MAX_SECTION_LENGTH: usize = 2000;
+
+/// How many words before "cancel safe" a negation like "not" still applies to it, as in
+/// "is not guaranteed to be cancel safe".
+const NEGATION_WINDOW: usize = 4;
+
+/// Words that negate a "cancel safe" following them within [`NEGATION_WINDOW`] words.
+const NEGATIONS: [&str; 4] = ["not", "never", "no", "cannot"];
+
+/// A markdown heading found in an item's doc comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocHeading {
+    /// Path of the item whose docs contain the heading.
+    pub item_path: String,
+    pub level: usize,
+    pub title: String,
+    /// Text below the heading, up to the next heading of the same or a higher level.
+    pub section: String,
+}
+
+/// Splits markdown into `(level, title, section)` triples, skipping fenced code blocks
+/// so that hidden `# ` doctest lines are not mistaken for headings.
+pub fn extract_headings(docs: &str) -> Vec<(usize, String, String)> {
+    let mut headings: Vec<(usize, String, Vec<&str>)> = Vec::new();
+    let mut in_fence = false;
+
+    for line in docs.lines() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        let is_heading = !in_fence && (1..=6).contains(&level) && trimmed[level..].starts_with(' ');
+
+        if is_heading {
+            headings.push((level, trimmed[level..].trim().to_string(), Vec::new()));
+            continue;
+        }
+
+        // A line belongs to every open heading it is nested under.
+        let mut current_level = usize::MAX;
+        for (heading_level, _, section) in headings.iter_mut().rev() {
+            if *heading_level < current_level {
+                section.push(line);
+                current_level = *heading_level;
+            }
+        }
+    }
+
+    headings
+        .into_iter()
+        .map(|(level, title, section)| (level, title, section.join("\n").trim().to_string()))
+        .collect()
+}
+
+/// Builds the heading index for a crate from its path map.
+pub fn build_heading_index(krate: &Crate, path_to_id: &HashMap<String, Id>) -> Vec<DocHeading> {
+    let mut seen = std::collections::HashSet::new();
+    let mut paths: Vec<(&String, &Id)> = path_to_id.iter().collect();
+    paths.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut index = Vec::new();
+    for (path, id) in paths {
+        if !seen.insert(*id) {
+            continue;
+        }
+        let Some(docs) = krate.index.get(id).and_then(|item| item.docs.as_deref()) else {
+            continue;
+        };
+        for (level, title, section) in extract_headings(docs) {
+            index.push(DocHeading {
+                item_path: path.clone(),
+                level,
+                title,
+                section,
+            });
+        }
+    }
+    index
+}
+
+/// Scores a heading title against a query, case-insensitively. Substring matches win.
+pub fn heading_score(query: &str, title: &str) -> f64 {
+    let query = query.to_lowercase();
+    let title = title.to_lowercase();
+    if title.contains(&query) {
+        1.0
+    } else {
+        jaro_winkler(&query, &title)
+    }
+}
+
+/// Whether an item's docs say it is cancellation safe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CancelSafety {
+    Yes,
+    No,
+    /// The docs have no "Cancel safety" section, or it does not say either way.
+    Unknown,
+}
+
+/// Classifies an item's cancellation safety from the "Cancel safety" section of its
+/// docs, as tokio documents it, returning the section along with it.
+pub fn cancellation_safety(docs: &str) -> (CancelSafety, Option<String>) {
+    let Some((_, _, section)) = extract_headings(docs)
+        .into_iter()
+        .find(|(_, title, _)| title.to_lowercase().contains("cancel"))
+    else {
+        return (CancelSafety::Unknown, None);
+    };
+    (section_verdict(&section), Some(section))
+}
+
+/// Reads the verdict off the first "cancel safe" or "cancellation safe" in a section,
+/// looking through emphasis and contractions: "is **not** cancel-safe", "isn't
+/// cancellation safe" and "is cancel unsafe" all say no.
+fn section_verdict(section: &str) -> CancelSafety {
+    let text = section
+        .to_lowercase()
+        .replace(['*', '_', '`'], "")
+        .replace('’', "'")
+        .replace("n't", " not");
+    let words: Vec<&str> = text
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect();
+
+    for (i, pair) in words.windows(2).enumerate() {
+        if !matches!(pair[0], "cancel" | "cancellation") {
+            continue;
+        }
+        let negated = words[i.saturating_sub(NEGATION_WINDOW)..i]
+            .iter()
+            .any(|word| NEGATIONS.contains(word));
+        match pair[1] {
+            "unsafe" => return CancelSafety::No,
+            "safe" if negated => return CancelSafety::No,
+            "safe" => return CancelSafety::Yes,
+            _ => {}
+        }
+    }
+    CancelSafety::Unknown
+}
+
+/// Truncates a section to a size suitable for a tool response.
+pub fn truncate_section(section: &str) -> String {
+    if section.len() <= MAX_SECTION_LENGTH {
+        return section.to_string();
+    }
+    let mut end = MAX_SECTION_LENGTH;
+    while !section.is_char_boundary(end) {
+        end -= 1;
+    }
+    format!("{}…", &section[..end])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_headings() {
+        let docs = "Intro\n\n# Examples\n\n```\n# use std::fmt;\nlet x = 1;\n```\n\n## Cancel safety\n\nThis method is cancel safe.\n\n# Panics\n\nNever.";
+        let headings = extract_headings(docs);
+
+        assert_eq!(headings.len(), 3);
+        assert_eq!(headings[0].0, 1);
+        assert_eq!(headings[0].1, "Examples");
+        assert!(headings[0].2.contains("let x = 1;"));
+        assert!(headings[0].2.contains("cancel safe"));
+        assert_eq!(
+            headings[1],
+            (
+                2,
+                "Cancel safety".to_string(),
+                "This method is cancel safe.".to_string()
+            )
+        );
+        assert_eq!(headings[2], (1, "Panics".to_string(), "Never.".to_string()));
+    }
+
+    #[test]
+    fn test_cancellation_safety() {
+        let (verdict, section) = cancellation_safety(
+            "Receives a value.\n\n# Cancel safety\n\nThis method is cancel safe.",
+        );
+        assert_eq!(verdict, CancelSafety::Yes);
+        assert_eq!(section.as_deref(), Some("This method is cancel safe."));
+
+        let (verdict, _) = cancellation_safety(
+            "# Cancel safety\n\nThis method is not cancellation safe. Data may be lost.",
+        );
+        assert_eq!(verdict, CancelSafety::No);
+
+        assert_eq!(
+            cancellation_safety("No sections here."),
+            (CancelSafety::Unknown, None)
+        );
+        assert_eq!(
+            cancellation_safety("# Cancel safety\n\nSee the module docs.").0,
+            CancelSafety::Unknown
+        );
+    }
+
+    #[test]
+    fn test_cancellation_safety_negations() {
+        assert_eq!(
+            section_verdict("This method is **not** cancel safe."),
+            CancelSafety::No
+        );
+        assert_eq!(
+            section_verdict("This method is _not_ cancel-safe."),
+            CancelSafety::No
+        );
+        assert_eq!(
+            section_verdict("This method isn't cancellation safe."),
+            CancelSafety::No
+        );
+        assert_eq!(
+            section_verdict("This method doesn’t stay cancel safe."),
+            CancelSafety::No
+        );
+        assert_eq!(
+            section_verdict("This method is not guaranteed to be cancel safe."),
+            CancelSafety::No
+        );
+        assert_eq!(
+            section_verdict("This method is cancel-unsafe."),
+            CancelSafety::No
+        );
+        assert_eq!(
+            section_verdict("It is never cancellation safe."),
+            CancelSafety::No
+        );
+        // Negations after the phrase do not apply to it.
+        assert_eq!(
+            section_verdict("This method is cancel safe: no data is lost if it is not polled."),
+            CancelSafety::Yes
+        );
+        assert_eq!(
+            section_verdict("Nothing is lost, so this method is *cancel safe*."),
+            CancelSafety::Yes
+        );
+    }
+
+    #[test]
+    fn test_heading_score() {
+        assert_eq!(heading_score("cancel", "Cancellation safety"), 1.0);
+        assert!(heading_score("Exmaples", "Examples") > 0.8);
+    }
+}
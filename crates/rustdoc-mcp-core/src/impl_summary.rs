@@ -0,0 +1,466 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rustdoc_types::{Attribute, Crate, Impl, Item, ItemEnum};
+
+use crate::markdown::{
+    format_generics, format_impl_header, format_item_definition, format_where_clause,
+};
+use crate::types::TraitImpl;
+
+/// The first line of an item's docs, if it has any.
+pub fn doc_summary(item: &Item) -> Option<&str> {
+    item.docs
+        .as_deref()
+        .and_then(|docs| docs.lines().next())
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+}
+
+/// Renders a method's signature for a listing, preceded by its one-line summary as a doc
+/// comment, the way docs.rs shows collapsed methods. Each line is prefixed by `indent`.
+fn method_entry(method: &Item, indent: &str) -> String {
+    let signature = format_item_definition(method);
+    match doc_summary(method) {
+        Some(summary) => format!("{indent}/// {summary}\n{indent}{signature}"),
+        None => format!("{indent}{signature}"),
+    }
+}
+
+/// Renders a type's implementations compactly, for types with thousands of impls such as
+/// those generated by bindgen or svd2rust: trait impls are counted per trait, synthetic and
+/// blanket impls only counted, and inherent methods listed one page at a time (`page` is
+/// 1-based). The methods of hand-written trait impls are listed by impl, `page_size` impls
+/// per page. Methods come with their one-line summaries. Derived impls are collapsed into one
+/// line unless `expand_derived` is set, so hand-written impls stand out. Returns `None` for
+/// items without impls.
+pub fn implementations_section(
+    krate: &Crate,
+    item: &Item,
+    page: usize,
+    page_size: usize,
+    expand_derived: bool,
+) -> Option<String> {
+    let impls = match &item.inner {
+        ItemEnum::Struct(s) => &s.impls,
+        ItemEnum::Enum(e) => &e.impls,
+        ItemEnum::Union(u) => &u.impls,
+        _ => return None,
+    };
+
+    let mut methods = Vec::new();
+    let mut trait_methods: Vec<String> = Vec::new();
+    let mut traits: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut derived: BTreeSet<&str> = BTreeSet::new();
+    let mut generated = 0;
+    for impl_item in impls.iter().filter_map(|id| krate.index.get(id)) {
+        let ItemEnum::Impl(impl_) = &impl_item.inner else {
+            continue;
+        };
+        match &impl_.trait_ {
+            None => methods.extend(
+                impl_
+                    .items
+                    .iter()
+                    .filter_map(|id| krate.index.get(id))
+                    .filter(|method| matches!(method.inner, ItemEnum::Function(_)))
+                    .map(|method| {
+                        (
+                            method.name.clone().unwrap_or_default(),
+                            method_entry(method, ""),
+                        )
+                    }),
+            ),
+            Some(_) if impl_.is_synthetic || impl_.blanket_impl.is_some() => generated += 1,
+            Some(trait_)
+                if !expand_derived
+                    && impl_item
+                        .attrs
+                        .iter()
+                        .any(|attr| matches!(attr, Attribute::AutomaticallyDerived)) =>
+            {
+                derived.insert(trait_.path.rsplit("::").next().unwrap_or(&trait_.path));
+            }
+            Some(trait_) => {
+                *traits.entry(trait_.path.as_str()).or_default() += 1;
+                let entries: Vec<String> = impl_
+                    .items
+                    .iter()
+                    .filter_map(|id| krate.index.get(id))
+                    .filter(|method| matches!(method.inner, ItemEnum::Function(_)))
+                    .map(|method| method_entry(method, "    "))
+                    .collect();
+                if !entries.is_empty() {
+                    trait_methods.push(format!(
+                        "{} {{\n{}\n}}",
+                        format_impl_header(impl_),
+                        entries.join("\n")
+                    ));
+                }
+            }
+        }
+    }
+    if methods.is_empty() && traits.is_empty() && derived.is_empty() {
+        return None;
+    }
+    methods.sort();
+
+    let trait_impls: usize = traits.values().sum();
+    let mut section = format!(
+        "\n## Implementations\n\n{} inherent methods, {} trait impls of {} traits, {} derived impls, {} auto trait and blanket impls.\n",
+        methods.len(),
+        trait_impls,
+        traits.len(),
+        derived.len(),
+        generated
+    );
+
+    if !derived.is_empty() {
+        let names: Vec<String> = derived.iter().map(|name| format!("`{name}`")).collect();
+        section.push_str(&format!(
+            "\nDerived: {}. Pass `expand_derived` to list them with the other traits.\n",
+            names.join(", ")
+        ));
+    }
+
+    if !traits.is_empty() {
+        let mut by_count: Vec<(&str, usize)> = traits.into_iter().collect();
+        by_count.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+        section.push_str("\n### Traits\n\n");
+        for (name, count) in by_count.iter().take(page_size) {
+            if *count == 1 {
+                section.push_str(&format!("- `{name}`\n"));
+            } else {
+                section.push_str(&format!("- `{name}` ({count} impls)\n"));
+            }
+        }
+        if by_count.len() > page_size {
+            section.push_str(&format!(
+                "- ... and {} more traits\n",
+                by_count.len() - page_size
+            ));
+        }
+    }
+
+    if !methods.is_empty() {
+        let pages = methods.len().div_ceil(page_size.max(1));
+        let page = page.clamp(1, pages);
+        let start = (page - 1) * page_size;
+        let shown: Vec<&str> = methods
+            .iter()
+            .skip(start)
+            .take(page_size)
+            .map(|(_, entry)| entry.as_str())
+            .collect();
+        section.push_str(&format!(
+            "\n### Methods (page {page} of {pages})\n\n```rust\n{}\n```\n",
+            shown.join("\n")
+        ));
+        if pages > 1 {
+            section.push_str("\nPass `page` to see the other methods.\n");
+        }
+    }
+
+    if !trait_methods.is_empty() {
+        trait_methods.sort();
+        let pages = trait_methods.len().div_ceil(page_size.max(1));
+        let page = page.clamp(1, pages);
+        let start = (page - 1) * page_size;
+        let shown: Vec<&str> = trait_methods
+            .iter()
+            .skip(start)
+            .take(page_size)
+            .map(String::as_str)
+            .collect();
+        section.push_str(&format!(
+            "\n### Trait Methods (page {page} of {pages})\n\n```rust\n{}\n```\n",
+            shown.join("\n\n")
+        ));
+        if pages > 1 {
+            section.push_str(
+                "\nPass `page` to see the other trait impls, or use `list_trait_impls` to list them all.\n",
+            );
+        }
+    }
+    Some(section)
+}
+
+/// The methods of all inherent impls of a struct, enum or union, sorted by name. Returns
+/// `None` for other items.
+pub fn inherent_methods<'a>(krate: &'a Crate, item: &Item) -> Option<Vec<&'a Item>> {
+    let impls = match &item.inner {
+        ItemEnum::Struct(s) => &s.impls,
+        ItemEnum::Enum(e) => &e.impls,
+        ItemEnum::Union(u) => &u.impls,
+        _ => return None,
+    };
+    let mut methods: Vec<&Item> = impls
+        .iter()
+        .filter_map(|id| krate.index.get(id))
+        .filter_map(|impl_item| match &impl_item.inner {
+            ItemEnum::Impl(impl_) if impl_.trait_.is_none() => Some(&impl_.items),
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|id| krate.index.get(id))
+        .filter(|method| matches!(method.inner, ItemEnum::Function(_)))
+        .collect();
+    methods.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(methods)
+}
+
+/// Lists every trait impl of a struct, enum or union: hand-written and derived ones
+/// first, then auto trait and blanket impls, each sorted by trait path. Returns `None` for
+/// other items.
+pub fn trait_impls(krate: &Crate, item: &Item) -> Option<Vec<TraitImpl>> {
+    let impls = match &item.inner {
+        ItemEnum::Struct(s) => &s.impls,
+        ItemEnum::Enum(e) => &e.impls,
+        ItemEnum::Union(u) => &u.impls,
+        _ => return None,
+    };
+    let rank = |kind: &str| match kind {
+        "auto" => 1,
+        "blanket" => 2,
+        _ => 0,
+    };
+    let mut found: Vec<TraitImpl> = impls
+        .iter()
+        .filter_map(|id| krate.index.get(id))
+        .filter_map(|impl_item| {
+            let ItemEnum::Impl(impl_) = &impl_item.inner else {
+                return None;
+            };
+            let trait_ = impl_.trait_.as_ref()?;
+            let kind = if impl_.is_synthetic {
+                "auto"
+            } else if impl_.blanket_impl.is_some() {
+                "blanket"
+            } else if impl_item
+                .attrs
+                .iter()
+                .any(|attr| matches!(attr, Attribute::AutomaticallyDerived))
+            {
+                "derived"
+            } else {
+                "explicit"
+            };
+            // Blanket impls list the type they apply to as `for_`; show the impl as written.
+            let header = match &impl_.blanket_impl {
+                Some(blanket) => format_impl_header(&Impl {
+                    for_: blanket.clone(),
+                    ..impl_.clone()
+                }),
+                None => format_impl_header(impl_),
+            };
+            Some(TraitImpl {
+                trait_path: krate
+                    .paths
+                    .get(&trait_.id)
+                    .map_or_else(|| trait_.path.clone(), |summary| summary.path.join("::")),
+                header,
+                generics: format_generics(&impl_.generics),
+                where_clause: format_where_clause(&impl_.generics),
+                kind: kind.to_string(),
+                is_unsafe: impl_.is_unsafe,
+                is_negative: impl_.is_negative,
+            })
+        })
+        .collect();
+    found.sort_by(|a, b| {
+        rank(&a.kind)
+            .cmp(&rank(&b.kind))
+            .then_with(|| a.trait_path.cmp(&b.trait_path))
+    });
+    Some(found)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{generics, item};
+    use rustdoc_types::{
+        Abi, Function, FunctionHeader, FunctionSignature, Id, Path, Struct, StructKind, Type,
+    };
+    use std::collections::HashMap;
+
+    fn impl_of(trait_: Option<&str>, items: Vec<Id>) -> ItemEnum {
+        ItemEnum::Impl(Impl {
+            is_unsafe: false,
+            generics: generics(),
+            provided_trait_methods: vec![],
+            trait_: trait_.map(|name| Path {
+                path: name.to_string(),
+                id: Id(99),
+                args: None,
+            }),
+            for_: Type::Primitive("unit".to_string()),
+            items,
+            is_negative: false,
+            is_synthetic: false,
+            blanket_impl: None,
+        })
+    }
+
+    fn derived(id: u32, trait_: &str) -> Item {
+        let mut derived = item(id, "", impl_of(Some(trait_), vec![]));
+        derived.attrs.push(Attribute::AutomaticallyDerived);
+        derived
+    }
+
+    fn method(id: u32, name: &str) -> Item {
+        item(
+            id,
+            name,
+            ItemEnum::Function(Function {
+                sig: FunctionSignature {
+                    inputs: vec![],
+                    output: None,
+                    is_c_variadic: false,
+                },
+                generics: generics(),
+                header: FunctionHeader {
+                    is_const: false,
+                    is_unsafe: false,
+                    is_async: false,
+                    abi: Abi::Rust,
+                },
+                has_body: true,
+            }),
+        )
+    }
+
+    #[test]
+    fn test_implementations_section_pages_trait_methods() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let mut impl_ids = Vec::new();
+        for (i, trait_) in ["Clone", "Default", "Display"].into_iter().enumerate() {
+            let (impl_id, method_id) = (2 + i as u32, 10 + i as u32);
+            let name = format!("{}_method", trait_.to_lowercase());
+            krate.index.insert(Id(method_id), method(method_id, &name));
+            let impl_item = item(impl_id, "", impl_of(Some(trait_), vec![Id(method_id)]));
+            krate.index.insert(Id(impl_id), impl_item);
+            impl_ids.push(Id(impl_id));
+        }
+        let ty = item(
+            1,
+            "Ty",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: generics(),
+                impls: impl_ids,
+            }),
+        );
+
+        let first = implementations_section(&krate, &ty, 1, 2, false).unwrap();
+        assert!(first.contains("### Trait Methods (page 1 of 2)"));
+        assert!(first.contains("fn clone_method()"));
+        assert!(first.contains("fn default_method()"));
+        assert!(!first.contains("fn display_method()"));
+        assert!(first.contains("Pass `page` to see the other trait impls"));
+
+        let second = implementations_section(&krate, &ty, 2, 2, false).unwrap();
+        assert!(second.contains("### Trait Methods (page 2 of 2)"));
+        assert!(second.contains("impl Display for unit {\n    fn display_method()\n}"));
+        assert!(!second.contains("fn clone_method()"));
+    }
+
+    #[test]
+    fn test_implementations_section() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+
+        let method_ids: Vec<Id> = (10..16).map(Id).collect();
+        for (i, id) in method_ids.iter().enumerate() {
+            let mut method = item(
+                id.0,
+                &format!("read_{i}"),
+                ItemEnum::Function(Function {
+                    sig: FunctionSignature {
+                        inputs: vec![],
+                        output: None,
+                        is_c_variadic: false,
+                    },
+                    generics: generics(),
+                    header: FunctionHeader {
+                        is_const: false,
+                        is_unsafe: false,
+                        is_async: false,
+                        abi: Abi::Rust,
+                    },
+                    has_body: true,
+                }),
+            );
+            if i == 0 {
+                method.docs = Some("Reads the register.\n\nMore details.".to_string());
+            }
+            krate.index.insert(method.id, method);
+        }
+        // The last method belongs to a trait impl.
+        let clone_method = method_ids[5];
+        krate.index.get_mut(&clone_method).unwrap().name = Some("clone".to_string());
+        let impls = [
+            item(2, "", impl_of(None, method_ids[..5].to_vec())),
+            item(3, "", impl_of(Some("From"), vec![])),
+            item(4, "", impl_of(Some("From"), vec![])),
+            item(5, "", impl_of(Some("Clone"), vec![clone_method])),
+            derived(6, "core::fmt::Debug"),
+            derived(7, "PartialEq"),
+        ];
+        let reg = item(
+            1,
+            "Reg",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: generics(),
+                impls: impls.iter().map(|i| i.id).collect(),
+            }),
+        );
+        for i in impls {
+            krate.index.insert(i.id, i);
+        }
+
+        let section = implementations_section(&krate, &reg, 2, 2, false).unwrap();
+        assert!(section.contains("5 inherent methods, 3 trait impls of 2 traits"));
+        assert!(section.contains("- `From` (2 impls)\n- `Clone`\n"));
+        assert!(section.contains("### Methods (page 2 of 3)"));
+        assert!(section.contains("fn read_2()\nfn read_3()"));
+        assert!(!section.contains("fn read_1()"));
+        assert!(section.contains("2 derived impls"));
+        assert!(section.contains("Derived: `Debug`, `PartialEq`."));
+        assert!(!section.contains("- `PartialEq`"));
+        assert!(section.contains(
+            "### Trait Methods (page 1 of 1)\n\n```rust\nimpl Clone for unit {\n    fn clone()\n}\n```"
+        ));
+
+        let first_page = implementations_section(&krate, &reg, 1, 2, false).unwrap();
+        assert!(first_page.contains("/// Reads the register.\nfn read_0()\nfn read_1()"));
+
+        let expanded = implementations_section(&krate, &reg, 1, 10, true).unwrap();
+        assert!(expanded.contains("5 trait impls of 4 traits, 0 derived impls"));
+        assert!(expanded.contains("- `PartialEq`\n"));
+        assert!(!expanded.contains("Derived:"));
+    }
+}
@@ -0,0 +1,3101 @@
+use std::collections::{HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::types::{ItemOwner, ItemSummary, SearchExplanation};
+use anyhow::{Context, Result};
+use cargo_metadata::Package;
+use dashmap::DashMap;
+use rustdoc_types::{Crate, GenericBound, Id, Item, ItemEnum, Type};
+use strsim::jaro_winkler;
+use tokio::fs;
+use tokio::sync::Semaphore;
+use tokio::task::JoinHandle;
+use tracing::{debug, info, instrument, warn};
+
+use crate::config::Config;
+#[cfg(feature = "tantivy")]
+use crate::config::SearchBackend;
+use crate::crate_info::{build_failure_hint, failed_build_script_package};
+use crate::doc_gen::{DocGenerator, format_mismatch_hint};
+use crate::git_checkout;
+use crate::headings::{DocHeading, build_heading_index, extract_headings};
+use crate::index_cache;
+use crate::json_cache::{Expected, crate_version, find_reusable, fingerprint, stale_reason};
+use crate::markdown::item_owners;
+use crate::signatures::{SignatureTypes, match_signature, signature_types};
+use crate::stability::{UnstableItems, unstable_feature};
+use crate::sysroot::{docs_path, install_docs_component, is_sysroot_crate, nightly_sysroot};
+#[cfg(feature = "tantivy")]
+use crate::text_index::{self, TextIndex};
+use crate::workspace::Workspace;
+
+/// Modules with at least this many bytes of docs are treated as guides.
+pub const DEFAULT_GUIDE_MIN_LENGTH: usize = 2000;
+
+/// Lists the features of the docs in a directory of
+/// [`features_target_dir`](CrateIndex::features_target_dir), one per line.
+const FEATURES_FILE: &str = "features.txt";
+
+/// How many crates [`CrateIndex::ensure_all_loaded`] loads at once. Generating docs runs
+/// `cargo rustdoc`, so loading many crates at once mostly adds memory pressure.
+const MAX_CONCURRENT_LOADS: usize = 4;
+
+/// How many of its best BM25 matches a crate contributes to a search.
+#[cfg(feature = "tantivy")]
+const TEXT_SEARCH_LIMIT: usize = 500;
+
+#[derive(Debug, Clone)]
+pub struct LoadedCrate {
+    pub krate: Crate,
+    pub path_to_id: HashMap<String, Id>,
+    /// Markdown headings found in the docs of indexed items.
+    pub headings: Vec<DocHeading>,
+    /// Full paths of the types and traits each type or trait alias expands to.
+    pub alias_targets: HashMap<Id, Vec<String>>,
+    /// `Type::name` paths that several impls provide an item for, with the qualified path
+    /// of each candidate, e.g. `demo::Type::to_string` and `<demo::Type as ToString>::to_string`.
+    pub ambiguous_paths: HashMap<String, Vec<(String, Id)>>,
+    /// The normalized argument and return types of every indexed function.
+    pub(crate) signatures: HashMap<Id, SignatureTypes>,
+    /// The type and trait each associated item belongs to.
+    pub owners: HashMap<Id, ItemOwner>,
+    /// The shortest path of every indexed item, the first in order when several are as
+    /// short.
+    pub shortest_paths: HashMap<Id, String>,
+    /// The features the docs were generated with by [`CrateIndex::load_with_features`];
+    /// `None` for those the workspace resolves.
+    pub features: Option<Vec<String>>,
+    /// The repository URL and revision the docs were generated from by
+    /// [`CrateIndex::load_git_revision`].
+    pub git_revision: Option<(String, String)>,
+    /// The BM25 index searches go through with `search.backend = "tantivy"`.
+    #[cfg(feature = "tantivy")]
+    pub(crate) text_index: Option<Arc<TextIndex>>,
+}
+
+impl LoadedCrate {
+    /// Wraps a parsed crate and its path map, building the derived indexes.
+    pub fn new(krate: Crate, mut path_to_id: HashMap<String, Id>) -> Self {
+        let ambiguous_paths = ambiguous_paths(&krate, &path_to_id);
+        // Like method call syntax, the plain path names the inherent item when there is one.
+        for (path, candidates) in &ambiguous_paths {
+            if let Some((qualified, id)) = candidates.first()
+                && !qualified.starts_with('<')
+            {
+                path_to_id.insert(path.clone(), *id);
+            }
+        }
+        let headings = build_heading_index(&krate, &path_to_id);
+        let alias_targets = path_to_id
+            .values()
+            .filter_map(|id| {
+                let targets = alias_targets(&krate, krate.index.get(id)?);
+                (!targets.is_empty()).then_some((*id, targets))
+            })
+            .collect();
+        let signatures = signature_types(&krate, path_to_id.values());
+        let owners = item_owners(&krate);
+        let shortest_paths = shortest_paths(&path_to_id);
+        Self {
+            krate,
+            path_to_id,
+            headings,
+            alias_targets,
+            ambiguous_paths,
+            signatures,
+            owners,
+            shortest_paths,
+            features: None,
+            git_revision: None,
+            #[cfg(feature = "tantivy")]
+            text_index: None,
+        }
+    }
+
+    /// Returns the modules whose docs are at least `min_length` bytes long, sorted by path.
+    pub fn guides(&self, min_length: usize) -> Vec<(&str, &Item)> {
+        let mut guides: Vec<(&str, &Item)> = self
+            .path_to_id
+            .iter()
+            .filter_map(|(path, id)| {
+                let item = self.krate.index.get(id)?;
+                let docs = item.docs.as_deref()?;
+                (matches!(item.inner, ItemEnum::Module(_)) && docs.len() >= min_length)
+                    .then_some((path.as_str(), item))
+            })
+            .collect();
+        guides.sort_by(|a, b| a.0.cmp(b.0));
+        guides
+    }
+
+    /// Returns the items whose docs contain every one of `terms` (lowercase), with how often
+    /// the terms occur, most occurrences first, then by path. An item reachable through
+    /// several paths is listed once, under its shortest path.
+    pub fn doc_text_matches(&self, terms: &[String]) -> Vec<(&str, &Item, usize)> {
+        let mut found: Vec<(&str, &Item, usize)> = self
+            .shortest_paths
+            .iter()
+            .filter_map(|(id, path)| {
+                let item = self.krate.index.get(id)?;
+                let docs = item.docs.as_deref()?.to_lowercase();
+                let hits = terms
+                    .iter()
+                    .map(|term| docs.matches(term.as_str()).count())
+                    .try_fold(0, |total, count| (count > 0).then_some(total + count))?;
+                Some((path.as_str(), item, hits))
+            })
+            .collect();
+        found.sort_by(|a, b| b.2.cmp(&a.2).then(a.0.cmp(b.0)));
+        found
+    }
+
+    /// The first line of an item's docs mentioning one of `terms` (lowercase), trimmed.
+    pub fn doc_excerpt<'a>(item: &'a Item, terms: &[String]) -> &'a str {
+        item.docs
+            .as_deref()
+            .unwrap_or_default()
+            .lines()
+            .find(|line| {
+                let line = line.to_lowercase();
+                terms.iter().any(|term| line.contains(term.as_str()))
+            })
+            .unwrap_or_default()
+            .trim()
+    }
+
+    /// Returns every indexed item whose last path segment is `name`, sorted by path.
+    pub fn find_by_name(&self, name: &str) -> Vec<(&str, &Item)> {
+        let mut found: Vec<(&str, &Item)> = self
+            .path_to_id
+            .iter()
+            .filter(|(path, _)| path.rsplit("::").next() == Some(name))
+            .filter_map(|(path, id)| Some((path.as_str(), self.krate.index.get(id)?)))
+            .collect();
+        found.sort_by(|a, b| a.0.cmp(b.0));
+        found
+    }
+
+    /// Returns the fields, variants and associated items indexed directly below `type_path`.
+    pub fn members_of(&self, type_path: &str) -> Vec<(&str, &Item)> {
+        let prefix = format!("{type_path}::");
+        let mut members: Vec<(&str, &Item)> = self
+            .path_to_id
+            .iter()
+            .filter(|(path, _)| {
+                path.strip_prefix(&prefix)
+                    .is_some_and(|rest| !rest.contains("::"))
+            })
+            .filter_map(|(path, id)| Some((path.as_str(), self.krate.index.get(id)?)))
+            .collect();
+        members.sort_by(|a, b| a.0.cmp(b.0));
+        members
+    }
+
+    /// Returns the traits declaring an associated item called `name`, sorted by path.
+    pub fn traits_declaring(&self, name: &str) -> Vec<(&str, &Item)> {
+        let mut traits: Vec<(&str, &Item)> = self
+            .path_to_id
+            .iter()
+            .filter_map(|(path, id)| {
+                let item = self.krate.index.get(id)?;
+                let ItemEnum::Trait(t) = &item.inner else {
+                    return None;
+                };
+                t.items
+                    .iter()
+                    .filter_map(|id| self.krate.index.get(id))
+                    .any(|assoc| assoc.name.as_deref() == Some(name))
+                    .then_some((path.as_str(), item))
+            })
+            .collect();
+        traits.sort_by(|a, b| a.0.cmp(b.0));
+        traits
+    }
+
+    /// Returns the names a module exports as `(name, id)`, resolving non-glob `pub use`
+    /// re-exports to their targets. Glob imports are expanded `glob_depth` levels deep.
+    fn module_exports(&self, items: &[Id], glob_depth: usize) -> Vec<(String, Id)> {
+        let mut exports = Vec::new();
+        for item_id in items {
+            let Some(child) = self.krate.index.get(item_id) else {
+                continue;
+            };
+            match &child.inner {
+                ItemEnum::Use(u) if u.is_glob => {
+                    if glob_depth > 0
+                        && let Some(target) = u.id.and_then(|id| self.krate.index.get(&id))
+                        && let ItemEnum::Module(m) = &target.inner
+                    {
+                        exports.extend(self.module_exports(&m.items, glob_depth - 1));
+                    }
+                }
+                ItemEnum::Use(u) => {
+                    if let Some(target) = u.id {
+                        exports.push((u.name.clone(), target));
+                    }
+                }
+                _ => {
+                    if let Some(name) = &child.name {
+                        exports.push((name.clone(), child.id));
+                    }
+                }
+            }
+        }
+        exports
+    }
+
+    /// Lists what each `prelude` module of the crate brings into scope, as
+    /// `(prelude_path, name, id)` sorted by prelude and name.
+    pub fn prelude_entries(&self) -> Vec<(String, String, Id)> {
+        let mut entries = Vec::new();
+        for (path, id) in &self.path_to_id {
+            if path.rsplit("::").next() != Some("prelude") {
+                continue;
+            }
+            if let Some(item) = self.krate.index.get(id)
+                && let ItemEnum::Module(m) = &item.inner
+            {
+                for (name, target) in self.module_exports(&m.items, 1) {
+                    entries.push((path.clone(), name, target));
+                }
+            }
+        }
+        entries.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+        entries
+    }
+
+    /// Returns every indexed item usable in const contexts, sorted by path.
+    pub fn const_items(&self) -> Vec<(&str, &Item)> {
+        let mut items: Vec<(&str, &Item)> = self
+            .path_to_id
+            .iter()
+            .filter_map(|(path, id)| Some((path.as_str(), self.krate.index.get(id)?)))
+            .filter(|(_, item)| is_const_usable(item))
+            .collect();
+        items.sort_by(|a, b| a.0.cmp(b.0));
+        items
+    }
+
+    /// Returns what `type_path` derefs to through a documented `Deref` impl: the full path
+    /// of the target type, or a primitive name such as `str`. Generic targets are skipped.
+    pub fn deref_target(&self, type_path: &str) -> Option<String> {
+        let item = self.krate.index.get(self.path_to_id.get(type_path)?)?;
+        let impls = match &item.inner {
+            ItemEnum::Struct(s) => &s.impls,
+            ItemEnum::Enum(e) => &e.impls,
+            ItemEnum::Union(u) => &u.impls,
+            _ => return None,
+        };
+
+        impls
+            .iter()
+            .filter_map(|id| self.krate.index.get(id))
+            .find_map(|impl_item| {
+                let ItemEnum::Impl(impl_) = &impl_item.inner else {
+                    return None;
+                };
+                let trait_ = impl_.trait_.as_ref()?;
+                if trait_.path.rsplit("::").next() != Some("Deref") {
+                    return None;
+                }
+                impl_
+                    .items
+                    .iter()
+                    .filter_map(|id| self.krate.index.get(id))
+                    .find_map(|assoc| match (&assoc.inner, assoc.name.as_deref()) {
+                        (
+                            ItemEnum::AssocType {
+                                type_: Some(ty), ..
+                            },
+                            Some("Target"),
+                        ) => match ty {
+                            Type::ResolvedPath(p) => {
+                                self.krate.paths.get(&p.id).map(|s| s.path.join("::"))
+                            }
+                            Type::Primitive(name) => Some(name.clone()),
+                            _ => None,
+                        },
+                        _ => None,
+                    })
+            })
+    }
+
+    /// Returns the path `id` is indexed under, preferring the shortest, or else the path
+    /// rustdoc recorded for it (e.g. for items re-exported from other crates).
+    pub fn path_of(&self, id: Id) -> Option<String> {
+        self.shortest_paths
+            .get(&id)
+            .cloned()
+            .or_else(|| Some(self.krate.paths.get(&id)?.path.join("::")))
+    }
+
+    /// The candidates to choose from when several impls provide the item `path` names and
+    /// none of them is inherent. Like method call syntax, the plain path names the inherent
+    /// item when there is one (see [`LoadedCrate::trait_alternatives`]).
+    pub fn disambiguation(&self, path: &str) -> Option<&[(String, Id)]> {
+        let candidates = self.ambiguous_paths.get(path)?;
+        candidates
+            .iter()
+            .all(|(qualified, _)| qualified.starts_with('<'))
+            .then_some(candidates.as_slice())
+    }
+
+    /// The trait-qualified paths of the items traits provide under the name of the
+    /// inherent item `path` names, like `<demo::Type as ToString>::to_string`.
+    pub fn trait_alternatives(&self, path: &str) -> &[(String, Id)] {
+        match self.ambiguous_paths.get(path) {
+            Some(candidates) if self.disambiguation(path).is_none() => &candidates[1..],
+            _ => &[],
+        }
+    }
+
+    /// Resolves a trait-qualified path like `<demo::Type as Display>::fmt` to the item the
+    /// type's impl of that trait provides. Generic arguments of the trait are ignored.
+    pub fn resolve_qualified(&self, path: &str) -> Option<Id> {
+        let (type_path, rest) = path.strip_prefix('<')?.split_once(" as ")?;
+        let (trait_name, name) = rest.rsplit_once(">::")?;
+        let trait_name = trait_name.split('<').next()?.rsplit("::").next()?;
+        let item = self.krate.index.get(self.path_to_id.get(type_path)?)?;
+        impl_members(&self.krate, item)
+            .into_iter()
+            .find_map(|(member, trait_path, id)| {
+                let trait_path = trait_path?;
+                (member == name && trait_path.rsplit("::").next() == Some(trait_name)).then_some(id)
+            })
+    }
+
+    /// Maps every item reachable through a `prelude` module to that module's path.
+    pub fn prelude_map(&self) -> HashMap<Id, String> {
+        let mut map = HashMap::new();
+        for (prelude, _, id) in self.prelude_entries() {
+            map.entry(id).or_insert(prelude);
+        }
+        map
+    }
+}
+
+/// Returns the full paths of the types or traits a type alias or trait alias expands to,
+/// e.g. `core::result::Result` for `anyhow::Result`.
+pub fn alias_targets(krate: &Crate, item: &Item) -> Vec<String> {
+    let ids: Vec<Id> = match &item.inner {
+        ItemEnum::TypeAlias(t) => match &t.type_ {
+            Type::ResolvedPath(p) => vec![p.id],
+            _ => Vec::new(),
+        },
+        ItemEnum::TraitAlias(t) => t
+            .params
+            .iter()
+            .filter_map(|bound| match bound {
+                GenericBound::TraitBound { trait_, .. } => Some(trait_.id),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    ids.iter()
+        .filter_map(|id| krate.paths.get(id))
+        .map(|summary| summary.path.join("::"))
+        .collect()
+}
+
+/// Lists the associated items of a type's impls as `(name, trait path, id)`, inherent
+/// items first. The trait path is `None` for inherent impls.
+fn impl_members<'a>(krate: &'a Crate, item: &Item) -> Vec<(&'a str, Option<&'a str>, Id)> {
+    let impls = match &item.inner {
+        ItemEnum::Struct(s) => &s.impls,
+        ItemEnum::Enum(e) => &e.impls,
+        ItemEnum::Union(u) => &u.impls,
+        _ => return Vec::new(),
+    };
+    let mut members: Vec<(&str, Option<&str>, Id)> = impls
+        .iter()
+        .filter_map(|id| match &krate.index.get(id)?.inner {
+            ItemEnum::Impl(impl_) if !impl_.is_negative => Some(impl_),
+            _ => None,
+        })
+        .flat_map(|impl_| {
+            let trait_path = impl_.trait_.as_ref().map(|t| t.path.as_str());
+            impl_
+                .items
+                .iter()
+                .filter_map(|id| krate.index.get(id))
+                .filter_map(move |assoc| Some((assoc.name.as_deref()?, trait_path, assoc.id)))
+        })
+        .collect();
+    members.sort_by_key(|(_, trait_path, _)| trait_path.is_some());
+    members
+}
+
+/// Finds the `Type::name` paths that more than one impl of a type provides an item for,
+/// such as an inherent `to_string` next to the one from `ToString`, and qualifies each
+/// candidate: `demo::Type::to_string` or `<demo::Type as ToString>::to_string`.
+pub fn ambiguous_paths(
+    krate: &Crate,
+    path_to_id: &HashMap<String, Id>,
+) -> HashMap<String, Vec<(String, Id)>> {
+    let mut ambiguous = HashMap::new();
+    for (path, id) in path_to_id {
+        let Some(item) = krate.index.get(id) else {
+            continue;
+        };
+        let mut by_name: HashMap<&str, Vec<(String, Id)>> = HashMap::new();
+        for (name, trait_path, id) in impl_members(krate, item) {
+            let qualified = match trait_path {
+                Some(trait_path) => format!("<{path} as {trait_path}>::{name}"),
+                None => format!("{path}::{name}"),
+            };
+            by_name.entry(name).or_default().push((qualified, id));
+        }
+        ambiguous.extend(
+            by_name
+                .into_iter()
+                .filter(|(_, candidates)| candidates.len() > 1)
+                .map(|(name, candidates)| (format!("{path}::{name}"), candidates)),
+        );
+    }
+    ambiguous
+}
+
+/// Docs of a crate a session loaded in place of those the workspace resolves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum DocsVariant {
+    /// Generated with exactly these features.
+    Features(Vec<String>),
+    /// Generated from a checkout of a git repository at a revision.
+    Git { url: String, rev: String },
+}
+
+impl DocsVariant {
+    /// The key the docs are cached under next to the workspace's, like `tokio[<hash of the
+    /// features>]` or `tokio@a1b2c3#<hash of the URL>`, which keeps forks at the same
+    /// revision apart.
+    fn key(&self, crate_name: &str) -> String {
+        match self {
+            Self::Features(features) => {
+                format!("{crate_name}[{}]", short_hash(feature_set(features)))
+            }
+            Self::Git { url, rev } => format!("{crate_name}@{rev}#{}", short_hash(url)),
+        }
+    }
+}
+
+/// A hash of `value` as 16 hex digits, to name things after inputs of any length.
+fn short_hash(value: impl Hash) -> String {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// `features` sorted and without duplicates, so a feature set has one name however it
+/// was asked for.
+fn feature_set(features: &[String]) -> Vec<&str> {
+    let mut set: Vec<&str> = features.iter().map(String::as_str).collect();
+    set.sort_unstable();
+    set.dedup();
+    set
+}
+
+/// The crate of a key of [`CrateIndex::crates`].
+fn key_crate(key: &str) -> &str {
+    key.split(['[', '@']).next().unwrap_or(key)
+}
+
+/// Picks the shortest path each item is indexed under, the first in order when several
+/// are as short.
+fn shortest_paths(path_to_id: &HashMap<String, Id>) -> HashMap<Id, String> {
+    let mut best: HashMap<Id, &str> = HashMap::new();
+    for (path, id) in path_to_id {
+        let shortest = best.entry(*id).or_insert(path.as_str());
+        if (path.len(), path.as_str()) < (shortest.len(), *shortest) {
+            *shortest = path.as_str();
+        }
+    }
+    best.into_iter()
+        .map(|(id, path)| (id, path.to_string()))
+        .collect()
+}
+
+/// Picks a title for a block of module prose: its first heading outside code blocks, or
+/// else its first line.
+pub fn guide_title(docs: &str) -> String {
+    extract_headings(docs)
+        .into_iter()
+        .next()
+        .map(|(_, title, _)| title)
+        .or_else(|| docs.lines().next().map(|line| line.trim().to_string()))
+        .unwrap_or_default()
+}
+
+/// How long each stage of loading a crate took, and how much it loaded.
+#[derive(Debug, Default)]
+pub struct LoadTimings {
+    /// Running `cargo rustdoc`; zero when the JSON was already on disk.
+    pub generate: Duration,
+    pub read: Duration,
+    pub parse: Duration,
+    pub path_map: Duration,
+    /// Building the heading, alias and disambiguation indexes.
+    pub derived_indexes: Duration,
+    pub json_bytes: usize,
+    pub paths: usize,
+    /// Whether the parsed index was read from the on-disk cache instead of the JSON, in
+    /// which case `read` times reading and decoding it.
+    pub from_index_cache: bool,
+}
+
+/// The outcome of loading one of several crates with [`CrateIndex::ensure_all_loaded`].
+#[derive(Debug)]
+pub enum LoadOutcome {
+    Loaded,
+    Failed(anyhow::Error),
+    /// Still loading when the request stopped waiting; the load carries on in the
+    /// background, so asking again later finds the crate loaded.
+    Pending,
+}
+
+impl LoadOutcome {
+    /// Turns the outcome into an error for requests that need the crate.
+    pub fn required(self, crate_name: &str) -> Result<(), String> {
+        match self {
+            Self::Loaded => Ok(()),
+            Self::Failed(e) => Err(e.to_string()),
+            Self::Pending => Err(format!(
+                "The docs of `{crate_name}` are still being built; try again shortly"
+            )),
+        }
+    }
+}
+
+/// The crates a request spanning several can use, split by [`CrateIndex::load_available`]
+/// by how loading them went, each in the order given.
+#[derive(Debug, Default)]
+pub struct AvailableCrates {
+    /// Crates whose docs are loaded.
+    pub loaded: Vec<String>,
+    /// Crates whose docs failed to load.
+    pub unavailable: Vec<String>,
+    /// Crates still loading after `docs.load_timeout_secs`.
+    pub pending: Vec<String>,
+}
+
+/// Which items [`CrateIndex::search`] returns and how it matches them. The default
+/// matches item paths in every loaded crate and keeps all items, except that unstable
+/// ones are handled as configured in `search.unstable` when the workspace does not use
+/// nightly.
+#[derive(Debug, Clone, Default)]
+pub struct SearchOptions {
+    /// Only search this crate, loading it first.
+    pub crate_name: Option<String>,
+    /// Only return items usable in const contexts: `const fn`s and constants.
+    pub const_only: bool,
+    /// Only return items of these kinds (see [`ITEM_KINDS`]).
+    pub kinds: Option<Vec<String>>,
+    /// What to do with nightly-only items, overriding `search.unstable`.
+    pub unstable: Option<UnstableItems>,
+    /// Also match items whose docs contain every word of the query, ranked after path
+    /// matches.
+    pub full_text: bool,
+    /// Leave out `#[deprecated]` items.
+    pub exclude_deprecated: bool,
+}
+
+/// The docs of a crate borrowed from a [`CrateIndex`] by [`CrateIndex::get_crate`].
+pub type CrateRef<'a> = dashmap::mapref::one::Ref<'a, String, LoadedCrate>;
+
+#[derive(Debug, Clone)]
+pub struct CrateIndex {
+    /// Cache of loaded crates, shared by every session: `crate_name` -> `LoadedCrate`, or
+    /// [`DocsVariant::key`] for docs generated with a chosen feature set or from a git
+    /// revision.
+    crates: Arc<DashMap<String, LoadedCrate>>,
+    /// The docs this session loaded by [`load_with_features`](Self::load_with_features)
+    /// or [`load_git_revision`](Self::load_git_revision), in place of those the workspace
+    /// resolves: `crate_name` -> variant.
+    selected: Arc<DashMap<String, DocsVariant>>,
+    /// The sessions using each entry of [`CrateIndex::crates`], so that one session
+    /// unloading docs leaves them in memory for the others.
+    holders: Arc<DashMap<String, HashSet<u64>>>,
+    /// Identifies this session in [`CrateIndex::holders`].
+    session_id: u64,
+    /// The last session id handed out by [`session`](Self::session).
+    sessions: Arc<AtomicU64>,
+    /// Held while a crate is being loaded by `ensure_loaded`, so that concurrent requests
+    /// for it wait for that load instead of starting their own.
+    loading: Arc<DashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+    workspace: Workspace,
+    config: Config,
+}
+
+impl CrateIndex {
+    pub fn new(workspace: Workspace, config: Config) -> Self {
+        Self {
+            crates: Arc::new(DashMap::new()),
+            selected: Arc::new(DashMap::new()),
+            holders: Arc::new(DashMap::new()),
+            session_id: 0,
+            sessions: Arc::new(AtomicU64::new(0)),
+            loading: Arc::new(DashMap::new()),
+            workspace,
+            config,
+        }
+    }
+
+    /// A handle on the same loaded crates for another client session, which starts out
+    /// seeing every crate with the features the workspace resolves. Feature sets it picks
+    /// with [`load_with_features`](Self::load_with_features) stay out of the views of
+    /// other sessions, and docs it unloads stay in memory while another session uses them.
+    pub fn session(&self) -> Self {
+        Self {
+            selected: Arc::new(DashMap::new()),
+            session_id: self.sessions.fetch_add(1, Ordering::Relaxed) + 1,
+            ..self.clone()
+        }
+    }
+
+    /// Records that this session uses the docs cached under `key`.
+    fn hold(&self, key: &str) {
+        self.holders
+            .entry(key.to_string())
+            .or_default()
+            .insert(self.session_id);
+    }
+
+    /// The key of the docs of `crate_name` this session sees in [`CrateIndex::crates`].
+    fn key(&self, crate_name: &str) -> String {
+        match self.selected.get(crate_name) {
+            Some(variant) => variant.key(crate_name),
+            None => crate_name.to_string(),
+        }
+    }
+
+    /// Whether the docs cached under `key` are the ones this session sees of their crate.
+    fn is_visible(&self, key: &str) -> bool {
+        self.key(key_crate(key)) == key
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// Matches `candidate` (an item path, or the target of an alias) against `query`,
+    /// returning how it matched or `None`. Short queries only match by prefix of the last
+    /// segment or by substring, since fuzzy matching them hits nearly every path; longer
+    /// queries are scored fuzzily.
+    fn match_path(&self, query: &str, candidate: &str) -> Option<SearchExplanation> {
+        let search = &self.config.search;
+        let name = candidate.rsplit("::").next().unwrap_or(candidate);
+        let matcher = if name == query || candidate == query {
+            Some("exact")
+        } else if name.starts_with(query) {
+            Some("prefix")
+        } else if candidate.contains(query) {
+            Some("substring")
+        } else {
+            None
+        };
+
+        let (matcher, score, jaro_winkler_score) =
+            if query.chars().count() <= search.short_query_length {
+                let matcher = matcher?;
+                let score = match matcher {
+                    "exact" => 1.0,
+                    "prefix" => 0.9,
+                    _ => 0.5,
+                };
+                (matcher, score, None)
+            } else {
+                // Every non-fuzzy matcher implies the candidate contains the query.
+                let score = jaro_winkler(query, candidate);
+                if score <= search.fuzzy_threshold && matcher.is_none() {
+                    return None;
+                }
+                (matcher.unwrap_or("fuzzy"), score, Some(score))
+            };
+
+        Some(SearchExplanation {
+            path: String::new(),
+            kind: String::new(),
+            matched: candidate.to_string(),
+            matcher: matcher.to_string(),
+            score,
+            jaro_winkler: jaro_winkler_score,
+            unstable: None,
+        })
+    }
+
+    /// Where the rustdoc JSON of a crate is generated, and read from.
+    pub fn json_path(&self, crate_name: &str) -> PathBuf {
+        self.workspace
+            .metadata
+            .target_directory
+            .as_std_path()
+            .join("doc")
+            .join(format!("{}.json", crate_name.replace('-', "_")))
+    }
+
+    /// Checks docs already in the target directory, e.g. from the user's own `cargo rustdoc`
+    /// runs, before any are generated. Docs at `json_path` from another nightly, another
+    /// version of the package or older than the sources of a local package are removed;
+    /// when none are left, valid docs found elsewhere in the target directory (like
+    /// `target/<triple>/doc`) are linked into place.
+    fn reuse_existing_json(&self, crate_name: &str, json_path: &Path) {
+        let package = self.workspace.find_package(crate_name);
+        let crate_version = package.map(|p| p.version.to_string());
+        let expected = Expected {
+            format_version: rustdoc_types::FORMAT_VERSION,
+            crate_version: crate_version.as_deref(),
+            source_dir: package
+                .filter(|p| p.source.is_none())
+                .and_then(|p| p.manifest_path.parent())
+                .map(|dir| dir.as_std_path()),
+        };
+
+        if json_path.exists() {
+            match stale_reason(json_path, &expected) {
+                None => return,
+                Some(reason) => {
+                    info!("Discarding docs at {:?}: {}", json_path, reason);
+                    std::fs::remove_file(json_path).ok();
+                }
+            }
+        }
+
+        let target_dir = self.workspace.metadata.target_directory.as_std_path();
+        let file_name = format!("{}.json", crate_name.replace('-', "_"));
+        if let Some(found) = find_reusable(target_dir, &file_name, &expected) {
+            info!("Reusing docs from {:?}", found);
+            if let Some(parent) = json_path.parent() {
+                std::fs::create_dir_all(parent).ok();
+            }
+            std::fs::hard_link(&found, json_path)
+                .or_else(|_| std::fs::copy(&found, json_path).map(|_| ()))
+                .ok();
+        }
+    }
+
+    /// Appends an explanation to doc generation errors caused by a failing build script.
+    fn with_build_hint(&self, e: anyhow::Error) -> anyhow::Error {
+        // The hint quotes the failing package's README, which policy may forbid sharing.
+        let message = e.to_string();
+        if failed_build_script_package(&message)
+            .is_some_and(|name| self.config.crates.check(name).is_err())
+        {
+            return e;
+        }
+        match build_failure_hint(&self.workspace, &message) {
+            Some(hint) => anyhow::anyhow!("{e}\n\n{hint}"),
+            None => e,
+        }
+    }
+
+    /// Ensures the documentation for the given crate is loaded.
+    #[instrument(skip(self))]
+    pub async fn ensure_loaded(&self, crate_name: &str) -> Result<()> {
+        self.config.crates.check(crate_name)?;
+        let key = self.key(crate_name);
+        if self.crates.contains_key(&key) {
+            debug!("Crate {} is already loaded", key);
+            self.hold(&key);
+            return Ok(());
+        }
+
+        let lock = self.loading.entry(key.clone()).or_default().clone();
+        let _loading = lock.lock().await;
+        if self.crates.contains_key(&key) {
+            debug!("Crate {} was loaded by a concurrent request", key);
+            self.hold(&key);
+            return Ok(());
+        }
+
+        info!("Ensuring docs loaded for crate: {}", key);
+        // Another session may have unloaded the variant this one picked.
+        let selected = self.selected.get(crate_name).map(|v| v.clone());
+        let timings = match selected {
+            Some(DocsVariant::Features(features)) => {
+                self.cache_with_features(crate_name, &features).await?
+            }
+            Some(DocsVariant::Git { url, rev }) => {
+                self.cache_git_revision(crate_name, &url, &rev).await?
+            }
+            None => self.load(crate_name).await?,
+        };
+        self.hold(&key);
+        debug!("Loaded {} in stages: {:?}", crate_name, timings);
+        Ok(())
+    }
+
+    /// Ensures several crates are loaded, loading up to [`MAX_CONCURRENT_LOADS`] at a time
+    /// rather than one after the other. Returns each distinct crate with the outcome of
+    /// loading it, in the order given. Crates still loading after `docs.load_timeout_secs`
+    /// are reported as [`LoadOutcome::Pending`] and keep loading in the background, so a
+    /// slow build does not hold up the others.
+    pub async fn ensure_all_loaded<S: AsRef<str>>(
+        &self,
+        crate_names: &[S],
+    ) -> Vec<(String, LoadOutcome)> {
+        let mut names: Vec<String> = Vec::new();
+        for name in crate_names {
+            if !names.iter().any(|n| n == name.as_ref()) {
+                names.push(name.as_ref().to_string());
+            }
+        }
+
+        let permits = Arc::new(Semaphore::new(MAX_CONCURRENT_LOADS));
+        let loads: Vec<JoinHandle<Result<()>>> = names
+            .iter()
+            .map(|name| {
+                let index = self.clone();
+                let name = name.clone();
+                let permits = permits.clone();
+                // Spawned so that loads outliving the deadline are not cancelled with it.
+                tokio::spawn(async move {
+                    let _permit = permits.acquire_owned().await;
+                    index.ensure_loaded(&name).await
+                })
+            })
+            .collect();
+
+        let timeout = self.config.docs.load_timeout_secs;
+        let deadline = (timeout > 0).then(|| Instant::now() + Duration::from_secs(timeout));
+        let mut outcomes = Vec::with_capacity(names.len());
+        for (name, load) in names.into_iter().zip(loads) {
+            let joined = match deadline {
+                Some(deadline) => tokio::time::timeout_at(deadline.into(), load).await,
+                None => Ok(load.await),
+            };
+            let outcome = match joined {
+                Ok(Ok(Ok(()))) => LoadOutcome::Loaded,
+                Ok(Ok(Err(e))) => LoadOutcome::Failed(e),
+                Ok(Err(e)) => {
+                    LoadOutcome::Failed(anyhow::anyhow!("Loading {name} did not complete: {e}"))
+                }
+                Err(_) => {
+                    info!("{} is still loading after {}s", name, timeout);
+                    LoadOutcome::Pending
+                }
+            };
+            outcomes.push((name, outcome));
+        }
+        outcomes
+    }
+
+    /// Loads several crates like [`ensure_all_loaded`](Self::ensure_all_loaded) for a
+    /// request that answers with whichever of them are available, skipping those that
+    /// fail or are still loading.
+    pub async fn load_available<S: AsRef<str>>(&self, crate_names: &[S]) -> AvailableCrates {
+        let mut available = AvailableCrates::default();
+        for (name, outcome) in self.ensure_all_loaded(crate_names).await {
+            match outcome {
+                LoadOutcome::Loaded => available.loaded.push(name),
+                LoadOutcome::Failed(e) => {
+                    debug!("Skipping {}: {}", name, e);
+                    available.unavailable.push(name);
+                }
+                LoadOutcome::Pending => available.pending.push(name),
+            }
+        }
+        available
+    }
+
+    /// Generates the rustdoc JSON of a crate if it is missing, then parses and indexes it,
+    /// replacing any loaded copy. The parsed index is cached next to the JSON, so later
+    /// runs skip parsing until the JSON is regenerated. Reports how long each stage took.
+    pub async fn load(&self, crate_name: &str) -> Result<LoadTimings> {
+        self.config.crates.check(crate_name)?;
+        let mut timings = LoadTimings::default();
+
+        let target_dir = self.workspace.metadata.target_directory.as_std_path();
+        // Standard library docs come prebuilt with the toolchain rather than generated.
+        let sysroot = !self.workspace.fixture && is_sysroot_crate(crate_name);
+        let toolchain = self.config.docs.toolchain_for(crate_name);
+        let json_path = if sysroot {
+            let path = docs_path(&nightly_sysroot(toolchain).await?, crate_name);
+            if !path.exists() {
+                let start = Instant::now();
+                install_docs_component(toolchain).await?;
+                timings.generate = start.elapsed();
+                if !path.exists() {
+                    anyhow::bail!(
+                        "The rust-docs-json component has no docs for `{crate_name}` at {}",
+                        path.display()
+                    );
+                }
+            }
+            path
+        } else {
+            self.json_path(crate_name)
+        };
+
+        debug!("Expected JSON path: {:?}", json_path);
+
+        if !self.workspace.fixture && !sysroot {
+            self.reuse_existing_json(crate_name, &json_path);
+        }
+        if self.workspace.fixture && !json_path.exists() {
+            anyhow::bail!("The fixture has no docs for crate `{crate_name}`");
+        }
+        // Existing docs are checked against the fingerprint of their inputs, and
+        // regenerated when the lockfile, the features or local sources changed.
+        if !self.workspace.fixture && !sysroot {
+            let modified = || {
+                std::fs::metadata(&json_path)
+                    .and_then(|m| m.modified())
+                    .ok()
+            };
+            let previous = modified();
+            let start = Instant::now();
+            let package = self.workspace.packages.get(crate_name).or_else(|| {
+                self.workspace
+                    .packages
+                    .iter()
+                    .find(|(k, _)| k.replace('-', "_") == crate_name)
+                    .map(|(_, v)| v)
+            });
+
+            if let Some(pkg) = package {
+                let features = self
+                    .workspace
+                    .metadata
+                    .resolve
+                    .as_ref()
+                    .and_then(|resolve| {
+                        resolve
+                            .nodes
+                            .iter()
+                            .find(|node| node.id == pkg.id)
+                            .map(|node| {
+                                node.features
+                                    .iter()
+                                    .map(std::string::ToString::to_string)
+                                    .collect::<Vec<_>>()
+                            })
+                    });
+                let fingerprint = self.package_fingerprint(pkg, features.as_deref());
+
+                DocGenerator::generate(
+                    &pkg.name,
+                    features.as_deref(),
+                    toolchain,
+                    self.workspace.root.to_str().unwrap(),
+                    target_dir,
+                    None,
+                    Some(&fingerprint),
+                )
+                .await
+                .map_err(|e| self.with_build_hint(e))?;
+            } else {
+                DocGenerator::generate(
+                    crate_name,
+                    None,
+                    toolchain,
+                    self.workspace.root.to_str().unwrap(),
+                    target_dir,
+                    None,
+                    None,
+                )
+                .await
+                .map_err(|e| self.with_build_hint(e))?;
+            }
+            if previous.is_none() || modified() != previous {
+                timings.generate = start.elapsed();
+            }
+        }
+
+        // Fixture docs are checked in and never regenerated, so they are not cached.
+        let cache_path = (!self.workspace.fixture).then(|| {
+            index_cache::cache_path(target_dir, crate_name, crate_version(&json_path).as_deref())
+        });
+        let start = Instant::now();
+        let cached = cache_path
+            .as_deref()
+            .and_then(|path| index_cache::read(path, &json_path));
+        let (mut krate, path_to_id) = match cached {
+            Some(cached) => {
+                info!("Read the index of {} from the cache", crate_name);
+                timings.read = start.elapsed();
+                timings.from_index_cache = true;
+                cached
+            }
+            None => {
+                let (krate, path_to_id) =
+                    Self::parse_json(crate_name, &json_path, &mut timings).await?;
+                if let Some(path) = &cache_path
+                    && let Err(e) = index_cache::write(path, &krate, &path_to_id)
+                {
+                    warn!("Failed to cache the index of {}: {:#}", crate_name, e);
+                }
+                (krate, path_to_id)
+            }
+        };
+        timings.paths = path_to_id.len();
+        self.config.redact.redact_crate(&mut krate, &path_to_id);
+
+        let start = Instant::now();
+        let loaded = LoadedCrate::new(krate, path_to_id);
+        #[cfg(feature = "tantivy")]
+        let loaded = self.with_text_index(crate_name, &json_path, loaded);
+        timings.derived_indexes = start.elapsed();
+
+        self.crates.insert(crate_name.to_string(), loaded);
+        info!("Crate {} loaded successfully", crate_name);
+        Ok(timings)
+    }
+
+    /// Adds the full-text index of a crate when searches go through one, opening it from
+    /// the target directory or building it there. Fixture indexes are kept in memory.
+    #[cfg(feature = "tantivy")]
+    fn with_text_index(
+        &self,
+        crate_name: &str,
+        json_path: &Path,
+        mut loaded: LoadedCrate,
+    ) -> LoadedCrate {
+        if self.config.search.backend != SearchBackend::Tantivy {
+            return loaded;
+        }
+        let target_dir = self.workspace.metadata.target_directory.as_std_path();
+        let dir = (!self.workspace.fixture).then(|| {
+            text_index::index_dir(target_dir, crate_name, crate_version(json_path).as_deref())
+        });
+        match TextIndex::open_or_build(dir.as_deref(), json_path, &loaded) {
+            Ok(text_index) => loaded.text_index = Some(Arc::new(text_index)),
+            Err(e) => warn!(
+                "Failed to build the full-text index of {}, searching it fuzzily: {:#}",
+                crate_name, e
+            ),
+        }
+        loaded
+    }
+
+    /// Reads and parses the rustdoc JSON of a crate and builds its path map.
+    async fn parse_json(
+        crate_name: &str,
+        json_path: &Path,
+        timings: &mut LoadTimings,
+    ) -> Result<(Crate, HashMap<String, Id>)> {
+        info!("Reading rustdoc JSON from {:?}", json_path);
+        let start = Instant::now();
+        let content = fs::read_to_string(json_path)
+            .await
+            .context("Failed to read rustdoc JSON")?;
+        timings.read = start.elapsed();
+        timings.json_bytes = content.len();
+
+        let start = Instant::now();
+        let krate: Crate = serde_json::from_str(&content).map_err(|e| {
+            let e = anyhow::Error::new(e).context("Failed to parse rustdoc JSON");
+            match format_mismatch_hint(&content) {
+                Some(hint) => anyhow::anyhow!("{e:#}\n\n{hint}"),
+                None => e,
+            }
+        })?;
+        timings.parse = start.elapsed();
+        drop(content);
+
+        let start = Instant::now();
+        let path_to_id = Self::build_path_map(&krate, crate_name);
+        timings.path_map = start.elapsed();
+        Ok((krate, path_to_id))
+    }
+
+    /// Stops this session using the docs of a crate it sees and goes back to the docs the
+    /// workspace resolves. The docs are dropped from memory once no other session uses
+    /// them, keeping their generated JSON on disk so that loading them again is cheap.
+    /// Returns whether they were dropped.
+    pub fn unload(&self, crate_name: &str) -> bool {
+        let key = self.key(crate_name);
+        self.selected.remove(crate_name);
+        if let Some(mut sessions) = self.holders.get_mut(&key) {
+            sessions.remove(&self.session_id);
+        }
+        self.holders
+            .remove_if(&key, |_, sessions| sessions.is_empty());
+        if self.holders.contains_key(&key) {
+            debug!("Crate {} is still used by another session", key);
+            return false;
+        }
+        self.crates.remove(&key).is_some()
+    }
+
+    /// Regenerates the docs of a crate this session sees, so that edits to workspace
+    /// members since the last load are picked up. Docs loaded with chosen features are
+    /// regenerated with the same features, and those of a git revision are reread. The
+    /// docs are replaced in place, so other sessions using them keep the old ones until
+    /// the new ones are loaded.
+    pub async fn reload(&self, crate_name: &str) -> Result<()> {
+        self.config.crates.check(crate_name)?;
+        let key = self.key(crate_name);
+        let lock = self.loading.entry(key.clone()).or_default().clone();
+        let _loading = lock.lock().await;
+
+        let selected = self.selected.get(crate_name).map(|v| v.clone());
+        match selected {
+            Some(DocsVariant::Features(features)) => {
+                self.cache_with_features(crate_name, &features).await?;
+            }
+            Some(DocsVariant::Git { url, rev }) => {
+                self.cache_git_revision(crate_name, &url, &rev).await?;
+            }
+            None => {
+                let json_path = self.json_path(crate_name);
+                // Fixture docs cannot be regenerated, only reread.
+                if json_path.exists() && !self.workspace.fixture {
+                    fs::remove_file(&json_path)
+                        .await
+                        .context("Failed to remove stale rustdoc JSON")?;
+                }
+                self.load(crate_name).await?;
+            }
+        }
+        self.hold(&key);
+        Ok(())
+    }
+
+    /// The fingerprint of the inputs of a package's docs built with `features` (see
+    /// [`fingerprint`]).
+    fn package_fingerprint(&self, package: &Package, features: Option<&[String]>) -> String {
+        let lockfile = self
+            .workspace
+            .metadata
+            .workspace_root
+            .as_std_path()
+            .join("Cargo.lock");
+        let source_dir = package
+            .manifest_path
+            .parent()
+            .filter(|_| package.source.is_none())
+            .map(|dir| dir.as_std_path());
+        fingerprint(&lockfile, source_dir, features)
+    }
+
+    /// The target directory of the docs built with exactly `features` enabled, shared by
+    /// every package built with that feature set. It is named after a hash of the sorted
+    /// features, so long feature lists and odd feature names still make a valid name, and
+    /// lists them in [`FEATURES_FILE`].
+    fn features_target_dir(&self, features: &[String]) -> PathBuf {
+        self.workspace
+            .metadata
+            .target_directory
+            .as_std_path()
+            .join("rustdoc-mcp-features")
+            .join(short_hash(feature_set(features)))
+    }
+
+    /// Generates a package's docs with exactly `features` enabled into their
+    /// [`features_target_dir`](Self::features_target_dir) and returns the path of the
+    /// JSON. With a `fingerprint`, JSON generated from the same inputs is reused.
+    async fn generate_with_features(
+        &self,
+        package_name: &str,
+        features: &[String],
+        fingerprint: Option<&str>,
+    ) -> Result<PathBuf> {
+        let target_dir = self.features_target_dir(features);
+        fs::create_dir_all(&target_dir)
+            .await
+            .with_context(|| format!("Failed to create {}", target_dir.display()))?;
+        let label = match feature_set(features) {
+            set if set.is_empty() => "(no features)".to_string(),
+            set => set.join("\n"),
+        };
+        fs::write(target_dir.join(FEATURES_FILE), label + "\n")
+            .await
+            .with_context(|| format!("Failed to write {}", target_dir.display()))?;
+
+        DocGenerator::generate(
+            package_name,
+            Some(features),
+            self.config.docs.toolchain_for(package_name),
+            self.workspace.root.to_str().unwrap(),
+            &target_dir,
+            // Share the compiled dependencies of the workspace's own target directory.
+            Some(self.workspace.metadata.target_directory.as_std_path()),
+            fingerprint,
+        )
+        .await
+        .map_err(|e| self.with_build_hint(e))
+    }
+
+    /// Generates a package's docs with exactly `features` enabled and loads them in place
+    /// of its docs with the features the workspace resolves, for this session only and
+    /// until it unloads them. The JSON of each feature set is kept and reused until its
+    /// inputs change. Searches of these docs do not go through the full-text index of
+    /// `search.backend = "tantivy"`.
+    pub async fn load_with_features(
+        &self,
+        crate_name: &str,
+        features: &[String],
+    ) -> Result<LoadTimings> {
+        let timings = self.cache_with_features(crate_name, features).await?;
+        let crate_name = crate_name.replace('-', "_");
+        let variant = DocsVariant::Features(features.to_vec());
+        self.hold(&variant.key(&crate_name));
+        self.selected.insert(crate_name, variant);
+        Ok(timings)
+    }
+
+    /// Generates and loads a package's docs with exactly `features` enabled like
+    /// [`load_with_features`](Self::load_with_features), next to its other docs and
+    /// without showing them to this session.
+    pub async fn cache_with_features(
+        &self,
+        crate_name: &str,
+        features: &[String],
+    ) -> Result<LoadTimings> {
+        self.config.crates.check(crate_name)?;
+        if self.workspace.fixture {
+            anyhow::bail!("Fixture docs are prebuilt and cannot be generated with other features");
+        }
+        let package = self
+            .workspace
+            .find_package(crate_name)
+            .with_context(|| format!("Package not found: {crate_name}"))?;
+        let crate_name = package.name.replace('-', "_");
+        let mut timings = LoadTimings::default();
+
+        let json_path = self
+            .features_target_dir(features)
+            .join("doc")
+            .join(format!("{crate_name}.json"));
+        let modified = || {
+            std::fs::metadata(&json_path)
+                .and_then(|m| m.modified())
+                .ok()
+        };
+        let previous = modified();
+        let start = Instant::now();
+        let fingerprint = self.package_fingerprint(package, Some(features));
+        self.generate_with_features(&package.name, features, Some(&fingerprint))
+            .await?;
+        if previous.is_none() || modified() != previous {
+            timings.generate = start.elapsed();
+        }
+
+        let (mut krate, path_to_id) =
+            Self::parse_json(&crate_name, &json_path, &mut timings).await?;
+        timings.paths = path_to_id.len();
+        self.config.redact.redact_crate(&mut krate, &path_to_id);
+
+        let start = Instant::now();
+        let mut loaded = LoadedCrate::new(krate, path_to_id);
+        loaded.features = Some(features.to_vec());
+        timings.derived_indexes = start.elapsed();
+
+        let key = DocsVariant::Features(features.to_vec()).key(&crate_name);
+        self.crates.insert(key, loaded);
+        info!("Crate {} loaded with features {:?}", crate_name, features);
+        Ok(timings)
+    }
+
+    /// Clones the git repository at `url`, checks out `rev` and loads the docs of the
+    /// package `package_name` generated there in place of its docs from the workspace,
+    /// for this session only and until it unloads them. The checkout and its docs are
+    /// kept under the target directory, so asking for the same revision again is quick.
+    pub async fn load_git_revision(
+        &self,
+        package_name: &str,
+        url: &str,
+        rev: &str,
+    ) -> Result<LoadTimings> {
+        let timings = self.cache_git_revision(package_name, url, rev).await?;
+        let variant = DocsVariant::Git {
+            url: url.to_string(),
+            rev: rev.to_string(),
+        };
+        let crate_name = package_name.replace('-', "_");
+        self.hold(&variant.key(&crate_name));
+        self.selected.insert(crate_name, variant);
+        Ok(timings)
+    }
+
+    /// Checks out and loads the docs of a git revision like
+    /// [`load_git_revision`](Self::load_git_revision), without showing them to this
+    /// session.
+    async fn cache_git_revision(
+        &self,
+        package_name: &str,
+        url: &str,
+        rev: &str,
+    ) -> Result<LoadTimings> {
+        let crate_name = package_name.replace('-', "_");
+        self.config.crates.check(&crate_name)?;
+        if self.workspace.fixture {
+            anyhow::bail!("Fixture docs are prebuilt and cannot be generated from git");
+        }
+        let mut timings = LoadTimings::default();
+        let target_dir = self.workspace.metadata.target_directory.as_std_path();
+        let dir = git_checkout::checkout_dir(target_dir, url, rev);
+
+        let start = Instant::now();
+        git_checkout::checkout(url, rev, &dir).await?;
+        let cwd = dir
+            .to_str()
+            .context("The checkout directory is not valid UTF-8")?;
+        let json_path = DocGenerator::generate(
+            package_name,
+            None,
+            self.config.docs.toolchain_for(package_name),
+            cwd,
+            &dir.join("target"),
+            None,
+            None,
+        )
+        .await
+        .map_err(|e| self.with_build_hint(e))?;
+        timings.generate = start.elapsed();
+
+        let (mut krate, path_to_id) =
+            Self::parse_json(&crate_name, &json_path, &mut timings).await?;
+        timings.paths = path_to_id.len();
+        self.config.redact.redact_crate(&mut krate, &path_to_id);
+
+        let start = Instant::now();
+        let mut loaded = LoadedCrate::new(krate, path_to_id);
+        loaded.git_revision = Some((url.to_string(), rev.to_string()));
+        timings.derived_indexes = start.elapsed();
+
+        let variant = DocsVariant::Git {
+            url: url.to_string(),
+            rev: rev.to_string(),
+        };
+        self.crates.insert(variant.key(&crate_name), loaded);
+        info!("Crate {} loaded from {} at {}", crate_name, url, rev);
+        Ok(timings)
+    }
+
+    /// Generates a package's docs with exactly `features` enabled and returns them with
+    /// their path map, without loading them. They are built in a separate target directory
+    /// so the docs the server reads are left alone, and regenerated on every call.
+    pub async fn build_with_features(
+        &self,
+        package_name: &str,
+        features: &[String],
+    ) -> Result<(Crate, HashMap<String, Id>)> {
+        let crate_name = package_name.replace('-', "_");
+        let stale = self
+            .features_target_dir(features)
+            .join("doc")
+            .join(format!("{crate_name}.json"));
+        if stale.exists() {
+            fs::remove_file(&stale)
+                .await
+                .context("Failed to remove earlier rustdoc JSON")?;
+        }
+        let json_path = self
+            .generate_with_features(package_name, features, None)
+            .await?;
+
+        let content = fs::read_to_string(&json_path)
+            .await
+            .context("Failed to read rustdoc JSON")?;
+        let krate: Crate =
+            serde_json::from_str(&content).context("Failed to parse rustdoc JSON")?;
+        let path_to_id = Self::build_path_map(&krate, &crate_name);
+        Ok((krate, path_to_id))
+    }
+
+    fn build_path_map(krate: &Crate, crate_name: &str) -> HashMap<String, Id> {
+        debug!("Building path map for crate: {}", crate_name);
+        let mut map = HashMap::new();
+
+        // Traverse `index` starting from root.
+        let root_id = &krate.root;
+        if let Some(root_item) = krate.index.get(root_id) {
+            Self::traverse_item(krate, root_item, crate_name, &mut map);
+        }
+
+        info!("Indexed {} paths for crate {}", map.len(), crate_name);
+
+        map
+    }
+
+    fn traverse_item(
+        krate: &Crate,
+        item: &Item,
+        current_path: &str,
+        map: &mut HashMap<String, Id>,
+    ) {
+        map.insert(current_path.to_string(), item.id);
+
+        match &item.inner {
+            ItemEnum::Module(m) => {
+                let mut primitives = Vec::new();
+                for item_id in &m.items {
+                    if let Some(child) = krate.index.get(item_id)
+                        && let Some(name) = &child.name
+                    {
+                        let child_path = format!("{current_path}::{name}");
+                        match &child.inner {
+                            ItemEnum::Primitive(primitive) => {
+                                primitives.push((child_path, child.id, primitive));
+                            }
+                            _ => Self::traverse_item(krate, child, &child_path, map),
+                        }
+                    }
+                }
+                // Primitives share their paths with modules, like `std::str`: the module
+                // keeps the path, while the items of the primitive's impls go below it,
+                // inherent ones over trait ones, so `std::u64::MAX` is the constant of
+                // `u64`.
+                for (primitive_path, id, primitive) in primitives {
+                    map.entry(primitive_path.clone()).or_insert(id);
+                    let mut impls: Vec<&rustdoc_types::Impl> = primitive
+                        .impls
+                        .iter()
+                        .filter_map(|id| match &krate.index.get(id)?.inner {
+                            ItemEnum::Impl(i) => Some(i),
+                            _ => None,
+                        })
+                        .collect();
+                    impls.sort_by_key(|i| i.trait_.is_none());
+                    for item in impls
+                        .iter()
+                        .flat_map(|i| &i.items)
+                        .filter_map(|id| krate.index.get(id))
+                    {
+                        if let Some(name) = &item.name {
+                            map.insert(format!("{primitive_path}::{name}"), item.id);
+                        }
+                    }
+                }
+            }
+            ItemEnum::Struct(s) => {
+                let mut add_field = |field_id: &Id| {
+                    if let Some(field) = krate.index.get(field_id)
+                        && let Some(name) = &field.name
+                    {
+                        let field_path = format!("{current_path}::{name}");
+                        map.insert(field_path, field.id);
+                    }
+                };
+
+                match &s.kind {
+                    rustdoc_types::StructKind::Unit => {}
+                    rustdoc_types::StructKind::Tuple(ids) => {
+                        for field_id in ids.iter().flatten() {
+                            add_field(field_id);
+                        }
+                    }
+                    rustdoc_types::StructKind::Plain { fields, .. } => {
+                        for field_id in fields {
+                            add_field(field_id);
+                        }
+                    }
+                }
+                for impl_id in &s.impls {
+                    if let Some(impl_item) = krate.index.get(impl_id)
+                        && let ItemEnum::Impl(i) = &impl_item.inner
+                    {
+                        for item_id in &i.items {
+                            if let Some(item) = krate.index.get(item_id)
+                                && let Some(name) = &item.name
+                            {
+                                let item_path = format!("{current_path}::{name}");
+                                map.insert(item_path, item.id);
+                            }
+                        }
+                    }
+                }
+            }
+            ItemEnum::Enum(e) => {
+                for variant_id in &e.variants {
+                    if let Some(variant) = krate.index.get(variant_id)
+                        && let Some(name) = &variant.name
+                    {
+                        let variant_path = format!("{current_path}::{name}");
+                        // Fields of struct variants live below the variant, like
+                        // `Event::Key::code`.
+                        if let ItemEnum::Variant(v) = &variant.inner
+                            && let rustdoc_types::VariantKind::Struct { fields, .. } = &v.kind
+                        {
+                            for field in fields.iter().filter_map(|id| krate.index.get(id)) {
+                                if let Some(field_name) = &field.name {
+                                    map.insert(format!("{variant_path}::{field_name}"), field.id);
+                                }
+                            }
+                        }
+                        map.insert(variant_path, variant.id);
+                    }
+                }
+                for impl_id in &e.impls {
+                    if let Some(impl_item) = krate.index.get(impl_id)
+                        && let ItemEnum::Impl(i) = &impl_item.inner
+                    {
+                        for item_id in &i.items {
+                            if let Some(item) = krate.index.get(item_id)
+                                && let Some(name) = &item.name
+                            {
+                                let item_path = format!("{current_path}::{name}");
+                                map.insert(item_path, item.id);
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Returns the docs of a loaded crate as this session sees them, or `None` when they
+    /// are not in memory; call [`ensure_loaded`](Self::ensure_loaded) first. The entry
+    /// stays locked while the returned guard is alive, so drop it before loading or
+    /// unloading crates.
+    pub fn get_crate(&self, crate_name: &str) -> Option<CrateRef<'_>> {
+        self.crates.get(&self.key(crate_name))
+    }
+
+    /// Returns the names of the crates this session sees that are held in memory.
+    pub fn loaded_crate_names(&self) -> Vec<String> {
+        self.crates
+            .iter()
+            .filter(|entry| self.is_visible(entry.key()))
+            .map(|entry| key_crate(entry.key()).to_string())
+            .collect()
+    }
+
+    /// Returns the crates held in memory with docs generated for a chosen feature set, by
+    /// any session, with those features.
+    pub fn feature_variants(&self) -> Vec<(String, Vec<String>)> {
+        self.crates
+            .iter()
+            .filter_map(|entry| {
+                let features = entry.value().features.clone()?;
+                Some((key_crate(entry.key()).to_string(), features))
+            })
+            .collect()
+    }
+
+    /// Fuzzy-searches item paths, returning every match allowed by `options`, best first.
+    pub async fn search(&self, query: &str, options: &SearchOptions) -> Result<Vec<ItemSummary>> {
+        Ok(self
+            .search_explained(query, options)
+            .await?
+            .into_iter()
+            .map(|(summary, _)| summary)
+            .collect())
+    }
+
+    /// Like [`CrateIndex::search`], but also reports for every result which matcher
+    /// fired and its component scores.
+    pub async fn search_explained(
+        &self,
+        query: &str,
+        options: &SearchOptions,
+    ) -> Result<Vec<(ItemSummary, SearchExplanation)>> {
+        let SearchOptions {
+            crate_name,
+            const_only,
+            kinds,
+            unstable,
+            full_text,
+            exclude_deprecated,
+        } = options;
+        let (crate_name, kinds) = (crate_name.as_deref(), kinds.as_deref());
+        if let Some(unknown) = kinds
+            .into_iter()
+            .flatten()
+            .find(|kind| !ITEM_KINDS.contains(&kind.as_str()))
+        {
+            anyhow::bail!(
+                "Unknown item kind `{unknown}`, expected one of: {}",
+                ITEM_KINDS.join(", ")
+            );
+        }
+        let unstable = unstable.unwrap_or(if self.workspace.nightly {
+            UnstableItems::Include
+        } else {
+            self.config.search.unstable
+        });
+        debug!(
+            "Searching index for '{}' (crate scope: {:?}, const only: {}, kinds: {:?}, unstable: {:?}, full text: {}, exclude deprecated: {})",
+            query, crate_name, const_only, kinds, unstable, full_text, exclude_deprecated
+        );
+        if let Some(name) = crate_name {
+            self.ensure_loaded(name).await?;
+        }
+
+        let terms: Vec<String> = query.split_whitespace().map(str::to_lowercase).collect();
+        let mut matches = Vec::new();
+
+        for entry in self.crates.iter() {
+            if !self.is_visible(entry.key()) {
+                continue;
+            }
+            let krate_name = key_crate(entry.key()).to_string();
+            if let Some(target) = crate_name
+                && krate_name != target
+            {
+                continue;
+            }
+
+            let loaded_crate = entry.value();
+            let filtered_out = |item: Option<&Item>| {
+                kinds.is_some_and(|kinds| {
+                    !item.is_some_and(|item| kinds.contains(&get_item_kind(item)))
+                }) || (*const_only && !item.is_some_and(is_const_usable))
+                    || (*exclude_deprecated && item.is_some_and(|item| item.deprecation.is_some()))
+            };
+            let mut push = |path: &str, id: &Id, mut explanation: SearchExplanation| {
+                let item = loaded_crate.krate.index.get(id);
+                explanation.unstable = item.and_then(unstable_feature);
+                if explanation.unstable.is_some() && unstable == UnstableItems::Exclude {
+                    return;
+                }
+                let summary = ItemSummary::from_item(path, &krate_name, loaded_crate, *id);
+                explanation.path = path.to_string();
+                explanation.kind = summary.kind.clone();
+                matches.push((summary, explanation));
+            };
+
+            #[cfg(feature = "tantivy")]
+            if let Some(text_index) = &loaded_crate.text_index {
+                for (path, id, score) in text_index.search(query, TEXT_SEARCH_LIMIT)? {
+                    if filtered_out(loaded_crate.krate.index.get(&id)) {
+                        continue;
+                    }
+                    let explanation = SearchExplanation {
+                        path: String::new(),
+                        kind: String::new(),
+                        matched: path.clone(),
+                        matcher: "bm25".to_string(),
+                        score: f64::from(score),
+                        jaro_winkler: None,
+                        unstable: None,
+                    };
+                    push(&path, &id, explanation);
+                }
+                continue;
+            }
+
+            let mut path_matched = HashSet::new();
+            for (path, id) in &loaded_crate.path_to_id {
+                if filtered_out(loaded_crate.krate.index.get(id)) {
+                    continue;
+                }
+                // Aliases also match through the path of the type they expand to.
+                let best = std::iter::once(path)
+                    .chain(loaded_crate.alias_targets.get(id).into_iter().flatten())
+                    .filter_map(|candidate| self.match_path(query, candidate))
+                    .reduce(|best, m| if m.score > best.score { m } else { best });
+                if let Some(explanation) = best {
+                    path_matched.insert(*id);
+                    push(path, id, explanation);
+                }
+            }
+
+            if *full_text && !terms.is_empty() {
+                for (path, item, hits) in loaded_crate.doc_text_matches(&terms) {
+                    if path_matched.contains(&item.id) || filtered_out(Some(item)) {
+                        continue;
+                    }
+                    // Below every path match, growing with the number of occurrences.
+                    let score = 0.5 * hits as f64 / (hits as f64 + 1.0);
+                    let explanation = SearchExplanation {
+                        path: String::new(),
+                        kind: String::new(),
+                        matched: LoadedCrate::doc_excerpt(item, &terms).to_string(),
+                        matcher: "docs".to_string(),
+                        score,
+                        jaro_winkler: None,
+                        unstable: None,
+                    };
+                    push(path, &item.id, explanation);
+                }
+            }
+        }
+
+        debug!("Found {} potential matches before sorting", matches.len());
+
+        let demoted = |explanation: &SearchExplanation| {
+            unstable == UnstableItems::Demote && explanation.unstable.is_some()
+        };
+        matches.sort_by(|a, b| {
+            demoted(&a.1).cmp(&demoted(&b.1)).then_with(|| {
+                b.1.score
+                    .partial_cmp(&a.1.score)
+                    .unwrap_or(std::cmp::Ordering::Equal)
+                    // Ties go to the shorter path, the one to write, then by path so that
+                    // pages do not overlap.
+                    .then_with(|| a.1.path.len().cmp(&b.1.path.len()))
+                    .then_with(|| a.1.path.cmp(&b.1.path))
+            })
+        });
+
+        Ok(matches)
+    }
+
+    /// Finds the functions taking each of `inputs` (in any order, among other arguments)
+    /// and, with `output`, returning it. Types are matched after [`normalize_type`], and
+    /// `_` stands for any type. Functions with the fewest other arguments come first,
+    /// then by path.
+    ///
+    /// [`normalize_type`]: crate::signatures::normalize_type
+    pub async fn search_by_signature(
+        &self,
+        inputs: &[String],
+        output: Option<&str>,
+        crate_name: Option<&str>,
+    ) -> Result<Vec<ItemSummary>> {
+        if inputs.is_empty() && output.is_none() {
+            anyhow::bail!("Give the argument types, the return type or both");
+        }
+        if let Some(name) = crate_name {
+            self.ensure_loaded(name).await?;
+        }
+
+        let mut matches = Vec::new();
+        for entry in self.crates.iter() {
+            if !self.is_visible(entry.key()) {
+                continue;
+            }
+            let krate_name = key_crate(entry.key()).to_string();
+            if crate_name.is_some_and(|target| krate_name != target) {
+                continue;
+            }
+            let loaded_crate = entry.value();
+            for (&id, path) in &loaded_crate.shortest_paths {
+                let Some(extra) = loaded_crate
+                    .signatures
+                    .get(&id)
+                    .and_then(|signature| match_signature(signature, inputs, output))
+                else {
+                    continue;
+                };
+                let summary = ItemSummary::from_item(path, &krate_name, loaded_crate, id);
+                matches.push((extra, summary));
+            }
+        }
+        matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.name.cmp(&b.1.name)));
+        Ok(matches.into_iter().map(|(_, summary)| summary).collect())
+    }
+}
+
+/// Whether an item can be used in const contexts: a `const fn` or a constant.
+pub fn is_const_usable(item: &Item) -> bool {
+    match &item.inner {
+        ItemEnum::Function(f) => f.header.is_const,
+        ItemEnum::Constant { .. } | ItemEnum::AssocConst { .. } => true,
+        _ => false,
+    }
+}
+
+/// Every kind [`get_item_kind`] reports.
+pub const ITEM_KINDS: &[&str] = &[
+    "module",
+    "extern_crate",
+    "import",
+    "union",
+    "struct",
+    "struct_field",
+    "enum",
+    "variant",
+    "function",
+    "type_alias",
+    "trait",
+    "trait_alias",
+    "impl",
+    "static",
+    "macro",
+    "proc_macro",
+    "primitive",
+    "assoc_const",
+    "assoc_type",
+    "other",
+];
+
+pub fn get_item_kind(item: &rustdoc_types::Item) -> String {
+    use rustdoc_types::ItemEnum::{
+        AssocConst, AssocType, Enum, ExternCrate, Function, Impl, Macro, Module, Primitive,
+        ProcMacro, Static, Struct, StructField, Trait, TraitAlias, TypeAlias, Union, Use, Variant,
+    };
+    match &item.inner {
+        Module(_) => "module",
+        ExternCrate { .. } => "extern_crate",
+        Use(_) => "import",
+        Union(_) => "union",
+        Struct(_) => "struct",
+        StructField(_) => "struct_field",
+        Enum(_) => "enum",
+        Variant(_) => "variant",
+        Function(_) => "function",
+        TypeAlias(_) => "type_alias",
+        Trait(_) => "trait",
+        TraitAlias(_) => "trait_alias",
+        Impl(_) => "impl",
+        Static(_) => "static",
+        Macro(_) => "macro",
+        ProcMacro(_) => "proc_macro",
+        Primitive(_) => "primitive",
+        AssocConst { .. } => "assoc_const",
+        AssocType { .. } => "assoc_type",
+        _ => "other",
+    }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_types::{Crate, Generics, Id, Item, ItemEnum, Span, Visibility};
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    fn create_dummy_metadata() -> cargo_metadata::Metadata {
+        serde_json::from_str(
+            r#"{
+            "packages": [],
+            "workspace_members": [],
+            "workspace_default_members": [],
+            "resolve": null,
+            "target_directory": "/tmp",
+            "version": 1,
+            "workspace_root": "/tmp"
+        }"#,
+        )
+        .unwrap()
+    }
+
+    fn create_dummy_workspace() -> Workspace {
+        Workspace {
+            root: PathBuf::from("/tmp"),
+            metadata: create_dummy_metadata(),
+            packages: HashMap::new(),
+            fixture: false,
+            registries: HashMap::new(),
+            vendor_dirs: Vec::new(),
+            nightly: false,
+        }
+    }
+
+    fn create_dummy_item(name: &str, inner: ItemEnum) -> Item {
+        let id_val = name.len() as u32;
+        Item {
+            id: Id(id_val),
+            crate_id: 0,
+            name: Some(name.to_string()),
+            span: Some(Span {
+                filename: Default::default(),
+                begin: (0, 0),
+                end: (0, 0),
+            }),
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner,
+        }
+    }
+
+    /// A crate holding just a module at `path`.
+    fn module_crate(path: &str) -> LoadedCrate {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let module = create_dummy_item(
+            path.rsplit("::").next().unwrap(),
+            ItemEnum::Module(rustdoc_types::Module {
+                is_crate: false,
+                items: vec![],
+                is_stripped: false,
+            }),
+        );
+        let id = module.id;
+        krate.index.insert(id, module);
+        LoadedCrate::new(krate, HashMap::from([(path.to_string(), id)]))
+    }
+
+    #[test]
+    fn test_get_item_kind() {
+        let item = create_dummy_item(
+            "test",
+            ItemEnum::Struct(rustdoc_types::Struct {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                kind: rustdoc_types::StructKind::Unit,
+                impls: vec![],
+            }),
+        );
+        assert_eq!(get_item_kind(&item), "struct");
+
+        let item = create_dummy_item(
+            "test",
+            ItemEnum::Function(rustdoc_types::Function {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                header: rustdoc_types::FunctionHeader {
+                    is_const: false,
+                    is_unsafe: false,
+                    is_async: false,
+                    abi: rustdoc_types::Abi::Rust,
+                },
+                has_body: true,
+                sig: rustdoc_types::FunctionSignature {
+                    inputs: vec![],
+                    output: None,
+                    is_c_variadic: false,
+                },
+            }),
+        );
+        assert_eq!(get_item_kind(&item), "function");
+    }
+
+    #[test]
+    fn test_is_const_usable() {
+        let mut item = create_dummy_item(
+            "test",
+            ItemEnum::Function(rustdoc_types::Function {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                header: rustdoc_types::FunctionHeader {
+                    is_const: false,
+                    is_unsafe: false,
+                    is_async: false,
+                    abi: rustdoc_types::Abi::Rust,
+                },
+                has_body: true,
+                sig: rustdoc_types::FunctionSignature {
+                    inputs: vec![],
+                    output: None,
+                    is_c_variadic: false,
+                },
+            }),
+        );
+        assert!(!is_const_usable(&item));
+
+        if let ItemEnum::Function(f) = &mut item.inner {
+            f.header.is_const = true;
+        }
+        assert!(is_const_usable(&item));
+
+        let constant = create_dummy_item(
+            "MAX",
+            ItemEnum::AssocConst {
+                type_: Type::Primitive("usize".to_string()),
+                value: None,
+            },
+        );
+        assert!(is_const_usable(&constant));
+    }
+
+    #[test]
+    fn test_guides() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+
+        let mut long_module = create_dummy_item(
+            "runtime",
+            ItemEnum::Module(rustdoc_types::Module {
+                is_crate: false,
+                items: vec![],
+                is_stripped: false,
+            }),
+        );
+        long_module.docs = Some(format!(
+            "Intro line\n\n# The Tokio runtime\n{}",
+            "x".repeat(100)
+        ));
+        krate.index.insert(long_module.id, long_module);
+
+        let mut short_module = create_dummy_item(
+            "io",
+            ItemEnum::Module(rustdoc_types::Module {
+                is_crate: false,
+                items: vec![],
+                is_stripped: false,
+            }),
+        );
+        short_module.docs = Some("Short".to_string());
+        krate.index.insert(short_module.id, short_module);
+
+        let mut path_to_id = HashMap::new();
+        path_to_id.insert("tokio::runtime".to_string(), Id(7));
+        path_to_id.insert("tokio::io".to_string(), Id(2));
+        let loaded = LoadedCrate::new(krate, path_to_id);
+
+        let guides = loaded.guides(50);
+        assert_eq!(guides.len(), 1);
+        assert_eq!(guides[0].0, "tokio::runtime");
+        assert_eq!(
+            guide_title(guides[0].1.docs.as_deref().unwrap()),
+            "The Tokio runtime"
+        );
+        assert_eq!(guide_title("Just prose\nmore"), "Just prose");
+        // Hidden doctest lines are not headings.
+        assert_eq!(
+            guide_title("Runs tasks.\n\n```\n# use tokio::runtime;\n```\n\n# Usage\n"),
+            "Usage"
+        );
+    }
+
+    #[test]
+    fn test_doc_text_matches() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let module = |name: &str, docs: &str| {
+            let mut item = create_dummy_item(
+                name,
+                ItemEnum::Module(rustdoc_types::Module {
+                    is_crate: false,
+                    items: vec![],
+                    is_stripped: false,
+                }),
+            );
+            item.docs = Some(docs.to_string());
+            item
+        };
+        for item in [
+            module(
+                "retry",
+                "Retries requests with backoff. The backoff doubles.",
+            ),
+            module("config", "Configures the client, including retry backoff."),
+            module("io", "Reads bytes."),
+        ] {
+            krate.index.insert(item.id, item);
+        }
+        let path_to_id = HashMap::from([
+            ("app::net::retry".to_string(), Id(5)),
+            ("app::retry".to_string(), Id(5)),
+            ("app::config".to_string(), Id(6)),
+            ("app::io".to_string(), Id(2)),
+        ]);
+        let loaded = LoadedCrate::new(krate, path_to_id);
+
+        let terms = vec!["retr".to_string(), "backoff".to_string()];
+        let found: Vec<(&str, usize)> = loaded
+            .doc_text_matches(&terms)
+            .into_iter()
+            .map(|(path, _, hits)| (path, hits))
+            .collect();
+        assert_eq!(found, vec![("app::retry", 3), ("app::config", 2)]);
+    }
+
+    #[test]
+    fn test_prelude_entries() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let module = |items| {
+            ItemEnum::Module(rustdoc_types::Module {
+                is_crate: false,
+                items,
+                is_stripped: false,
+            })
+        };
+
+        let mut ext = create_dummy_item(
+            "Ext",
+            ItemEnum::Trait(rustdoc_types::Trait {
+                is_auto: false,
+                is_unsafe: false,
+                is_dyn_compatible: true,
+                items: vec![],
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                bounds: vec![],
+                implementations: vec![],
+            }),
+        );
+        ext.id = Id(10);
+        let mut io = create_dummy_item("io", module(vec![Id(10)]));
+        io.id = Id(11);
+
+        let mut glob = create_dummy_item(
+            "glob",
+            ItemEnum::Use(rustdoc_types::Use {
+                source: "crate::io".to_string(),
+                name: "io".to_string(),
+                id: Some(Id(11)),
+                is_glob: true,
+            }),
+        );
+        glob.id = Id(12);
+        glob.name = None;
+        let mut prelude = create_dummy_item("prelude", module(vec![Id(12)]));
+        prelude.id = Id(13);
+
+        for item in [ext, io, glob, prelude] {
+            krate.index.insert(item.id, item);
+        }
+
+        let mut path_to_id = HashMap::new();
+        path_to_id.insert("demo::io".to_string(), Id(11));
+        path_to_id.insert("demo::io::Ext".to_string(), Id(10));
+        path_to_id.insert("demo::prelude".to_string(), Id(13));
+        let loaded = LoadedCrate::new(krate, path_to_id);
+
+        assert_eq!(
+            loaded.prelude_entries(),
+            vec![("demo::prelude".to_string(), "Ext".to_string(), Id(10))]
+        );
+        assert_eq!(
+            loaded.prelude_map().get(&Id(10)).map(String::as_str),
+            Some("demo::prelude")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_alias_targets() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        krate.paths.insert(
+            Id(50),
+            rustdoc_types::ItemSummary {
+                crate_id: 1,
+                path: vec![
+                    "core".to_string(),
+                    "result".to_string(),
+                    "Result".to_string(),
+                ],
+                kind: rustdoc_types::ItemKind::Enum,
+            },
+        );
+        let alias = create_dummy_item(
+            "Result",
+            ItemEnum::TypeAlias(rustdoc_types::TypeAlias {
+                type_: Type::ResolvedPath(rustdoc_types::Path {
+                    path: "Result".to_string(),
+                    id: Id(50),
+                    args: None,
+                }),
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+            }),
+        );
+        krate.index.insert(alias.id, alias);
+
+        let mut path_to_id = HashMap::new();
+        path_to_id.insert("anyhow::Result".to_string(), Id(6));
+        let loaded = LoadedCrate::new(krate, path_to_id);
+        assert_eq!(
+            loaded.alias_targets.get(&Id(6)),
+            Some(&vec!["core::result::Result".to_string()])
+        );
+
+        let index = CrateIndex::new(create_dummy_workspace(), Config::default());
+        index.crates.insert("anyhow".to_string(), loaded);
+        let results = index
+            .search("core::result", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert!(results.iter().any(|r| r.name == "anyhow::Result"));
+    }
+
+    #[test]
+    fn test_deref_target() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let generics = || Generics {
+            params: vec![],
+            where_predicates: vec![],
+        };
+
+        let mut wrapper = create_dummy_item(
+            "Wrapper",
+            ItemEnum::Struct(rustdoc_types::Struct {
+                kind: rustdoc_types::StructKind::Unit,
+                generics: generics(),
+                impls: vec![Id(21)],
+            }),
+        );
+        wrapper.id = Id(20);
+        let mut deref_impl = create_dummy_item(
+            "",
+            ItemEnum::Impl(rustdoc_types::Impl {
+                is_unsafe: false,
+                generics: generics(),
+                provided_trait_methods: vec![],
+                trait_: Some(rustdoc_types::Path {
+                    path: "core::ops::Deref".to_string(),
+                    id: Id(99),
+                    args: None,
+                }),
+                for_: Type::Primitive("unit".to_string()),
+                items: vec![Id(22)],
+                is_negative: false,
+                is_synthetic: false,
+                blanket_impl: None,
+            }),
+        );
+        deref_impl.id = Id(21);
+        deref_impl.name = None;
+        let mut target = create_dummy_item(
+            "Target",
+            ItemEnum::AssocType {
+                generics: generics(),
+                bounds: vec![],
+                type_: Some(Type::Primitive("str".to_string())),
+            },
+        );
+        target.id = Id(22);
+
+        for item in [wrapper, deref_impl, target] {
+            krate.index.insert(item.id, item);
+        }
+        let mut path_to_id = HashMap::new();
+        path_to_id.insert("demo::Wrapper".to_string(), Id(20));
+        let loaded = LoadedCrate::new(krate, path_to_id);
+
+        assert_eq!(loaded.deref_target("demo::Wrapper").as_deref(), Some("str"));
+        assert_eq!(loaded.deref_target("demo::Missing"), None);
+    }
+
+    #[test]
+    fn test_primitive_paths() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let with_id = |mut item: Item, id: u32| {
+            item.id = Id(id);
+            item
+        };
+        let module = |name: &str, items: Vec<Id>| {
+            create_dummy_item(
+                name,
+                ItemEnum::Module(rustdoc_types::Module {
+                    is_crate: false,
+                    items,
+                    is_stripped: false,
+                }),
+            )
+        };
+        let impl_ = |trait_: Option<&str>, items: Vec<Id>| {
+            create_dummy_item(
+                "",
+                ItemEnum::Impl(rustdoc_types::Impl {
+                    is_unsafe: false,
+                    generics: Generics {
+                        params: vec![],
+                        where_predicates: vec![],
+                    },
+                    provided_trait_methods: vec![],
+                    trait_: trait_.map(|path| rustdoc_types::Path {
+                        path: path.to_string(),
+                        id: Id(99),
+                        args: None,
+                    }),
+                    for_: Type::Primitive("str".to_string()),
+                    items,
+                    is_negative: false,
+                    is_synthetic: false,
+                    blanket_impl: None,
+                }),
+            )
+        };
+        // The inherent impl comes last, yet its `len` wins over the trait's.
+        let items = [
+            with_id(module("std", vec![Id(1), Id(2)]), 0),
+            with_id(module("str", vec![Id(3)]), 1),
+            with_id(
+                create_dummy_item(
+                    "str",
+                    ItemEnum::Primitive(rustdoc_types::Primitive {
+                        name: "str".to_string(),
+                        impls: vec![Id(5), Id(4)],
+                    }),
+                ),
+                2,
+            ),
+            with_id(create_dummy_item("from_utf8", ItemEnum::ExternType), 3),
+            with_id(impl_(None, vec![Id(6)]), 4),
+            with_id(impl_(Some("Len"), vec![Id(7), Id(8)]), 5),
+            with_id(create_dummy_item("len", ItemEnum::ExternType), 6),
+            with_id(create_dummy_item("len", ItemEnum::ExternType), 7),
+            with_id(create_dummy_item("to_string", ItemEnum::ExternType), 8),
+        ];
+        for item in items {
+            krate.index.insert(item.id, item);
+        }
+
+        let map = CrateIndex::build_path_map(&krate, "std");
+        assert_eq!(map.get("std::str"), Some(&Id(1)));
+        assert_eq!(map.get("std::str::from_utf8"), Some(&Id(3)));
+        assert_eq!(map.get("std::str::len"), Some(&Id(6)));
+        assert_eq!(map.get("std::str::to_string"), Some(&Id(8)));
+    }
+
+    /// A crate with a struct `demo::Name` (id 1) whose impls are given as (impl id, trait
+    /// path or `None` for inherent, id of its only method, method name).
+    fn name_crate(impls: &[(u32, Option<&str>, u32, &str)]) -> LoadedCrate {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let generics = || Generics {
+            params: vec![],
+            where_predicates: vec![],
+        };
+        let impl_item = |id: u32, trait_: Option<&str>, method: u32| {
+            let mut item = create_dummy_item(
+                "",
+                ItemEnum::Impl(rustdoc_types::Impl {
+                    is_unsafe: false,
+                    generics: generics(),
+                    provided_trait_methods: vec![],
+                    trait_: trait_.map(|path| rustdoc_types::Path {
+                        path: path.to_string(),
+                        id: Id(90 + id),
+                        args: None,
+                    }),
+                    for_: Type::Primitive("unit".to_string()),
+                    items: vec![Id(method)],
+                    is_negative: false,
+                    is_synthetic: false,
+                    blanket_impl: None,
+                }),
+            );
+            item.id = Id(id);
+            item.name = None;
+            item
+        };
+        let method = |id: u32, name: &str| {
+            let mut item = create_dummy_item(
+                name,
+                ItemEnum::AssocConst {
+                    type_: Type::Primitive("usize".to_string()),
+                    value: None,
+                },
+            );
+            item.id = Id(id);
+            item
+        };
+
+        let mut strukt = create_dummy_item(
+            "Name",
+            ItemEnum::Struct(rustdoc_types::Struct {
+                kind: rustdoc_types::StructKind::Unit,
+                generics: generics(),
+                impls: impls.iter().map(|(id, ..)| Id(*id)).collect(),
+            }),
+        );
+        strukt.id = Id(1);
+        krate.index.insert(strukt.id, strukt);
+        let mut path_to_id = HashMap::from([("demo::Name".to_string(), Id(1))]);
+        for (impl_id, trait_, method_id, name) in impls {
+            for item in [
+                impl_item(*impl_id, *trait_, *method_id),
+                method(*method_id, name),
+            ] {
+                krate.index.insert(item.id, item);
+            }
+            // Like `build_path_map`, the last impl providing a name takes the plain path.
+            path_to_id.insert(format!("demo::Name::{name}"), Id(*method_id));
+        }
+        LoadedCrate::new(krate, path_to_id)
+    }
+
+    #[test]
+    fn test_ambiguous_impl_methods() {
+        let loaded = name_crate(&[
+            (2, Some("alloc::string::ToString"), 5, "to_string"),
+            (3, None, 6, "to_string"),
+            (4, Some("core::fmt::Display"), 7, "fmt"),
+        ]);
+
+        assert_eq!(
+            loaded.ambiguous_paths["demo::Name::to_string"],
+            vec![
+                ("demo::Name::to_string".to_string(), Id(6)),
+                (
+                    "<demo::Name as alloc::string::ToString>::to_string".to_string(),
+                    Id(5)
+                ),
+            ]
+        );
+        assert!(!loaded.ambiguous_paths.contains_key("demo::Name::fmt"));
+        assert_eq!(loaded.path_to_id["demo::Name::to_string"], Id(6));
+        assert_eq!(
+            loaded.resolve_qualified("<demo::Name as ToString>::to_string"),
+            Some(Id(5))
+        );
+        assert_eq!(
+            loaded.resolve_qualified("<demo::Name as fmt::Display>::fmt"),
+            Some(Id(7))
+        );
+        assert_eq!(loaded.resolve_qualified("<demo::Name as Debug>::fmt"), None);
+        assert_eq!(loaded.path_of(Id(1)).as_deref(), Some("demo::Name"));
+        assert_eq!(loaded.path_of(Id(42)), None);
+    }
+
+    #[test]
+    fn test_shortest_paths() {
+        let path_to_id = HashMap::from([
+            ("demo::inner::Name".to_string(), Id(1)),
+            ("demo::Name".to_string(), Id(1)),
+            ("demo::b::Other".to_string(), Id(2)),
+            ("demo::a::Other".to_string(), Id(2)),
+        ]);
+        let shortest = shortest_paths(&path_to_id);
+        assert_eq!(shortest.len(), 2);
+        assert_eq!(shortest[&Id(1)], "demo::Name");
+        // Ties go to the first path in order.
+        assert_eq!(shortest[&Id(2)], "demo::a::Other");
+    }
+
+    #[test]
+    fn test_disambiguation_paths_resolve() {
+        // Without an inherent `to_string`, the plain path lists the trait ones, each of
+        // which names its item.
+        let loaded = name_crate(&[
+            (2, Some("alloc::string::ToString"), 5, "to_string"),
+            (3, Some("demo::Describe"), 6, "to_string"),
+        ]);
+        let candidates = loaded.disambiguation("demo::Name::to_string").unwrap();
+        assert_eq!(candidates.len(), 2);
+        for (path, id) in candidates {
+            assert!(loaded.disambiguation(path).is_none());
+            assert_eq!(loaded.resolve_qualified(path), Some(*id));
+        }
+
+        // With one, the plain path names it and the trait ones are offered next to it.
+        let loaded = name_crate(&[
+            (2, Some("alloc::string::ToString"), 5, "to_string"),
+            (3, None, 6, "to_string"),
+        ]);
+        assert!(loaded.disambiguation("demo::Name::to_string").is_none());
+        assert_eq!(loaded.path_to_id["demo::Name::to_string"], Id(6));
+        let alternatives = loaded.trait_alternatives("demo::Name::to_string");
+        assert_eq!(alternatives.len(), 1);
+        for (path, id) in alternatives {
+            assert!(loaded.disambiguation(path).is_none());
+            assert_eq!(loaded.resolve_qualified(path), Some(*id));
+        }
+    }
+
+    #[test]
+    fn test_struct_variant_fields_are_indexed() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let field = create_dummy_item(
+            "code",
+            ItemEnum::StructField(Type::Primitive("u32".to_string())),
+        );
+        let variant = create_dummy_item(
+            "Key",
+            ItemEnum::Variant(rustdoc_types::Variant {
+                kind: rustdoc_types::VariantKind::Struct {
+                    fields: vec![field.id],
+                    has_stripped_fields: false,
+                },
+                discriminant: None,
+            }),
+        );
+        let event = create_dummy_item(
+            "Event",
+            ItemEnum::Enum(rustdoc_types::Enum {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                variants: vec![variant.id],
+                impls: vec![],
+                has_stripped_variants: false,
+            }),
+        );
+        let root = Item {
+            id: Id(0),
+            ..create_dummy_item(
+                "demo",
+                ItemEnum::Module(rustdoc_types::Module {
+                    is_crate: true,
+                    items: vec![event.id],
+                    is_stripped: false,
+                }),
+            )
+        };
+        for item in [field, variant, event, root] {
+            krate.index.insert(item.id, item);
+        }
+
+        let map = CrateIndex::build_path_map(&krate, "demo");
+        assert_eq!(map.get("demo::Event::Key"), Some(&Id(3)));
+        assert_eq!(map.get("demo::Event::Key::code"), Some(&Id(4)));
+    }
+
+    /// An index with a `std` crate holding `std::vec::Vec` (id 3, with `vec_docs`) and
+    /// `std::string::String` (id 6), and an empty `other` crate.
+    fn std_index(vec_docs: Option<&str>) -> CrateIndex {
+        let index = CrateIndex::new(create_dummy_workspace(), Config::default());
+        let empty = || Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+
+        let mut krate = empty();
+        for name in ["Vec", "String"] {
+            let mut item = create_dummy_item(
+                name,
+                ItemEnum::Struct(rustdoc_types::Struct {
+                    generics: Generics {
+                        params: vec![],
+                        where_predicates: vec![],
+                    },
+                    kind: rustdoc_types::StructKind::Unit,
+                    impls: vec![],
+                }),
+            );
+            if name == "Vec" {
+                item.docs = vec_docs.map(str::to_string);
+            }
+            krate.index.insert(item.id, item);
+        }
+        // Since we used len() as ID, Vec -> 3, String -> 6
+        let path_to_id = HashMap::from([
+            ("std::vec::Vec".to_string(), Id(3)),
+            ("std::string::String".to_string(), Id(6)),
+        ]);
+        index
+            .crates
+            .insert("std".to_string(), LoadedCrate::new(krate, path_to_id));
+        index.crates.insert(
+            "other".to_string(),
+            LoadedCrate::new(empty(), HashMap::new()),
+        );
+        index
+    }
+
+    /// Loads a crate `name` holding a single struct `Vec` (id 3) at `name::Vec`, changed
+    /// by `edit`.
+    fn insert_vec_crate(index: &CrateIndex, name: &str, edit: impl FnOnce(&mut Item)) {
+        let mut item = create_dummy_item(
+            "Vec",
+            ItemEnum::Struct(rustdoc_types::Struct {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                kind: rustdoc_types::StructKind::Unit,
+                impls: vec![],
+            }),
+        );
+        edit(&mut item);
+        let krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::from([(item.id, item)]),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        index.crates.insert(
+            name.to_string(),
+            LoadedCrate::new(krate, HashMap::from([(format!("{name}::Vec"), Id(3))])),
+        );
+    }
+
+    #[tokio::test]
+    async fn test_search_docs() {
+        let index = std_index(None);
+        let options = SearchOptions::default();
+
+        // Test exact match
+        let results = index.search("Vec", &options).await.unwrap();
+        assert!(results.iter().any(|r| r.name == "std::vec::Vec"));
+
+        // Test fuzzy match
+        let results = index.search("std::string::Strng", &options).await.unwrap();
+        assert!(results.iter().any(|r| r.name == "std::string::String"));
+
+        // Test crate filtering
+        let in_crate = |name: &str| SearchOptions {
+            crate_name: Some(name.to_string()),
+            ..Default::default()
+        };
+        let results = index.search("Vec", &in_crate("std")).await.unwrap();
+        assert!(!results.is_empty());
+
+        let results = index.search("Vec", &in_crate("other")).await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_short_queries() {
+        // Short queries match by prefix only, not fuzzily
+        let index = std_index(None);
+        let results = index
+            .search("Str", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(results[0].name, "std::string::String");
+        let results = index.search("Vc", &SearchOptions::default()).await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_explained() {
+        let index = std_index(None);
+        let results = index
+            .search_explained("std::string::Strng", &SearchOptions::default())
+            .await
+            .unwrap();
+        let (_, explanation) = &results[0];
+        assert_eq!(explanation.path, "std::string::String");
+        assert_eq!(explanation.matcher, "fuzzy");
+        assert!(explanation.jaro_winkler.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_search_const_only() {
+        let index = std_index(None);
+        let options = SearchOptions {
+            const_only: true,
+            ..Default::default()
+        };
+        let results = index.search("Vec", &options).await.unwrap();
+        assert!(results.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_search_kinds() {
+        let index = std_index(None);
+        let of_kinds = |kinds: &[&str]| SearchOptions {
+            kinds: Some(kinds.iter().map(|k| k.to_string()).collect()),
+            ..Default::default()
+        };
+        let results = index
+            .search("Vec", &of_kinds(&["trait", "macro"]))
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+        let results = index.search("Vec", &of_kinds(&["struct"])).await.unwrap();
+        assert!(results.iter().any(|r| r.name == "std::vec::Vec"));
+        let err = index
+            .search("Vec", &of_kinds(&["class"]))
+            .await
+            .unwrap_err();
+        assert!(err.to_string().starts_with("Unknown item kind `class`"));
+    }
+
+    #[tokio::test]
+    async fn test_search_full_text() {
+        // Full-text search matches the words of the docs
+        let index = std_index(Some(
+            "A contiguous growable array type.\n\nGrows as needed.",
+        ));
+        let results = index
+            .search("growable array", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert!(results.is_empty());
+        let options = SearchOptions {
+            full_text: true,
+            ..Default::default()
+        };
+        let results = index
+            .search_explained("growable array", &options)
+            .await
+            .unwrap();
+        let (summary, explanation) = &results[0];
+        assert_eq!(summary.name, "std::vec::Vec");
+        assert_eq!(explanation.matcher, "docs");
+        assert_eq!(explanation.matched, "A contiguous growable array type.");
+    }
+
+    #[tokio::test]
+    async fn test_search_unstable() {
+        // Unstable items rank after stable ones, or are left out on request
+        let index = std_index(None);
+        insert_vec_crate(&index, "unstable", |item| {
+            item.attrs.push(rustdoc_types::Attribute::Other(
+                r#"#[unstable(feature = "vec_next", issue = "1")]"#.to_string(),
+            ));
+        });
+        let results = index
+            .search("Vec", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(results[0].name, "std::vec::Vec");
+        assert!(results.iter().any(|r| r.name == "unstable::Vec"));
+        let options = SearchOptions {
+            unstable: Some(UnstableItems::Exclude),
+            ..Default::default()
+        };
+        let results = index.search("Vec", &options).await.unwrap();
+        assert!(!results.iter().any(|r| r.name == "unstable::Vec"));
+    }
+
+    #[tokio::test]
+    async fn test_search_exclude_deprecated() {
+        let index = std_index(None);
+        insert_vec_crate(&index, "old", |item| {
+            item.deprecation = Some(rustdoc_types::Deprecation {
+                since: Some("1.0.0".to_string()),
+                note: None,
+            });
+        });
+        let results = index
+            .search("Vec", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert!(
+            results
+                .iter()
+                .any(|r| r.name == "old::Vec" && r.is_deprecated)
+        );
+        let options = SearchOptions {
+            exclude_deprecated: true,
+            ..Default::default()
+        };
+        let results = index.search("Vec", &options).await.unwrap();
+        assert!(!results.iter().any(|r| r.name == "old::Vec"));
+        assert!(results.iter().any(|r| r.name == "std::vec::Vec"));
+    }
+
+    #[tokio::test]
+    async fn test_search_prefers_shortest_path() {
+        let index = CrateIndex::new(create_dummy_workspace(), Config::default());
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let module = create_dummy_item(
+            "map",
+            ItemEnum::Module(rustdoc_types::Module {
+                is_crate: false,
+                items: vec![],
+                is_stripped: false,
+            }),
+        );
+        let id = module.id;
+        krate.index.insert(id, module);
+        // Defined in `demo::a` and re-exported at the crate root.
+        let path_to_id = HashMap::from([
+            ("demo::a::map".to_string(), id),
+            ("demo::map".to_string(), id),
+        ]);
+        index
+            .crates
+            .insert("demo".to_string(), LoadedCrate::new(krate, path_to_id));
+
+        let results = index
+            .search("map", &SearchOptions::default())
+            .await
+            .unwrap();
+        let names: Vec<&str> = results.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["demo::map", "demo::a::map"]);
+        assert!(
+            results
+                .iter()
+                .all(|r| r.shortest_path.as_deref() == Some("demo::map"))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_feature_variants_are_per_session() {
+        let index = CrateIndex::new(create_dummy_workspace(), Config::default());
+        let full = vec!["full".to_string()];
+        index
+            .crates
+            .insert("demo".to_string(), module_crate("demo::base"));
+        let mut variant = module_crate("demo::full");
+        variant.features = Some(full.clone());
+        index
+            .crates
+            .insert(DocsVariant::Features(full.clone()).key("demo"), variant);
+
+        let session = index.session();
+        session
+            .selected
+            .insert("demo".to_string(), DocsVariant::Features(full.clone()));
+        let path = |index: &CrateIndex| {
+            let krate = index.get_crate("demo").unwrap();
+            krate.path_to_id.keys().next().unwrap().clone()
+        };
+        assert_eq!(path(&session), "demo::full");
+        assert_eq!(path(&index), "demo::base");
+        assert_eq!(session.loaded_crate_names(), vec!["demo"]);
+        assert_eq!(index.loaded_crate_names(), vec!["demo"]);
+        assert_eq!(
+            index.feature_variants(),
+            vec![("demo".to_string(), full.clone())]
+        );
+        let results = session
+            .search("full", &SearchOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].crate_name, "demo");
+        assert!(
+            index
+                .search("full", &SearchOptions::default())
+                .await
+                .unwrap()
+                .is_empty()
+        );
+
+        // Unloading goes back to the features the workspace resolves.
+        assert!(session.unload("demo"));
+        assert_eq!(path(&session), "demo::base");
+    }
+
+    #[test]
+    fn test_features_target_dir() {
+        let index = CrateIndex::new(create_dummy_workspace(), Config::default());
+        let features = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let root = index
+            .features_target_dir(&[])
+            .parent()
+            .unwrap()
+            .to_path_buf();
+
+        let many: Vec<String> = (0..100).map(|i| format!("feature-{i}")).collect();
+        let dir = index.features_target_dir(&many);
+        assert_eq!(dir.parent(), Some(root.as_path()));
+        assert!(dir.file_name().unwrap().len() <= 16);
+        let escaping = index.features_target_dir(&features(&["../../etc"]));
+        assert_eq!(escaping.parent(), Some(root.as_path()));
+
+        let (ab, ba) = (features(&["a", "b"]), features(&["b", "a", "b"]));
+        assert_eq!(
+            index.features_target_dir(&ab),
+            index.features_target_dir(&ba)
+        );
+        assert_eq!(
+            DocsVariant::Features(ab).key("demo"),
+            DocsVariant::Features(ba).key("demo")
+        );
+        assert_eq!(key_crate(&DocsVariant::Features(many).key("demo")), "demo");
+    }
+
+    #[test]
+    fn test_git_revisions_of_forks_are_kept_apart() {
+        let index = CrateIndex::new(create_dummy_workspace(), Config::default());
+        let fork = |url: &str| DocsVariant::Git {
+            url: url.to_string(),
+            rev: "main".to_string(),
+        };
+        let upstream = fork("https://github.com/example/demo");
+        let other = fork("https://github.com/someone/demo");
+        assert_ne!(upstream.key("demo"), other.key("demo"));
+        assert_eq!(key_crate(&upstream.key("demo")), "demo");
+
+        index
+            .crates
+            .insert(upstream.key("demo"), module_crate("demo::upstream"));
+        index
+            .crates
+            .insert(other.key("demo"), module_crate("demo::other"));
+        let first = index.session();
+        first.selected.insert("demo".to_string(), upstream);
+        let second = index.session();
+        second.selected.insert("demo".to_string(), other);
+        let path = |index: &CrateIndex| {
+            let krate = index.get_crate("demo").unwrap();
+            krate.path_to_id.keys().next().unwrap().clone()
+        };
+        assert_eq!(path(&first), "demo::upstream");
+        assert_eq!(path(&second), "demo::other");
+    }
+
+    #[tokio::test]
+    async fn test_ensure_all_loaded() {
+        let config = Config {
+            crates: crate::config::CratePolicy {
+                allow: None,
+                deny: vec!["denied".to_string()],
+            },
+            ..Config::default()
+        };
+        let index = CrateIndex::new(create_dummy_workspace(), config);
+        for name in ["first", "second"] {
+            let krate = Crate {
+                root: Id(0),
+                crate_version: None,
+                includes_private: false,
+                index: HashMap::new(),
+                paths: HashMap::new(),
+                external_crates: HashMap::new(),
+                format_version: 0,
+                target: rustdoc_types::Target {
+                    triple: "x86_64-unknown-linux-gnu".to_string(),
+                    target_features: vec![],
+                },
+            };
+            index
+                .crates
+                .insert(name.to_string(), LoadedCrate::new(krate, HashMap::new()));
+        }
+
+        let outcomes = index
+            .ensure_all_loaded(&["second", "denied", "first", "second"])
+            .await;
+        let outcomes: Vec<(&str, bool)> = outcomes
+            .iter()
+            .map(|(name, outcome)| (name.as_str(), matches!(outcome, LoadOutcome::Loaded)))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![("second", true), ("denied", false), ("first", true)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_load_available_skips_slow_crates() {
+        let mut config = Config {
+            crates: crate::config::CratePolicy {
+                allow: None,
+                deny: vec!["denied".to_string()],
+            },
+            ..Config::default()
+        };
+        config.docs.load_timeout_secs = 1;
+        let index = CrateIndex::new(create_dummy_workspace(), config);
+        let krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        index
+            .crates
+            .insert("fast".to_string(), LoadedCrate::new(krate, HashMap::new()));
+
+        // Holding the load lock keeps `slow` loading past the timeout.
+        let lock = index.loading.entry("slow".to_string()).or_default().clone();
+        let guard = lock.lock().await;
+        let available = index.load_available(&["slow", "denied", "fast"]).await;
+        drop(guard);
+        assert_eq!(available.loaded, vec!["fast"]);
+        assert_eq!(available.unavailable, vec!["denied"]);
+        assert_eq!(available.pending, vec!["slow"]);
+    }
+}
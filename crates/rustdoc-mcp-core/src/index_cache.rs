@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rustdoc_types::{Crate, Id};
+
+use crate::atomic_file;
+
+/// The directory under the target directory holding parsed indexes.
+const CACHE_DIR: &str = "rustdoc-mcp-index";
+
+/// Bumped whenever the paths indexed for a crate change, like when the items of
+/// primitive types were added, so that indexes cached before are rebuilt.
+const PATH_MAP_VERSION: u32 = 2;
+
+/// Where the parsed index of a crate is cached. The name carries the crate version, the
+/// rustdoc JSON format version and [`PATH_MAP_VERSION`], so upgrading any of them never
+/// reads an old index.
+pub fn cache_path(target_dir: &Path, crate_name: &str, crate_version: Option<&str>) -> PathBuf {
+    target_dir.join(CACHE_DIR).join(format!(
+        "{crate_name}-{}-v{}-p{PATH_MAP_VERSION}.postcard",
+        crate_version.unwrap_or("unversioned"),
+        rustdoc_types::FORMAT_VERSION
+    ))
+}
+
+/// Whether the index at `cache_path` was written after the JSON it was parsed from.
+fn is_fresh(cache_path: &Path, json_path: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(cache_path), modified(json_path)) {
+        (Some(cached), Some(generated)) => cached >= generated,
+        _ => false,
+    }
+}
+
+/// Reads a cached index when it is at least as new as the JSON at `json_path`. An index
+/// that fails to decode is removed so it is rewritten on the next load.
+pub fn read(cache_path: &Path, json_path: &Path) -> Option<(Crate, HashMap<String, Id>)> {
+    if !is_fresh(cache_path, json_path) {
+        return None;
+    }
+    let bytes = std::fs::read(cache_path).ok()?;
+    match postcard::from_bytes(&bytes) {
+        Ok(cached) => Some(cached),
+        Err(e) => {
+            tracing::warn!("Discarding unreadable index {:?}: {}", cache_path, e);
+            std::fs::remove_file(cache_path).ok();
+            None
+        }
+    }
+}
+
+/// Writes the parsed index of a crate, replacing the file at once so concurrent servers
+/// never read a partial index.
+pub fn write(cache_path: &Path, krate: &Crate, path_to_id: &HashMap<String, Id>) -> Result<()> {
+    let bytes =
+        postcard::to_allocvec(&(krate, path_to_id)).context("Failed to encode the index")?;
+    atomic_file::write(cache_path, bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("doc/demo.json");
+        std::fs::create_dir_all(json_path.parent().unwrap()).unwrap();
+        std::fs::write(&json_path, "{}").unwrap();
+
+        let krate = Crate {
+            root: Id(0),
+            crate_version: Some("0.1.0".to_string()),
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+            format_version: rustdoc_types::FORMAT_VERSION,
+        };
+        let path_to_id = HashMap::from([("demo".to_string(), Id(0))]);
+        let path = cache_path(dir.path(), "demo", Some("0.1.0"));
+        assert!(read(&path, &json_path).is_none());
+
+        write(&path, &krate, &path_to_id).unwrap();
+        let (cached, cached_paths) = read(&path, &json_path).unwrap();
+        assert_eq!(cached.crate_version.as_deref(), Some("0.1.0"));
+        assert_eq!(cached_paths, path_to_id);
+
+        // Regenerated docs make the index stale.
+        std::fs::File::options()
+            .write(true)
+            .open(&json_path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert!(read(&path, &json_path).is_none());
+    }
+}
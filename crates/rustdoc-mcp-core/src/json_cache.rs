@@ -0,0 +1,289 @@
+use std::fs::File;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+/// How many bytes at each end of a rustdoc JSON file are searched for its header fields.
+/// `crate_version` comes right after `root` at the start and `format_version` is last.
+const PROBE_BYTES: u64 = 4096;
+
+/// Reads up to `PROBE_BYTES` from the start or the end of a file.
+fn probe(path: &Path, from_end: bool) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    if from_end {
+        let len = file.metadata().ok()?.len();
+        file.seek(SeekFrom::Start(len.saturating_sub(PROBE_BYTES)))
+            .ok()?;
+    }
+    let mut bytes = Vec::new();
+    file.take(PROBE_BYTES).read_to_end(&mut bytes).ok()?;
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// The text right after `"field":` in `json`, with whitespace skipped.
+fn field<'a>(json: &'a str, name: &str) -> Option<&'a str> {
+    let key = format!("\"{name}\"");
+    let rest = &json[json.find(&key)? + key.len()..];
+    Some(rest.trim_start().strip_prefix(':')?.trim_start())
+}
+
+/// Reads the `format_version` of a rustdoc JSON file without parsing all of it.
+pub fn format_version(path: &Path) -> Option<u32> {
+    let tail = probe(path, true)?;
+    let value = field(&tail, "format_version")?;
+    let end = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    value[..end].parse().ok()
+}
+
+/// Reads the `crate_version` of a rustdoc JSON file, `None` when it is unset or unknown.
+pub fn crate_version(path: &Path) -> Option<String> {
+    let head = probe(path, false)?;
+    let value = field(&head, "crate_version")?.strip_prefix('"')?;
+    Some(value[..value.find('"')?].to_string())
+}
+
+/// The files under `dir` with their modification times, skipping `target` and hidden
+/// directories.
+fn source_files(dir: &Path) -> Vec<(PathBuf, SystemTime)> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                if name != "target" && !name.to_string_lossy().starts_with('.') {
+                    pending.push(entry.path());
+                }
+            } else if let Ok(modified) = entry.metadata().and_then(|m| m.modified()) {
+                files.push((entry.path(), modified));
+            }
+        }
+    }
+    files
+}
+
+/// The latest modification time of the files under `dir`.
+fn newest_modification(dir: &Path) -> Option<SystemTime> {
+    source_files(dir)
+        .into_iter()
+        .map(|(_, modified)| modified)
+        .max()
+}
+
+/// Where the fingerprint of the inputs docs were generated from is stored.
+pub fn fingerprint_path(json_path: &Path) -> PathBuf {
+    json_path.with_extension("fingerprint")
+}
+
+/// Hashes what a package's docs depend on: the lockfile, the enabled features and, for
+/// local packages, the modification times of the sources. Docs are regenerated when it
+/// changes, e.g. after a dependency bump.
+pub fn fingerprint(
+    lockfile: &Path,
+    source_dir: Option<&Path>,
+    features: Option<&[String]>,
+) -> String {
+    let mut hasher = DefaultHasher::new();
+    std::fs::read(lockfile)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    features.hash(&mut hasher);
+    if let Some(dir) = source_dir {
+        let mut files = source_files(dir);
+        files.sort();
+        files.hash(&mut hasher);
+    }
+    format!("{:016x}", hasher.finish())
+}
+
+/// What the docs of a package must match to be reused.
+pub struct Expected<'a> {
+    /// The rustdoc JSON format version the server reads.
+    pub format_version: u32,
+    /// The package version the workspace resolves.
+    pub crate_version: Option<&'a str>,
+    /// The source directory of a local package, whose edits make older docs stale.
+    pub source_dir: Option<&'a Path>,
+}
+
+/// Explains why an existing rustdoc JSON file cannot be reused, e.g. because it was
+/// generated by another nightly or before the last edit. Returns `None` when it can.
+pub fn stale_reason(json_path: &Path, expected: &Expected) -> Option<String> {
+    match format_version(json_path) {
+        Some(found) if found == expected.format_version => {}
+        Some(found) => {
+            return Some(format!(
+                "it uses format version {found}, not {}",
+                expected.format_version
+            ));
+        }
+        None => return Some("its format version cannot be read".to_string()),
+    }
+    if let (Some(found), Some(wanted)) = (crate_version(json_path), expected.crate_version)
+        && found != wanted
+    {
+        return Some(format!("it documents version {found}, not {wanted}"));
+    }
+    if let Some(dir) = expected.source_dir
+        && let Ok(generated) = std::fs::metadata(json_path).and_then(|m| m.modified())
+        && newest_modification(dir).is_some_and(|modified| modified > generated)
+    {
+        return Some(format!("{} changed since it was generated", dir.display()));
+    }
+    None
+}
+
+/// Finds reusable docs generated elsewhere in the target directory, like
+/// `target/<triple>/doc/<crate>.json` from a `cargo doc --target` run.
+pub fn find_reusable(target_dir: &Path, file_name: &str, expected: &Expected) -> Option<PathBuf> {
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(target_dir)
+        .ok()?
+        .flatten()
+        .map(|entry| entry.path().join("doc").join(file_name))
+        .filter(|path| path.is_file())
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .find(|path| stale_reason(path, expected).is_none())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn write_json(path: &Path, crate_version: &str, format_version: u32) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(
+            path,
+            format!(
+                r#"{{"root":0,"crate_version":{crate_version},"includes_private":false,"index":{{}},"format_version":{format_version}}}"#
+            ),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_header_fields() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("demo.json");
+        write_json(&path, r#""1.2.0""#, 57);
+        assert_eq!(format_version(&path), Some(57));
+        assert_eq!(crate_version(&path).as_deref(), Some("1.2.0"));
+
+        write_json(&path, "null", 57);
+        assert_eq!(crate_version(&path), None);
+    }
+
+    #[test]
+    fn test_stale_reason() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "pub fn f() {}\n").unwrap();
+        let path = dir.path().join("target/doc/demo.json");
+        write_json(&path, r#""0.1.0""#, 57);
+        let expected = Expected {
+            format_version: 57,
+            crate_version: Some("0.1.0"),
+            source_dir: Some(dir.path()),
+        };
+        // The JSON is written after the source.
+        File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(stale_reason(&path, &expected), None);
+
+        let newer = Expected {
+            format_version: 58,
+            ..expected
+        };
+        assert!(
+            stale_reason(&path, &newer)
+                .unwrap()
+                .contains("format version 57")
+        );
+        let other_version = Expected {
+            crate_version: Some("0.2.0"),
+            ..expected
+        };
+        assert!(
+            stale_reason(&path, &other_version)
+                .unwrap()
+                .contains("0.1.0")
+        );
+
+        File::options()
+            .write(true)
+            .open(src.join("lib.rs"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(120))
+            .unwrap();
+        assert!(stale_reason(&path, &expected).unwrap().contains("changed"));
+    }
+
+    #[test]
+    fn test_fingerprint() {
+        let dir = tempfile::tempdir().unwrap();
+        let lockfile = dir.path().join("Cargo.lock");
+        std::fs::write(&lockfile, "version = 4\n").unwrap();
+        let src = dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "pub fn f() {}\n").unwrap();
+
+        let before = fingerprint(&lockfile, Some(dir.path()), None);
+        assert_eq!(before, fingerprint(&lockfile, Some(dir.path()), None));
+        assert_ne!(
+            before,
+            fingerprint(&lockfile, Some(dir.path()), Some(&["std".to_string()]))
+        );
+
+        File::options()
+            .write(true)
+            .open(src.join("lib.rs"))
+            .unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(60))
+            .unwrap();
+        let edited = fingerprint(&lockfile, Some(dir.path()), None);
+        assert_ne!(before, edited);
+
+        std::fs::write(&lockfile, "version = 4\n\n[[package]]\nname = \"log\"\n").unwrap();
+        assert_ne!(edited, fingerprint(&lockfile, Some(dir.path()), None));
+    }
+
+    #[test]
+    fn test_find_reusable() {
+        let dir = tempfile::tempdir().unwrap();
+        write_json(
+            &dir.path().join("x86_64-unknown-linux-gnu/doc/demo.json"),
+            r#""0.1.0""#,
+            56,
+        );
+        let expected = Expected {
+            format_version: 57,
+            crate_version: Some("0.1.0"),
+            source_dir: None,
+        };
+        assert_eq!(find_reusable(dir.path(), "demo.json", &expected), None);
+
+        let reusable = dir.path().join("wasm32-unknown-unknown/doc/demo.json");
+        write_json(&reusable, r#""0.1.0""#, 57);
+        assert_eq!(
+            find_reusable(dir.path(), "demo.json", &expected),
+            Some(reusable)
+        );
+    }
+}
@@ -0,0 +1,60 @@
+//! Indexing and rendering of rustdoc JSON, the core of `rustdoc-mcp-server` without its
+//! MCP layer.
+//!
+//! - [`DocGenerator`] runs `cargo rustdoc` to produce the JSON of a package.
+//! - [`CrateIndex`] loads the JSON of the crates of a [`Workspace`] on demand, generating
+//!   it when needed, and answers path lookups and searches over their items.
+//! - [`render_item`] renders the docs of an item through a [`DocRenderer`]: markdown,
+//!   plain text, JSON or one of your own.
+//!
+//! ```no_run
+//! use rustdoc_mcp_core::{Config, CrateIndex, MarkdownRenderer, Workspace, render_item};
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let workspace = Workspace::load(".")?;
+//! let index = CrateIndex::new(workspace, Config::default());
+//! index.ensure_loaded("serde").await?;
+//! let krate = index.get_crate("serde").expect("just loaded");
+//! let id = krate.path_to_id["serde::Serialize"];
+//! let item = &krate.krate.index[&id];
+//! println!("{}", render_item(item, &krate.krate, &mut MarkdownRenderer::default()));
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod atomic_file;
+pub mod attributes;
+pub mod availability;
+pub mod config;
+pub mod crate_info;
+pub mod doc_examples;
+pub(crate) mod doc_gen;
+pub(crate) mod doc_links;
+pub(crate) mod git_checkout;
+pub mod headings;
+pub mod impl_summary;
+pub mod index;
+pub(crate) mod index_cache;
+pub(crate) mod json_cache;
+pub mod macro_args;
+pub mod markdown;
+pub mod redact;
+pub mod references;
+pub mod registry;
+pub mod render;
+pub(crate) mod signatures;
+pub mod stability;
+pub mod sysroot;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
+#[cfg(feature = "tantivy")]
+pub(crate) mod text_index;
+pub mod types;
+pub mod workspace;
+
+pub use config::Config;
+pub use doc_gen::DocGenerator;
+pub use index::{CrateIndex, LoadedCrate, SearchOptions};
+pub use markdown::render_item;
+pub use render::{DocRenderer, JsonRenderer, MarkdownRenderer, TextRenderer};
+pub use workspace::Workspace;
@@ -0,0 +1,218 @@
+use crate::headings::extract_headings;
+use crate::types::MacroArgument;
+
+/// Most example usages kept per argument.
+const MAX_EXAMPLES: usize = 3;
+
+/// Splits `s` at commas that are not nested in brackets or string literals.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut start = 0;
+
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if in_string => {
+                chars.next();
+            }
+            '"' => in_string = !in_string,
+            '(' | '[' | '{' if !in_string => depth += 1,
+            ')' | ']' | '}' if !in_string => depth = depth.saturating_sub(1),
+            ',' if !in_string && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+        .into_iter()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .collect()
+}
+
+/// Returns the text between the parenthesis at the start of `s` and its matching close.
+fn balanced_parens(s: &str) -> Option<&str> {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match c {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '(' if !in_string => depth += 1,
+            ')' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(&s[1..i]);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// The argument name: the leading identifier of `name = value` or `name(...)`.
+fn argument_name(arg: &str) -> Option<&str> {
+    let end = arg
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(arg.len());
+    let name = &arg[..end];
+    (!name.is_empty() && !name.starts_with(|c: char| c.is_ascii_digit())).then_some(name)
+}
+
+fn add_argument(
+    arguments: &mut Vec<MacroArgument>,
+    name: &str,
+    description: Option<String>,
+    example: Option<String>,
+) {
+    let index = arguments
+        .iter()
+        .position(|a| a.name == name)
+        .unwrap_or_else(|| {
+            arguments.push(MacroArgument {
+                name: name.to_string(),
+                description: None,
+                examples: Vec::new(),
+            });
+            arguments.len() - 1
+        });
+
+    let argument = &mut arguments[index];
+    if argument.description.is_none() {
+        argument.description = description;
+    }
+    if let Some(example) = example
+        && argument.examples.len() < MAX_EXAMPLES
+        && !argument.examples.contains(&example)
+    {
+        argument.examples.push(example);
+    }
+}
+
+/// Extracts the arguments an attribute macro accepts from its docs. Arguments are taken
+/// from example usages like `#[name(flavor = "current_thread")]` and from bullet lists in
+/// sections about arguments, options or configuration.
+pub fn extract_macro_arguments(macro_name: &str, docs: &str) -> Vec<MacroArgument> {
+    let mut arguments = Vec::new();
+
+    // Example usages: `#[name(...)]` or `#[some::path::name(...)]`.
+    let mut rest = docs;
+    while let Some(start) = rest.find("#[") {
+        rest = &rest[start + 2..];
+        let path_end = rest
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':'))
+            .unwrap_or(rest.len());
+        let path = &rest[..path_end];
+        if path.rsplit("::").next() != Some(macro_name) || !rest[path_end..].starts_with('(') {
+            continue;
+        }
+        if let Some(inner) = balanced_parens(&rest[path_end..]) {
+            for arg in split_top_level(inner) {
+                if let Some(name) = argument_name(arg) {
+                    add_argument(&mut arguments, name, None, Some(arg.to_string()));
+                }
+            }
+        }
+    }
+
+    // Documented lists: "- `name = ...`: description".
+    for (_, title, section) in extract_headings(docs) {
+        let title = title.to_lowercase();
+        if ![
+            "argument",
+            "option",
+            "attribute",
+            "config",
+            "parameter",
+            "setting",
+        ]
+        .iter()
+        .any(|keyword| title.contains(keyword))
+        {
+            continue;
+        }
+        for line in section.lines() {
+            let Some(item) = line
+                .trim_start()
+                .strip_prefix("- ")
+                .or_else(|| line.trim_start().strip_prefix("* "))
+            else {
+                continue;
+            };
+            let Some((code, description)) =
+                item.strip_prefix('`').and_then(|item| item.split_once('`'))
+            else {
+                continue;
+            };
+            if let Some(name) = argument_name(code) {
+                let description = description
+                    .trim_start_matches([':', '-', ' ', '—'])
+                    .trim()
+                    .to_string();
+                add_argument(
+                    &mut arguments,
+                    name,
+                    (!description.is_empty()).then_some(description),
+                    (code != name).then(|| code.to_string()),
+                );
+            }
+        }
+    }
+
+    arguments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arguments_from_examples() {
+        let docs = r#"Marks async function to be executed by the selected runtime.
+
+```
+#[tokio::main(flavor = "multi_thread", worker_threads = 10)]
+async fn main() {}
+```
+
+```
+#[tokio::main(flavor = "current_thread")]
+async fn main() {}
+```"#;
+        let args = extract_macro_arguments("main", docs);
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0].name, "flavor");
+        assert_eq!(
+            args[0].examples,
+            vec![
+                "flavor = \"multi_thread\"".to_string(),
+                "flavor = \"current_thread\"".to_string()
+            ]
+        );
+        assert_eq!(args[1].name, "worker_threads");
+    }
+
+    #[test]
+    fn test_arguments_from_documented_list() {
+        let docs = "# Arguments\n\n- `skip(arg, ...)`: Skips recording the given arguments.\n- `level = \"debug\"` - Sets the span level.\n\n```\n#[instrument(skip(self), fields(id = 1))]\nfn f(&self) {}\n```";
+        let args = extract_macro_arguments("instrument", docs);
+        let names: Vec<&str> = args.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["skip", "fields", "level"]);
+        assert_eq!(
+            args[0].description.as_deref(),
+            Some("Skips recording the given arguments.")
+        );
+        assert_eq!(args[2].description.as_deref(), Some("Sets the span level."));
+    }
+}
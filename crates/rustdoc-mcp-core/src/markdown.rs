@@ -0,0 +1,1401 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use rustdoc_types::{
+    AssocItemConstraintKind, Attribute, Crate, GenericArg, GenericArgs, GenericBound,
+    GenericParamDefKind, Generics, Id, Item, ItemEnum, MacroKind, PreciseCapturingArg, Term,
+    TraitBoundModifier, Type, WherePredicate,
+};
+use tracing::debug;
+
+use crate::attributes::correctness_attributes;
+use crate::availability::availability;
+use crate::doc_links::resolve_doc_links;
+use crate::impl_summary::doc_summary;
+use crate::index::{alias_targets, get_item_kind};
+use crate::macro_args::extract_macro_arguments;
+use crate::references::{item_references, with_members};
+use crate::render::{DocRenderer, MarkdownRenderer};
+use crate::types::ItemOwner;
+
+const NON_EXHAUSTIVE_MATCH_NOTE: &str = "This enum is `#[non_exhaustive]`: new variants may be added without a major version bump, so matches outside its crate must include a wildcard `_` arm.";
+const STRIPPED_VARIANTS_MATCH_NOTE: &str = "This enum has variants hidden from the documentation, so matches must include a wildcard `_` arm.";
+
+fn find_parent_impl(krate: &Crate, id: Id) -> Option<&Item> {
+    krate.index.values().find(|item| {
+        if let ItemEnum::Impl(impl_) = &item.inner {
+            impl_.items.contains(&id)
+        } else {
+            false
+        }
+    })
+}
+
+/// The owner of every item of an impl block or trait definition.
+pub fn item_owners(krate: &Crate) -> HashMap<Id, ItemOwner> {
+    let mut owners = HashMap::new();
+    for item in krate.index.values() {
+        let (items, owner) = match &item.inner {
+            ItemEnum::Impl(impl_) => (
+                &impl_.items,
+                ItemOwner {
+                    type_name: Some(format_type(&impl_.for_)),
+                    trait_name: impl_
+                        .trait_
+                        .as_ref()
+                        .map(|trait_| format_path_like(&trait_.path, trait_.args.as_deref())),
+                },
+            ),
+            ItemEnum::Trait(trait_) => (
+                &trait_.items,
+                ItemOwner {
+                    type_name: None,
+                    trait_name: item.name.clone(),
+                },
+            ),
+            _ => continue,
+        };
+        for id in items {
+            owners.insert(*id, owner.clone());
+        }
+    }
+    owners
+}
+
+/// The header of an impl block, like `impl<T: Clone> Clone for Wrapper<T>`.
+pub fn format_impl_header(impl_: &rustdoc_types::Impl) -> String {
+    let mut s = String::from(if impl_.is_unsafe {
+        "unsafe impl"
+    } else {
+        "impl"
+    });
+    s.push_str(&format_generics(&impl_.generics));
+    s.push(' ');
+
+    if let Some(trait_) = &impl_.trait_ {
+        if impl_.is_negative {
+            s.push('!');
+        }
+        s.push_str(&format_path_like(&trait_.path, trait_.args.as_deref()));
+        s.push_str(" for ");
+    }
+
+    s.push_str(&format_type(&impl_.for_));
+    s
+}
+
+pub fn generate_item_markdown(item: &Item, krate: &Crate) -> String {
+    render_item(item, krate, &mut MarkdownRenderer::default())
+}
+
+/// Renders the docs of an item: its title, definition, documentation and the details of
+/// its kind, like fields or variants.
+pub fn render_item(item: &Item, krate: &Crate, doc: &mut dyn DocRenderer) -> String {
+    let name = item
+        .name
+        .as_deref()
+        .or(match &item.inner {
+            ItemEnum::Use(u) => Some(u.name.as_str()),
+            _ => None,
+        })
+        .unwrap_or("<unnamed>");
+    let kind = get_item_kind(item);
+
+    doc.heading(1, &format!("{kind} {name}"));
+
+    if let Some(parent) = find_parent_impl(krate, item.id)
+        && let ItemEnum::Impl(impl_) = &parent.inner
+    {
+        doc.code("rust", &format_impl_header(impl_));
+    }
+
+    // Signature / Definition
+    let definition = format_item_definition(item);
+    if !definition.is_empty() {
+        doc.code("rust", &definition);
+    }
+
+    let (deprecated, attributes): (Vec<_>, Vec<_>) = correctness_attributes(item)
+        .into_iter()
+        .partition(|attribute| attribute.name == "deprecated");
+    // `#[deprecated]` is shown as a banner rather than listed with the others.
+    if let Some(banner) = deprecated
+        .first()
+        .and_then(|attribute| attribute.note.as_ref())
+    {
+        doc.paragraph(&format!("**{banner}**"));
+    }
+
+    if let Some(availability) = availability(item) {
+        doc.paragraph(&format!("_{}_", availability.description));
+    }
+
+    if !attributes.is_empty() {
+        doc.heading(2, "Attributes");
+        let attribute_list: Vec<String> = attributes
+            .iter()
+            .map(|attribute| match &attribute.note {
+                Some(note) => format!("`#[{}]` ({note}) - {}", attribute.name, attribute.guidance),
+                None => format!("`#[{}]` - {}", attribute.name, attribute.guidance),
+            })
+            .collect();
+        doc.list(&attribute_list);
+    }
+
+    // Documentation
+    if let Some(docs) = &item.docs {
+        doc.heading(2, "Documentation");
+        doc.markdown(&resolve_doc_links(docs, &item.links, krate));
+    }
+
+    // Specific details based on kind
+    match &item.inner {
+        ItemEnum::Struct(s) => {
+            if let rustdoc_types::StructKind::Plain { fields, .. } = &s.kind
+                && !fields.is_empty()
+            {
+                doc.heading(2, "Fields");
+                doc.list(&field_list(krate, fields));
+            }
+        }
+        ItemEnum::Variant(v) => {
+            if let rustdoc_types::VariantKind::Struct { fields, .. } = &v.kind
+                && !fields.is_empty()
+            {
+                doc.heading(2, "Fields");
+                doc.list(&field_list(krate, fields));
+            }
+        }
+        ItemEnum::Enum(e) => {
+            if !e.variants.is_empty() {
+                doc.heading(2, "Variants");
+                let mut variant_list = Vec::new();
+                for variant_id in &e.variants {
+                    if let Some(variant) = krate.index.get(variant_id) {
+                        let variant_name = variant.name.as_deref().unwrap_or("_");
+
+                        let mut line = format!("`{variant_name}`");
+
+                        if let ItemEnum::Variant(v) = &variant.inner {
+                            match &v.kind {
+                                rustdoc_types::VariantKind::Tuple(types) => {
+                                    if !types.is_empty() {
+                                        line.push_str("(...)");
+                                    }
+                                }
+                                rustdoc_types::VariantKind::Struct { fields, .. } => {
+                                    let fields: Vec<String> = fields
+                                        .iter()
+                                        .filter_map(|id| krate.index.get(id))
+                                        .filter_map(|field| match &field.inner {
+                                            ItemEnum::StructField(ty) => Some(format!(
+                                                "{}: {}",
+                                                field.name.as_deref().unwrap_or("_"),
+                                                format_type(ty)
+                                            )),
+                                            _ => None,
+                                        })
+                                        .collect();
+                                    if fields.is_empty() {
+                                        line.push_str(" { ... }");
+                                    } else {
+                                        line =
+                                            format!("`{variant_name} {{ {} }}`", fields.join(", "));
+                                    }
+                                }
+                                rustdoc_types::VariantKind::Plain => {}
+                            }
+                        }
+
+                        if let Some(d) = &variant.docs {
+                            let short = d.lines().next().unwrap_or("").trim();
+                            if !short.is_empty() {
+                                write!(&mut line, " - {short}").ok();
+                            }
+                        }
+                        variant_list.push(line);
+                    }
+                }
+
+                doc.list(&variant_list);
+            }
+
+            let non_exhaustive = item
+                .attrs
+                .iter()
+                .any(|attr| matches!(attr, Attribute::NonExhaustive));
+            if non_exhaustive || e.has_stripped_variants {
+                doc.heading(2, "Matching");
+                let note = if non_exhaustive {
+                    NON_EXHAUSTIVE_MATCH_NOTE
+                } else {
+                    STRIPPED_VARIANTS_MATCH_NOTE
+                };
+                doc.paragraph(note);
+                doc.code("rust", &format_match_skeleton(name, e, krate, true));
+            }
+        }
+        ItemEnum::TypeAlias(_) | ItemEnum::TraitAlias(_) => {
+            let targets = alias_targets(krate, item);
+            if !targets.is_empty() {
+                doc.heading(2, "Aliased Items");
+                let target_list: Vec<String> = targets
+                    .iter()
+                    .map(|target| format!(
+                        "`{target}` - use `get_docs` on this path for its methods and trait implementations"
+                    ))
+                    .collect();
+                doc.list(&target_list);
+            }
+        }
+        ItemEnum::ProcMacro(pm) => {
+            if pm.kind == MacroKind::Attr
+                && let Some(docs) = &item.docs
+            {
+                let arguments = extract_macro_arguments(name, docs);
+                if !arguments.is_empty() {
+                    doc.heading(2, "Accepted Arguments");
+                    let mut argument_list = Vec::new();
+                    for argument in arguments {
+                        let mut line = format!("`{}`", argument.name);
+                        if let Some(description) = &argument.description {
+                            write!(&mut line, " - {description}").ok();
+                        }
+                        if !argument.examples.is_empty() {
+                            let examples: Vec<String> =
+                                argument.examples.iter().map(|e| format!("`{e}`")).collect();
+                            write!(&mut line, " (e.g. {})", examples.join(", ")).ok();
+                        }
+                        argument_list.push(line);
+                    }
+                    doc.list(&argument_list);
+                }
+            }
+
+            if !pm.helpers.is_empty() {
+                doc.heading(2, "Helper Attributes");
+                let helper_list: Vec<String> = pm
+                    .helpers
+                    .iter()
+                    .map(|helper| format!("`#[{helper}]`"))
+                    .collect();
+                doc.list(&helper_list);
+            }
+        }
+        _ => {}
+    }
+
+    doc.finish()
+}
+
+fn get_item_kind(item: &Item) -> &'static str {
+    match &item.inner {
+        ItemEnum::Module(_) => "Module",
+        ItemEnum::ExternCrate { .. } => "Extern Crate",
+        ItemEnum::Union(_) => "Union",
+        ItemEnum::Struct(_) => "Struct",
+        ItemEnum::StructField(_) => "Field",
+        ItemEnum::Enum(_) => "Enum",
+        ItemEnum::Variant(_) => "Variant",
+        ItemEnum::Function(_) => "Function",
+        ItemEnum::Trait(_) => "Trait",
+        ItemEnum::TraitAlias(_) => "Trait Alias",
+        ItemEnum::Impl(_) => "Impl",
+        ItemEnum::TypeAlias(_) => "Type Alias",
+        ItemEnum::Static(_) => "Static",
+        ItemEnum::Macro(_) => "Macro",
+        ItemEnum::ProcMacro(_) => "Proc Macro",
+        ItemEnum::Primitive(_) => "Primitive",
+        ItemEnum::AssocConst { .. } => "Assoc Constant",
+        ItemEnum::AssocType { .. } => "Assoc Type",
+        ItemEnum::Use(_) => "Use",
+        ItemEnum::ExternType => "Extern Type",
+        ItemEnum::Constant {
+            type_: _,
+            const_: _,
+        } => "Constant",
+    }
+}
+
+pub fn format_generic_bound(bound: &GenericBound) -> String {
+    match bound {
+        GenericBound::TraitBound {
+            trait_,
+            generic_params,
+            modifier,
+        } => {
+            let mut s = String::new();
+            if !generic_params.is_empty() {
+                s.push_str("for<");
+                let params: Vec<String> = generic_params
+                    .iter()
+                    .map(|p| {
+                        if let GenericParamDefKind::Lifetime { outlives } = &p.kind {
+                            if outlives.is_empty() {
+                                p.name.clone()
+                            } else {
+                                format!("{}: {}", p.name, outlives.join(" + "))
+                            }
+                        } else {
+                            p.name.clone()
+                        }
+                    })
+                    .collect();
+                s.push_str(&params.join(", "));
+                s.push_str("> ");
+            }
+
+            match modifier {
+                TraitBoundModifier::None => {}
+                TraitBoundModifier::Maybe => s.push('?'),
+                TraitBoundModifier::MaybeConst => s.push_str("~const "),
+            }
+
+            s.push_str(&format_path_like(&trait_.path, trait_.args.as_deref()));
+            s
+        }
+        GenericBound::Outlives(l) => l.clone(),
+        GenericBound::Use(precise_capturing_args) => {
+            format_precise_capturing_args(precise_capturing_args)
+        }
+    }
+}
+
+pub fn format_generics(generics: &Generics) -> String {
+    let mut params = Vec::new();
+    for param in &generics.params {
+        match &param.kind {
+            GenericParamDefKind::Lifetime { outlives } => {
+                let mut s = param.name.clone();
+                if !outlives.is_empty() {
+                    s.push_str(": ");
+                    s.push_str(&outlives.join(" + "));
+                }
+                params.push(s);
+            }
+            GenericParamDefKind::Type {
+                bounds,
+                default,
+                is_synthetic,
+            } => {
+                if *is_synthetic {
+                    continue;
+                }
+                let mut s = param.name.clone();
+                if !bounds.is_empty() {
+                    s.push_str(": ");
+                    let bounds: Vec<String> = bounds.iter().map(format_generic_bound).collect();
+                    s.push_str(&bounds.join(" + "));
+                }
+                if let Some(ty) = default {
+                    s.push_str(" = ");
+                    s.push_str(&format_type(ty));
+                }
+                params.push(s);
+            }
+            GenericParamDefKind::Const { type_, default } => {
+                let mut s = format!("const {}: {}", param.name, format_type(type_));
+                if let Some(expr) = default {
+                    s.push_str(" = ");
+                    s.push_str(expr);
+                }
+                params.push(s);
+            }
+        }
+    }
+
+    if params.is_empty() {
+        return String::new();
+    }
+
+    format!("<{}>", params.join(", "))
+}
+
+/// Renders the predicates of a `where` clause, like `where U: From<T>`, or `None` if
+/// there are none.
+pub fn format_where_clause(generics: &Generics) -> Option<String> {
+    let predicates: Vec<String> = generics
+        .where_predicates
+        .iter()
+        .map(|predicate| match predicate {
+            WherePredicate::BoundPredicate {
+                type_,
+                bounds,
+                generic_params,
+            } => {
+                let binder = if generic_params.is_empty() {
+                    String::new()
+                } else {
+                    let names: Vec<&str> = generic_params.iter().map(|p| p.name.as_str()).collect();
+                    format!("for<{}> ", names.join(", "))
+                };
+                let bounds: Vec<String> = bounds.iter().map(format_generic_bound).collect();
+                format!("{binder}{}: {}", format_type(type_), bounds.join(" + "))
+            }
+            WherePredicate::LifetimePredicate { lifetime, outlives } => {
+                format!("{lifetime}: {}", outlives.join(" + "))
+            }
+            WherePredicate::EqPredicate { lhs, rhs } => {
+                let rhs = match rhs {
+                    Term::Type(t) => format_type(t),
+                    Term::Constant(c) => c.expr.clone(),
+                };
+                format!("{} = {rhs}", format_type(lhs))
+            }
+        })
+        .collect();
+    (!predicates.is_empty()).then(|| format!("where {}", predicates.join(", ")))
+}
+
+/// Lists named fields as `` `name: Type` - first line of docs ``.
+fn field_list(krate: &Crate, fields: &[Id]) -> Vec<String> {
+    let mut field_list = Vec::new();
+    for field_id in fields {
+        if let Some(field) = krate.index.get(field_id)
+            && let ItemEnum::StructField(ty) = &field.inner
+        {
+            let field_name = field.name.as_deref().unwrap_or("_");
+            let field_type = format_type(ty);
+
+            let mut line = format!("`{field_name}: {field_type}`");
+            if let Some(d) = &field.docs {
+                let short = d.lines().next().unwrap_or("").trim();
+                if !short.is_empty() {
+                    write!(&mut line, " - {short}").ok();
+                }
+            }
+            field_list.push(line);
+        }
+    }
+    field_list
+}
+
+/// Renders the generic parameter list of a type, trait, type alias or function, with
+/// bounds and defaults, e.g. `<K, V, S = RandomState>` or `<T, const N: usize>`. A `use`
+/// is followed to its target. Returns `None` for items without generic parameters.
+pub fn generic_params(item: &Item, krate: &Crate) -> Option<String> {
+    let generics = match &item.inner {
+        ItemEnum::Struct(s) => &s.generics,
+        ItemEnum::Enum(e) => &e.generics,
+        ItemEnum::Union(u) => &u.generics,
+        ItemEnum::Trait(t) => &t.generics,
+        ItemEnum::TypeAlias(t) => &t.generics,
+        ItemEnum::Function(f) => &f.generics,
+        ItemEnum::Use(u) if !u.is_glob => {
+            let target = krate.index.get(&u.id?)?;
+            return (!matches!(target.inner, ItemEnum::Use(_)))
+                .then(|| generic_params(target, krate))
+                .flatten();
+        }
+        _ => return None,
+    };
+    let params = format_generics(generics);
+    (!params.is_empty()).then_some(params)
+}
+
+/// Renders a `match` over every documented variant of an enum, binding each payload
+/// field (`v0`, `v1`, ... for tuple variants) with its type noted in a comment. Hidden
+/// fields are matched with `_` or `..`, and `wildcard` adds the `_` arm required for
+/// `#[non_exhaustive]` enums and enums with hidden variants.
+pub fn format_match_skeleton(
+    name: &str,
+    e: &rustdoc_types::Enum,
+    krate: &Crate,
+    wildcard: bool,
+) -> String {
+    let field_type = |id: &Id| match krate.index.get(id).map(|field| &field.inner) {
+        Some(ItemEnum::StructField(ty)) => format_type(ty),
+        _ => "_".to_string(),
+    };
+    let mut s = String::from("match value {\n");
+    for variant_id in &e.variants {
+        let Some(variant) = krate.index.get(variant_id) else {
+            continue;
+        };
+        let variant_name = variant.name.as_deref().unwrap_or("_");
+        // Fields of `#[non_exhaustive]` variants may grow, so patterns need `..`.
+        let open = variant
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, Attribute::NonExhaustive));
+        let mut bindings = Vec::new();
+        let mut types = Vec::new();
+        let pattern = match &variant.inner {
+            ItemEnum::Variant(v) => match &v.kind {
+                rustdoc_types::VariantKind::Plain => String::new(),
+                rustdoc_types::VariantKind::Tuple(fields) if fields.iter().all(Option::is_none) => {
+                    "(..)".to_string()
+                }
+                rustdoc_types::VariantKind::Tuple(fields) => {
+                    for (i, field) in fields.iter().enumerate() {
+                        match field {
+                            Some(id) => {
+                                bindings.push(format!("v{i}"));
+                                types.push(format!("v{i}: {}", field_type(id)));
+                            }
+                            None => bindings.push("_".to_string()),
+                        }
+                    }
+                    if open {
+                        bindings.push("..".to_string());
+                    }
+                    format!("({})", bindings.join(", "))
+                }
+                rustdoc_types::VariantKind::Struct {
+                    fields,
+                    has_stripped_fields,
+                } => {
+                    for id in fields {
+                        let field_name = krate
+                            .index
+                            .get(id)
+                            .and_then(|field| field.name.clone())
+                            .unwrap_or_else(|| "_".to_string());
+                        types.push(format!("{field_name}: {}", field_type(id)));
+                        bindings.push(field_name);
+                    }
+                    if *has_stripped_fields || open {
+                        bindings.push("..".to_string());
+                    }
+                    if bindings.is_empty() {
+                        " {}".to_string()
+                    } else {
+                        format!(" {{ {} }}", bindings.join(", "))
+                    }
+                }
+            },
+            _ => String::new(),
+        };
+        s.push_str(&format!("    {name}::{variant_name}{pattern} => todo!(),"));
+        if !types.is_empty() {
+            s.push_str(&format!(" // {}", types.join(", ")));
+        }
+        s.push('\n');
+    }
+    if wildcard {
+        s.push_str("    _ => todo!(),\n");
+    }
+    s.push('}');
+    s
+}
+
+pub fn format_item_definition(item: &Item) -> String {
+    let name = item.name.as_deref().unwrap_or("");
+    match &item.inner {
+        ItemEnum::Function(f) => {
+            let mut s = String::new();
+
+            if f.header.is_const {
+                s.push_str("const ");
+            }
+            if f.header.is_async {
+                s.push_str("async ");
+            }
+            if f.header.is_unsafe {
+                s.push_str("unsafe ");
+            }
+
+            s.push_str("fn ");
+            s.push_str(name);
+            s.push_str(&format_generics(&f.generics));
+            debug!(
+                "Formatting function: {} with generics: {:?}",
+                name, f.generics
+            );
+            s.push('(');
+            let args: Vec<String> = f
+                .sig
+                .inputs
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", name, format_type(ty)))
+                .collect();
+            s.push_str(&args.join(", "));
+            s.push(')');
+
+            if let Some(output) = &f.sig.output {
+                s.push_str(" -> ");
+                s.push_str(&format_type(output));
+            }
+            s
+        }
+        ItemEnum::Struct(s) => {
+            let mut def = format!("struct {}{}", name, format_generics(&s.generics));
+            match &s.kind {
+                rustdoc_types::StructKind::Unit => {
+                    def.push(';');
+                }
+                rustdoc_types::StructKind::Tuple(_) => {
+                    def.push_str("(/* ... */);");
+                }
+                rustdoc_types::StructKind::Plain { .. } => {
+                    def.push_str(" { ... }");
+                }
+            }
+            def
+        }
+        ItemEnum::Union(u) => format!("union {}{} {{ ... }}", name, format_generics(&u.generics)),
+        ItemEnum::Enum(e) => format!("enum {}{}", name, format_generics(&e.generics)),
+        ItemEnum::Trait(t) => format!("trait {}{}", name, format_generics(&t.generics)),
+        ItemEnum::TypeAlias(t) => {
+            format!(
+                "type {}{} = {};",
+                name,
+                format_generics(&t.generics),
+                format_type(&t.type_)
+            )
+        }
+        ItemEnum::Constant { type_, const_: _ } => {
+            format!("const {}: {} = ...;", name, format_type(type_))
+        }
+        ItemEnum::Static(st) => {
+            format!("static {}: {} = ...;", name, format_type(&st.type_))
+        }
+        ItemEnum::TraitAlias(t) => {
+            let bounds: Vec<String> = t.params.iter().map(format_generic_bound).collect();
+            format!(
+                "trait {}{} = {};",
+                name,
+                format_generics(&t.generics),
+                bounds.join(" + ")
+            )
+        }
+        ItemEnum::Use(u) => format!("use {};", u.source),
+        ItemEnum::StructField(ty) => format!("{name}: {}", format_type(ty)),
+        _ => String::new(),
+    }
+}
+
+/// The one-line signature of an item for a listing, or `None` for items without one,
+/// like modules and macros.
+pub fn item_signature(item: &Item) -> Option<String> {
+    let signature = format_item_definition(item);
+    (!signature.is_empty()).then_some(signature)
+}
+
+/// Renders the choices for a path that several impls provide an item for, each with its
+/// trait-qualified path and signature.
+pub fn format_disambiguation(path: &str, candidates: &[(String, Id)], krate: &Crate) -> String {
+    let mut md = format!(
+        "# `{path}` is ambiguous\n\nSeveral impls provide `{}`. Request one of these paths:\n\n",
+        path.rsplit("::").next().unwrap_or(path)
+    );
+    for (qualified, id) in candidates {
+        match krate.index.get(id) {
+            Some(item) => md.push_str(&format!(
+                "- `{qualified}`: `{}`\n",
+                format_item_definition(item)
+            )),
+            None => md.push_str(&format!("- `{qualified}`\n")),
+        }
+    }
+    md
+}
+
+pub fn format_type(ty: &Type) -> String {
+    match ty {
+        Type::ResolvedPath(p) => format_path_like(&p.path, p.args.as_deref()),
+        Type::Primitive(p) => p.clone(),
+        Type::Tuple(types) => {
+            let types: Vec<String> = types.iter().map(format_type).collect();
+            format!("({})", types.join(", "))
+        }
+        Type::Slice(ty) => format!("[{}]", format_type(ty)),
+        Type::Array { type_, len } => format!("[{}; {}]", format_type(type_), len),
+        Type::BorrowedRef {
+            lifetime,
+            is_mutable,
+            type_,
+        } => {
+            let mut s = String::from("&");
+            if let Some(l) = lifetime {
+                s.push_str(l);
+                s.push(' ');
+            }
+            if *is_mutable {
+                s.push_str("mut ");
+            }
+            s.push_str(&format_type(type_));
+            s
+        }
+        Type::RawPointer { is_mutable, type_ } => {
+            let mut s = String::from("*");
+            s.push_str(if *is_mutable { "mut " } else { "const " });
+            s.push_str(&format_type(type_));
+            s
+        }
+        Type::Generic(name) => name.clone(),
+        Type::ImplTrait(bounds) => {
+            let bounds: Vec<String> = bounds.iter().map(format_generic_bound).collect();
+            format!("impl {}", bounds.join(" + "))
+        }
+        Type::DynTrait(dyn_trait) => {
+            let mut s = String::from("dyn ");
+            if let Some(lifetime) = &dyn_trait.lifetime {
+                s.push_str(lifetime);
+                s.push_str(" + ");
+            }
+            let traits: Vec<String> = dyn_trait
+                .traits
+                .iter()
+                .map(|t| format_path_like(&t.trait_.path, t.trait_.args.as_deref()))
+                .collect();
+            s.push_str(&traits.join(" + "));
+            s
+        }
+        // Fallback for others
+        _ => "_".to_string(),
+    }
+}
+
+fn format_path_like(name: &str, args: Option<&GenericArgs>) -> String {
+    let mut s = name.to_string();
+    if let Some(args) = args {
+        match args {
+            GenericArgs::AngleBracketed { args, constraints } => {
+                if !args.is_empty() || !constraints.is_empty() {
+                    s.push('<');
+                    let mut params = Vec::new();
+                    for arg in args {
+                        match arg {
+                            GenericArg::Lifetime(l) => params.push(l.clone()),
+                            GenericArg::Type(t) => params.push(format_type(t)),
+                            GenericArg::Const(c) => params.push(format!("const {}", c.expr)),
+                            GenericArg::Infer => params.push("_".to_string()),
+                        }
+                    }
+                    for binding in constraints {
+                        match &binding.binding {
+                            AssocItemConstraintKind::Equality(term) => {
+                                let rhs = match term {
+                                    Term::Type(t) => format_type(t),
+                                    Term::Constant(c) => c.expr.clone(),
+                                };
+                                params.push(format!("{} = {}", binding.name, rhs));
+                            }
+                            AssocItemConstraintKind::Constraint(bounds) => {
+                                let bound_str = bounds
+                                    .iter()
+                                    .map(format_generic_bound)
+                                    .collect::<Vec<_>>()
+                                    .join(" + ");
+                                params.push(format!("{}: {}", binding.name, bound_str));
+                            }
+                        }
+                    }
+                    s.push_str(&params.join(", "));
+                    s.push('>');
+                }
+            }
+            GenericArgs::Parenthesized { inputs, output } => {
+                s.push('(');
+                let inputs: Vec<String> = inputs.iter().map(format_type).collect();
+                s.push_str(&inputs.join(", "));
+                s.push(')');
+                if let Some(out) = output {
+                    s.push_str(" -> ");
+                    s.push_str(&format_type(out));
+                }
+            }
+            GenericArgs::ReturnTypeNotation => s.push_str("(..)"),
+        }
+    }
+    s
+}
+
+fn format_precise_capturing_args(precise_capturing_args: &[PreciseCapturingArg]) -> String {
+    let args_str = precise_capturing_args
+        .iter()
+        .map(|arg| match arg {
+            PreciseCapturingArg::Lifetime(name) | PreciseCapturingArg::Param(name) => name.clone(),
+        })
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("use<{args_str}>")
+}
+
+/// Most fields or variants a type may have to be inlined by [`referenced_types_section`].
+const INLINE_MAX_MEMBERS: usize = 6;
+
+fn field_type(krate: &Crate, id: &Id) -> Option<(String, String)> {
+    let field = krate.index.get(id)?;
+    match &field.inner {
+        ItemEnum::StructField(ty) => Some((
+            field.name.clone().unwrap_or_else(|| "_".to_string()),
+            format_type(ty),
+        )),
+        _ => None,
+    }
+}
+
+/// Renders the complete definition of a small struct or enum (at most
+/// `INLINE_MAX_MEMBERS` fields or variants), or of a type alias.
+fn short_definition(item: &Item, krate: &Crate) -> Option<String> {
+    let name = item.name.as_deref()?;
+    let tuple = |fields: &[Option<Id>]| {
+        let types: Vec<String> = fields
+            .iter()
+            .map(|id| {
+                id.as_ref()
+                    .and_then(|id| field_type(krate, id))
+                    .map_or("_".to_string(), |(_, ty)| ty)
+            })
+            .collect();
+        types.join(", ")
+    };
+    let definition = match &item.inner {
+        ItemEnum::Struct(s) => {
+            let head = format!("pub struct {name}{}", format_generics(&s.generics));
+            match &s.kind {
+                rustdoc_types::StructKind::Unit => format!("{head};"),
+                rustdoc_types::StructKind::Tuple(fields) => {
+                    if fields.len() > INLINE_MAX_MEMBERS {
+                        return None;
+                    }
+                    format!("{head}({});", tuple(fields))
+                }
+                rustdoc_types::StructKind::Plain {
+                    fields,
+                    has_stripped_fields,
+                } => {
+                    if fields.len() > INLINE_MAX_MEMBERS {
+                        return None;
+                    }
+                    let mut def = format!("{head} {{\n");
+                    for (field, ty) in fields.iter().filter_map(|id| field_type(krate, id)) {
+                        def.push_str(&format!("    pub {field}: {ty},\n"));
+                    }
+                    if *has_stripped_fields {
+                        def.push_str("    /* private fields */\n");
+                    }
+                    def.push('}');
+                    def
+                }
+            }
+        }
+        ItemEnum::Enum(e) => {
+            if e.variants.len() > INLINE_MAX_MEMBERS {
+                return None;
+            }
+            let mut def = format!("pub enum {name}{} {{\n", format_generics(&e.generics));
+            for variant in e.variants.iter().filter_map(|id| krate.index.get(id)) {
+                let (Some(variant_name), ItemEnum::Variant(v)) = (&variant.name, &variant.inner)
+                else {
+                    continue;
+                };
+                let line = match &v.kind {
+                    rustdoc_types::VariantKind::Plain => variant_name.clone(),
+                    rustdoc_types::VariantKind::Tuple(fields) => {
+                        format!("{variant_name}({})", tuple(fields))
+                    }
+                    rustdoc_types::VariantKind::Struct { fields, .. } => {
+                        let fields: Vec<String> = fields
+                            .iter()
+                            .filter_map(|id| field_type(krate, id))
+                            .map(|(field, ty)| format!("{field}: {ty}"))
+                            .collect();
+                        format!("{variant_name} {{ {} }}", fields.join(", "))
+                    }
+                };
+                def.push_str(&format!("    {line},\n"));
+            }
+            if e.has_stripped_variants {
+                def.push_str("    // some variants omitted\n");
+            }
+            def.push('}');
+            def
+        }
+        ItemEnum::TypeAlias(_) => format!("pub {}", format_item_definition(item)),
+        _ => return None,
+    };
+    Some(match doc_summary(item) {
+        Some(summary) => format!("/// {summary}\n{definition}"),
+        None => definition,
+    })
+}
+
+/// Inlines the definitions of the small types from the same crate that an item's
+/// signature, fields or variants mention (one level deep), so the item can be used
+/// without looking each of them up.
+pub fn referenced_types_section(krate: &Crate, item: &Item) -> Option<String> {
+    let mut seen = vec![item.id];
+    let mut definitions = Vec::new();
+    for (_, id) in with_members(krate, item)
+        .into_iter()
+        .flat_map(item_references)
+    {
+        if seen.contains(&id) {
+            continue;
+        }
+        seen.push(id);
+        if let Some(referenced) = krate.index.get(&id)
+            && referenced.crate_id == 0
+            && let Some(definition) = short_definition(referenced, krate)
+        {
+            definitions.push(definition);
+        }
+    }
+    if definitions.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "\n## Referenced Types\n\n```rust\n{}\n```\n",
+        definitions.join("\n\n")
+    ))
+}
+
+/// Most siblings listed by [`related_items_section`].
+const RELATED_MAX_ITEMS: usize = 10;
+
+/// Lists the other items of the module an item is defined in, with their kinds, so that
+/// `try_from_str` is found next to `from_str` without listing the whole module.
+pub fn related_items_section(krate: &Crate, item: &Item) -> Option<String> {
+    let module = krate
+        .index
+        .values()
+        .find_map(|candidate| match &candidate.inner {
+            ItemEnum::Module(module) if module.items.contains(&item.id) => Some(module),
+            _ => None,
+        })?;
+    let siblings: Vec<String> = module
+        .items
+        .iter()
+        .filter(|id| **id != item.id)
+        .filter_map(|id| krate.index.get(id))
+        .filter(|sibling| !matches!(&sibling.inner, ItemEnum::Use(use_) if use_.is_glob))
+        .filter_map(|sibling| {
+            let name = sibling.name.as_deref()?;
+            Some(format!("- `{name}` ({})", get_item_kind(sibling)))
+        })
+        .collect();
+    if siblings.is_empty() {
+        return None;
+    }
+    let mut section = "\n## Related Items\n\n".to_string();
+    for sibling in siblings.iter().take(RELATED_MAX_ITEMS) {
+        section.push_str(sibling);
+        section.push('\n');
+    }
+    if siblings.len() > RELATED_MAX_ITEMS {
+        section.push_str(&format!(
+            "- ...and {} more, listed by `get_module`\n",
+            siblings.len() - RELATED_MAX_ITEMS
+        ));
+    }
+    Some(section)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_types::{Crate, Generics, Id, Item, ItemEnum, Span, StructKind, Visibility};
+    use std::collections::HashMap;
+
+    fn create_dummy_item(name: &str, inner: ItemEnum) -> Item {
+        let id_val = name.len() as u32;
+        Item {
+            id: Id(id_val),
+            crate_id: 0,
+            name: Some(name.to_string()),
+            span: Some(Span {
+                filename: Default::default(),
+                begin: (0, 0),
+                end: (0, 0),
+            }),
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner,
+        }
+    }
+
+    fn create_dummy_crate() -> Crate {
+        Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_format_type_primitive() {
+        let ty = Type::Primitive("i32".to_string());
+        assert_eq!(format_type(&ty), "i32");
+    }
+
+    #[test]
+    fn test_format_type_tuple() {
+        let ty = Type::Tuple(vec![
+            Type::Primitive("i32".to_string()),
+            Type::Primitive("String".to_string()),
+        ]);
+        assert_eq!(format_type(&ty), "(i32, String)");
+    }
+
+    #[test]
+    fn test_format_type_slice() {
+        let ty = Type::Slice(Box::new(Type::Primitive("u8".to_string())));
+        assert_eq!(format_type(&ty), "[u8]");
+    }
+
+    #[test]
+    fn test_generate_struct_markdown() {
+        let krate = create_dummy_crate();
+        let item = create_dummy_item(
+            "MyStruct",
+            ItemEnum::Struct(rustdoc_types::Struct {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                kind: StructKind::Plain {
+                    fields: vec![],
+                    has_stripped_fields: false,
+                },
+                impls: vec![],
+            }),
+        );
+
+        let md = generate_item_markdown(&item, &krate);
+        assert!(md.contains("# Struct MyStruct"));
+        assert!(md.contains("struct MyStruct { ... }"));
+    }
+
+    #[test]
+    fn test_generic_params() {
+        let mut krate = create_dummy_crate();
+        let param = |name: &str, kind| rustdoc_types::GenericParamDef {
+            name: name.to_string(),
+            kind,
+        };
+        let type_param = |default: Option<&str>| GenericParamDefKind::Type {
+            bounds: vec![],
+            default: default.map(|d| Type::Primitive(d.to_string())),
+            is_synthetic: false,
+        };
+        let item = create_dummy_item(
+            "Map",
+            ItemEnum::Struct(rustdoc_types::Struct {
+                generics: Generics {
+                    params: vec![
+                        param("K", type_param(None)),
+                        param("S", type_param(Some("RandomState"))),
+                        param(
+                            "N",
+                            GenericParamDefKind::Const {
+                                type_: Type::Primitive("usize".to_string()),
+                                default: None,
+                            },
+                        ),
+                    ],
+                    where_predicates: vec![],
+                },
+                kind: StructKind::Unit,
+                impls: vec![],
+            }),
+        );
+        assert_eq!(
+            generic_params(&item, &krate).as_deref(),
+            Some("<K, S = RandomState, const N: usize>")
+        );
+
+        let reexport = create_dummy_item(
+            "reexport",
+            ItemEnum::Use(rustdoc_types::Use {
+                source: "inner::Map".to_string(),
+                name: "Map".to_string(),
+                id: Some(item.id),
+                is_glob: false,
+            }),
+        );
+        krate.index.insert(item.id, item);
+        assert_eq!(
+            generic_params(&reexport, &krate).as_deref(),
+            Some("<K, S = RandomState, const N: usize>")
+        );
+        assert_eq!(
+            generic_params(&create_dummy_item("f", ItemEnum::ExternType), &krate),
+            None
+        );
+    }
+
+    #[test]
+    fn test_generate_enum_markdown() {
+        let krate = create_dummy_crate();
+        let item = create_dummy_item(
+            "MyEnum",
+            ItemEnum::Enum(rustdoc_types::Enum {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                variants: vec![],
+                impls: vec![],
+                has_stripped_variants: false,
+            }),
+        );
+
+        let md = generate_item_markdown(&item, &krate);
+        assert!(md.contains("# Enum MyEnum"));
+        assert!(md.contains("enum MyEnum"));
+    }
+
+    #[test]
+    fn test_generate_non_exhaustive_enum_markdown() {
+        let mut krate = create_dummy_crate();
+        let unit = create_dummy_item(
+            "A",
+            ItemEnum::Variant(rustdoc_types::Variant {
+                kind: rustdoc_types::VariantKind::Plain,
+                discriminant: None,
+            }),
+        );
+        let tuple = create_dummy_item(
+            "Bb",
+            ItemEnum::Variant(rustdoc_types::Variant {
+                kind: rustdoc_types::VariantKind::Tuple(vec![None]),
+                discriminant: None,
+            }),
+        );
+        let variants = vec![unit.id, tuple.id];
+        krate.index.insert(unit.id, unit);
+        krate.index.insert(tuple.id, tuple);
+
+        let mut item = create_dummy_item(
+            "MyEnum",
+            ItemEnum::Enum(rustdoc_types::Enum {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                variants,
+                impls: vec![],
+                has_stripped_variants: false,
+            }),
+        );
+        item.attrs.push(Attribute::NonExhaustive);
+
+        let md = generate_item_markdown(&item, &krate);
+        assert!(md.contains("## Matching"));
+        assert!(md.contains("MyEnum::A => todo!(),"));
+        assert!(md.contains("MyEnum::Bb(..) => todo!(),"));
+        assert!(md.contains("_ => todo!(),"));
+    }
+
+    #[test]
+    fn test_generate_struct_variant_markdown() {
+        let mut krate = create_dummy_crate();
+        let mut field = create_dummy_item(
+            "code",
+            ItemEnum::StructField(Type::Primitive("u32".to_string())),
+        );
+        field.docs = Some("The key code.".to_string());
+        let variant = create_dummy_item(
+            "Key",
+            ItemEnum::Variant(rustdoc_types::Variant {
+                kind: rustdoc_types::VariantKind::Struct {
+                    fields: vec![field.id],
+                    has_stripped_fields: false,
+                },
+                discriminant: None,
+            }),
+        );
+        let item = create_dummy_item(
+            "Event",
+            ItemEnum::Enum(rustdoc_types::Enum {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                variants: vec![variant.id],
+                impls: vec![],
+                has_stripped_variants: false,
+            }),
+        );
+        krate.index.insert(field.id, field.clone());
+        krate.index.insert(variant.id, variant.clone());
+
+        let md = generate_item_markdown(&item, &krate);
+        assert!(md.contains("`Key { code: u32 }`"));
+
+        let e = match &item.inner {
+            ItemEnum::Enum(e) => e,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            format_match_skeleton("Event", e, &krate, false),
+            "match value {\n    Event::Key { code } => todo!(), // code: u32\n}"
+        );
+
+        let md = generate_item_markdown(&variant, &krate);
+        assert!(md.contains("## Fields"));
+        assert!(md.contains("`code: u32` - The key code."));
+
+        let md = generate_item_markdown(&field, &krate);
+        assert!(md.contains("code: u32"));
+    }
+
+    #[test]
+    fn test_generate_function_markdown() {
+        let krate = create_dummy_crate();
+        let item = create_dummy_item(
+            "my_fn",
+            ItemEnum::Function(rustdoc_types::Function {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                header: rustdoc_types::FunctionHeader {
+                    is_const: false,
+                    is_unsafe: false,
+                    is_async: false,
+                    abi: rustdoc_types::Abi::Rust,
+                },
+                has_body: true,
+                sig: rustdoc_types::FunctionSignature {
+                    inputs: vec![("arg1".to_string(), Type::Primitive("i32".to_string()))],
+                    output: Some(Type::Primitive("bool".to_string())),
+                    is_c_variadic: false,
+                },
+            }),
+        );
+
+        let md = generate_item_markdown(&item, &krate);
+        assert!(md.contains("# Function my_fn"));
+        assert!(md.contains("fn my_fn(arg1: i32) -> bool"));
+    }
+
+    #[test]
+    fn test_deprecation_banner() {
+        let krate = create_dummy_crate();
+        let mut item = create_dummy_item("old_fn", ItemEnum::ExternType);
+        let md = generate_item_markdown(&item, &krate);
+        assert!(!md.contains("Deprecated"));
+
+        item.deprecation = Some(rustdoc_types::Deprecation {
+            since: Some("0.2.0".to_string()),
+            note: Some("use `other_fn`".to_string()),
+        });
+        item.attrs.push(Attribute::MustUse { reason: None });
+        let md = generate_item_markdown(&item, &krate);
+        assert!(md.contains("**Deprecated since 0.2.0: use `other_fn`**"));
+        assert_eq!(md.matches("Deprecated").count(), 1);
+        assert!(md.contains("`#[must_use]`"));
+    }
+
+    #[test]
+    fn test_referenced_types_section() {
+        let mut krate = create_dummy_crate();
+        let field = create_dummy_item(
+            "code",
+            ItemEnum::StructField(Type::Primitive("u32".to_string())),
+        );
+        let variant = create_dummy_item(
+            "Key",
+            ItemEnum::Variant(rustdoc_types::Variant {
+                kind: rustdoc_types::VariantKind::Struct {
+                    fields: vec![field.id],
+                    has_stripped_fields: false,
+                },
+                discriminant: None,
+            }),
+        );
+        let mut event = create_dummy_item(
+            "Event",
+            ItemEnum::Enum(rustdoc_types::Enum {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                variants: vec![variant.id],
+                impls: vec![],
+                has_stripped_variants: true,
+            }),
+        );
+        event.docs = Some("An input event.\n\nMore details.".to_string());
+        let item = create_dummy_item(
+            "handle",
+            ItemEnum::Function(rustdoc_types::Function {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                header: rustdoc_types::FunctionHeader {
+                    is_const: false,
+                    is_unsafe: false,
+                    is_async: false,
+                    abi: rustdoc_types::Abi::Rust,
+                },
+                has_body: true,
+                sig: rustdoc_types::FunctionSignature {
+                    inputs: vec![(
+                        "event".to_string(),
+                        Type::ResolvedPath(rustdoc_types::Path {
+                            path: "Event".to_string(),
+                            id: event.id,
+                            args: None,
+                        }),
+                    )],
+                    output: None,
+                    is_c_variadic: false,
+                },
+            }),
+        );
+        krate.index.insert(field.id, field);
+        krate.index.insert(variant.id, variant);
+        krate.index.insert(event.id, event);
+
+        let section = referenced_types_section(&krate, &item).unwrap();
+        assert!(section.contains("## Referenced Types"));
+        assert!(section.contains(
+            "/// An input event.\npub enum Event {\n    Key { code: u32 },\n    // some variants omitted\n}"
+        ));
+
+        let unrelated = create_dummy_item("unrelated_fn", ItemEnum::ExternType);
+        assert_eq!(referenced_types_section(&krate, &unrelated), None);
+    }
+
+    #[test]
+    fn test_related_items_section() {
+        let mut krate = create_dummy_crate();
+        let unit = || {
+            ItemEnum::Struct(rustdoc_types::Struct {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                kind: StructKind::Unit,
+                impls: vec![],
+            })
+        };
+        let item = create_dummy_item("Config", unit());
+        let sibling = create_dummy_item("ConfigBuilder", unit());
+        let module = create_dummy_item(
+            "demo",
+            ItemEnum::Module(rustdoc_types::Module {
+                is_crate: true,
+                items: vec![item.id, sibling.id],
+                is_stripped: false,
+            }),
+        );
+        krate.index.insert(item.id, item.clone());
+        krate.index.insert(sibling.id, sibling);
+        krate.index.insert(module.id, module);
+
+        assert_eq!(
+            related_items_section(&krate, &item).as_deref(),
+            Some("\n## Related Items\n\n- `ConfigBuilder` (struct)\n")
+        );
+        let unrelated = create_dummy_item("unrelated_fn", ItemEnum::ExternType);
+        assert_eq!(related_items_section(&krate, &unrelated), None);
+    }
+}
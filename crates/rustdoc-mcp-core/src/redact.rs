@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use rustdoc_types::{Crate, Id};
+use serde_json::Value;
+
+use crate::config::{RedactConfig, wildcard_match};
+
+impl RedactConfig {
+    pub fn is_active(&self) -> bool {
+        !self.patterns.is_empty() || !self.paths.is_empty()
+    }
+
+    /// Whether the item at `path` falls under the configured paths.
+    pub fn withholds(&self, path: &str) -> bool {
+        self.paths.iter().any(|glob| wildcard_match(glob, path))
+    }
+
+    /// Replaces every match of the configured patterns in `text`.
+    pub fn redact_text(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for pattern in &self.patterns {
+            text = pattern
+                .replace_all(&text, self.replacement.as_str())
+                .into_owned();
+        }
+        text
+    }
+
+    /// Redacts item docs as a crate is loaded, so that search, headings and guides never
+    /// see them: docs of items under the configured paths are replaced entirely and
+    /// pattern matches are replaced everywhere else.
+    pub fn redact_crate(&self, krate: &mut Crate, path_to_id: &HashMap<String, Id>) {
+        if !self.is_active() {
+            return;
+        }
+        for (path, id) in path_to_id {
+            if self.withholds(path)
+                && let Some(item) = krate.index.get_mut(id)
+                && item.docs.is_some()
+            {
+                item.docs = Some(self.replacement.clone());
+            }
+        }
+        for item in krate.index.values_mut() {
+            if let Some(docs) = &item.docs {
+                item.docs = Some(self.redact_text(docs));
+            }
+        }
+    }
+
+    /// Replaces every match of the configured patterns in the strings of `value`.
+    pub fn redact_json(&self, value: &mut Value) {
+        match value {
+            Value::String(s) => *s = self.redact_text(s),
+            Value::Array(values) => values.iter_mut().for_each(|v| self.redact_json(v)),
+            Value::Object(map) => map.values_mut().for_each(|v| self.redact_json(v)),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[test]
+    fn test_redact_text_and_json() {
+        let config = Config::parse(
+            "[redact]\npatterns = [\"sk_[a-z0-9]+\", \"https://internal\\\\.corp/\\\\S*\"]\n",
+        )
+        .unwrap()
+        .redact;
+        assert_eq!(
+            config.redact_text("Use sk_live42 against https://internal.corp/api now"),
+            "Use [REDACTED] against [REDACTED] now"
+        );
+
+        let mut value = serde_json::json!({"docs": ["token sk_abc"], "count": 1});
+        config.redact_json(&mut value);
+        assert_eq!(
+            value,
+            serde_json::json!({"docs": ["token [REDACTED]"], "count": 1})
+        );
+    }
+
+    #[test]
+    fn test_withholds() {
+        let config = Config::parse("[redact]\npaths = [\"demo::internal::*\"]\n")
+            .unwrap()
+            .redact;
+        assert!(config.withholds("demo::internal::Secret"));
+        assert!(!config.withholds("demo::Greeter"));
+    }
+}
@@ -0,0 +1,559 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use rustdoc_types::{
+    AssocItemConstraintKind, Crate, GenericArg, GenericArgs, GenericBound, GenericParamDefKind,
+    Generics, Id, Item, ItemEnum, Path, StructKind, Term, Type, VariantKind, WherePredicate,
+};
+
+use crate::index::LoadedCrate;
+use crate::types::ItemReference;
+
+/// Collects the ids of all items named in a type, including generic arguments.
+pub fn type_references(ty: &Type, out: &mut Vec<Id>) {
+    let mut paths = Vec::new();
+    type_paths(ty, &mut paths);
+    out.extend(paths.into_iter().map(|path| path.id));
+}
+
+/// Collects every resolved path named in a type, outermost first.
+fn type_paths<'a>(ty: &'a Type, out: &mut Vec<&'a Path>) {
+    match ty {
+        Type::ResolvedPath(p) => path_paths(p, out),
+        Type::DynTrait(dyn_trait) => {
+            for poly in &dyn_trait.traits {
+                path_paths(&poly.trait_, out);
+            }
+        }
+        Type::ImplTrait(bounds) => bounds_paths(bounds, out),
+        Type::FunctionPointer(f) => {
+            for (_, ty) in &f.sig.inputs {
+                type_paths(ty, out);
+            }
+            if let Some(ty) = &f.sig.output {
+                type_paths(ty, out);
+            }
+        }
+        Type::Tuple(types) => {
+            for ty in types {
+                type_paths(ty, out);
+            }
+        }
+        Type::Slice(ty)
+        | Type::Array { type_: ty, .. }
+        | Type::BorrowedRef { type_: ty, .. }
+        | Type::RawPointer { type_: ty, .. } => type_paths(ty, out),
+        Type::QualifiedPath {
+            self_type, trait_, ..
+        } => {
+            type_paths(self_type, out);
+            if let Some(trait_) = trait_ {
+                path_paths(trait_, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn path_paths<'a>(path: &'a Path, out: &mut Vec<&'a Path>) {
+    out.push(path);
+    match path.args.as_deref() {
+        Some(GenericArgs::AngleBracketed { args, constraints }) => {
+            for arg in args {
+                if let GenericArg::Type(ty) = arg {
+                    type_paths(ty, out);
+                }
+            }
+            for constraint in constraints {
+                match &constraint.binding {
+                    AssocItemConstraintKind::Equality(Term::Type(ty)) => type_paths(ty, out),
+                    AssocItemConstraintKind::Constraint(bounds) => bounds_paths(bounds, out),
+                    _ => {}
+                }
+            }
+        }
+        Some(GenericArgs::Parenthesized { inputs, output }) => {
+            for ty in inputs {
+                type_paths(ty, out);
+            }
+            if let Some(ty) = output {
+                type_paths(ty, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn bounds_paths<'a>(bounds: &'a [GenericBound], out: &mut Vec<&'a Path>) {
+    for bound in bounds {
+        if let GenericBound::TraitBound { trait_, .. } = bound {
+            path_paths(trait_, out);
+        }
+    }
+}
+
+fn generics_paths<'a>(generics: &'a Generics, out: &mut Vec<&'a Path>) {
+    for param in &generics.params {
+        match &param.kind {
+            GenericParamDefKind::Type {
+                bounds, default, ..
+            } => {
+                bounds_paths(bounds, out);
+                if let Some(ty) = default {
+                    type_paths(ty, out);
+                }
+            }
+            GenericParamDefKind::Const { type_, .. } => type_paths(type_, out),
+            _ => {}
+        }
+    }
+    for predicate in &generics.where_predicates {
+        match predicate {
+            WherePredicate::BoundPredicate { type_, bounds, .. } => {
+                type_paths(type_, out);
+                bounds_paths(bounds, out);
+            }
+            WherePredicate::EqPredicate { lhs, rhs } => {
+                type_paths(lhs, out);
+                if let Term::Type(ty) = rhs {
+                    type_paths(ty, out);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn collect<'a>(walk: impl FnOnce(&mut Vec<&'a Path>)) -> Vec<&'a Path> {
+    let mut paths = Vec::new();
+    walk(&mut paths);
+    paths
+}
+
+/// Returns the ids an item's signature refers to, each with the role it plays there:
+/// `field`, `parameter`, `return type`, `bound`, `supertrait`, `alias` or `type`.
+pub fn item_references(item: &Item) -> Vec<(&'static str, Id)> {
+    item_paths(item)
+        .into_iter()
+        .map(|(role, path)| (role, path.id))
+        .collect()
+}
+
+fn item_paths<'a>(item: &'a Item) -> Vec<(&'static str, &'a Path)> {
+    let mut refs = Vec::new();
+    let mut push = |role: &'static str, paths: Vec<&'a Path>| {
+        refs.extend(paths.into_iter().map(|path| (role, path)));
+    };
+
+    match &item.inner {
+        ItemEnum::StructField(ty) => push("field", collect(|out| type_paths(ty, out))),
+        ItemEnum::Function(f) => {
+            push(
+                "parameter",
+                collect(|out| {
+                    for (_, ty) in &f.sig.inputs {
+                        type_paths(ty, out);
+                    }
+                }),
+            );
+            if let Some(ty) = &f.sig.output {
+                push("return type", collect(|out| type_paths(ty, out)));
+            }
+            push("bound", collect(|out| generics_paths(&f.generics, out)));
+        }
+        ItemEnum::Struct(s) => push("bound", collect(|out| generics_paths(&s.generics, out))),
+        ItemEnum::Enum(e) => push("bound", collect(|out| generics_paths(&e.generics, out))),
+        ItemEnum::Union(u) => push("bound", collect(|out| generics_paths(&u.generics, out))),
+        ItemEnum::Trait(t) => {
+            push("supertrait", collect(|out| bounds_paths(&t.bounds, out)));
+            push("bound", collect(|out| generics_paths(&t.generics, out)));
+        }
+        ItemEnum::TypeAlias(alias) => {
+            push("alias", collect(|out| type_paths(&alias.type_, out)));
+            push("bound", collect(|out| generics_paths(&alias.generics, out)));
+        }
+        ItemEnum::Constant { type_, .. } | ItemEnum::AssocConst { type_, .. } => {
+            push("type", collect(|out| type_paths(type_, out)));
+        }
+        ItemEnum::Static(s) => push("type", collect(|out| type_paths(&s.type_, out))),
+        ItemEnum::AssocType { bounds, type_, .. } => {
+            push("bound", collect(|out| bounds_paths(bounds, out)));
+            if let Some(ty) = type_ {
+                push("type", collect(|out| type_paths(ty, out)));
+            }
+        }
+        _ => {}
+    }
+    refs
+}
+
+/// Returns every type an item's signature mentions as a typed reference: the name as
+/// written in the signature plus, when rustdoc's path summaries know it, the owning crate
+/// and defining path. Each id is reported once, in signature order.
+pub fn signature_references(krate: &Crate, item: &Item) -> Vec<ItemReference> {
+    let mut seen = BTreeSet::new();
+    item_paths(item)
+        .into_iter()
+        .filter(|(_, path)| seen.insert(path.id))
+        .map(|(_, path)| {
+            let summary = krate.paths.get(&path.id);
+            let crate_name = summary.and_then(|summary| {
+                if summary.crate_id == 0 {
+                    krate.index.get(&krate.root)?.name.clone()
+                } else {
+                    Some(krate.external_crates.get(&summary.crate_id)?.name.clone())
+                }
+            });
+            ItemReference {
+                display: path.path.clone(),
+                crate_name,
+                path: summary.map(|summary| summary.path.join("::")),
+                id: path.id.0,
+            }
+        })
+        .collect()
+}
+
+/// Returns an item followed by its fields, variant fields or trait items: everything whose
+/// signature is part of the item's own.
+pub fn with_members<'a>(krate: &'a Crate, item: &'a Item) -> Vec<&'a Item> {
+    let mut items = vec![item];
+    let children: Vec<&Id> = match &item.inner {
+        ItemEnum::Struct(s) => match &s.kind {
+            StructKind::Plain { fields, .. } => fields.iter().collect(),
+            StructKind::Tuple(fields) => fields.iter().flatten().collect(),
+            StructKind::Unit => Vec::new(),
+        },
+        ItemEnum::Enum(e) => e
+            .variants
+            .iter()
+            .filter_map(|id| krate.index.get(id))
+            .flat_map(|variant| match &variant.inner {
+                ItemEnum::Variant(v) => match &v.kind {
+                    VariantKind::Tuple(fields) => fields.iter().flatten().collect(),
+                    VariantKind::Struct { fields, .. } => fields.iter().collect(),
+                    VariantKind::Plain => Vec::new(),
+                },
+                _ => Vec::new(),
+            })
+            .collect(),
+        ItemEnum::Trait(t) => t.items.iter().collect(),
+        _ => Vec::new(),
+    };
+    items.extend(children.into_iter().filter_map(|id| krate.index.get(id)));
+    items
+}
+
+/// Groups the external items an item's signature mentions by the crate that owns them,
+/// as recorded in rustdoc's `external_crates`. Fields, variant fields and trait items are
+/// included, so a struct reports the crates of its field types.
+pub fn external_references(krate: &Crate, item: &Item) -> BTreeMap<String, BTreeSet<String>> {
+    let ids = with_members(krate, item)
+        .into_iter()
+        .flat_map(item_references)
+        .map(|(_, id)| id);
+    by_crate(krate, ids)
+}
+
+/// Groups the external items among `ids` by the crate that owns them, as defining paths.
+fn by_crate(krate: &Crate, ids: impl Iterator<Item = Id>) -> BTreeMap<String, BTreeSet<String>> {
+    let mut crates: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    for id in ids {
+        let Some(summary) = krate.paths.get(&id) else {
+            continue;
+        };
+        if let Some(external) = krate.external_crates.get(&summary.crate_id) {
+            crates
+                .entry(external.name.clone())
+                .or_default()
+                .insert(summary.path.join("::"));
+        }
+    }
+    crates
+}
+
+/// Groups the external items an item's intra-doc links (like `[Deserialize]`) point to by
+/// the crate that owns them.
+pub fn doc_link_references(krate: &Crate, item: &Item) -> BTreeMap<String, BTreeSet<String>> {
+    by_crate(krate, item.links.values().copied())
+}
+
+/// Finds the public items of `loaded` whose signatures mention the item at `target_path`
+/// (its defining path, as recorded in rustdoc's path summaries). Returns each referencing
+/// item path with the role the target plays, sorted and without duplicates.
+pub fn find_references(loaded: &LoadedCrate, target_path: &str) -> Vec<(String, &'static str)> {
+    let mut found: Vec<(String, &'static str)> = loaded
+        .path_to_id
+        .iter()
+        .filter_map(|(path, id)| Some((path, loaded.krate.index.get(id)?)))
+        .flat_map(|(path, item)| {
+            item_references(item)
+                .into_iter()
+                .filter(|(_, id)| {
+                    loaded
+                        .krate
+                        .paths
+                        .get(id)
+                        .is_some_and(|summary| summary.path.join("::") == target_path)
+                })
+                .map(|(role, _)| (path.clone(), role))
+        })
+        .collect();
+    found.sort();
+    found.dedup();
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn path(name: &str, id: u32, args: Vec<Type>) -> Type {
+        Type::ResolvedPath(Path {
+            path: name.to_string(),
+            id: Id(id),
+            args: (!args.is_empty()).then(|| {
+                Box::new(GenericArgs::AngleBracketed {
+                    args: args.into_iter().map(GenericArg::Type).collect(),
+                    constraints: vec![],
+                })
+            }),
+        })
+    }
+
+    #[test]
+    fn test_type_references() {
+        let ty = Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: false,
+            type_: Box::new(path(
+                "Vec",
+                1,
+                vec![path("Arc", 2, vec![path("Config", 3, vec![])])],
+            )),
+        };
+        let mut ids = Vec::new();
+        type_references(&ty, &mut ids);
+        assert_eq!(ids, vec![Id(1), Id(2), Id(3)]);
+    }
+
+    #[test]
+    fn test_item_references() {
+        let item = Item {
+            id: Id(0),
+            crate_id: 0,
+            name: Some("connect".to_string()),
+            span: None,
+            visibility: rustdoc_types::Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner: ItemEnum::Function(rustdoc_types::Function {
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                header: rustdoc_types::FunctionHeader {
+                    is_const: false,
+                    is_unsafe: false,
+                    is_async: false,
+                    abi: rustdoc_types::Abi::Rust,
+                },
+                has_body: true,
+                sig: rustdoc_types::FunctionSignature {
+                    inputs: vec![("config".to_string(), path("Config", 3, vec![]))],
+                    output: Some(path("Result", 4, vec![path("Client", 5, vec![])])),
+                    is_c_variadic: false,
+                },
+            }),
+        };
+        assert_eq!(
+            item_references(&item),
+            vec![
+                ("parameter", Id(3)),
+                ("return type", Id(4)),
+                ("return type", Id(5))
+            ]
+        );
+    }
+
+    fn empty_crate() -> Crate {
+        Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        }
+    }
+
+    #[test]
+    fn test_external_references() {
+        let mut krate = empty_crate();
+        krate.external_crates.insert(
+            7,
+            rustdoc_types::ExternalCrate {
+                name: "proc_macro2".to_string(),
+                html_root_url: None,
+                path: Default::default(),
+            },
+        );
+        krate.paths.insert(
+            Id(3),
+            rustdoc_types::ItemSummary {
+                crate_id: 7,
+                path: vec!["proc_macro2".to_string(), "TokenStream".to_string()],
+                kind: rustdoc_types::ItemKind::Struct,
+            },
+        );
+        krate.paths.insert(
+            Id(4),
+            rustdoc_types::ItemSummary {
+                crate_id: 0,
+                path: vec!["demo".to_string(), "Output".to_string()],
+                kind: rustdoc_types::ItemKind::Struct,
+            },
+        );
+
+        let field = Item {
+            id: Id(1),
+            crate_id: 0,
+            name: Some("tokens".to_string()),
+            span: None,
+            visibility: rustdoc_types::Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner: ItemEnum::StructField(path("Vec", 4, vec![path("TokenStream", 3, vec![])])),
+        };
+        let mut strukt = field.clone();
+        strukt.id = Id(2);
+        strukt.inner = ItemEnum::Struct(rustdoc_types::Struct {
+            kind: StructKind::Plain {
+                fields: vec![Id(1)],
+                has_stripped_fields: false,
+            },
+            generics: Generics {
+                params: vec![],
+                where_predicates: vec![],
+            },
+            impls: vec![],
+        });
+        krate.index.insert(field.id, field);
+
+        let crates = external_references(&krate, &strukt);
+        assert_eq!(crates.len(), 1);
+        assert!(crates["proc_macro2"].contains("proc_macro2::TokenStream"));
+    }
+
+    #[test]
+    fn test_doc_link_references() {
+        let mut krate = empty_crate();
+        krate.external_crates.insert(
+            2,
+            rustdoc_types::ExternalCrate {
+                name: "serde".to_string(),
+                html_root_url: None,
+                path: Default::default(),
+            },
+        );
+        krate.paths.insert(
+            Id(5),
+            rustdoc_types::ItemSummary {
+                crate_id: 2,
+                path: vec!["serde".to_string(), "Deserialize".to_string()],
+                kind: rustdoc_types::ItemKind::Trait,
+            },
+        );
+        krate.paths.insert(
+            Id(6),
+            rustdoc_types::ItemSummary {
+                crate_id: 0,
+                path: vec!["demo".to_string(), "Config".to_string()],
+                kind: rustdoc_types::ItemKind::Struct,
+            },
+        );
+        let item = Item {
+            id: Id(1),
+            crate_id: 0,
+            name: Some("load".to_string()),
+            span: None,
+            visibility: rustdoc_types::Visibility::Public,
+            docs: Some("Loads a [`Config`] with [`Deserialize`].".to_string()),
+            links: HashMap::from([
+                ("`Config`".to_string(), Id(6)),
+                ("`Deserialize`".to_string(), Id(5)),
+            ]),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner: ItemEnum::ExternType,
+        };
+
+        let crates = doc_link_references(&krate, &item);
+        assert_eq!(crates.len(), 1);
+        assert!(crates["serde"].contains("serde::Deserialize"));
+    }
+
+    #[test]
+    fn test_signature_references() {
+        let mut krate = empty_crate();
+        krate.external_crates.insert(
+            1,
+            rustdoc_types::ExternalCrate {
+                name: "alloc".to_string(),
+                html_root_url: None,
+                path: Default::default(),
+            },
+        );
+        krate.paths.insert(
+            Id(4),
+            rustdoc_types::ItemSummary {
+                crate_id: 1,
+                path: vec!["alloc".to_string(), "vec".to_string(), "Vec".to_string()],
+                kind: rustdoc_types::ItemKind::Struct,
+            },
+        );
+
+        let field = Item {
+            id: Id(1),
+            crate_id: 0,
+            name: Some("items".to_string()),
+            span: None,
+            visibility: rustdoc_types::Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner: ItemEnum::StructField(path(
+                "Vec",
+                4,
+                vec![path("Opaque", 9, vec![]), path("Vec", 4, vec![])],
+            )),
+        };
+        assert_eq!(
+            signature_references(&krate, &field),
+            vec![
+                ItemReference {
+                    display: "Vec".to_string(),
+                    crate_name: Some("alloc".to_string()),
+                    path: Some("alloc::vec::Vec".to_string()),
+                    id: 4,
+                },
+                ItemReference {
+                    display: "Opaque".to_string(),
+                    crate_name: None,
+                    path: None,
+                    id: 9,
+                },
+            ]
+        );
+    }
+}
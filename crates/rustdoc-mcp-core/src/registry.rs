@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use cargo_metadata::Source;
+
+/// Index URLs of crates.io, in git and sparse form.
+const CRATES_IO_INDEXES: [&str; 2] = [
+    "https://github.com/rust-lang/crates.io-index",
+    "sparse+https://index.crates.io",
+];
+
+/// Where a package comes from, as reported by the `source` field of `cargo metadata`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PackageSource {
+    /// A path dependency or workspace member.
+    Local,
+    CratesIo,
+    /// An alternate registry, with its name when the cargo config declares one for `index`.
+    Registry {
+        name: Option<String>,
+        index: String,
+    },
+    Git(String),
+}
+
+impl PackageSource {
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Local => "local path".to_string(),
+            Self::CratesIo => "crates.io".to_string(),
+            Self::Registry {
+                name: Some(name),
+                index,
+            } => format!("registry `{name}` ({index})"),
+            Self::Registry { name: None, index } => format!("registry {index}"),
+            Self::Git(url) => format!("git {url}"),
+        }
+    }
+}
+
+/// Normalizes an index URL for comparison: `registry+` and trailing slashes are dropped,
+/// while `sparse+` is kept since it selects the protocol.
+fn normalize_index(index: &str) -> &str {
+    let index = index.strip_prefix("registry+").unwrap_or(index);
+    index.trim_end_matches('/')
+}
+
+fn is_crates_io(index: &str) -> bool {
+    CRATES_IO_INDEXES.contains(&normalize_index(index))
+}
+
+/// Classifies a package source, naming alternate registries from `registries`.
+pub fn package_source(
+    source: Option<&Source>,
+    registries: &HashMap<String, String>,
+) -> PackageSource {
+    let Some(source) = source else {
+        return PackageSource::Local;
+    };
+    let repr = source.repr.as_str();
+    if let Some(git) = repr.strip_prefix("git+") {
+        let url = git.split(['?', '#']).next().unwrap_or(git);
+        return PackageSource::Git(url.to_string());
+    }
+    if is_crates_io(repr) {
+        return PackageSource::CratesIo;
+    }
+    let index = repr.strip_prefix("registry+").unwrap_or(repr).to_string();
+    PackageSource::Registry {
+        name: registry_name(registries, &index).map(str::to_string),
+        index,
+    }
+}
+
+/// Finds the name the cargo config gives the registry at `index`.
+pub fn registry_name<'a>(registries: &'a HashMap<String, String>, index: &str) -> Option<&'a str> {
+    let index = normalize_index(index);
+    registries
+        .iter()
+        .find(|(_, url)| normalize_index(url) == index)
+        .map(|(name, _)| name.as_str())
+}
+
+/// Renders the manifest key selecting the registry of a dependency whose index URL is
+/// `index`: `registry = "name"` when the cargo config names it, `registry-index` otherwise.
+/// Returns `None` for crates.io.
+pub fn dependency_registry(index: &str, registries: &HashMap<String, String>) -> Option<String> {
+    if is_crates_io(index) {
+        return None;
+    }
+    Some(match registry_name(registries, index) {
+        Some(name) => format!("registry = {name:?}"),
+        None => format!(
+            "registry-index = {:?}",
+            index.strip_prefix("registry+").unwrap_or(index)
+        ),
+    })
+}
+
+/// Returns `$CARGO_HOME`, defaulting to `~/.cargo`.
+fn cargo_home() -> Option<PathBuf> {
+    std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cargo")))
+}
+
+/// Lists the cargo config files that apply to a workspace at `root`, from lowest to
+/// highest precedence: `$CARGO_HOME`, then `.cargo` directories from the filesystem root
+/// down to `root`.
+fn cargo_config_files(root: &Path) -> Vec<PathBuf> {
+    let mut dirs: Vec<PathBuf> = cargo_home().into_iter().collect();
+    let mut ancestors: Vec<PathBuf> = root.ancestors().map(|dir| dir.join(".cargo")).collect();
+    ancestors.reverse();
+    dirs.extend(ancestors);
+    dirs.into_iter()
+        .flat_map(|dir| [dir.join("config"), dir.join("config.toml")])
+        .filter(|file| file.is_file())
+        .collect()
+}
+
+fn read_config(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// Reads the `[registries]` tables of a cargo config as name -> index URL.
+fn config_registries(config: &toml::Table) -> HashMap<String, String> {
+    config
+        .get("registries")
+        .and_then(|registries| registries.as_table())
+        .into_iter()
+        .flatten()
+        .filter_map(|(name, registry)| {
+            let index = registry.get("index")?.as_str()?;
+            Some((name.clone(), index.to_string()))
+        })
+        .collect()
+}
+
+/// Reads the `directory` sources of a cargo config (what `cargo vendor` sets up), resolving
+/// relative paths against `base` like cargo does.
+fn config_vendor_dirs(config: &toml::Table, base: &Path) -> Vec<PathBuf> {
+    config
+        .get("source")
+        .and_then(|sources| sources.as_table())
+        .into_iter()
+        .flatten()
+        .filter_map(|(_, source)| source.get("directory")?.as_str())
+        .map(|dir| base.join(dir))
+        .collect()
+}
+
+/// Collects the registries configured for a workspace at `root`, as name -> index URL.
+/// Like cargo, config files closer to `root` override those further up and in
+/// `$CARGO_HOME`, and `CARGO_REGISTRIES_<NAME>_INDEX` variables override all files.
+pub fn configured_registries(root: &Path) -> HashMap<String, String> {
+    let mut registries = HashMap::new();
+    for file in cargo_config_files(root) {
+        if let Some(config) = read_config(&file) {
+            registries.extend(config_registries(&config));
+        }
+    }
+    for (key, value) in std::env::vars() {
+        if let Some(name) = key
+            .strip_prefix("CARGO_REGISTRIES_")
+            .and_then(|rest| rest.strip_suffix("_INDEX"))
+        {
+            registries.insert(name.to_lowercase().replace('_', "-"), value);
+        }
+    }
+    registries
+}
+
+/// Collects the vendor directories the cargo config of a workspace at `root` replaces
+/// sources with, closest config first.
+pub fn configured_vendor_dirs(root: &Path) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for file in cargo_config_files(root).iter().rev() {
+        // Paths in a config are relative to the directory containing its `.cargo` directory.
+        let Some(base) = file.parent().and_then(Path::parent) else {
+            continue;
+        };
+        if let Some(config) = read_config(file) {
+            dirs.extend(config_vendor_dirs(&config, base));
+        }
+    }
+    dirs
+}
+
+/// Finds the file a rustdoc span points at. Relative paths are resolved against `root`.
+/// When the file is missing because dependencies are vendored, a path into the registry
+/// cache (`.../registry/src/<index>/<name>-<version>/...`) is mapped to the same file in a
+/// vendor directory, which `cargo vendor` names `<name>` or `<name>-<version>`.
+pub fn resolve_source_file(
+    filename: &Path,
+    root: &Path,
+    vendor_dirs: &[PathBuf],
+) -> Option<PathBuf> {
+    let path = root.join(filename);
+    if path.is_file() {
+        return Some(path);
+    }
+
+    let components: Vec<&std::ffi::OsStr> = filename.iter().collect();
+    let registry_src = components
+        .windows(2)
+        .position(|pair| pair[0] == "registry" && pair[1] == "src")?;
+    let package_dir = components.get(registry_src + 3)?.to_str()?;
+    let rest: PathBuf = components.get(registry_src + 4..)?.iter().collect();
+
+    let unversioned = package_dir
+        .rsplit_once('-')
+        .filter(|(_, version)| version.starts_with(|c: char| c.is_ascii_digit()))
+        .map(|(name, _)| name);
+    let rest = rest.as_path();
+    vendor_dirs
+        .iter()
+        .flat_map(|dir| {
+            [Some(package_dir), unversioned]
+                .into_iter()
+                .flatten()
+                .map(move |package| dir.join(package).join(&rest))
+        })
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn source(repr: &str) -> Source {
+        Source {
+            repr: repr.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_package_source() {
+        let registries = HashMap::from([(
+            "kellnr".to_string(),
+            "sparse+https://kellnr.example.com/api/v1/crates/".to_string(),
+        )]);
+        assert_eq!(package_source(None, &registries), PackageSource::Local);
+        assert_eq!(
+            package_source(
+                Some(&source(
+                    "registry+https://github.com/rust-lang/crates.io-index"
+                )),
+                &registries
+            ),
+            PackageSource::CratesIo
+        );
+        assert_eq!(
+            package_source(
+                Some(&source("sparse+https://index.crates.io/")),
+                &registries
+            ),
+            PackageSource::CratesIo
+        );
+        assert_eq!(
+            package_source(
+                Some(&source("sparse+https://kellnr.example.com/api/v1/crates/")),
+                &registries
+            )
+            .describe(),
+            "registry `kellnr` (sparse+https://kellnr.example.com/api/v1/crates/)"
+        );
+        assert_eq!(
+            package_source(
+                Some(&source(
+                    "registry+https://artifactory.example.com/git/index"
+                )),
+                &registries
+            ),
+            PackageSource::Registry {
+                name: None,
+                index: "https://artifactory.example.com/git/index".to_string()
+            }
+        );
+        assert_eq!(
+            package_source(
+                Some(&source("git+https://github.com/foo/bar?branch=main#abc123")),
+                &registries
+            ),
+            PackageSource::Git("https://github.com/foo/bar".to_string())
+        );
+    }
+
+    #[test]
+    fn test_dependency_registry() {
+        let registries = HashMap::from([(
+            "kellnr".to_string(),
+            "sparse+https://kellnr.example.com/api/v1/crates/".to_string(),
+        )]);
+        assert_eq!(
+            dependency_registry(
+                "sparse+https://kellnr.example.com/api/v1/crates",
+                &registries
+            )
+            .as_deref(),
+            Some(r#"registry = "kellnr""#)
+        );
+        assert_eq!(
+            dependency_registry("https://artifactory.example.com/git/index", &registries)
+                .as_deref(),
+            Some(r#"registry-index = "https://artifactory.example.com/git/index""#)
+        );
+        assert_eq!(
+            dependency_registry("https://github.com/rust-lang/crates.io-index", &registries),
+            None
+        );
+    }
+
+    #[test]
+    fn test_config_registries() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(
+            &path,
+            "[registries.kellnr]\nindex = \"sparse+https://kellnr.example.com/api/v1/crates/\"\ntoken = \"secret\"\n\n[net]\ngit-fetch-with-cli = true\n",
+        )
+        .unwrap();
+        assert_eq!(
+            config_registries(&read_config(&path).unwrap()),
+            HashMap::from([(
+                "kellnr".to_string(),
+                "sparse+https://kellnr.example.com/api/v1/crates/".to_string()
+            )])
+        );
+        assert!(read_config(&dir.path().join("missing.toml")).is_none());
+    }
+
+    #[test]
+    fn test_resolve_vendored_source_file() {
+        let dir = TempDir::new().unwrap();
+        let root = dir.path();
+        std::fs::create_dir_all(root.join(".cargo")).unwrap();
+        std::fs::write(
+            root.join(".cargo/config.toml"),
+            "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = \"vendor\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("vendor/serde/src/de")).unwrap();
+        std::fs::write(root.join("vendor/serde/src/de/mod.rs"), "").unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+
+        let vendor_dirs = configured_vendor_dirs(root);
+        assert_eq!(vendor_dirs.first(), Some(&root.join("vendor")));
+
+        assert_eq!(
+            resolve_source_file(Path::new("src/lib.rs"), root, &vendor_dirs),
+            Some(root.join("src/lib.rs"))
+        );
+        assert_eq!(
+            resolve_source_file(
+                Path::new(
+                    "/home/dev/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/serde-1.0.228/src/de/mod.rs"
+                ),
+                root,
+                &vendor_dirs
+            ),
+            Some(root.join("vendor/serde/src/de/mod.rs"))
+        );
+        assert_eq!(
+            resolve_source_file(
+                Path::new(
+                    "/home/dev/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f/tokio-1.49.0/src/lib.rs"
+                ),
+                root,
+                &vendor_dirs
+            ),
+            None
+        );
+    }
+}
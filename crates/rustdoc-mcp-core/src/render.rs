@@ -0,0 +1,310 @@
+use markdown_builder::{CodeBlock, ListBuilder, Markdown};
+use serde_json::{Value, json};
+
+/// Receives rendered docs block by block and turns them into one output format.
+pub trait DocRenderer {
+    /// A heading; level 1 is the title of the document.
+    fn heading(&mut self, level: usize, text: &str);
+
+    /// A paragraph of text, which may contain inline markdown like `` `code` `` and links.
+    fn paragraph(&mut self, text: &str);
+
+    /// A code block in `language`, which may be empty.
+    fn code(&mut self, language: &str, code: &str);
+
+    /// An unordered list.
+    fn list(&mut self, items: &[String]);
+
+    /// Markdown written by crate authors, such as doc comments. It is split into blocks
+    /// unless the output is markdown itself.
+    fn markdown(&mut self, markdown: &str) {
+        render_blocks(markdown, self);
+    }
+
+    /// Returns everything rendered so far and starts a new document.
+    fn finish(&mut self) -> String;
+}
+
+/// Renders markdown, as most tools produce it, in the format of `renderer`.
+pub fn render_markdown(markdown: &str, renderer: &mut dyn DocRenderer) -> String {
+    render_blocks(markdown, renderer);
+    renderer.finish()
+}
+
+/// Splits markdown into headings, paragraphs, code blocks and lists. Blank lines and
+/// horizontal rules only separate blocks.
+fn render_blocks<R: DocRenderer + ?Sized>(markdown: &str, renderer: &mut R) {
+    let mut paragraph: Vec<&str> = Vec::new();
+    let mut list: Vec<String> = Vec::new();
+    let mut code: Option<(&str, Vec<&str>)> = None;
+    let flush = |renderer: &mut R, paragraph: &mut Vec<&str>, list: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            renderer.paragraph(&paragraph.join("\n"));
+            paragraph.clear();
+        }
+        if !list.is_empty() {
+            renderer.list(list);
+            list.clear();
+        }
+    };
+
+    for line in markdown.lines() {
+        let trimmed = line.trim_end();
+        if let Some(fence) = trimmed.trim_start().strip_prefix("```") {
+            match code.take() {
+                Some((language, lines)) => renderer.code(language, &lines.join("\n")),
+                None => {
+                    flush(renderer, &mut paragraph, &mut list);
+                    code = Some((fence.trim_start_matches('`').trim(), Vec::new()));
+                }
+            }
+            continue;
+        }
+        if let Some((_, lines)) = &mut code {
+            lines.push(trimmed);
+            continue;
+        }
+
+        let text = trimmed.trim_start();
+        if text.is_empty() || text.chars().all(|c| c == '-' || c == '*' || c == '_') {
+            flush(renderer, &mut paragraph, &mut list);
+        } else if let Some((hashes, heading)) = atx_heading(text) {
+            flush(renderer, &mut paragraph, &mut list);
+            let level = match hashes {
+                1 if !trimmed.starts_with("# ") => 2,
+                hashes => hashes,
+            };
+            renderer.heading(level, heading);
+        } else if let Some(item) = text.strip_prefix("- ").or_else(|| text.strip_prefix("* ")) {
+            if !paragraph.is_empty() {
+                flush(renderer, &mut paragraph, &mut list);
+            }
+            list.push(item.to_string());
+        } else {
+            if !list.is_empty() {
+                flush(renderer, &mut paragraph, &mut list);
+            }
+            paragraph.push(text);
+        }
+    }
+    if let Some((language, lines)) = code {
+        renderer.code(language, &lines.join("\n"));
+    }
+    flush(renderer, &mut paragraph, &mut list);
+}
+
+/// Splits an ATX heading like `## Methods` into its level and text. Like CommonMark, it
+/// takes one to six `#`s followed by a space, so `#[derive(Debug)]` or `#1234` stay text.
+fn atx_heading(line: &str) -> Option<(usize, &str)> {
+    let hashes = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[hashes..];
+    ((1..=6).contains(&hashes) && (rest.is_empty() || rest.starts_with(' ')))
+        .then(|| (hashes, rest.trim()))
+}
+
+/// Renders markdown, as every tool did before other formats existed.
+pub struct MarkdownRenderer {
+    doc: Markdown,
+}
+
+impl Default for MarkdownRenderer {
+    fn default() -> Self {
+        Self {
+            doc: Markdown::new(),
+        }
+    }
+}
+
+impl DocRenderer for MarkdownRenderer {
+    fn heading(&mut self, level: usize, text: &str) {
+        match level {
+            1 => {
+                self.doc.header1(text);
+            }
+            2 => {
+                self.doc.header2(text);
+            }
+            _ => {
+                self.doc.paragraph(format!("{} {text}", "#".repeat(level)));
+            }
+        }
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        self.doc.paragraph(text);
+    }
+
+    fn code(&mut self, language: &str, code: &str) {
+        self.doc
+            .paragraph(code.to_string().to_code_block_with_language(language));
+    }
+
+    fn list(&mut self, items: &[String]) {
+        let mut list = ListBuilder::new();
+        for item in items {
+            list = list.append(item.clone());
+        }
+        self.doc.list(list.unordered());
+    }
+
+    fn markdown(&mut self, markdown: &str) {
+        self.doc.paragraph(markdown);
+    }
+
+    fn finish(&mut self) -> String {
+        std::mem::take(self).doc.render()
+    }
+}
+
+/// Renders dense plain text: headings without markers (sections end in `:`), code blocks
+/// reduced to their lines, and no inline backticks, link targets or blank lines.
+#[derive(Default)]
+pub struct TextRenderer {
+    lines: Vec<String>,
+}
+
+impl DocRenderer for TextRenderer {
+    fn heading(&mut self, level: usize, text: &str) {
+        self.lines.push(match level {
+            1 => text.to_string(),
+            _ => format!("{text}:"),
+        });
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        self.lines.extend(
+            text.lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(inline_text),
+        );
+    }
+
+    fn code(&mut self, _language: &str, code: &str) {
+        self.lines.extend(
+            code.lines()
+                .map(str::trim_end)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string),
+        );
+    }
+
+    fn list(&mut self, items: &[String]) {
+        self.lines
+            .extend(items.iter().map(|item| format!("- {}", inline_text(item))));
+    }
+
+    fn finish(&mut self) -> String {
+        std::mem::take(&mut self.lines).join("\n")
+    }
+}
+
+/// Drops inline code backticks and keeps only the text of `[text](target)` links.
+fn inline_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('[') {
+        let (before, after) = rest.split_at(start);
+        out.push_str(before);
+        let link = after
+            .find("](")
+            .and_then(|mid| Some((mid, mid + after[mid..].find(')')?)));
+        match link {
+            Some((mid, end)) if !after[1..mid].contains('[') => {
+                out.push_str(&after[1..mid]);
+                rest = &after[end + 1..];
+            }
+            _ => {
+                out.push('[');
+                rest = &after[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out.replace('`', "")
+}
+
+/// Renders `{"blocks": [...]}`, one object per block tagged by `type` (`heading`,
+/// `paragraph`, `code` or `list`). Text keeps its inline markdown.
+#[derive(Default)]
+pub struct JsonRenderer {
+    blocks: Vec<Value>,
+}
+
+impl DocRenderer for JsonRenderer {
+    fn heading(&mut self, level: usize, text: &str) {
+        self.blocks
+            .push(json!({"type": "heading", "level": level, "text": text}));
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        self.blocks.push(json!({"type": "paragraph", "text": text}));
+    }
+
+    fn code(&mut self, language: &str, code: &str) {
+        self.blocks
+            .push(json!({"type": "code", "language": language, "code": code}));
+    }
+
+    fn list(&mut self, items: &[String]) {
+        self.blocks.push(json!({"type": "list", "items": items}));
+    }
+
+    fn finish(&mut self) -> String {
+        let blocks = std::mem::take(&mut self.blocks);
+        serde_json::to_string_pretty(&json!({ "blocks": blocks })).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MARKDOWN: &str = "# Struct Greeter\n\n```rust\npub struct Greeter;\n```\n\nGreets people.\nPolitely.\n\n## Methods\n\n* `greet`\n* `wave`\n";
+
+    #[test]
+    fn test_render_json() {
+        let rendered = render_markdown(MARKDOWN, &mut JsonRenderer::default());
+        let value: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(
+            value,
+            json!({"blocks": [
+                {"type": "heading", "level": 1, "text": "Struct Greeter"},
+                {"type": "code", "language": "rust", "code": "pub struct Greeter;"},
+                {"type": "paragraph", "text": "Greets people.\nPolitely."},
+                {"type": "heading", "level": 2, "text": "Methods"},
+                {"type": "list", "items": ["`greet`", "`wave`"]},
+            ]})
+        );
+    }
+
+    #[test]
+    fn test_render_hashes_outside_headings() {
+        let markdown = "# Struct Tag\n\n#[derive(Debug)] is implemented, see #1234.\n\n####### Too deep\n\n### Notes\n";
+        let rendered = render_markdown(markdown, &mut JsonRenderer::default());
+        let value: Value = serde_json::from_str(&rendered).unwrap();
+        assert_eq!(
+            value,
+            json!({"blocks": [
+                {"type": "heading", "level": 1, "text": "Struct Tag"},
+                {"type": "paragraph", "text": "#[derive(Debug)] is implemented, see #1234."},
+                {"type": "paragraph", "text": "####### Too deep"},
+                {"type": "heading", "level": 3, "text": "Notes"},
+            ]})
+        );
+    }
+
+    #[test]
+    fn test_render_doc_comments() {
+        // Doc comments are split into blocks unless the output is markdown.
+        let docs = "Greets.\n\n# Examples\n\n```\ngreet();\n```";
+        let mut text = TextRenderer::default();
+        text.heading(1, "Function greet");
+        text.markdown(docs);
+        assert_eq!(text.finish(), "Function greet\nGreets.\nExamples\ngreet();");
+
+        let mut markdown = MarkdownRenderer::default();
+        markdown.markdown(docs);
+        assert!(markdown.finish().contains("# Examples\n"));
+    }
+}
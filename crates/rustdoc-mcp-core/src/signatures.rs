@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use rustdoc_types::{Crate, Id, ItemEnum};
+
+use crate::markdown::format_type;
+
+/// The argument and return types of a function, written with [`normalize_type`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignatureTypes {
+    /// Receivers are listed as `Self`, `&Self` or `&mut Self`.
+    pub inputs: Vec<String>,
+    /// `()` for functions returning nothing.
+    pub output: String,
+}
+
+/// Splits a type into words and punctuation, dropping lifetimes and module paths, so
+/// `&'a std::path::Path` is `&`, `Path`.
+fn type_tokens(ty: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut chars = ty.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        }
+        if c == '\'' {
+            // A lifetime, and the `+` joining it to trait bounds.
+            while chars
+                .next_if(|c| c.is_alphanumeric() || *c == '_')
+                .is_some()
+            {}
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            chars.next_if_eq(&'+');
+        } else if c.is_alphanumeric() || c == '_' {
+            let mut word = c.to_string();
+            while let Some(c) = chars.next_if(|c| c.is_alphanumeric() || *c == '_') {
+                word.push(c);
+            }
+            if chars.next_if_eq(&':').is_some() {
+                // A module path segment, left out along with its `::`.
+                chars.next_if_eq(&':');
+                continue;
+            }
+            tokens.push(word);
+        } else if c == ':' {
+            chars.next_if_eq(&':');
+        } else if c == '-' && chars.next_if_eq(&'>').is_some() {
+            tokens.push("->".to_string());
+        } else {
+            tokens.push(c.to_string());
+        }
+    }
+    tokens
+}
+
+fn is_word(token: &str) -> bool {
+    token.starts_with(|c: char| c.is_alphanumeric() || c == '_')
+}
+
+/// Writes a type the way signatures are compared: without lifetimes, module paths or
+/// spaces other than between words, e.g. `Result<PathBuf,_>` or `&mut dyn Write`.
+pub fn normalize_type(ty: &str) -> String {
+    let mut normalized = String::new();
+    let mut previous_word = false;
+    for token in type_tokens(ty) {
+        let word = is_word(&token);
+        if word && previous_word {
+            normalized.push(' ');
+        }
+        normalized.push_str(&token);
+        previous_word = word;
+    }
+    normalized
+}
+
+/// The signature types of the functions among `ids`.
+pub fn signature_types<'a>(
+    krate: &Crate,
+    ids: impl IntoIterator<Item = &'a Id>,
+) -> HashMap<Id, SignatureTypes> {
+    ids.into_iter()
+        .filter_map(|id| {
+            let ItemEnum::Function(function) = &krate.index.get(id)?.inner else {
+                return None;
+            };
+            let inputs = function
+                .sig
+                .inputs
+                .iter()
+                .map(|(_, ty)| normalize_type(&format_type(ty)))
+                .collect();
+            let output = function
+                .sig
+                .output
+                .as_ref()
+                .map_or_else(|| "()".to_string(), |ty| normalize_type(&format_type(ty)));
+            Some((*id, SignatureTypes { inputs, output }))
+        })
+        .collect()
+}
+
+/// How many tokens the type starting `tokens` spans: up to the first `,`, `;` or
+/// unbalanced closing bracket.
+fn type_len(tokens: &[String]) -> usize {
+    let mut depth = 0usize;
+    for (i, token) in tokens.iter().enumerate() {
+        match token.as_str() {
+            "<" | "(" | "[" => depth += 1,
+            ">" | ")" | "]" if depth == 0 => return i,
+            ">" | ")" | "]" => depth -= 1,
+            "," | ";" if depth == 0 => return i,
+            _ => {}
+        }
+    }
+    tokens.len()
+}
+
+fn tokens_match(pattern: &[String], ty: &[String]) -> bool {
+    match pattern {
+        [] => ty.is_empty(),
+        // Trailing `_` arguments may be missing, so `Result<T, _>` also finds `io::Result<T>`.
+        [comma, wildcard, closer, ..]
+            if comma == "," && wildcard == "_" && closer == ">" && ty.first() == Some(closer) =>
+        {
+            tokens_match(&pattern[2..], ty)
+        }
+        [wildcard, rest @ ..] if wildcard == "_" => {
+            let len = type_len(ty);
+            len > 0 && tokens_match(rest, &ty[len..])
+        }
+        [token, rest @ ..] => ty.first() == Some(token) && tokens_match(rest, &ty[1..]),
+    }
+}
+
+/// Whether the normalized type `ty` matches `pattern`, in which `_` stands for any type.
+pub fn type_matches(pattern: &str, ty: &str) -> bool {
+    tokens_match(&type_tokens(pattern), &type_tokens(ty))
+}
+
+/// Matches every one of `inputs` to an input of its own in `signature`, in any order.
+fn assign(inputs: &[String], signature: &[String], used: &mut [bool]) -> bool {
+    let Some((pattern, rest)) = inputs.split_first() else {
+        return true;
+    };
+    for (i, ty) in signature.iter().enumerate() {
+        if !used[i] && type_matches(pattern, ty) {
+            used[i] = true;
+            if assign(rest, signature, used) {
+                return true;
+            }
+            used[i] = false;
+        }
+    }
+    false
+}
+
+/// How many inputs of `signature` are left over once each of `inputs` is matched to an
+/// input of its own, or `None` when that fails or the return type does not match `output`.
+pub fn match_signature(
+    signature: &SignatureTypes,
+    inputs: &[String],
+    output: Option<&str>,
+) -> Option<usize> {
+    if output.is_some_and(|output| !type_matches(output, &signature.output)) {
+        return None;
+    }
+    let mut used = vec![false; signature.inputs.len()];
+    assign(inputs, &signature.inputs, &mut used)
+        .then(|| signature.inputs.len().saturating_sub(inputs.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_type() {
+        assert_eq!(normalize_type("&'a std::path::Path"), "&Path");
+        assert_eq!(
+            normalize_type("Result<std::path::PathBuf, io::Error>"),
+            "Result<PathBuf,Error>"
+        );
+        assert_eq!(normalize_type("&mut dyn 'a + Write"), "&mut dyn Write");
+        assert_eq!(normalize_type("impl Fn(u8) -> bool"), "impl Fn(u8)->bool");
+    }
+
+    #[test]
+    fn test_match_signature() {
+        assert!(type_matches("Result<PathBuf, _>", "Result<PathBuf,Error>"));
+        assert!(type_matches("Result<PathBuf, _>", "Result<PathBuf>"));
+        assert!(type_matches("Vec<_>", "Vec<(String,u8)>"));
+        assert!(!type_matches("Vec<_>", "Vec<u8,u8>"));
+        assert!(!type_matches("&str", "&mut str"));
+
+        let signature = SignatureTypes {
+            inputs: vec!["&Self".to_string(), "&str".to_string(), "usize".to_string()],
+            output: "Result<PathBuf,Error>".to_string(),
+        };
+        let inputs = |types: &[&str]| types.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            match_signature(
+                &signature,
+                &inputs(&["usize", "&str"]),
+                Some("Result<PathBuf, _>")
+            ),
+            Some(1)
+        );
+        assert_eq!(
+            match_signature(&signature, &inputs(&["&str", "&str"]), None),
+            None
+        );
+        assert_eq!(match_signature(&signature, &[], Some("PathBuf")), None);
+    }
+}
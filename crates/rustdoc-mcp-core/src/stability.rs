@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+
+use rustdoc_types::{Attribute, Item};
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::availability::availability;
+
+/// Crate features that conventionally gate nightly-only APIs.
+const NIGHTLY_FEATURES: [&str; 2] = ["nightly", "unstable"];
+
+/// How searches treat unstable (nightly-only) items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum UnstableItems {
+    /// Rank unstable items like any other.
+    Include,
+    /// List unstable items after all stable ones.
+    #[default]
+    Demote,
+    /// Leave unstable items out.
+    Exclude,
+}
+
+/// Reads `feature = "name"` or `feature: "name"` out of an attribute.
+fn feature_name(text: &str) -> Option<String> {
+    let rest = &text[text.find("feature")? + "feature".len()..];
+    let rest = rest.trim_start().strip_prefix(['=', ':'])?.trim_start();
+    let name = rest.strip_prefix('"')?;
+    Some(name[..name.find('"')?].to_string())
+}
+
+/// Names the feature gate an item needs on nightly: the `feature` of an `#[unstable]`
+/// attribute, or a crate feature like `nightly` from its `#[doc(cfg(...))]`. Returns
+/// `unknown` for unstable attributes without a readable feature name.
+pub fn unstable_feature(item: &Item) -> Option<String> {
+    for attr in &item.attrs {
+        if let Attribute::Other(text) = attr
+            && (text.contains("#[unstable(") || text.contains("level: Unstable"))
+        {
+            return Some(feature_name(text).unwrap_or_else(|| "unknown".to_string()));
+        }
+    }
+    availability(item)?.features.into_iter().find(|feature| {
+        NIGHTLY_FEATURES
+            .iter()
+            .any(|nightly| feature == nightly || feature.starts_with(&format!("{nightly}-")))
+    })
+}
+
+/// Whether the workspace builds with a nightly toolchain: its `rust-toolchain(.toml)`
+/// file names nightly, or one of its crate roots enables `#![feature(...)]`.
+pub fn uses_nightly(root: &Path, crate_roots: &[PathBuf]) -> bool {
+    if ["rust-toolchain.toml", "rust-toolchain"]
+        .iter()
+        .filter_map(|file| std::fs::read_to_string(root.join(file)).ok())
+        .any(|content| content.contains("nightly"))
+    {
+        return true;
+    }
+    crate_roots
+        .iter()
+        .filter_map(|path| std::fs::read_to_string(path).ok())
+        .any(|source| {
+            source
+                .lines()
+                .any(|line| line.trim_start().starts_with("#![feature("))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_types::{Id, ItemEnum, Visibility};
+    use std::collections::HashMap;
+
+    fn create_item(attrs: Vec<&str>) -> Item {
+        Item {
+            id: Id(0),
+            crate_id: 0,
+            name: Some("f".to_string()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: attrs
+                .into_iter()
+                .map(|attr| Attribute::Other(attr.to_string()))
+                .collect(),
+            deprecation: None,
+            inner: ItemEnum::ExternType,
+        }
+    }
+
+    #[test]
+    fn test_unstable_feature() {
+        let item = create_item(vec![
+            r#"#[unstable(feature = "vec_peek_mut", issue = "122742")]"#,
+        ]);
+        assert_eq!(unstable_feature(&item).as_deref(), Some("vec_peek_mut"));
+
+        let item = create_item(vec![
+            r#"#[attr = Stability { stability: Stability { level: Unstable { reason: None, issue: None, is_soft: false }, feature: "try_trait_v2" } }]"#,
+        ]);
+        assert_eq!(unstable_feature(&item).as_deref(), Some("try_trait_v2"));
+
+        let item = create_item(vec![r#"#[doc(cfg(feature = "nightly"))]"#]);
+        assert_eq!(unstable_feature(&item).as_deref(), Some("nightly"));
+
+        let item = create_item(vec![
+            r#"#[stable(feature = "rust1", since = "1.0.0")]"#,
+            r#"#[doc(cfg(feature = "fs"))]"#,
+        ]);
+        assert_eq!(unstable_feature(&item), None);
+    }
+
+    #[test]
+    fn test_uses_nightly() {
+        let dir = tempfile::tempdir().unwrap();
+        let lib = dir.path().join("lib.rs");
+        std::fs::write(&lib, "//! Docs\n#![forbid(unsafe_code)]\n").unwrap();
+        assert!(!uses_nightly(dir.path(), std::slice::from_ref(&lib)));
+
+        std::fs::write(&lib, "#![feature(try_trait_v2)]\n").unwrap();
+        assert!(uses_nightly(dir.path(), std::slice::from_ref(&lib)));
+
+        std::fs::write(
+            dir.path().join("rust-toolchain.toml"),
+            "[toolchain]\nchannel = \"nightly-2025-01-01\"\n",
+        )
+        .unwrap();
+        assert!(uses_nightly(dir.path(), &[]));
+    }
+}
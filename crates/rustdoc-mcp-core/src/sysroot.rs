@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use tokio::process::Command;
+use tracing::info;
+
+/// Crates of the standard library. Their docs are not generated but ship prebuilt in the
+/// `rust-docs-json` rustup component.
+pub const SYSROOT_CRATES: [&str; 5] = ["std", "core", "alloc", "proc_macro", "test"];
+
+/// The rustup component holding the rustdoc JSON of the standard library crates.
+const DOCS_COMPONENT: &str = "rust-docs-json";
+
+pub fn is_sysroot_crate(crate_name: &str) -> bool {
+    SYSROOT_CRATES.contains(&crate_name)
+}
+
+/// Where the component installs the docs of `crate_name` within a toolchain sysroot.
+pub fn docs_path(sysroot: &Path, crate_name: &str) -> PathBuf {
+    sysroot
+        .join("share")
+        .join("doc")
+        .join("rust")
+        .join("json")
+        .join(format!("{crate_name}.json"))
+}
+
+/// The sysroot of the nightly `toolchain`, which other docs are generated with too, so the
+/// format versions agree.
+pub async fn nightly_sysroot(toolchain: &str) -> Result<PathBuf> {
+    let output = Command::new("rustc")
+        .arg(format!("+{toolchain}"))
+        .arg("--print")
+        .arg("sysroot")
+        .output()
+        .await
+        .context("Failed to execute rustc")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to locate the {toolchain} toolchain: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim(),
+    ))
+}
+
+/// Installs the `rust-docs-json` component into the nightly `toolchain`.
+pub async fn install_docs_component(toolchain: &str) -> Result<()> {
+    info!("Installing the {} component", DOCS_COMPONENT);
+    let output = Command::new("rustup")
+        .arg("component")
+        .arg("add")
+        .arg(DOCS_COMPONENT)
+        .arg("--toolchain")
+        .arg(toolchain)
+        .output()
+        .await
+        .context("Failed to execute rustup")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "Failed to install the {DOCS_COMPONENT} component: {}\n\nInstall it with `rustup component add {DOCS_COMPONENT} --toolchain {toolchain}` to query the standard library.",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_docs_path() {
+        let sysroot = Path::new("/home/me/.rustup/toolchains/nightly-x86_64-unknown-linux-gnu");
+        assert_eq!(
+            docs_path(sysroot, "alloc"),
+            sysroot.join("share/doc/rust/json/alloc.json")
+        );
+        assert!(is_sysroot_crate("proc_macro"));
+        assert!(!is_sysroot_crate("serde"));
+    }
+}
@@ -0,0 +1,30 @@
+//! Builders of rustdoc items for tests, shared with the tests of the server through the
+//! `test-support` feature.
+
+use std::collections::HashMap;
+
+use rustdoc_types::{Generics, Id, Item, ItemEnum, Visibility};
+
+/// A public item without a span, docs or attributes.
+pub fn item(id: u32, name: &str, inner: ItemEnum) -> Item {
+    Item {
+        id: Id(id),
+        crate_id: 0,
+        name: Some(name.to_string()),
+        span: None,
+        visibility: Visibility::Public,
+        docs: None,
+        links: HashMap::new(),
+        attrs: Vec::new(),
+        deprecation: None,
+        inner,
+    }
+}
+
+/// Generics without parameters or where clauses.
+pub fn generics() -> Generics {
+    Generics {
+        params: vec![],
+        where_predicates: vec![],
+    }
+}
@@ -0,0 +1,271 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use rustdoc_types::Id;
+use tantivy::collector::TopDocs;
+use tantivy::query::QueryParser;
+use tantivy::schema::{Field, STORED, Schema, TEXT, Value};
+use tantivy::{Index, IndexReader, IndexWriter, TantivyDocument};
+
+use crate::index::LoadedCrate;
+
+/// The directory under the target directory holding full-text indexes.
+const INDEX_DIR: &str = "rustdoc-mcp-tantivy";
+
+/// Memory the writer may buffer while building an index.
+const WRITER_MEMORY: usize = 50_000_000;
+
+/// Where the full-text index of a crate is kept, named like its cached index (see
+/// [`crate::index_cache::cache_path`]).
+pub fn index_dir(target_dir: &Path, crate_name: &str, crate_version: Option<&str>) -> PathBuf {
+    target_dir.join(INDEX_DIR).join(format!(
+        "{crate_name}-{}-v{}",
+        crate_version.unwrap_or("unversioned"),
+        rustdoc_types::FORMAT_VERSION
+    ))
+}
+
+fn schema() -> Schema {
+    let mut builder = Schema::builder();
+    builder.add_text_field("path", TEXT | STORED);
+    builder.add_text_field("name", TEXT);
+    builder.add_text_field("docs", TEXT);
+    builder.add_u64_field("id", STORED);
+    builder.build()
+}
+
+/// Whether the index in `dir` was committed after the JSON it was built from.
+fn is_fresh(dir: &Path, json_path: &Path) -> bool {
+    let modified = |path: &Path| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+    match (modified(&dir.join("meta.json")), modified(json_path)) {
+        (Some(built), Some(generated)) => built >= generated,
+        _ => false,
+    }
+}
+
+/// A BM25 index over the path, name and docs of every item of a crate.
+pub struct TextIndex {
+    index: Index,
+    reader: IndexReader,
+    path: Field,
+    name: Field,
+    docs: Field,
+    id: Field,
+}
+
+impl std::fmt::Debug for TextIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TextIndex").finish_non_exhaustive()
+    }
+}
+
+impl TextIndex {
+    /// Opens the index in `dir` when it is at least as new as the JSON at `json_path`, and
+    /// builds it there from `loaded` otherwise. Without `dir`, it is built in memory.
+    pub fn open_or_build(
+        dir: Option<&Path>,
+        json_path: &Path,
+        loaded: &LoadedCrate,
+    ) -> Result<Self> {
+        let index = match dir {
+            Some(dir) if is_fresh(dir, json_path) => {
+                Index::open_in_dir(dir).context("Failed to open the full-text index")?
+            }
+            Some(dir) => {
+                // Built aside and moved into place, so concurrent servers never open a
+                // partial index.
+                let partial = dir.with_extension("partial");
+                if partial.exists() {
+                    std::fs::remove_dir_all(&partial).ok();
+                }
+                std::fs::create_dir_all(&partial)
+                    .context("Failed to create the full-text index directory")?;
+                let index = Index::create_in_dir(&partial, schema())
+                    .context("Failed to create the full-text index")?;
+                fill(&index, loaded)?;
+                drop(index);
+                if dir.exists() {
+                    std::fs::remove_dir_all(dir)
+                        .context("Failed to remove a stale full-text index")?;
+                }
+                std::fs::rename(&partial, dir).context("Failed to write the full-text index")?;
+                Index::open_in_dir(dir).context("Failed to open the full-text index")?
+            }
+            None => {
+                let index = Index::create_in_ram(schema());
+                fill(&index, loaded)?;
+                index
+            }
+        };
+
+        let schema = index.schema();
+        let field = |name: &str| {
+            schema
+                .get_field(name)
+                .context("The full-text index has an unexpected schema")
+        };
+        Ok(Self {
+            reader: index
+                .reader()
+                .context("Failed to read the full-text index")?,
+            path: field("path")?,
+            name: field("name")?,
+            docs: field("docs")?,
+            id: field("id")?,
+            index,
+        })
+    }
+
+    /// The `limit` items ranking best for `query` by BM25, as `(path, id, score)`. Matches
+    /// in the name weigh most, then in the path, then in the docs.
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<(String, Id, f32)>> {
+        // Only words are searched; `::` and the like would be read as query syntax.
+        let words: String = query
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+            .collect();
+        let mut parser = QueryParser::for_index(&self.index, vec![self.name, self.path, self.docs]);
+        parser.set_field_boost(self.name, 3.0);
+        parser.set_field_boost(self.path, 2.0);
+        let (query, _) = parser.parse_query_lenient(&words);
+
+        let searcher = self.reader.searcher();
+        let top = searcher
+            .search(&query, &TopDocs::with_limit(limit))
+            .context("Full-text search failed")?;
+        top.into_iter()
+            .map(|(score, address)| {
+                let document: TantivyDocument = searcher
+                    .doc(address)
+                    .context("Failed to read the full-text index")?;
+                let path = document
+                    .get_first(self.path)
+                    .and_then(|value| value.as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let id = document
+                    .get_first(self.id)
+                    .and_then(|value| value.as_u64())
+                    .unwrap_or_default();
+                Ok((path, Id(id as u32), score))
+            })
+            .collect()
+    }
+}
+
+/// Adds every item of `loaded` to `index`, once, under its shortest path.
+fn fill(index: &Index, loaded: &LoadedCrate) -> Result<()> {
+    let schema = index.schema();
+    let field = |name: &str| {
+        schema
+            .get_field(name)
+            .context("The full-text index has an unexpected schema")
+    };
+    let (path_field, name_field, docs_field, id_field) =
+        (field("path")?, field("name")?, field("docs")?, field("id")?);
+
+    let mut writer: IndexWriter = index
+        .writer(WRITER_MEMORY)
+        .context("Failed to write the full-text index")?;
+    for (&id, path) in &loaded.shortest_paths {
+        let mut document = TantivyDocument::default();
+        document.add_text(path_field, path);
+        document.add_text(name_field, path.rsplit("::").next().unwrap_or(path));
+        if let Some(docs) = loaded
+            .krate
+            .index
+            .get(&id)
+            .and_then(|item| item.docs.as_deref())
+        {
+            document.add_text(docs_field, docs);
+        }
+        document.add_u64(id_field, u64::from(id.0));
+        writer
+            .add_document(document)
+            .context("Failed to write the full-text index")?;
+    }
+    writer
+        .commit()
+        .context("Failed to write the full-text index")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    use rustdoc_types::{Crate, Item, ItemEnum, Visibility};
+
+    fn documented(id: u32, name: &str, docs: &str) -> Item {
+        Item {
+            id: Id(id),
+            crate_id: 0,
+            name: Some(name.to_string()),
+            span: None,
+            visibility: Visibility::Public,
+            docs: Some(docs.to_string()),
+            links: HashMap::new(),
+            attrs: vec![],
+            deprecation: None,
+            inner: ItemEnum::ExternType,
+        }
+    }
+
+    #[test]
+    fn test_search() {
+        let krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::from([
+                (
+                    Id(1),
+                    documented(1, "send", "Sends the request, retrying with backoff."),
+                ),
+                (Id(2), documented(2, "retry", "Builds a retry policy.")),
+                (Id(3), documented(3, "close", "Closes the connection.")),
+            ]),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let loaded = LoadedCrate::new(
+            krate,
+            HashMap::from([
+                ("http::Client::send".to_string(), Id(1)),
+                ("http::retry".to_string(), Id(2)),
+                ("http::policy::retry".to_string(), Id(2)),
+                ("http::Client::close".to_string(), Id(3)),
+            ]),
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let json_path = dir.path().join("http.json");
+        std::fs::write(&json_path, "{}").unwrap();
+        let index_path = index_dir(dir.path(), "http", Some("1.0.0"));
+        TextIndex::open_or_build(Some(&index_path), &json_path, &loaded).unwrap();
+        assert!(is_fresh(&index_path, &json_path));
+
+        let index = TextIndex::open_or_build(Some(&index_path), &json_path, &loaded).unwrap();
+        let found: Vec<(String, Id)> = index
+            .search("retry backoff", 10)
+            .unwrap()
+            .into_iter()
+            .map(|(path, id, _)| (path, id))
+            .collect();
+        // The name match ranks first, and `retry` is listed once, under its shortest path.
+        assert_eq!(
+            found,
+            vec![
+                ("http::retry".to_string(), Id(2)),
+                ("http::Client::send".to_string(), Id(1))
+            ]
+        );
+        assert!(index.search("std::vec::Vec", 10).unwrap().is_empty());
+    }
+}
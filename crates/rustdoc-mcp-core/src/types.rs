@@ -0,0 +1,159 @@
+use rustdoc_types::Id;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::impl_summary::doc_summary;
+use crate::index::{LoadedCrate, get_item_kind};
+use crate::markdown::{generic_params, item_signature};
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ItemSummary {
+    pub name: String,
+    pub kind: String,
+    /// The crate whose docs the item was found in; pass it with `id` to `get_docs`.
+    pub crate_name: String,
+    /// The rustdoc id of the item within `crate_name`.
+    pub id: u32,
+    /// Generic parameters with bounds and defaults, e.g. `<K, V, S = RandomState>`.
+    pub generics: Option<String>,
+    /// The first line of the item's docs.
+    pub summary: Option<String>,
+    /// The item's signature, e.g. `fn from_str<T>(s: &str) -> Result<T>`; unset for
+    /// modules and macros.
+    pub signature: Option<String>,
+    /// The type and trait a method or associated item belongs to; unset for other items.
+    pub owner: Option<ItemOwner>,
+    /// Whether the item is `#[deprecated]`.
+    pub is_deprecated: bool,
+    /// The shortest path the item is reachable by, like `serde::Deserialize` for
+    /// `serde::de::Deserialize`: the one to write in `use` declarations.
+    pub shortest_path: Option<String>,
+}
+
+impl ItemSummary {
+    /// Summarizes the item `id` of the docs of `crate_name`, listed as `name`. An id the
+    /// docs do not index, like that of a re-export from another crate, is of kind
+    /// `unknown`.
+    pub fn from_item(name: &str, crate_name: &str, loaded: &LoadedCrate, id: Id) -> Self {
+        let item = loaded.krate.index.get(&id);
+        Self {
+            name: name.to_string(),
+            kind: item.map_or_else(|| "unknown".to_string(), get_item_kind),
+            crate_name: crate_name.to_string(),
+            id: id.0,
+            generics: item.and_then(|item| generic_params(item, &loaded.krate)),
+            summary: item.and_then(doc_summary).map(str::to_string),
+            signature: item.and_then(item_signature),
+            owner: loaded.owners.get(&id).cloned(),
+            is_deprecated: item.is_some_and(|item| item.deprecation.is_some()),
+            shortest_path: loaded.shortest_paths.get(&id).cloned(),
+        }
+    }
+}
+
+/// Where an associated item is defined: in an impl block of a type, possibly implementing
+/// a trait, or in a trait definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, JsonSchema)]
+pub struct ItemOwner {
+    /// The implementing type, e.g. `UnboundedSender<T>`; unset for items of a trait
+    /// definition.
+    #[serde(rename = "type")]
+    pub type_name: Option<String>,
+    /// The trait implemented or defined, e.g. `Clone` or `From<String>`.
+    #[serde(rename = "trait")]
+    pub trait_name: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MacroArgument {
+    pub name: String,
+    pub description: Option<String>,
+    pub examples: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ItemAttribute {
+    pub name: String,
+    pub note: Option<String>,
+    pub guidance: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct Availability {
+    /// The predicate of the item's `#[doc(cfg(...))]`, e.g. `all(unix, feature = "fs")`.
+    pub cfg: String,
+    /// The docs.rs banner text, e.g. "Available on Unix and crate feature `fs` only."
+    pub description: String,
+    /// Crate features the predicate mentions outside `not(...)`.
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SearchExplanation {
+    pub path: String,
+    pub kind: String,
+    pub matched: String,
+    pub matcher: String,
+    pub score: f64,
+    pub jaro_winkler: Option<f64>,
+    /// The feature gate of an unstable (nightly-only) item.
+    pub unstable: Option<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CrateInfo {
+    pub name: String,
+    pub version: String,
+    pub description: Option<String>,
+    pub license: Option<String>,
+    pub repository: Option<String>,
+    pub edition: String,
+    pub directness: String,
+    /// Where the package comes from: crates.io, an alternate registry, git or a local path.
+    pub source: String,
+    pub has_lib: bool,
+    pub has_build_script: bool,
+    pub links: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, JsonSchema)]
+pub struct ItemReference {
+    pub display: String,
+    pub crate_name: Option<String>,
+    pub path: Option<String>,
+    pub id: u32,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TraitImpl {
+    /// The full path of the trait, when known (e.g. `core::clone::Clone`).
+    pub trait_path: String,
+    /// The impl header, e.g. `impl<T, U> Into<U> for T`.
+    pub header: String,
+    /// The impl's generic parameters with their bounds, e.g. `<T: Clone>`.
+    pub generics: String,
+    pub where_clause: Option<String>,
+    /// `explicit`, `derived`, `auto` (an auto trait like `Send`, implemented by the
+    /// compiler) or `blanket` (an impl for all types meeting its bounds).
+    pub kind: String,
+    pub is_unsafe: bool,
+    /// A negative impl like `impl !Send for T`.
+    pub is_negative: bool,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DocExample {
+    /// The code as its doctest compiles it, including the lines rustdoc hides.
+    pub code: String,
+    pub no_run: bool,
+    /// `ignore`, or `ignore-<target>` for some targets.
+    pub ignore: bool,
+    pub should_panic: bool,
+    pub compile_fail: bool,
+    /// Other fence attributes, like `edition2021`.
+    pub attributes: Vec<String>,
+    /// The heading the example is under, e.g. `Examples`.
+    pub section: Option<String>,
+    /// The paragraph right before the example.
+    pub prose: Option<String>,
+}
@@ -0,0 +1,291 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use cargo_metadata::camino::Utf8PathBuf;
+use cargo_metadata::{Metadata, MetadataCommand, Package};
+
+use crate::registry::{
+    PackageSource, configured_registries, configured_vendor_dirs, package_source,
+    resolve_source_file,
+};
+use crate::stability::uses_nightly;
+
+/// How close a package is to the workspace, from most to least direct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Directness {
+    /// A workspace member itself.
+    Member,
+    /// A dependency declared by a workspace member (sysroot crates count as direct too).
+    Direct,
+    /// Only reachable through other dependencies.
+    Transitive,
+}
+
+impl Directness {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Member => "member",
+            Self::Direct => "direct",
+            Self::Transitive => "transitive",
+        }
+    }
+}
+
+/// Name of the optional `cargo metadata` output in a fixture directory.
+pub const FIXTURE_METADATA_FILE: &str = "metadata.json";
+
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub metadata: Metadata,
+    /// Map of package name to Package
+    pub packages: HashMap<String, Package>,
+    /// Whether this is a fixture, whose docs are prebuilt and never regenerated.
+    pub fixture: bool,
+    /// Alternate registries declared in the cargo config, as name -> index URL.
+    pub registries: HashMap<String, String>,
+    /// Directories `cargo vendor` put dependency sources in, from the cargo config.
+    pub vendor_dirs: Vec<PathBuf>,
+    /// Whether the workspace builds with nightly, so unstable APIs are usable.
+    pub nightly: bool,
+}
+
+impl Workspace {
+    pub fn load(root: impl AsRef<Path>) -> Result<Self> {
+        let root = root.as_ref();
+        let metadata = MetadataCommand::new()
+            .manifest_path(root.join("Cargo.toml"))
+            .exec()
+            .context("Failed to load cargo metadata")?;
+
+        Ok(Self::from_metadata(root, metadata, false))
+    }
+
+    /// Loads a fixture directory instead of a cargo workspace: prebuilt rustdoc JSON in
+    /// `doc/`, plus optionally the `cargo metadata` output of the workspace it was built
+    /// from in `metadata.json`. No cargo command is run, so no toolchain or network is
+    /// needed.
+    pub fn load_fixture(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref();
+        let utf8_dir = Utf8PathBuf::from_path_buf(dir.to_path_buf())
+            .map_err(|dir| anyhow::anyhow!("Fixture path is not UTF-8: {}", dir.display()))?;
+        let metadata_path = dir.join(FIXTURE_METADATA_FILE);
+        let mut metadata: Metadata = if metadata_path.exists() {
+            let content = std::fs::read_to_string(&metadata_path)
+                .context("Failed to read fixture metadata")?;
+            serde_json::from_str(&content).context("Failed to parse fixture metadata")?
+        } else {
+            serde_json::from_value(serde_json::json!({
+                "packages": [],
+                "workspace_members": [],
+                "workspace_default_members": [],
+                "resolve": null,
+                "target_directory": utf8_dir,
+                "version": 1,
+                "workspace_root": utf8_dir,
+            }))
+            .context("Failed to build fixture metadata")?
+        };
+        // Docs are read from `<target_directory>/doc`, which is the fixture itself.
+        metadata.target_directory = utf8_dir.clone();
+        metadata.workspace_root = utf8_dir;
+
+        Ok(Self::from_metadata(dir, metadata, true))
+    }
+
+    fn from_metadata(root: &Path, metadata: Metadata, fixture: bool) -> Self {
+        let mut packages = HashMap::new();
+        for pkg in &metadata.packages {
+            packages.insert(pkg.name.to_string(), pkg.clone());
+        }
+
+        // A fixture is not built, so the cargo config and toolchain of wherever it lives
+        // do not apply.
+        let (registries, vendor_dirs, nightly) = if fixture {
+            (HashMap::new(), Vec::new(), false)
+        } else {
+            let crate_roots: Vec<PathBuf> = metadata
+                .workspace_packages()
+                .iter()
+                .flat_map(|pkg| &pkg.targets)
+                .map(|target| target.src_path.clone().into_std_path_buf())
+                .collect();
+            (
+                configured_registries(root),
+                configured_vendor_dirs(root),
+                uses_nightly(root, &crate_roots),
+            )
+        };
+
+        Self {
+            root: root.to_path_buf(),
+            metadata,
+            packages,
+            fixture,
+            registries,
+            vendor_dirs,
+            nightly,
+        }
+    }
+
+    pub fn has_nightly_toolchain() -> bool {
+        Command::new("rustc")
+            .arg("+nightly")
+            .arg("--version")
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+
+    /// Returns the workspace member called `name`, or the root package (falling back to the
+    /// first member) when no name is given.
+    pub fn member_package(&self, name: Option<&str>) -> Option<&Package> {
+        let members = self.metadata.workspace_packages();
+        match name {
+            Some(name) => members.into_iter().find(|p| p.name.as_str() == name),
+            None => self
+                .metadata
+                .root_package()
+                .or_else(|| members.into_iter().next()),
+        }
+    }
+
+    /// The crate `crate::` paths refer to by default: the library of the root package (or
+    /// of the first member), named as it is in code.
+    pub fn focus_crate(&self) -> Option<String> {
+        let package = self.member_package(None)?;
+        Some(
+            package
+                .targets
+                .iter()
+                .find(|t| t.is_lib() || t.is_proc_macro())
+                .map_or_else(|| package.name.replace('-', "_"), |t| t.name.clone()),
+        )
+    }
+
+    /// Finds a package by crate name; hyphens and underscores are interchangeable.
+    pub fn find_package(&self, crate_name: &str) -> Option<&Package> {
+        let normalized = crate_name.replace('-', "_");
+        self.packages.get(crate_name).or_else(|| {
+            self.packages
+                .values()
+                .find(|p| p.name.replace('-', "_") == normalized)
+        })
+    }
+
+    /// Classifies `crate_name` (hyphens and underscores are interchangeable) by how directly
+    /// the workspace depends on it.
+    pub fn directness(&self, crate_name: &str) -> Directness {
+        let Some(package) = self.find_package(crate_name) else {
+            return Directness::Direct;
+        };
+
+        if self.metadata.workspace_members.contains(&package.id) {
+            return Directness::Member;
+        }
+
+        let is_direct = self.metadata.resolve.as_ref().is_some_and(|resolve| {
+            resolve
+                .nodes
+                .iter()
+                .filter(|node| self.metadata.workspace_members.contains(&node.id))
+                .any(|node| node.deps.iter().any(|dep| dep.pkg == package.id))
+        });
+
+        if is_direct {
+            Directness::Direct
+        } else {
+            Directness::Transitive
+        }
+    }
+
+    /// Classifies where `package` comes from, naming alternate registries.
+    pub fn package_source(&self, package: &Package) -> PackageSource {
+        package_source(package.source.as_ref(), &self.registries)
+    }
+
+    /// Finds the file a rustdoc span points at, looking in the vendor directories when the
+    /// registry cache path it was generated with does not exist.
+    pub fn resolve_source_file(&self, filename: &Path) -> Option<PathBuf> {
+        resolve_source_file(
+            filename,
+            self.metadata.workspace_root.as_std_path(),
+            &self.vendor_dirs,
+        )
+    }
+
+    /// Returns a list of all dependencies (direct and transitive) for the workspace members.
+    pub fn get_dependencies(&self) -> Vec<&Package> {
+        self.packages.values().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::File;
+    use std::io::Write;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_workspace_load() {
+        // Create a temp directory
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+
+        // Create a Cargo.toml
+        let cargo_toml_path = root.join("Cargo.toml");
+        let mut file = File::create(&cargo_toml_path).expect("Failed to create Cargo.toml");
+        writeln!(
+            file,
+            r#"
+            [package]
+            name = "test-package"
+            version = "0.1.0"
+            edition = "2021"
+
+            [dependencies]
+            serde = "1.0"
+            "#
+        )
+        .expect("Failed to write to Cargo.toml");
+
+        // Initialize git repo to avoid cargo warnings/errors about being outside a workspace
+        // (optional, but good practice if tests run in weird environments)
+        // Actually, cargo metadata should work fine without git.
+
+        // Create src/main.rs so cargo build/metadata doesn't complain about missing sources if it checks
+        std::fs::create_dir(root.join("src")).ok();
+        let mut main_rs = File::create(root.join("src/main.rs")).expect("Failed to create main.rs");
+        writeln!(main_rs, "fn main() {{}}").expect("Failed to write main.rs");
+
+        // Test loading
+        let workspace = Workspace::load(root).expect("Failed to load workspace");
+
+        assert_eq!(workspace.root, root);
+        assert!(workspace.packages.contains_key("test-package"));
+
+        // Check dependencies
+        let deps = workspace.get_dependencies();
+        assert!(deps.iter().any(|p| p.name == "test-package"));
+        // Note: `get_dependencies` returns workspace members (which are packages), not their dependencies.
+        // Wait, looking at implementation: `self.packages.values().collect()`
+        // `packages` is populated from `metadata.packages`.
+        // metadata.packages includes dependencies too.
+
+        assert!(workspace.packages.contains_key("serde"));
+    }
+
+    #[test]
+    fn test_load_fixture() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../fixtures/demo");
+        let workspace = Workspace::load_fixture(&dir).expect("Failed to load fixture");
+
+        assert!(workspace.fixture);
+        assert_eq!(workspace.root, dir);
+        assert_eq!(workspace.metadata.target_directory.as_std_path(), dir);
+        assert!(workspace.packages.is_empty());
+    }
+}
@@ -0,0 +1,26 @@
+//! A tiny crate used as a rustdoc JSON fixture.
+
+/// Greets people by name.
+pub struct Greeter {
+    /// The greeting to use, like "Hello".
+    pub greeting: String,
+}
+
+impl Greeter {
+    /// Creates a greeter with the given greeting.
+    pub fn new(greeting: &str) -> Self {
+        Self {
+            greeting: greeting.to_string(),
+        }
+    }
+
+    /// Greets `name`.
+    pub fn greet(&self, name: &str) -> String {
+        format!("{}, {name}!", self.greeting)
+    }
+}
+
+/// Returns the default greeting.
+pub fn default_greeting() -> &'static str {
+    "Hello"
+}
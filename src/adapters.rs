@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+
+use rustdoc_mcp_core::index::LoadedCrate;
+use rustdoc_mcp_core::markdown::{format_item_definition, format_type};
+use rustdoc_mcp_core::references::signature_references;
+use rustdoc_types::{Crate, GenericBound, Item, ItemEnum};
+
+use crate::instantiate::substitute_function;
+use crate::types::AdapterMethod;
+
+/// Traits whose implementors can start an adapter chain.
+const CHAIN_TRAITS: [&str; 2] = ["Iterator", "Stream"];
+
+/// The `Iterator` or `Stream` impl of a type.
+pub struct ChainImpl {
+    pub trait_name: String,
+    pub item_type: String,
+    pub provided_methods: Vec<String>,
+}
+
+/// Finds the `Iterator` (or `Stream`) impl of `item`, with its `Item` type and the names
+/// of the provided trait methods it inherits, such as `map` and `filter`.
+pub fn chain_impl(krate: &Crate, item: &Item) -> Option<ChainImpl> {
+    let impls = match &item.inner {
+        ItemEnum::Struct(s) => &s.impls,
+        ItemEnum::Enum(e) => &e.impls,
+        ItemEnum::Union(u) => &u.impls,
+        _ => return None,
+    };
+
+    impls
+        .iter()
+        .filter_map(|id| krate.index.get(id))
+        .find_map(|impl_item| {
+            let ItemEnum::Impl(impl_) = &impl_item.inner else {
+                return None;
+            };
+            let trait_name = impl_.trait_.as_ref()?.path.rsplit("::").next()?;
+            if !CHAIN_TRAITS.contains(&trait_name) {
+                return None;
+            }
+            let item_type = impl_
+                .items
+                .iter()
+                .filter_map(|id| krate.index.get(id))
+                .find_map(|assoc| match &assoc.inner {
+                    ItemEnum::AssocType {
+                        type_: Some(ty), ..
+                    } if assoc.name.as_deref() == Some("Item") => Some(format_type(ty)),
+                    _ => None,
+                })?;
+
+            let mut provided_methods = impl_.provided_trait_methods.clone();
+            provided_methods.sort();
+            Some(ChainImpl {
+                trait_name: trait_name.to_string(),
+                item_type,
+                provided_methods,
+            })
+        })
+}
+
+/// Whether a trait extends `base_trait`, either as a supertrait (`Itertools: Iterator`)
+/// or by the `{base_trait}Ext` naming convention (`StreamExt`).
+fn extends(item: &Item, base_trait: &str) -> bool {
+    let ItemEnum::Trait(t) = &item.inner else {
+        return false;
+    };
+    item.name.as_deref() == Some(&format!("{base_trait}Ext"))
+        || t.bounds.iter().any(|bound| {
+            matches!(bound, GenericBound::TraitBound { trait_, .. }
+                if trait_.path.rsplit("::").next() == Some(base_trait))
+        })
+}
+
+/// Lists the methods of the extension traits of `base_trait` in `loaded`, with `Self::Item`
+/// replaced by `item_type`. Traits re-exported under several paths are listed once, under
+/// their shortest path.
+pub fn ext_trait_adapters(
+    loaded: &LoadedCrate,
+    base_trait: &str,
+    item_type: &str,
+) -> Vec<AdapterMethod> {
+    let mut traits: Vec<(&str, &Item)> = loaded
+        .path_to_id
+        .iter()
+        .filter_map(|(path, id)| Some((path.as_str(), loaded.krate.index.get(id)?)))
+        .filter(|(_, item)| extends(item, base_trait))
+        .collect();
+    traits.sort_by_key(|(path, _)| (path.len(), *path));
+
+    let map = HashMap::from([("Self::Item".to_string(), item_type.to_string())]);
+    let mut seen = HashSet::new();
+    let mut methods = Vec::new();
+    for (path, item) in traits {
+        if !seen.insert(&item.id) {
+            continue;
+        }
+        let ItemEnum::Trait(t) = &item.inner else {
+            continue;
+        };
+        for method in t.items.iter().filter_map(|id| loaded.krate.index.get(id)) {
+            let ItemEnum::Function(f) = &method.inner else {
+                continue;
+            };
+            let mut method = method.clone();
+            method.inner = ItemEnum::Function(substitute_function(f, &map));
+            methods.push(AdapterMethod {
+                trait_path: path.to_string(),
+                name: method.name.clone().unwrap_or_default(),
+                signature: format_item_definition(&method),
+                references: signature_references(&loaded.krate, &method),
+            });
+        }
+    }
+    methods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_mcp_core::test_support::{generics, item};
+    use rustdoc_types::{Id, Impl, Path, Struct, StructKind, Trait, Type};
+
+    #[test]
+    fn test_chain_impl() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let lines = item(
+            1,
+            "Lines",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: generics(),
+                impls: vec![Id(2)],
+            }),
+        );
+        let iterator_impl = Item {
+            name: None,
+            ..item(
+                2,
+                "",
+                ItemEnum::Impl(Impl {
+                    is_unsafe: false,
+                    generics: generics(),
+                    provided_trait_methods: vec!["map".to_string(), "filter".to_string()],
+                    trait_: Some(Path {
+                        path: "Iterator".to_string(),
+                        id: Id(99),
+                        args: None,
+                    }),
+                    for_: Type::Primitive("unit".to_string()),
+                    items: vec![Id(3)],
+                    is_negative: false,
+                    is_synthetic: false,
+                    blanket_impl: None,
+                }),
+            )
+        };
+        let item_type = item(
+            3,
+            "Item",
+            ItemEnum::AssocType {
+                generics: generics(),
+                bounds: vec![],
+                type_: Some(Type::Primitive("str".to_string())),
+            },
+        );
+        for i in [lines, iterator_impl, item_type] {
+            krate.index.insert(i.id, i);
+        }
+
+        let chain = chain_impl(&krate, &krate.index[&Id(1)]).unwrap();
+        assert_eq!(chain.trait_name, "Iterator");
+        assert_eq!(chain.item_type, "str");
+        assert_eq!(chain.provided_methods, vec!["filter", "map"]);
+    }
+
+    #[test]
+    fn test_extends() {
+        let ext = |name: &str, bounds| {
+            item(
+                1,
+                name,
+                ItemEnum::Trait(Trait {
+                    is_auto: false,
+                    is_unsafe: false,
+                    is_dyn_compatible: true,
+                    items: vec![],
+                    generics: generics(),
+                    bounds,
+                    implementations: vec![],
+                }),
+            )
+        };
+        let iterator_bound = GenericBound::TraitBound {
+            trait_: Path {
+                path: "Iterator".to_string(),
+                id: Id(99),
+                args: None,
+            },
+            generic_params: vec![],
+            modifier: rustdoc_types::TraitBoundModifier::None,
+        };
+
+        assert!(extends(&ext("Itertools", vec![iterator_bound]), "Iterator"));
+        assert!(extends(&ext("StreamExt", vec![]), "Stream"));
+        assert!(!extends(&ext("Display", vec![]), "Iterator"));
+    }
+}
@@ -0,0 +1,365 @@
+use std::collections::BTreeMap;
+use std::path::{Component, Path, PathBuf};
+
+use cargo_metadata::semver::Version;
+use rustdoc_mcp_core::index::{LoadedCrate, get_item_kind};
+use rustdoc_mcp_core::markdown::{format_item_definition, format_type};
+use rustdoc_types::{Attribute, Crate, Item, ItemEnum, StructKind, VariantKind};
+
+use crate::types::{ApiChange, ApiEntry};
+
+/// Default file name of a snapshot, stored next to the package's `Cargo.toml`.
+pub const SNAPSHOT_FILE_NAME: &str = "public-api.json";
+
+/// Resolves a snapshot path given by a client against the package directory, refusing
+/// paths outside the workspace root, so that snapshots cannot overwrite arbitrary files.
+/// `..` is resolved without following symlinks, and the directory the file goes in is
+/// checked again once symlinks are resolved.
+pub fn snapshot_file(
+    package_dir: &Path,
+    workspace_root: &Path,
+    path: &str,
+) -> Result<PathBuf, String> {
+    let mut resolved = PathBuf::new();
+    for component in package_dir.join(path).components() {
+        match component {
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::CurDir => {}
+            other => resolved.push(other),
+        }
+    }
+    let outside = || format!("Snapshot path `{path}` is outside the workspace");
+    if !resolved.starts_with(workspace_root) || resolved == workspace_root {
+        return Err(outside());
+    }
+    if let Some(dir) = resolved.parent()
+        && let Ok(dir) = dir.canonicalize()
+        && let Ok(root) = workspace_root.canonicalize()
+        && !dir.starts_with(root)
+    {
+        return Err(outside());
+    }
+    Ok(resolved)
+}
+
+const NON_EXHAUSTIVE: &str = "non_exhaustive";
+const PRIVATE_FIELDS: &str = "private_fields";
+const REQUIRED: &str = "required";
+
+fn field_type(krate: &Crate, id: &rustdoc_types::Id) -> String {
+    match krate.index.get(id).map(|f| &f.inner) {
+        Some(ItemEnum::StructField(ty)) => format_type(ty),
+        _ => "_".to_string(),
+    }
+}
+
+/// Renders what a snapshot compares for an item. Fields and variants, which have no
+/// definition of their own, are rendered with their types.
+fn signature(item: &Item, krate: &Crate) -> String {
+    let name = item.name.as_deref().unwrap_or_default();
+    match &item.inner {
+        ItemEnum::Module(_) => format!("mod {name}"),
+        ItemEnum::StructField(ty) => format!("{name}: {}", format_type(ty)),
+        ItemEnum::Variant(v) => match &v.kind {
+            VariantKind::Plain => name.to_string(),
+            VariantKind::Tuple(fields) => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|f| {
+                        f.as_ref()
+                            .map_or("_".to_string(), |id| field_type(krate, id))
+                    })
+                    .collect();
+                format!("{name}({})", fields.join(", "))
+            }
+            VariantKind::Struct { fields, .. } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .filter_map(|id| {
+                        let field = krate.index.get(id)?;
+                        Some(format!(
+                            "{}: {}",
+                            field.name.as_deref().unwrap_or_default(),
+                            field_type(krate, id)
+                        ))
+                    })
+                    .collect();
+                format!("{name} {{ {} }}", fields.join(", "))
+            }
+        },
+        ItemEnum::AssocType { type_: None, .. } => format!("type {name}"),
+        ItemEnum::AssocType {
+            type_: Some(ty), ..
+        } => format!("type {name} = {}", format_type(ty)),
+        ItemEnum::AssocConst { type_, .. } => format!("const {name}: {}", format_type(type_)),
+        _ => format_item_definition(item),
+    }
+}
+
+fn flags(item: &Item) -> Vec<String> {
+    let mut flags = Vec::new();
+    if item
+        .attrs
+        .iter()
+        .any(|attr| matches!(attr, Attribute::NonExhaustive))
+    {
+        flags.push(NON_EXHAUSTIVE.to_string());
+    }
+    let private_fields = match &item.inner {
+        ItemEnum::Struct(s) => match &s.kind {
+            StructKind::Plain {
+                has_stripped_fields,
+                ..
+            } => *has_stripped_fields,
+            StructKind::Tuple(fields) => fields.iter().any(Option::is_none),
+            StructKind::Unit => false,
+        },
+        _ => false,
+    };
+    if private_fields {
+        flags.push(PRIVATE_FIELDS.to_string());
+    }
+    // Trait items without a default must be provided by every implementation.
+    let required = match &item.inner {
+        ItemEnum::Function(f) => !f.has_body,
+        ItemEnum::AssocType { type_, .. } => type_.is_none(),
+        ItemEnum::AssocConst { value, .. } => value.is_none(),
+        _ => false,
+    };
+    if required {
+        flags.push(REQUIRED.to_string());
+    }
+    flags
+}
+
+/// Captures the public API of a loaded crate: every indexed path with its kind and
+/// rendered signature.
+pub fn public_api(loaded: &LoadedCrate) -> BTreeMap<String, ApiEntry> {
+    loaded
+        .path_to_id
+        .iter()
+        .filter_map(|(path, id)| {
+            let item = loaded.krate.index.get(id)?;
+            Some((
+                path.clone(),
+                ApiEntry {
+                    kind: get_item_kind(item),
+                    signature: signature(item, &loaded.krate),
+                    flags: flags(item),
+                },
+            ))
+        })
+        .collect()
+}
+
+fn has_flag(entry: Option<&ApiEntry>, flag: &str) -> bool {
+    entry.is_some_and(|e| e.flags.iter().any(|f| f == flag))
+}
+
+/// Explains why adding `entry` below `parent` breaks downstream code, if it does.
+fn addition_breaks(entry: &ApiEntry, parent: Option<&ApiEntry>) -> Option<&'static str> {
+    let parent_kind = parent.map(|p| p.kind.as_str());
+    let exhaustive = !has_flag(parent, NON_EXHAUSTIVE);
+    match (entry.kind.as_str(), parent_kind) {
+        ("variant", Some("enum")) if exhaustive => {
+            Some("new variant of an exhaustive enum breaks exhaustive matches")
+        }
+        ("struct_field", Some("struct")) if exhaustive && !has_flag(parent, PRIVATE_FIELDS) => {
+            Some("new field of an exhaustive struct breaks struct literals and patterns")
+        }
+        (_, Some("trait")) if has_flag(Some(entry), REQUIRED) => {
+            Some("new required trait item breaks existing implementations")
+        }
+        _ => None,
+    }
+}
+
+fn change(
+    path: &str,
+    kind: &str,
+    breaking: bool,
+    reason: &str,
+    before: Option<&ApiEntry>,
+    after: Option<&ApiEntry>,
+) -> ApiChange {
+    ApiChange {
+        path: path.to_string(),
+        change: kind.to_string(),
+        breaking,
+        reason: reason.to_string(),
+        before: before.map(|e| e.signature.clone()),
+        after: after.map(|e| e.signature.clone()),
+    }
+}
+
+/// Compares two snapshots and classifies each difference following the Cargo SemVer
+/// guidelines: removals, signature changes and additions that existing code cannot absorb
+/// (variants, fields, required trait items, `#[non_exhaustive]`) are breaking.
+pub fn diff_api(
+    old: &BTreeMap<String, ApiEntry>,
+    new: &BTreeMap<String, ApiEntry>,
+) -> Vec<ApiChange> {
+    let mut changes = Vec::new();
+    for (path, before) in old {
+        let Some(after) = new.get(path) else {
+            changes.push(change(
+                path,
+                "removed",
+                true,
+                "item removed",
+                Some(before),
+                None,
+            ));
+            continue;
+        };
+        if before.kind != after.kind {
+            changes.push(change(
+                path,
+                "changed",
+                true,
+                "item kind changed",
+                Some(before),
+                Some(after),
+            ));
+        } else if before.signature != after.signature {
+            changes.push(change(
+                path,
+                "changed",
+                true,
+                "signature changed",
+                Some(before),
+                Some(after),
+            ));
+        } else if !has_flag(Some(before), NON_EXHAUSTIVE) && has_flag(Some(after), NON_EXHAUSTIVE) {
+            changes.push(change(
+                path,
+                "changed",
+                true,
+                "marked #[non_exhaustive]",
+                Some(before),
+                Some(after),
+            ));
+        } else if has_flag(Some(before), NON_EXHAUSTIVE) && !has_flag(Some(after), NON_EXHAUSTIVE) {
+            changes.push(change(
+                path,
+                "changed",
+                false,
+                "no longer #[non_exhaustive]",
+                Some(before),
+                Some(after),
+            ));
+        }
+    }
+
+    for (path, after) in new {
+        if old.contains_key(path) {
+            continue;
+        }
+        let parent = path
+            .rsplit_once("::")
+            .and_then(|(parent, _)| old.get(parent));
+        let breaks = addition_breaks(after, parent);
+        changes.push(change(
+            path,
+            "added",
+            breaks.is_some(),
+            breaks.unwrap_or("item added"),
+            None,
+            Some(after),
+        ));
+    }
+    changes
+}
+
+/// The smallest version bump allowed for `changes`. Before 1.0, Cargo treats the minor
+/// version as the breaking one.
+pub fn required_bump(changes: &[ApiChange], current_version: &Version) -> &'static str {
+    let breaking = changes.iter().any(|c| c.breaking);
+    let additive = changes.iter().any(|c| c.change == "added");
+    match (breaking, additive, current_version.major == 0) {
+        (true, _, false) => "major",
+        (true, _, true) | (false, true, false) => "minor",
+        (false, true, true) => "patch",
+        (false, false, _) => "none",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_snapshot_file() {
+        let root = Path::new("/work/app");
+        let package = root.join("crates/lib");
+        assert_eq!(
+            snapshot_file(&package, root, "api/public-api.json"),
+            Ok(package.join("api/public-api.json"))
+        );
+        assert_eq!(
+            snapshot_file(&package, root, "../../snapshots/lib.json"),
+            Ok(root.join("snapshots/lib.json"))
+        );
+        assert!(snapshot_file(&package, root, "../../../../etc/passwd").is_err());
+        assert!(snapshot_file(&package, root, "/home/me/.bashrc").is_err());
+        assert!(snapshot_file(&package, root, "/work/app-other/api.json").is_err());
+    }
+
+    fn entry(kind: &str, signature: &str, flags: &[&str]) -> ApiEntry {
+        ApiEntry {
+            kind: kind.to_string(),
+            signature: signature.to_string(),
+            flags: flags.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_diff_api() {
+        let old = BTreeMap::from([
+            ("demo::Mode".to_string(), entry("enum", "enum Mode", &[])),
+            ("demo::Mode::A".to_string(), entry("variant", "A", &[])),
+            (
+                "demo::Opts".to_string(),
+                entry("struct", "struct Opts { ... }", &["non_exhaustive"]),
+            ),
+            (
+                "demo::run".to_string(),
+                entry("function", "fn run(opts: Opts)", &[]),
+            ),
+            ("demo::old".to_string(), entry("function", "fn old()", &[])),
+        ]);
+        let mut new = old.clone();
+        new.remove("demo::old");
+        new.insert(
+            "demo::run".to_string(),
+            entry("function", "fn run(opts: &Opts)", &[]),
+        );
+        new.insert("demo::Mode::B".to_string(), entry("variant", "B", &[]));
+        new.insert(
+            "demo::Opts::verbose".to_string(),
+            entry("struct_field", "verbose: bool", &[]),
+        );
+
+        let changes = diff_api(&old, &new);
+        let find = |path: &str| changes.iter().find(|c| c.path == path).unwrap();
+        assert!(find("demo::old").breaking);
+        assert_eq!(find("demo::run").reason, "signature changed");
+        assert!(find("demo::Mode::B").breaking);
+        assert!(!find("demo::Opts::verbose").breaking);
+        assert_eq!(changes.len(), 4);
+    }
+
+    #[test]
+    fn test_required_bump() {
+        let added = change("demo::f", "added", false, "item added", None, None);
+        let removed = change("demo::g", "removed", true, "item removed", None, None);
+        let v1 = Version::new(1, 2, 0);
+        let v0 = Version::new(0, 3, 1);
+        assert_eq!(required_bump(&[], &v1), "none");
+        assert_eq!(required_bump(std::slice::from_ref(&added), &v1), "minor");
+        assert_eq!(required_bump(std::slice::from_ref(&added), &v0), "patch");
+        assert_eq!(required_bump(&[added, removed], &v1), "major");
+    }
+}
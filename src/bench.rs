@@ -0,0 +1,188 @@
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use rustdoc_mcp_core::config::Config;
+use rustdoc_mcp_core::index::{CrateIndex, LoadedCrate, SearchOptions};
+use rustdoc_mcp_core::workspace::Workspace;
+
+/// Loads a crate's docs and reports the time spent in each loading stage, the change in
+/// resident memory, and search latency percentiles over `queries` synthetic queries.
+pub async fn bench_index(
+    cwd: &str,
+    config_path: Option<&str>,
+    crate_name: &str,
+    queries: usize,
+) -> Result<String> {
+    let workspace = Workspace::load(cwd).context("Failed to load workspace")?;
+    let config = Config::load(config_path.map(Path::new), &workspace.root)?;
+    let index = CrateIndex::new(workspace, config);
+
+    let memory_before = resident_memory();
+    let timings = index.load(crate_name).await?;
+    let memory_after = resident_memory();
+
+    let queries = match index.get_crate(crate_name) {
+        Some(loaded) => synthetic_queries(&loaded, queries),
+        None => Vec::new(),
+    };
+    let mut latencies = Vec::with_capacity(queries.len());
+    for query in &queries {
+        let start = Instant::now();
+        index
+            .search(
+                query,
+                &SearchOptions {
+                    crate_name: Some(crate_name.to_string()),
+                    ..Default::default()
+                },
+            )
+            .await?;
+        latencies.push(start.elapsed());
+    }
+    latencies.sort();
+
+    let mut report = format!(
+        "Crate: {crate_name} ({:.1} MiB of JSON, {} paths)\n",
+        mebibytes(timings.json_bytes as u64),
+        timings.paths
+    );
+    let generate = if timings.generate.is_zero() {
+        "cached".to_string()
+    } else {
+        millis(timings.generate)
+    };
+    report.push_str(&format!("Generate docs:    {generate}\n"));
+    report.push_str(&format!("Read JSON:        {}\n", millis(timings.read)));
+    report.push_str(&format!("Parse JSON:       {}\n", millis(timings.parse)));
+    report.push_str(&format!("Build path map:   {}\n", millis(timings.path_map)));
+    report.push_str(&format!(
+        "Derived indexes:  {}\n",
+        millis(timings.derived_indexes)
+    ));
+    match (memory_before, memory_after) {
+        (Some(before), Some(after)) => report.push_str(&format!(
+            "Memory delta:     {:+.1} MiB\n",
+            mebibytes(after) - mebibytes(before)
+        )),
+        _ => report.push_str("Memory delta:     unavailable on this platform\n"),
+    }
+    if latencies.is_empty() {
+        report.push_str("Search latency:   no queries\n");
+    } else {
+        report.push_str(&format!(
+            "Search latency:   p50 {}, p90 {}, p99 {}, max {} ({} queries)\n",
+            millis(percentile(&latencies, 50.0)),
+            millis(percentile(&latencies, 90.0)),
+            millis(percentile(&latencies, 99.0)),
+            millis(percentile(&latencies, 100.0)),
+            latencies.len()
+        ));
+    }
+    Ok(report)
+}
+
+/// Derives a deterministic query mix from a crate's item names: exact names, short
+/// prefixes and names with two letters swapped, so exact, prefix and fuzzy matching are
+/// all exercised.
+pub fn synthetic_queries(loaded: &LoadedCrate, count: usize) -> Vec<String> {
+    let mut names: Vec<&str> = loaded
+        .path_to_id
+        .keys()
+        .filter_map(|path| path.rsplit("::").next())
+        .collect();
+    names.sort_unstable();
+    names.dedup();
+    if names.is_empty() {
+        return Vec::new();
+    }
+
+    let step = (names.len() / count.max(1)).max(1);
+    names
+        .iter()
+        .step_by(step)
+        .cycle()
+        .enumerate()
+        .take(count)
+        .map(|(i, name)| match i % 3 {
+            0 => name.to_string(),
+            1 => name.chars().take(3).collect(),
+            _ => swap_letters(name),
+        })
+        .collect()
+}
+
+/// Swaps the two middle characters of `name`, imitating a typo.
+fn swap_letters(name: &str) -> String {
+    let mut chars: Vec<char> = name.chars().collect();
+    if chars.len() >= 2 {
+        let middle = chars.len() / 2;
+        chars.swap(middle - 1, middle);
+    }
+    chars.into_iter().collect()
+}
+
+/// Returns the nearest-rank percentile of sorted, non-empty latencies.
+fn percentile(sorted: &[Duration], p: f64) -> Duration {
+    let rank = ((p / 100.0) * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// Reads the resident set size of this process in bytes, where `/proc` is available.
+fn resident_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+fn mebibytes(bytes: u64) -> f64 {
+    bytes as f64 / (1024.0 * 1024.0)
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_types::{Crate, Id};
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_percentile() {
+        let latencies: Vec<Duration> = (1..=10).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&latencies, 50.0), Duration::from_millis(5));
+        assert_eq!(percentile(&latencies, 90.0), Duration::from_millis(9));
+        assert_eq!(percentile(&latencies, 99.0), Duration::from_millis(10));
+        assert_eq!(percentile(&latencies, 0.0), Duration::from_millis(1));
+    }
+
+    #[test]
+    fn test_synthetic_queries() {
+        let krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let path_to_id = HashMap::from([
+            ("demo::Runtime".to_string(), Id(1)),
+            ("demo::spawn".to_string(), Id(2)),
+        ]);
+        let loaded = LoadedCrate::new(krate, path_to_id);
+
+        assert_eq!(
+            synthetic_queries(&loaded, 4),
+            vec!["Runtime", "spa", "Rutnime", "spawn"]
+        );
+    }
+}
@@ -0,0 +1,83 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use rustdoc_mcp_core::atomic_file;
+
+use crate::types::Bookmark;
+
+/// Where a workspace's bookmarks are kept, under its target directory so that they
+/// never show up in the project's sources.
+pub fn bookmarks_path(target_dir: &Path) -> PathBuf {
+    target_dir.join("rustdoc-mcp").join("bookmarks.json")
+}
+
+/// Reads the bookmarks at `path`; there are none until the first is saved.
+pub fn read(path: &Path) -> Result<Vec<Bookmark>> {
+    atomic_file::read_json(path)
+}
+
+/// Adds a bookmark, replacing the note of an item that is already bookmarked. Returns
+/// whether one was replaced.
+pub fn add(bookmarks: &mut Vec<Bookmark>, bookmark: Bookmark) -> bool {
+    match bookmarks.iter_mut().find(|b| b.path == bookmark.path) {
+        Some(existing) => {
+            *existing = bookmark;
+            true
+        }
+        None => {
+            bookmarks.push(bookmark);
+            false
+        }
+    }
+}
+
+/// Writes the bookmarks, replacing the file at once so concurrent servers never read a
+/// partial list.
+pub fn write(path: &Path, bookmarks: &[Bookmark]) -> Result<()> {
+    atomic_file::write_json(path, bookmarks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bookmark(path: &str, note: &str) -> Bookmark {
+        Bookmark {
+            path: path.to_string(),
+            kind: "struct".to_string(),
+            note: note.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = bookmarks_path(dir.path());
+        let mut bookmarks = read(&path).unwrap();
+        assert!(bookmarks.is_empty());
+
+        assert!(!add(
+            &mut bookmarks,
+            bookmark("demo::Greeter", "entry point")
+        ));
+        assert!(!add(&mut bookmarks, bookmark("demo::Config", "settings")));
+        assert!(add(
+            &mut bookmarks,
+            bookmark("demo::Greeter", "builds greetings")
+        ));
+        write(&path, &bookmarks).unwrap();
+
+        let saved = read(&path).unwrap();
+        let saved: Vec<(&str, &str)> = saved
+            .iter()
+            .map(|b| (b.path.as_str(), b.note.as_str()))
+            .collect();
+        assert_eq!(
+            saved,
+            vec![
+                ("demo::Greeter", "builds greetings"),
+                ("demo::Config", "settings")
+            ]
+        );
+    }
+}
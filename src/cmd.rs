@@ -1,4 +1,4 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
 
 #[derive(Debug, Parser)]
 pub struct CmdOptions {
@@ -14,6 +14,69 @@ pub enum AppCommand {
             help = "Specify the working directory, defaults to current directory"
         )]
         cwd: Option<String>,
+        #[clap(
+            long,
+            help = "Path to a TOML config file, defaults to rustdoc-mcp.toml in the workspace root"
+        )]
+        config: Option<String>,
+        #[clap(
+            long,
+            help = "Serve prebuilt rustdoc JSON from this directory instead of running cargo"
+        )]
+        fixture: Option<String>,
+        #[clap(
+            long,
+            value_enum,
+            default_value_t = Transport::Stdio,
+            help = "How clients connect: over stdin/stdout, or over Streamable HTTP to share one server between clients"
+        )]
+        transport: Transport,
+        #[clap(
+            long,
+            default_value_t = 8080,
+            help = "Port to listen on (localhost only) with --transport http or --daemon"
+        )]
+        port: u16,
+        #[clap(
+            long,
+            help = "Keep serving over HTTP between editor sessions, saving the loaded crates when idle and loading them again on the next start"
+        )]
+        daemon: bool,
+        #[clap(
+            long,
+            default_value_t = 60,
+            help = "Seconds without tool calls after which --daemon saves the loaded crates"
+        )]
+        idle_secs: u64,
+    },
+    /// Print the JSON schemas of all tools
+    Schemas,
+    /// Load a crate's docs and report load times, memory use and search latency
+    BenchIndex {
+        #[clap(long = "crate", help = "The crate to load")]
+        crate_name: String,
+        #[clap(
+            long,
+            help = "Specify the working directory, defaults to current directory"
+        )]
+        cwd: Option<String>,
+        #[clap(
+            long,
+            help = "Path to a TOML config file, defaults to rustdoc-mcp.toml in the workspace root"
+        )]
+        config: Option<String>,
+        #[clap(
+            long,
+            default_value_t = 300,
+            help = "Number of synthetic search queries to time"
+        )]
+        queries: usize,
     },
     Version,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Transport {
+    Stdio,
+    Http,
+}
@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use rmcp::model::{CallToolResult, JsonObject, RawContent, Tool};
+use rustdoc_mcp_core::config::OutputFormat;
+use rustdoc_mcp_core::render::{JsonRenderer, TextRenderer, render_markdown};
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+
+/// Argument accepted by every tool to choose the output format of its response.
+pub const FORMAT_ARG: &str = "format";
+
+/// Advertises the `format` argument in a tool's input schema.
+pub fn add_format_param(tool: &mut Tool) {
+    let schema = Arc::make_mut(&mut tool.input_schema);
+    let properties = schema
+        .entry("properties")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Value::Object(properties) = properties {
+        properties.insert(
+            FORMAT_ARG.to_string(),
+            json!({
+                "type": ["string", "null"],
+                "enum": ["full", "compact", "json", null],
+                "description": "`compact` returns dense plain text instead of markdown or JSON, using fewer tokens. `json` returns markdown as a JSON list of blocks. Defaults to the server's `output.format`."
+            }),
+        );
+    }
+}
+
+/// Removes the `format` argument before the call reaches the tool, returning the format
+/// it selects, if any.
+pub fn take_format(arguments: &mut Option<JsonObject>) -> Result<Option<OutputFormat>, String> {
+    let Some(format) = arguments.as_mut().and_then(|args| args.remove(FORMAT_ARG)) else {
+        return Ok(None);
+    };
+    match format {
+        Value::Null => Ok(None),
+        other => OutputFormat::deserialize(&other).map(Some).map_err(|_| {
+            format!("Invalid format: {other} (expected \"full\", \"compact\" or \"json\")")
+        }),
+    }
+}
+
+/// Strips markdown down to its text: no heading markers, list bullets, code fences,
+/// inline backticks, link targets or blank lines. Code block lines are kept verbatim.
+pub fn compact_markdown(markdown: &str) -> String {
+    render_markdown(markdown, &mut TextRenderer::default())
+}
+
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.replace('\n', " "),
+        Value::Array(items) if items.iter().all(is_scalar) => {
+            items.iter().map(scalar).collect::<Vec<_>>().join(", ")
+        }
+        other => other.to_string(),
+    }
+}
+
+fn is_scalar(value: &Value) -> bool {
+    !matches!(value, Value::Array(_) | Value::Object(_))
+}
+
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::Null => true,
+        Value::Array(items) => items.is_empty(),
+        Value::Object(fields) => fields.is_empty(),
+        _ => false,
+    }
+}
+
+/// Renders an array of objects as a header line of their keys and one pipe-delimited
+/// row per object. Keys that are empty in every row are left out.
+fn table(rows: &[Value], indent: &str, out: &mut Vec<String>) {
+    let mut keys: Vec<&str> = Vec::new();
+    for row in rows {
+        if let Value::Object(fields) = row {
+            for (key, value) in fields {
+                if !is_empty(value) && !keys.contains(&key.as_str()) {
+                    keys.push(key);
+                }
+            }
+        }
+    }
+    out.push(format!("{indent}{}", keys.join("|")));
+    for row in rows {
+        let cells: Vec<String> = keys
+            .iter()
+            .map(|key| row.get(*key).map(scalar).unwrap_or_default())
+            .collect();
+        out.push(format!("{indent}{}", cells.join("|")));
+    }
+}
+
+fn object(fields: &Map<String, Value>, indent: &str, out: &mut Vec<String>) {
+    for (key, value) in fields {
+        if is_empty(value) {
+            continue;
+        }
+        match value {
+            Value::Object(nested) => {
+                out.push(format!("{indent}{key}:"));
+                object(nested, &format!("{indent} "), out);
+            }
+            Value::Array(items) if items.iter().all(Value::is_object) => {
+                out.push(format!("{indent}{key}[{}]:", items.len()));
+                table(items, &format!("{indent} "), out);
+            }
+            Value::Array(items) if !items.iter().all(is_scalar) => {
+                out.push(format!("{indent}{key}[{}]:", items.len()));
+                for item in items {
+                    out.push(format!("{indent} {}", scalar(item)));
+                }
+            }
+            _ => out.push(format!("{indent}{key}: {}", scalar(value))),
+        }
+    }
+}
+
+/// Flattens JSON into `key: value` lines, nesting objects by one space of indentation
+/// and rendering arrays of objects as pipe-delimited tables. Nulls and empty collections
+/// are omitted.
+pub fn compact_json(value: &Value) -> String {
+    let mut out = Vec::new();
+    match value {
+        Value::Object(fields) => object(fields, "", &mut out),
+        Value::Array(items) if items.iter().all(Value::is_object) => table(items, "", &mut out),
+        other => out.push(scalar(other)),
+    }
+    out.join("\n")
+}
+
+/// Rewrites the text of a tool result in the compact format. JSON text is flattened with
+/// [`compact_json`] and anything else is treated as markdown. Structured content is
+/// dropped, since the text no longer mirrors it.
+pub fn compact_tool_result(result: &mut CallToolResult) {
+    for content in &mut result.content {
+        if let RawContent::Text(text) = &mut content.raw {
+            text.text = match serde_json::from_str::<Value>(&text.text) {
+                Ok(value @ (Value::Object(_) | Value::Array(_))) => compact_json(&value),
+                _ => compact_markdown(&text.text),
+            };
+        }
+    }
+    result.structured_content = None;
+}
+
+/// Rewrites markdown text of a tool result as JSON blocks. Text that already is JSON is
+/// left alone.
+pub fn json_tool_result(result: &mut CallToolResult) {
+    for content in &mut result.content {
+        if let RawContent::Text(text) = &mut content.raw
+            && !matches!(
+                serde_json::from_str::<Value>(&text.text),
+                Ok(Value::Object(_) | Value::Array(_))
+            )
+        {
+            text.text = render_markdown(&text.text, &mut JsonRenderer::default());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_markdown() {
+        let markdown = "# Struct Greeter\n\n```rust\npub struct Greeter {\n    name: String,\n}\n```\n\nGreets people. See [`Vec`](std::vec::Vec).\n\n## Fields\n\n* `name: String` - The name.\n\n---\n";
+        assert_eq!(
+            compact_markdown(markdown),
+            "Struct Greeter\npub struct Greeter {\n    name: String,\n}\nGreets people. See Vec.\nFields:\n- name: String - The name."
+        );
+    }
+
+    #[test]
+    fn test_compact_json() {
+        let value = json!({
+            "crate_name": "demo",
+            "traits": ["Send", "Clone"],
+            "rows": [
+                {"path": "demo::A", "kind": "struct", "note": null},
+                {"path": "demo::B", "kind": "enum", "note": null},
+            ],
+            "coverage": {"implemented": 2},
+            "unavailable": [],
+        });
+        assert_eq!(
+            compact_json(&value),
+            "coverage:\n implemented: 2\ncrate_name: demo\nrows[2]:\n kind|path\n struct|demo::A\n enum|demo::B\ntraits: Send, Clone"
+        );
+    }
+
+    #[test]
+    fn test_take_format() {
+        let mut args: Option<JsonObject> = Some(
+            json!({"path": "tokio", "format": "compact"})
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+        assert_eq!(take_format(&mut args), Ok(Some(OutputFormat::Compact)));
+        assert!(!args.as_ref().unwrap().contains_key(FORMAT_ARG));
+        assert_eq!(take_format(&mut args), Ok(None));
+
+        let mut args = json!({"format": "json"}).as_object().cloned();
+        assert_eq!(take_format(&mut args), Ok(Some(OutputFormat::Json)));
+
+        let mut args = json!({"format": "yaml"}).as_object().cloned();
+        assert!(
+            take_format(&mut args)
+                .unwrap_err()
+                .contains("Invalid format")
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Result;
+use rustdoc_mcp_core::atomic_file;
+use serde::{Deserialize, Serialize};
+
+use crate::http;
+use crate::server::RustDocMCPServer;
+
+/// The crates a daemon had loaded, restored when it starts again.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    pub crates: Vec<String>,
+    /// Packages loaded with a chosen feature set by `generate_docs`, with those features.
+    #[serde(default)]
+    pub feature_variants: Vec<(String, Vec<String>)>,
+}
+
+/// Where a workspace's daemon session is kept, next to its bookmarks.
+pub fn session_path(target_dir: &Path) -> PathBuf {
+    target_dir.join("rustdoc-mcp").join("session.json")
+}
+
+/// Reads the session at `path`; there is none before the first daemon saved one.
+pub fn read(path: &Path) -> Result<Session> {
+    atomic_file::read_json(path)
+}
+
+/// Writes the session, replacing the file at once so a starting daemon never reads a
+/// partial one.
+pub fn write(path: &Path, session: &Session) -> Result<()> {
+    atomic_file::write_json(path, session)
+}
+
+/// Saves the loaded crates unless they are the ones saved last.
+fn save(server: &RustDocMCPServer, path: &Path, saved: &mut Session) {
+    let session = Session {
+        crates: server.loaded_crates(),
+        feature_variants: server.feature_variants(),
+    };
+    if session == *saved {
+        return;
+    }
+    match write(path, &session) {
+        Ok(()) => {
+            tracing::info!(
+                "Saved {} loaded crates and {} feature sets to {}",
+                session.crates.len(),
+                session.feature_variants.len(),
+                path.display()
+            );
+            *saved = session;
+        }
+        Err(e) => tracing::warn!("{e:#}"),
+    }
+}
+
+/// Serves over HTTP like [`http::serve`], for a server meant to outlive editor sessions:
+/// the crates loaded by the previous run, including those `generate_docs` loaded with
+/// chosen features, are loaded again in the background, and the loaded crates are saved
+/// whenever no tool was called for `idle`, and on shutdown. Loading them again is quick
+/// since their indexes are cached under the target directory as they load.
+pub async fn serve(server: RustDocMCPServer, port: u16, idle: Duration) -> Result<()> {
+    let Some(path) = server.session_path() else {
+        tracing::info!("Fixture workspaces keep no daemon session");
+        return http::serve(server, port).await;
+    };
+
+    let session = read(&path).unwrap_or_else(|e| {
+        tracing::warn!("Ignoring the previous session: {e:#}");
+        Session::default()
+    });
+    let mut saved = session.clone();
+
+    // Saving waits for the restore, so that it never records only part of the session.
+    let saver = {
+        let server = server.clone();
+        let path = path.clone();
+        let mut saved = saved.clone();
+        tokio::spawn(async move {
+            if !session.crates.is_empty() {
+                tracing::info!("Restoring {} crates", session.crates.len());
+                let failed = server.restore_crates(&session.crates).await;
+                tracing::info!(
+                    "Restored {} of {} crates",
+                    session.crates.len() - failed,
+                    session.crates.len()
+                );
+            }
+            let variants = &session.feature_variants;
+            if !variants.is_empty() {
+                let failed = server.restore_feature_variants(variants).await;
+                tracing::info!(
+                    "Restored {} of {} feature sets",
+                    variants.len() - failed,
+                    variants.len()
+                );
+            }
+            let mut ticks = tokio::time::interval((idle / 4).max(Duration::from_secs(1)));
+            loop {
+                ticks.tick().await;
+                if server.idle_time() >= idle {
+                    save(&server, &path, &mut saved);
+                }
+            }
+        })
+    };
+
+    let served = http::serve(server.clone(), port).await;
+    saver.abort();
+    save(&server, &path, &mut saved);
+    served
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = session_path(dir.path());
+        assert_eq!(read(&path).unwrap(), Session::default());
+
+        let session = Session {
+            crates: vec!["demo".to_string(), "serde".to_string()],
+            feature_variants: vec![("tokio".to_string(), vec!["full".to_string()])],
+        };
+        write(&path, &session).unwrap();
+        assert_eq!(read(&path).unwrap(), session);
+        assert!(!path.with_extension("partial").exists());
+
+        // Sessions saved before feature sets were recorded still read.
+        std::fs::write(&path, r#"{"crates": ["demo"]}"#).unwrap();
+        assert!(read(&path).unwrap().feature_variants.is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use cargo_metadata::{DependencyKind, Package, PackageId};
+use rustdoc_mcp_core::crate_info::has_build_script;
+use rustdoc_mcp_core::workspace::Workspace;
+
+use crate::types::DepCost;
+
+/// The nodes reachable from `roots` along `edges`, the roots included, never entering
+/// `skip`.
+pub fn reachable<T: Ord + Copy>(
+    edges: &BTreeMap<T, Vec<T>>,
+    roots: impl IntoIterator<Item = T>,
+    skip: Option<T>,
+) -> BTreeSet<T> {
+    let mut seen = BTreeSet::new();
+    let mut pending: Vec<T> = roots.into_iter().filter(|&n| Some(n) != skip).collect();
+    while let Some(node) = pending.pop() {
+        if !seen.insert(node) {
+            continue;
+        }
+        for &next in edges.get(&node).into_iter().flatten() {
+            if Some(next) != skip && !seen.contains(&next) {
+                pending.push(next);
+            }
+        }
+    }
+    seen
+}
+
+/// The dependency graph of the resolved workspace. Dev-dependencies are left out since
+/// they are not built for dependents.
+fn dependency_graph(workspace: &Workspace) -> BTreeMap<&PackageId, Vec<&PackageId>> {
+    let Some(resolve) = &workspace.metadata.resolve else {
+        return BTreeMap::new();
+    };
+    resolve
+        .nodes
+        .iter()
+        .map(|node| {
+            let deps = node
+                .deps
+                .iter()
+                .filter(|dep| {
+                    dep.dep_kinds
+                        .iter()
+                        .any(|info| info.kind != DependencyKind::Development)
+                })
+                .map(|dep| &dep.pkg)
+                .collect();
+            (&node.id, deps)
+        })
+        .collect()
+}
+
+/// Estimates what depending on a package costs to build: the packages it pulls in, those
+/// nothing else in the workspace needs, and the build scripts and proc-macros among them
+/// (the package itself included).
+/// `public_items` is left unset for the caller, who knows whether its docs are loaded.
+pub fn dep_cost(workspace: &Workspace, crate_name: &str) -> Option<DepCost> {
+    let package = workspace.find_package(crate_name)?;
+    let graph = dependency_graph(workspace);
+
+    let tree = reachable(&graph, [&package.id], None);
+    let others = reachable(
+        &graph,
+        workspace.metadata.workspace_members.iter(),
+        Some(&package.id),
+    );
+    let names = |keep: &dyn Fn(&Package) -> bool| -> Vec<String> {
+        let mut names: Vec<String> = workspace
+            .metadata
+            .packages
+            .iter()
+            .filter(|p| tree.contains(&p.id) && keep(p))
+            .map(|p| p.name.to_string())
+            .collect();
+        names.sort();
+        names.dedup();
+        names
+    };
+
+    let exclusive_deps = names(&|p| p.id != package.id && !others.contains(&p.id));
+    let build_scripts = names(&|p| has_build_script(p));
+    let proc_macros = names(&|p| p.targets.iter().any(|t| t.is_proc_macro()));
+    Some(DepCost {
+        name: package.name.to_string(),
+        version: package.version.to_string(),
+        transitive_deps: tree.len().saturating_sub(1),
+        exclusive_deps,
+        has_build_script: has_build_script(package),
+        is_proc_macro: package.targets.iter().any(|t| t.is_proc_macro()),
+        build_scripts,
+        proc_macros,
+        public_items: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reachable() {
+        // app -> (http -> bytes, log), log
+        let edges = BTreeMap::from([
+            ("app", vec!["http", "log"]),
+            ("http", vec!["bytes", "log"]),
+            ("bytes", vec![]),
+            ("log", vec![]),
+        ]);
+        assert_eq!(
+            reachable(&edges, ["http"], None),
+            BTreeSet::from(["bytes", "http", "log"])
+        );
+        // Without `http`, only `log` is still needed, so `bytes` is exclusive to it.
+        assert_eq!(
+            reachable(&edges, ["app"], Some("http")),
+            BTreeSet::from(["app", "log"])
+        );
+    }
+}
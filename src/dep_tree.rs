@@ -0,0 +1,126 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use cargo_metadata::{NodeDep, PackageId};
+use rustdoc_mcp_core::workspace::{Directness, Workspace};
+
+use crate::dep_cost::reachable;
+use crate::types::{DependencyEdge, DependencyNode, GetDependencyTreeResult};
+
+/// The kinds of a dependency edge and the platforms it is limited to, each sorted and
+/// listed once.
+fn edge_kinds(dep: &NodeDep) -> (Vec<String>, Vec<String>) {
+    let kinds: BTreeSet<String> = dep.dep_kinds.iter().map(|i| i.kind.to_string()).collect();
+    let targets: BTreeSet<String> = dep
+        .dep_kinds
+        .iter()
+        .filter_map(|info| info.target.as_ref().map(ToString::to_string))
+        .collect();
+    (kinds.into_iter().collect(), targets.into_iter().collect())
+}
+
+/// The resolved dependency graph of the workspace, or of the packages `crate_name`
+/// depends on, with the kinds of every edge. `None` when `crate_name` is not a package
+/// of the workspace.
+pub fn dependency_tree(
+    workspace: &Workspace,
+    crate_name: Option<&str>,
+) -> Option<GetDependencyTreeResult> {
+    let root = match crate_name {
+        Some(name) => Some(&workspace.find_package(name)?.id),
+        None => None,
+    };
+    let Some(resolve) = &workspace.metadata.resolve else {
+        return Some(GetDependencyTreeResult { packages: vec![] });
+    };
+    let packages: HashMap<&PackageId, _> = workspace
+        .metadata
+        .packages
+        .iter()
+        .map(|p| (&p.id, p))
+        .collect();
+    let members = &workspace.metadata.workspace_members;
+    let direct: BTreeSet<&PackageId> = resolve
+        .nodes
+        .iter()
+        .filter(|node| members.contains(&node.id))
+        .flat_map(|node| node.deps.iter().map(|dep| &dep.pkg))
+        .collect();
+    let included = root.map(|root| {
+        let edges: BTreeMap<&PackageId, Vec<&PackageId>> = resolve
+            .nodes
+            .iter()
+            .map(|node| (&node.id, node.deps.iter().map(|dep| &dep.pkg).collect()))
+            .collect();
+        reachable(&edges, [root], None)
+    });
+
+    let mut nodes: Vec<(Directness, DependencyNode)> = resolve
+        .nodes
+        .iter()
+        .filter(|node| included.as_ref().is_none_or(|set| set.contains(&node.id)))
+        .filter_map(|node| {
+            let package = packages.get(&node.id)?;
+            let directness = if members.contains(&node.id) {
+                Directness::Member
+            } else if direct.contains(&node.id) {
+                Directness::Direct
+            } else {
+                Directness::Transitive
+            };
+            let mut dependencies: Vec<DependencyEdge> = node
+                .deps
+                .iter()
+                .filter_map(|dep| {
+                    let target = packages.get(&dep.pkg)?;
+                    let (kinds, targets) = edge_kinds(dep);
+                    Some(DependencyEdge {
+                        name: target.name.to_string(),
+                        version: target.version.to_string(),
+                        kinds,
+                        targets,
+                    })
+                })
+                .collect();
+            dependencies.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+            let node = DependencyNode {
+                name: package.name.to_string(),
+                version: package.version.to_string(),
+                directness: directness.as_str().to_string(),
+                dependencies,
+            };
+            Some((directness, node))
+        })
+        .collect();
+    nodes.sort_by(|(a, a_node), (b, b_node)| {
+        (a, &a_node.name, &a_node.version).cmp(&(b, &b_node.name, &b_node.version))
+    });
+    Some(GetDependencyTreeResult {
+        packages: nodes.into_iter().map(|(_, node)| node).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edge_kinds() {
+        let dep: NodeDep = serde_json::from_value(serde_json::json!({
+            "name": "winapi",
+            "pkg": "registry+https://github.com/rust-lang/crates.io-index#winapi@0.3.9",
+            "dep_kinds": [
+                {"kind": null, "target": "cfg(windows)"},
+                {"kind": "build", "target": null},
+                {"kind": null, "target": "cfg(windows)"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(
+            edge_kinds(&dep),
+            (
+                vec!["build".to_string(), "normal".to_string()],
+                vec!["cfg(windows)".to_string()]
+            )
+        );
+    }
+}
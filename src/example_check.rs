@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::fs::File;
+
+use anyhow::{Context, Result};
+use cargo_metadata::{DependencyKind, Message, Package};
+use fs2::FileExt;
+use rustdoc_mcp_core::registry::dependency_registry;
+use rustdoc_mcp_core::workspace::Workspace;
+use tokio::process::Command;
+use tracing::{debug, info, instrument};
+
+use crate::types::CheckExampleResult;
+
+/// Name of the binary target the snippet is compiled as.
+const CHECK_BIN_NAME: &str = "rustdoc_mcp_check";
+
+pub struct ExampleChecker;
+
+impl ExampleChecker {
+    /// Wraps a snippet the way rustdoc wraps a doctest: hidden `# ` lines are
+    /// kept, crate attributes are hoisted and the body is put into `fn main`
+    /// unless it already defines one.
+    pub fn wrap_snippet(code: &str) -> String {
+        let mut crate_attrs = Vec::new();
+        let mut body = Vec::new();
+
+        for line in code.lines() {
+            let line = if line.trim() == "#" {
+                ""
+            } else if let Some(rest) = line.trim_start().strip_prefix("# ") {
+                rest
+            } else if line.trim_start().starts_with("##") {
+                &line.trim_start()[1..]
+            } else {
+                line
+            };
+
+            if line.trim_start().starts_with("#![") {
+                crate_attrs.push(line.trim());
+            } else {
+                body.push(line);
+            }
+        }
+
+        let mut source = String::from("#![allow(unused)]\n");
+        for attr in crate_attrs {
+            source.push_str(attr);
+            source.push('\n');
+        }
+
+        let body = body.join("\n");
+        if body.contains("fn main") {
+            source.push_str(&body);
+            source.push('\n');
+        } else {
+            source.push_str("fn main() {\n");
+            for line in body.lines() {
+                if !line.is_empty() {
+                    source.push_str("    ");
+                }
+                source.push_str(line);
+                source.push('\n');
+            }
+            source.push_str("}\n");
+        }
+        source
+    }
+
+    /// Renders the manifest of the scratch package: `package` itself (when it
+    /// has a library) plus its normal and dev dependencies, as a doctest sees them.
+    fn scratch_manifest(package: &Package, registries: &HashMap<String, String>) -> String {
+        let mut deps = BTreeMap::new();
+
+        if package
+            .targets
+            .iter()
+            .any(|t| t.is_lib() || t.is_proc_macro())
+        {
+            let dir = package
+                .manifest_path
+                .parent()
+                .unwrap_or(package.manifest_path.as_path());
+            deps.insert(
+                package.name.to_string(),
+                format!(
+                    "{{ package = \"{}\", path = {:?} }}",
+                    package.name,
+                    dir.as_str()
+                ),
+            );
+        }
+
+        for dep in &package.dependencies {
+            if !matches!(
+                dep.kind,
+                DependencyKind::Normal | DependencyKind::Development
+            ) {
+                continue;
+            }
+
+            let mut spec = format!("{{ package = \"{}\"", dep.name);
+            if let Some(path) = &dep.path {
+                write!(spec, ", path = {:?}", path.as_str()).ok();
+            } else if let Some(git) = dep
+                .source
+                .as_ref()
+                .and_then(|s| s.repr.strip_prefix("git+"))
+            {
+                let url = git.split(['?', '#']).next().unwrap_or(git);
+                write!(spec, ", git = {url:?}").ok();
+            } else {
+                write!(spec, ", version = \"{}\"", dep.req).ok();
+                // `cargo metadata` reports the index URL, but manifests name registries.
+                if let Some(registry) = dep
+                    .registry
+                    .as_deref()
+                    .and_then(|index| dependency_registry(index, registries))
+                {
+                    write!(spec, ", {registry}").ok();
+                }
+            }
+            if !dep.features.is_empty() {
+                write!(spec, ", features = {:?}", dep.features).ok();
+            }
+            if !dep.uses_default_features {
+                spec.push_str(", default-features = false");
+            }
+            spec.push_str(" }");
+
+            let key = dep.rename.clone().unwrap_or_else(|| dep.name.clone());
+            deps.entry(key).or_insert(spec);
+        }
+
+        let mut manifest = format!(
+            "[package]\nname = \"rustdoc-mcp-check\"\nversion = \"0.0.0\"\nedition = \"{}\"\npublish = false\n\n[[bin]]\nname = \"{CHECK_BIN_NAME}\"\npath = \"main.rs\"\n\n[dependencies]\n",
+            package.edition.as_str()
+        );
+        for (key, spec) in deps {
+            writeln!(manifest, "{key} = {spec}").ok();
+        }
+        // Keep the scratch package out of the enclosing workspace.
+        manifest.push_str("\n[workspace]\n");
+        manifest
+    }
+
+    /// Compiles `code` as a doctest-style example against the dependencies of
+    /// `package` (or the workspace's root package) and returns the diagnostics.
+    #[instrument(skip(workspace, code))]
+    pub async fn check(
+        workspace: &Workspace,
+        package: Option<&str>,
+        code: &str,
+    ) -> Result<CheckExampleResult> {
+        if workspace.fixture {
+            anyhow::bail!("Checking examples runs cargo, which fixture mode does not do");
+        }
+        let package = workspace
+            .member_package(package)
+            .context("No matching workspace member to check the example against")?;
+
+        let target_dir = workspace.metadata.target_directory.as_std_path();
+        let scratch_dir = target_dir.join("rustdoc-mcp").join("check-example");
+        std::fs::create_dir_all(&scratch_dir).context("Failed to create scratch directory")?;
+
+        let lock_file =
+            File::create(scratch_dir.join("check.lock")).context("Failed to create lock file")?;
+        lock_file.lock_exclusive().context("Failed to lock file")?;
+
+        let source = Self::wrap_snippet(code);
+        std::fs::write(scratch_dir.join("main.rs"), &source)
+            .context("Failed to write example source")?;
+        std::fs::write(
+            scratch_dir.join("Cargo.toml"),
+            Self::scratch_manifest(package, &workspace.registries),
+        )
+        .context("Failed to write scratch manifest")?;
+
+        // Resolve the same dependency versions as the workspace does.
+        let lockfile = workspace.metadata.workspace_root.join("Cargo.lock");
+        if lockfile.exists() {
+            std::fs::copy(&lockfile, scratch_dir.join("Cargo.lock")).ok();
+        }
+
+        info!("Checking example against package: {}", package.name);
+        let output = Command::new("cargo")
+            .current_dir(&scratch_dir)
+            .arg("check")
+            .arg("--message-format")
+            .arg("json")
+            .arg("--target-dir")
+            .arg(target_dir)
+            .output()
+            .await
+            .context("Failed to execute cargo check");
+        lock_file.unlock().ok();
+        let output = output?;
+
+        let mut diagnostics = Vec::new();
+        for message in Message::parse_stream(output.stdout.as_slice()).flatten() {
+            if let Message::CompilerMessage(msg) = message
+                && msg.target.name == CHECK_BIN_NAME
+            {
+                diagnostics.push(msg.message.rendered.unwrap_or(msg.message.message));
+            }
+        }
+
+        let success = output.status.success();
+        if !success && diagnostics.is_empty() {
+            // Cargo failed before reaching the compiler, e.g. while resolving dependencies.
+            diagnostics.push(String::from_utf8_lossy(&output.stderr).into_owned());
+        }
+
+        debug!("Example check finished, success: {}", success);
+        Ok(CheckExampleResult {
+            success,
+            diagnostics,
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrap_snippet_adds_main() {
+        let wrapped = ExampleChecker::wrap_snippet("# use std::fmt;\nlet x = 1;");
+        assert_eq!(
+            wrapped,
+            "#![allow(unused)]\nfn main() {\n    use std::fmt;\n    let x = 1;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_wrap_snippet_keeps_main_and_hoists_attrs() {
+        let wrapped =
+            ExampleChecker::wrap_snippet("#![deny(warnings)]\nfn main() {\n    run();\n}");
+        assert_eq!(
+            wrapped,
+            "#![allow(unused)]\n#![deny(warnings)]\nfn main() {\n    run();\n}\n"
+        );
+    }
+}
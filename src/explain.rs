@@ -0,0 +1,231 @@
+use rustdoc_mcp_core::index::CrateIndex;
+use rustdoc_mcp_core::workspace::Workspace;
+use rustdoc_types::ItemEnum;
+use strsim::jaro_winkler;
+
+use crate::imports::suggest_imports;
+use crate::types::{ExplainErrorResult, ItemSummary};
+
+/// The names a compiler error is about, extracted from its message.
+#[derive(Debug, Default, PartialEq)]
+pub struct ErrorQuery {
+    pub code: Option<String>,
+    pub message: String,
+    /// The type the error happened on, e.g. the receiver of a missing method.
+    pub type_name: Option<String>,
+    /// A method or field looked up on `type_name`.
+    pub member_name: Option<String>,
+    /// A name that failed to resolve in scope.
+    pub unresolved_name: Option<String>,
+    /// A trait the error refers to, e.g. an unsatisfied bound.
+    pub trait_name: Option<String>,
+}
+
+/// Parses either the rendered text of a rustc error or a JSON diagnostic
+/// (as emitted by `rustc --error-format json` or `cargo --message-format json`).
+pub fn parse_diagnostic(input: &str) -> ErrorQuery {
+    let (message, code) = match serde_json::from_str::<serde_json::Value>(input) {
+        Ok(value) => {
+            // Cargo wraps the compiler diagnostic in a `compiler-message` object.
+            let diagnostic = value
+                .get("message")
+                .filter(|m| m.is_object())
+                .unwrap_or(&value);
+            (
+                diagnostic["message"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                diagnostic["code"]["code"].as_str().map(ToString::to_string),
+            )
+        }
+        Err(_) => parse_rendered(input),
+    };
+
+    let quoted: Vec<&str> = message.split('`').skip(1).step_by(2).collect();
+    let mut query = ErrorQuery {
+        code,
+        ..Default::default()
+    };
+
+    if message.contains("no method named")
+        || message.contains("no function or associated item named")
+        || message.contains("no associated item named")
+        || message.contains("no field")
+    {
+        query.member_name = quoted.first().map(|s| (*s).to_string());
+        query.type_name = quoted.get(1).map(|s| base_type_name(s));
+    } else if message.contains("the trait bound") {
+        if let Some((ty, trait_)) = quoted.first().and_then(|s| s.split_once(": ")) {
+            query.type_name = Some(base_type_name(ty));
+            query.trait_name = Some(base_type_name(trait_));
+        }
+    } else if message.contains("is not implemented for") {
+        query.trait_name = quoted.first().map(|s| base_type_name(s));
+        query.type_name = quoted.get(1).map(|s| base_type_name(s));
+    } else if message.contains("cannot find")
+        || message.contains("undeclared")
+        || message.contains("unresolved import")
+        || message.contains("failed to resolve")
+    {
+        query.unresolved_name = quoted.first().map(|s| base_type_name(s));
+    }
+
+    query.message = message;
+    query
+}
+
+/// Picks the primary message and error code out of rendered compiler output.
+fn parse_rendered(input: &str) -> (String, Option<String>) {
+    let line = input
+        .lines()
+        .map(str::trim)
+        .find(|line| line.starts_with("error"))
+        .or_else(|| input.lines().map(str::trim).find(|line| !line.is_empty()))
+        .unwrap_or_default();
+
+    let code = line
+        .strip_prefix("error[")
+        .and_then(|rest| rest.split_once(']'))
+        .map(|(code, _)| code.to_string());
+
+    let message = line
+        .split_once(": ")
+        .filter(|(head, _)| head.starts_with("error"))
+        .map_or(line, |(_, message)| message);
+
+    (message.to_string(), code)
+}
+
+/// Reduces a type as printed by rustc (`&mut Vec<u8>`, `std::fs::File`) to its bare name.
+fn base_type_name(ty: &str) -> String {
+    let ty = ty
+        .trim()
+        .trim_start_matches('&')
+        .trim_start_matches("mut ")
+        .trim_start_matches("dyn ")
+        .trim_start_matches("impl ");
+    let ty = ty.split('<').next().unwrap_or(ty);
+    ty.rsplit("::").next().unwrap_or(ty).trim().to_string()
+}
+
+fn is_type_like(inner: &ItemEnum) -> bool {
+    matches!(
+        inner,
+        ItemEnum::Struct(_)
+            | ItemEnum::Enum(_)
+            | ItemEnum::Union(_)
+            | ItemEnum::Trait(_)
+            | ItemEnum::TypeAlias(_)
+            | ItemEnum::Primitive(_)
+    )
+}
+
+/// Maps a parsed error onto the loaded documentation: the types involved, similarly
+/// named members, traits that provide the missing member and imports to add.
+pub fn lookup(
+    index: &CrateIndex,
+    workspace: &Workspace,
+    query: &ErrorQuery,
+    crate_name: Option<&str>,
+) -> ExplainErrorResult {
+    let mut result = ExplainErrorResult {
+        code: query.code.clone(),
+        message: query.message.clone(),
+        types: Vec::new(),
+        similar_items: Vec::new(),
+        providing_traits: Vec::new(),
+        imports: Vec::new(),
+    };
+
+    for name in index.loaded_crate_names() {
+        if crate_name.is_some_and(|target| target != name) {
+            continue;
+        }
+        let Some(loaded) = index.get_crate(&name) else {
+            continue;
+        };
+        if let Some(type_name) = &query.type_name {
+            for (path, item) in loaded.find_by_name(type_name) {
+                if !is_type_like(&item.inner) {
+                    continue;
+                }
+                result
+                    .types
+                    .push(ItemSummary::from_item(path, &name, &loaded, item.id));
+
+                if let Some(member) = &query.member_name {
+                    for (member_path, member_item) in loaded.members_of(path) {
+                        let member_name = member_path.rsplit("::").next().unwrap_or(member_path);
+                        if jaro_winkler(member, member_name) > 0.8 {
+                            result.similar_items.push(ItemSummary::from_item(
+                                member_path,
+                                &name,
+                                &loaded,
+                                member_item.id,
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+
+        if let Some(member) = &query.member_name {
+            for (path, item) in loaded.traits_declaring(member) {
+                result
+                    .providing_traits
+                    .push(ItemSummary::from_item(path, &name, &loaded, item.id));
+                result.imports.push(format!("use {path};"));
+            }
+        }
+    }
+
+    for wanted in [&query.unresolved_name, &query.trait_name]
+        .into_iter()
+        .flatten()
+    {
+        result.imports.extend(
+            suggest_imports(index, workspace, wanted, crate_name)
+                .into_iter()
+                .map(|suggestion| suggestion.use_statement),
+        );
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    result.imports.retain(|import| seen.insert(import.clone()));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rendered_missing_method() {
+        let query = parse_diagnostic(
+            "error[E0599]: no method named `next` found for struct `Foo<u8>` in the current scope\n  --> src/main.rs:4:7",
+        );
+        assert_eq!(query.code.as_deref(), Some("E0599"));
+        assert_eq!(query.member_name.as_deref(), Some("next"));
+        assert_eq!(query.type_name.as_deref(), Some("Foo"));
+        assert!(query.message.starts_with("no method named"));
+    }
+
+    #[test]
+    fn test_parse_json_unresolved_name() {
+        let query = parse_diagnostic(
+            r#"{"reason":"compiler-message","message":{"message":"failed to resolve: use of undeclared type `HashMap`","code":{"code":"E0433","explanation":null}}}"#,
+        );
+        assert_eq!(query.code.as_deref(), Some("E0433"));
+        assert_eq!(query.unresolved_name.as_deref(), Some("HashMap"));
+    }
+
+    #[test]
+    fn test_parse_trait_bound() {
+        let query = parse_diagnostic(
+            "error[E0277]: the trait bound `&mut Config: serde::Serialize` is not satisfied",
+        );
+        assert_eq!(query.type_name.as_deref(), Some("Config"));
+        assert_eq!(query.trait_name.as_deref(), Some("Serialize"));
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::{BTreeMap, HashMap};
+
+use cargo_metadata::Package;
+use rustdoc_mcp_core::headings::extract_headings;
+use rustdoc_mcp_core::workspace::Workspace;
+use rustdoc_types::Id;
+
+use crate::types::FeatureInfo;
+
+/// Extracts feature descriptions written as `## ` comments above the entries of the
+/// `[features]` table, the convention used by the `document-features` crate.
+pub fn manifest_feature_docs(manifest: &str) -> HashMap<String, String> {
+    let mut docs = HashMap::new();
+    let mut in_features = false;
+    let mut pending: Vec<&str> = Vec::new();
+
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_features = line == "[features]";
+            pending.clear();
+            continue;
+        }
+        if !in_features {
+            continue;
+        }
+
+        if let Some(comment) = line.strip_prefix("##") {
+            pending.push(comment.trim());
+        } else if let Some((name, _)) = line.split_once('=')
+            && !line.starts_with('#')
+        {
+            if !pending.is_empty() {
+                let name = name.trim().trim_matches('"').to_string();
+                docs.insert(name, pending.join(" "));
+            }
+            pending.clear();
+        } else if !line.starts_with('#') || line.starts_with("#!") {
+            pending.clear();
+        }
+    }
+    docs
+}
+
+/// Extracts feature descriptions from list items like "- `name`: description" or
+/// "* **name** - description" in doc sections whose heading mentions features.
+pub fn doc_feature_docs(markdown: &str) -> HashMap<String, String> {
+    let mut docs = HashMap::new();
+    for (_, title, section) in extract_headings(markdown) {
+        if !title.to_lowercase().contains("feature") {
+            continue;
+        }
+        for line in section.lines() {
+            let line = line.trim_start();
+            let Some(item) = line.strip_prefix("- ").or_else(|| line.strip_prefix("* ")) else {
+                continue;
+            };
+            let item = item.trim_start_matches("**").trim_start_matches('`');
+            let Some(end) = item.find(['`', '*']) else {
+                continue;
+            };
+            let name = &item[..end];
+            if name.is_empty() || name.contains(char::is_whitespace) {
+                continue;
+            }
+            let description = item[end..]
+                .trim_start_matches(['`', '*', ':', '-', '—', ' '])
+                .trim();
+            if !description.is_empty() {
+                docs.entry(name.to_string())
+                    .or_insert_with(|| description.to_string());
+            }
+        }
+    }
+    docs
+}
+
+/// Reads the `//!` crate docs from the start of a library's root source file.
+fn crate_root_docs(source: &str) -> String {
+    source
+        .lines()
+        .map(str::trim_start)
+        .filter_map(|line| line.strip_prefix("//!"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The features of a package enabled in the current build, as resolved by cargo.
+pub fn enabled_features(workspace: &Workspace, package: &Package) -> Vec<String> {
+    workspace
+        .metadata
+        .resolve
+        .as_ref()
+        .and_then(|resolve| resolve.nodes.iter().find(|node| node.id == package.id))
+        .map(|node| node.features.iter().map(ToString::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Lists a package's features with what they enable, whether they are on by default or
+/// enabled in the current build, and a description taken from `Cargo.toml` comments, the
+/// crate docs or the README, in that order.
+pub fn list_features(workspace: &Workspace, crate_name: &str) -> Option<Vec<FeatureInfo>> {
+    let package = workspace.find_package(crate_name)?;
+
+    let mut descriptions = std::fs::read_to_string(&package.manifest_path)
+        .map(|manifest| manifest_feature_docs(&manifest))
+        .unwrap_or_default();
+    let lib_docs = package
+        .targets
+        .iter()
+        .find(|t| t.is_lib() || t.is_proc_macro())
+        .and_then(|t| std::fs::read_to_string(&t.src_path).ok())
+        .map(|source| crate_root_docs(&source));
+    let readme = package
+        .readme()
+        .and_then(|path| std::fs::read_to_string(path).ok());
+    for markdown in [lib_docs, readme].into_iter().flatten() {
+        for (name, description) in doc_feature_docs(&markdown) {
+            descriptions.entry(name).or_insert(description);
+        }
+    }
+
+    let enabled = enabled_features(workspace, package);
+    let defaults = package.features.get("default");
+    let optional: Vec<String> = package
+        .dependencies
+        .iter()
+        .filter(|dep| dep.optional)
+        .map(|dep| dep.rename.clone().unwrap_or_else(|| dep.name.clone()))
+        .collect();
+
+    Some(
+        package
+            .features
+            .iter()
+            .map(|(name, enables)| FeatureInfo {
+                name: name.clone(),
+                enables: enables.clone(),
+                default: defaults.is_some_and(|d| d.contains(name)),
+                enabled: enabled.contains(name),
+                description: descriptions.get(name).cloned(),
+                optional_dependencies: optional_dependencies(&package.features, &optional, name),
+            })
+            .collect(),
+    )
+}
+
+/// Whether enabling `from` turns on `feature`, directly or through the features it
+/// enables. Entries naming dependencies (`dep:x`, `x/y`) are not followed.
+fn enables(features: &BTreeMap<String, Vec<String>>, from: &str, feature: &str) -> bool {
+    let mut pending = vec![from];
+    let mut seen = Vec::new();
+    while let Some(current) = pending.pop() {
+        if current == feature {
+            return true;
+        }
+        if seen.contains(&current) {
+            continue;
+        }
+        seen.push(current);
+        pending.extend(
+            features
+                .get(current)
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        );
+    }
+    false
+}
+
+/// The features to build a package's docs with for a `generate_docs` request: `features`
+/// plus `default` unless `no_default_features`, or all of them with `all_features`.
+/// Sorted. Fails on features the package does not have.
+pub fn select_features(
+    features: &BTreeMap<String, Vec<String>>,
+    requested: &[String],
+    all_features: bool,
+    no_default_features: bool,
+) -> Result<Vec<String>, String> {
+    if let Some(unknown) = requested.iter().find(|f| !features.contains_key(*f)) {
+        let known: Vec<&str> = features.keys().map(String::as_str).collect();
+        return Err(format!(
+            "Unknown feature `{unknown}`. The features are: {}",
+            known.join(", ")
+        ));
+    }
+    if all_features {
+        return Ok(features.keys().cloned().collect());
+    }
+    let mut selected = requested.to_vec();
+    if !no_default_features && features.contains_key("default") {
+        selected.push("default".to_string());
+    }
+    selected.sort();
+    selected.dedup();
+    Ok(selected)
+}
+
+/// The optional dependencies among `optional` that enabling `feature` turns on: those
+/// named by `dep:x` or `x/y` entries (but not `x?/y`, which only applies when `x` is on
+/// anyway) of it and the features it enables, and those enabled by their bare name when
+/// no feature has it. Sorted.
+pub fn optional_dependencies(
+    features: &BTreeMap<String, Vec<String>>,
+    optional: &[String],
+    feature: &str,
+) -> Vec<String> {
+    let mut dependencies = Vec::new();
+    let mut pending = vec![feature];
+    let mut seen = Vec::new();
+    while let Some(current) = pending.pop() {
+        if seen.contains(&current) {
+            continue;
+        }
+        seen.push(current);
+        let dependency = match features.get(current) {
+            Some(enables) => {
+                pending.extend(enables.iter().map(String::as_str));
+                None
+            }
+            None => Some(
+                current
+                    .strip_prefix("dep:")
+                    .or_else(|| current.split_once('/').map(|(dep, _)| dep))
+                    .unwrap_or(current),
+            ),
+        };
+        if let Some(dependency) = dependency
+            && optional.iter().any(|dep| dep == dependency)
+            && !dependencies.contains(&dependency)
+        {
+            dependencies.push(dependency);
+        }
+    }
+    dependencies.sort_unstable();
+    dependencies.into_iter().map(str::to_string).collect()
+}
+
+/// The feature sets to build a package's docs with to see what `feature` adds: the
+/// currently enabled features without it (and without the features enabling it, like a
+/// `full` feature), and the enabled features plus it. Both are sorted.
+pub fn feature_sets(
+    features: &BTreeMap<String, Vec<String>>,
+    enabled: &[String],
+    feature: &str,
+) -> (Vec<String>, Vec<String>) {
+    let mut without: Vec<String> = enabled
+        .iter()
+        .filter(|name| !enables(features, name, feature))
+        .cloned()
+        .collect();
+    let mut with = enabled.to_vec();
+    if !with.iter().any(|name| name == feature) {
+        with.push(feature.to_string());
+    }
+    without.sort();
+    with.sort();
+    (without, with)
+}
+
+/// Compares the item paths of two builds of a crate's docs, returning the paths only in
+/// `with` and those only in `without`, both sorted.
+pub fn diff_paths(
+    without: &HashMap<String, Id>,
+    with: &HashMap<String, Id>,
+) -> (Vec<String>, Vec<String>) {
+    let mut added: Vec<String> = with
+        .keys()
+        .filter(|path| !without.contains_key(*path))
+        .cloned()
+        .collect();
+    let mut removed: Vec<String> = without
+        .keys()
+        .filter(|path| !with.contains_key(*path))
+        .cloned()
+        .collect();
+    added.sort();
+    removed.sort();
+    (added, removed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manifest_feature_docs() {
+        let manifest = r#"
+[package]
+name = "demo"
+
+[features]
+#! ### Runtime features
+## Enables the multi-threaded runtime.
+## Requires `std`.
+rt-multi-thread = ["rt"]
+rt = []
+# internal, not documented
+"macros" = []
+
+[dependencies]
+## not a feature
+serde = "1"
+"#;
+        let docs = manifest_feature_docs(manifest);
+        assert_eq!(
+            docs.get("rt-multi-thread").map(String::as_str),
+            Some("Enables the multi-threaded runtime. Requires `std`.")
+        );
+        assert!(!docs.contains_key("rt"));
+        assert!(!docs.contains_key("macros"));
+        assert_eq!(docs.len(), 1);
+    }
+
+    #[test]
+    fn test_doc_feature_docs() {
+        let markdown = "# demo\n\n## Feature flags\n\n- `derive`: Derive macros for the traits.\n* **std** - Use the standard library.\n- plain text item\n\n## Usage\n\n- `other`: not a feature";
+        let docs = doc_feature_docs(markdown);
+        assert_eq!(
+            docs.get("derive").map(String::as_str),
+            Some("Derive macros for the traits.")
+        );
+        assert_eq!(
+            docs.get("std").map(String::as_str),
+            Some("Use the standard library.")
+        );
+        assert!(!docs.contains_key("other"));
+    }
+
+    #[test]
+    fn test_crate_root_docs() {
+        assert_eq!(
+            crate_root_docs("//! Title\n//!\n//! ## Features\n#![no_std]\nfn f() {}"),
+            "Title\n\n## Features"
+        );
+    }
+
+    #[test]
+    fn test_feature_sets() {
+        let features = BTreeMap::from([
+            ("default".to_string(), vec!["rt".to_string()]),
+            (
+                "full".to_string(),
+                vec!["net".to_string(), "rt".to_string()],
+            ),
+            ("net".to_string(), vec!["dep:socket2".to_string()]),
+            ("rt".to_string(), vec![]),
+        ]);
+        let enabled: Vec<String> = ["default", "full", "net", "rt"].map(String::from).to_vec();
+        let (without, with) = feature_sets(&features, &enabled, "net");
+        assert_eq!(without, vec!["default", "rt"]);
+        assert_eq!(with, enabled);
+
+        let (without, with) = feature_sets(&features, &["rt".to_string()], "full");
+        assert_eq!(without, vec!["rt"]);
+        assert_eq!(with, vec!["full", "rt"]);
+    }
+
+    #[test]
+    fn test_select_features() {
+        let features = BTreeMap::from([
+            ("default".to_string(), vec!["rt".to_string()]),
+            ("full".to_string(), vec!["rt".to_string()]),
+            ("rt".to_string(), vec![]),
+        ]);
+        let full = vec!["full".to_string()];
+        assert_eq!(
+            select_features(&features, &full, false, false).unwrap(),
+            vec!["default", "full"]
+        );
+        assert_eq!(
+            select_features(&features, &full, false, true).unwrap(),
+            vec!["full"]
+        );
+        assert_eq!(
+            select_features(&features, &[], true, true).unwrap(),
+            vec!["default", "full", "rt"]
+        );
+        assert!(
+            select_features(&features, &["net".to_string()], false, false)
+                .unwrap_err()
+                .contains("default, full, rt")
+        );
+    }
+
+    #[test]
+    fn test_optional_dependencies() {
+        let features = BTreeMap::from([
+            (
+                "full".to_string(),
+                vec![
+                    "net".to_string(),
+                    "serde?/std".to_string(),
+                    "log".to_string(),
+                ],
+            ),
+            (
+                "net".to_string(),
+                vec!["dep:socket2".to_string(), "mio/net".to_string()],
+            ),
+            ("tracing".to_string(), vec!["dep:tracing-core".to_string()]),
+        ]);
+        let optional: Vec<String> = ["log", "mio", "serde", "socket2", "tracing", "tracing-core"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(
+            optional_dependencies(&features, &optional, "full"),
+            vec!["log", "mio", "socket2"]
+        );
+        assert_eq!(
+            optional_dependencies(&features, &optional, "tracing"),
+            vec!["tracing-core"]
+        );
+    }
+
+    #[test]
+    fn test_diff_paths() {
+        let without = HashMap::from([("demo::a".to_string(), Id(1))]);
+        let with = HashMap::from([
+            ("demo::a".to_string(), Id(1)),
+            ("demo::net::TcpStream".to_string(), Id(2)),
+        ]);
+        assert_eq!(
+            diff_paths(&without, &with),
+            (vec!["demo::net::TcpStream".to_string()], vec![])
+        );
+    }
+}
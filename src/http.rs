@@ -0,0 +1,195 @@
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use axum::extract::{Request, State};
+use axum::http::{HeaderMap, StatusCode, header};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use rmcp::transport::streamable_http_server::{
+    StreamableHttpServerConfig, StreamableHttpService, session::local::LocalSessionManager,
+};
+use rustdoc_mcp_core::config::HttpConfig;
+
+use crate::server::RustDocMCPServer;
+
+/// Path the MCP endpoint is served at.
+pub const MCP_PATH: &str = "/mcp";
+
+/// Origin hosts that are always allowed, since pages served from them are local.
+const LOCAL_HOSTS: [&str; 3] = ["localhost", "127.0.0.1", "[::1]"];
+
+/// What requests must carry, resolved from [`HttpConfig`] once at startup.
+struct Guard {
+    token: Option<String>,
+    config: HttpConfig,
+}
+
+/// The host of a `Host` header or origin, without its port.
+fn host_name(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        // `[::1]:8080`, but not a bare `[::1]`.
+        Some((name, port)) if !port.ends_with(']') => name,
+        _ => host,
+    }
+}
+
+/// Compares tokens in time independent of where they differ.
+fn same_token(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+impl Guard {
+    /// Why a request with these headers is refused, if it is.
+    fn check(&self, headers: &HeaderMap) -> Result<(), (StatusCode, &'static str)> {
+        let header =
+            |name: header::HeaderName| headers.get(name).and_then(|value| value.to_str().ok());
+
+        let host = header(header::HOST).map(host_name);
+        if !host.is_some_and(|host| self.config.allowed_hosts.iter().any(|h| h == host)) {
+            return Err((StatusCode::FORBIDDEN, "Host not allowed"));
+        }
+
+        if let Some(origin) = header(header::ORIGIN) {
+            let local = origin
+                .split_once("://")
+                .is_some_and(|(_, rest)| LOCAL_HOSTS.contains(&host_name(rest)));
+            if !local && !self.config.allowed_origins.iter().any(|o| o == origin) {
+                return Err((StatusCode::FORBIDDEN, "Origin not allowed"));
+            }
+        }
+
+        if let Some(token) = &self.token {
+            let sent = header(header::AUTHORIZATION).and_then(|v| v.strip_prefix("Bearer "));
+            if !sent.is_some_and(|sent| same_token(sent.as_bytes(), token.as_bytes())) {
+                return Err((StatusCode::UNAUTHORIZED, "Missing or invalid bearer token"));
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn guard(State(guard): State<Arc<Guard>>, request: Request, next: Next) -> Response {
+    match guard.check(request.headers()) {
+        Ok(()) => next.run(request).await,
+        Err((status, reason)) => {
+            tracing::warn!("Refused HTTP request: {reason}");
+            (status, reason).into_response()
+        }
+    }
+}
+
+/// The MCP endpoint behind the checks of the `[http]` config: the `Host` header, the
+/// `Origin` of browser requests and, when configured, a bearer token. Every client
+/// session gets its own handle on the server (see [`RustDocMCPServer::for_session`]).
+pub fn router(server: RustDocMCPServer) -> anyhow::Result<axum::Router> {
+    let config = server.config().http.clone();
+    let token = config.token()?;
+    if token.is_none() {
+        tracing::warn!("No `http.token` is configured: any local process may use the server");
+    }
+    let guard_state = Arc::new(Guard { token, config });
+
+    let service = StreamableHttpService::new(
+        move || Ok(server.for_session()),
+        LocalSessionManager::default().into(),
+        StreamableHttpServerConfig::default(),
+    );
+    Ok(axum::Router::new()
+        .nest_service(MCP_PATH, service)
+        .layer(middleware::from_fn_with_state(guard_state, guard)))
+}
+
+/// Serves the MCP protocol over Streamable HTTP on localhost until interrupted.
+pub async fn serve(server: RustDocMCPServer, port: u16) -> anyhow::Result<()> {
+    let router = router(server)?;
+
+    let addr = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to listen on {addr}: {e}"))?;
+    tracing::info!("Serving MCP over HTTP at http://{addr}{MCP_PATH}");
+
+    axum::serve(listener, router)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await
+        .map_err(|e| anyhow::anyhow!("HTTP server error: {e}"))?;
+    tracing::info!("Server stopped gracefully");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    use super::*;
+
+    /// Serves a fixture server with `config` on a free port and returns its address.
+    async fn serve_fixture(config: &str) -> SocketAddr {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("rustdoc-mcp.toml");
+        std::fs::write(&config_path, config).unwrap();
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/demo");
+        let server = RustDocMCPServer::new(
+            None,
+            Some(config_path.to_string_lossy().into_owned()),
+            Some(fixture.to_string_lossy().into_owned()),
+        )
+        .unwrap();
+        let router = router(server).unwrap();
+
+        let listener = tokio::net::TcpListener::bind((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, router).await });
+        addr
+    }
+
+    /// Posts an empty MCP request with `headers` and returns the status code.
+    async fn status(addr: SocketAddr, headers: &str) -> u16 {
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "POST {MCP_PATH} HTTP/1.1\r\n{headers}Content-Type: application/json\r\nContent-Length: 2\r\nConnection: close\r\n\r\n{{}}"
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+            .split_whitespace()
+            .nth(1)
+            .and_then(|code| code.parse().ok())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unauthorized_requests() {
+        let addr = serve_fixture("[http]\ntoken = \"secret\"\n").await;
+        let host = "Host: localhost\r\n";
+
+        assert_eq!(status(addr, host).await, 401);
+        let wrong = format!("{host}Authorization: Bearer guess\r\n");
+        assert_eq!(status(addr, &wrong).await, 401);
+
+        let authorized = format!("{host}Authorization: Bearer secret\r\n");
+        let rebound = "Host: evil.example\r\nAuthorization: Bearer secret\r\n";
+        assert_eq!(status(addr, rebound).await, 403);
+        let foreign = format!("{authorized}Origin: https://evil.example\r\n");
+        assert_eq!(status(addr, &foreign).await, 403);
+
+        // Past the checks, the MCP service answers for itself.
+        let local = format!("{authorized}Origin: http://localhost:3000\r\n");
+        assert!(![401, 403].contains(&status(addr, &local).await));
+    }
+
+    #[test]
+    fn test_host_name() {
+        assert_eq!(host_name("localhost:8080"), "localhost");
+        assert_eq!(host_name("[::1]:8080"), "[::1]");
+        assert_eq!(host_name("[::1]"), "[::1]");
+        assert_eq!(host_name("127.0.0.1"), "127.0.0.1");
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+
+use rustdoc_mcp_core::index::{LoadedCrate, get_item_kind};
+use rustdoc_types::{
+    GenericBound, GenericParamDefKind, Generics, Impl, ItemEnum, TraitBoundModifier, WherePredicate,
+};
+
+use crate::types::{ImplMatrixResult, ImplMatrixRow, TraitCoverage};
+
+pub const IMPLEMENTED: &str = "yes";
+pub const CONDITIONAL: &str = "conditional";
+pub const MISSING: &str = "no";
+
+/// The last path segment of a trait, without generic arguments: `serde::Serialize` and
+/// `Serialize` both give `Serialize`.
+fn trait_name(path: &str) -> &str {
+    let path = path.split('<').next().unwrap_or(path);
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+/// Whether an impl only applies under bounds, like `impl<T: Send> Send for Wrapper<T>`.
+/// `?Sized` relaxations do not count.
+fn has_bounds(generics: &Generics) -> bool {
+    let bounded = |bounds: &[GenericBound]| {
+        bounds.iter().any(|bound| {
+            !matches!(
+                bound,
+                GenericBound::TraitBound {
+                    modifier: TraitBoundModifier::Maybe,
+                    ..
+                }
+            )
+        })
+    };
+    generics
+        .where_predicates
+        .iter()
+        .any(|predicate| match predicate {
+            WherePredicate::BoundPredicate { bounds, .. } => bounded(bounds),
+            _ => true,
+        })
+        || generics.params.iter().any(|param| match &param.kind {
+            GenericParamDefKind::Type { bounds, .. } => bounded(bounds),
+            GenericParamDefKind::Lifetime { .. } | GenericParamDefKind::Const { .. } => false,
+        })
+}
+
+fn status(impl_: &Impl) -> &'static str {
+    if impl_.is_negative {
+        MISSING
+    } else if has_bounds(&impl_.generics) {
+        CONDITIONAL
+    } else {
+        IMPLEMENTED
+    }
+}
+
+/// Builds a matrix of which public structs, enums and unions of a crate implement each of
+/// `traits`, including auto traits and blanket impls. Traits match by their last path
+/// segment. Each cell is `yes`, `no` or `conditional` (the impl has bounds, e.g. a
+/// generic type that is `Send` only when its parameters are).
+pub fn impl_matrix(loaded: &LoadedCrate, crate_name: &str, traits: &[String]) -> ImplMatrixResult {
+    let krate = &loaded.krate;
+    let names: Vec<&str> = traits.iter().map(|t| trait_name(t)).collect();
+
+    let mut types: Vec<(&str, &rustdoc_types::Item)> = loaded
+        .path_to_id
+        .iter()
+        .filter_map(|(path, id)| Some((path.as_str(), krate.index.get(id)?)))
+        .filter(|(_, item)| {
+            matches!(
+                item.inner,
+                ItemEnum::Struct(_) | ItemEnum::Enum(_) | ItemEnum::Union(_)
+            )
+        })
+        .collect();
+    // Re-exported types are indexed under several paths; keep the shortest.
+    types.sort_by(|a, b| {
+        a.0.matches("::")
+            .count()
+            .cmp(&b.0.matches("::").count())
+            .then_with(|| a.0.cmp(b.0))
+    });
+    let mut seen = HashSet::new();
+    types.retain(|(_, item)| seen.insert(item.id));
+    types.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut rows = Vec::with_capacity(types.len());
+    for (path, item) in types {
+        let impls = match &item.inner {
+            ItemEnum::Struct(s) => &s.impls,
+            ItemEnum::Enum(e) => &e.impls,
+            ItemEnum::Union(u) => &u.impls,
+            _ => continue,
+        };
+        let mut cells = vec![MISSING; names.len()];
+        for impl_ in impls.iter().filter_map(|id| krate.index.get(id)) {
+            let ItemEnum::Impl(impl_) = &impl_.inner else {
+                continue;
+            };
+            let Some(trait_) = &impl_.trait_ else {
+                continue;
+            };
+            let impl_status = status(impl_);
+            for (cell, name) in cells.iter_mut().zip(&names) {
+                // An unconditional impl wins over a conditional one of the same trait.
+                if trait_name(&trait_.path) == *name && *cell != IMPLEMENTED {
+                    *cell = impl_status;
+                }
+            }
+        }
+        rows.push(ImplMatrixRow {
+            path: path.to_string(),
+            kind: get_item_kind(item),
+            cells: cells.into_iter().map(str::to_string).collect(),
+        });
+    }
+
+    let coverage = traits
+        .iter()
+        .enumerate()
+        .map(|(column, trait_path)| {
+            let count = |value: &str| rows.iter().filter(|row| row.cells[column] == value).count();
+            TraitCoverage {
+                trait_path: trait_path.clone(),
+                implemented: count(IMPLEMENTED),
+                conditional: count(CONDITIONAL),
+                missing: count(MISSING),
+            }
+        })
+        .collect();
+
+    ImplMatrixResult {
+        crate_name: crate_name.to_string(),
+        traits: traits.to_vec(),
+        rows,
+        coverage,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_mcp_core::test_support::item;
+    use rustdoc_types::{Crate, GenericParamDef, Id, Path, Struct, StructKind, Type};
+    use std::collections::HashMap;
+
+    fn impl_of(trait_: &str, params: Vec<GenericParamDef>, is_negative: bool) -> ItemEnum {
+        ItemEnum::Impl(Impl {
+            is_unsafe: false,
+            generics: Generics {
+                params,
+                where_predicates: vec![],
+            },
+            provided_trait_methods: vec![],
+            trait_: Some(Path {
+                path: trait_.to_string(),
+                id: Id(99),
+                args: None,
+            }),
+            for_: Type::Primitive("unit".to_string()),
+            items: vec![],
+            is_negative,
+            is_synthetic: true,
+            blanket_impl: None,
+        })
+    }
+
+    fn bounded_param() -> GenericParamDef {
+        GenericParamDef {
+            name: "T".to_string(),
+            kind: GenericParamDefKind::Type {
+                bounds: vec![GenericBound::TraitBound {
+                    trait_: Path {
+                        path: "Send".to_string(),
+                        id: Id(98),
+                        args: None,
+                    },
+                    generic_params: vec![],
+                    modifier: TraitBoundModifier::None,
+                }],
+                default: None,
+                is_synthetic: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_impl_matrix() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let impls = [
+            item(10, "", impl_of("Send", vec![], false)),
+            item(11, "", impl_of("core::clone::Clone", vec![], false)),
+            item(12, "", impl_of("Send", vec![bounded_param()], false)),
+            item(13, "", impl_of("Sync", vec![], true)),
+        ];
+        let handle = item(
+            1,
+            "Handle",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                impls: vec![Id(10), Id(11)],
+            }),
+        );
+        let wrapper = item(
+            2,
+            "Wrapper",
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: Generics {
+                    params: vec![],
+                    where_predicates: vec![],
+                },
+                impls: vec![Id(12), Id(13)],
+            }),
+        );
+        for i in impls.into_iter().chain([handle, wrapper]) {
+            krate.index.insert(i.id, i);
+        }
+        let path_to_id = HashMap::from([
+            ("demo::Handle".to_string(), Id(1)),
+            ("demo::Wrapper".to_string(), Id(2)),
+            ("demo::sync::Wrapper".to_string(), Id(2)),
+        ]);
+        let loaded = LoadedCrate::new(krate, path_to_id);
+
+        let traits = vec!["Send".to_string(), "Sync".to_string(), "Clone".to_string()];
+        let matrix = impl_matrix(&loaded, "demo", &traits);
+        let rows: Vec<(&str, Vec<&str>)> = matrix
+            .rows
+            .iter()
+            .map(|row| {
+                (
+                    row.path.as_str(),
+                    row.cells.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("demo::Handle", vec!["yes", "no", "yes"]),
+                ("demo::Wrapper", vec!["conditional", "no", "no"]),
+            ]
+        );
+        assert_eq!(matrix.coverage[0].implemented, 1);
+        assert_eq!(matrix.coverage[0].conditional, 1);
+        assert_eq!(matrix.coverage[1].missing, 2);
+    }
+}
@@ -0,0 +1,139 @@
+use rustdoc_mcp_core::index::{CrateIndex, LoadedCrate, get_item_kind};
+use rustdoc_mcp_core::workspace::{Directness, Workspace};
+use rustdoc_types::ItemEnum;
+
+use crate::types::{ImportSuggestion, SymbolExport};
+
+/// An item can be imported by path when it lives directly in a module (or is an enum variant).
+fn is_importable(loaded: &LoadedCrate, path: &str, inner: &ItemEnum) -> bool {
+    if matches!(inner, ItemEnum::Variant(_)) {
+        return true;
+    }
+    path.rsplit_once("::")
+        .and_then(|(parent, _)| loaded.path_to_id.get(parent))
+        .and_then(|id| loaded.krate.index.get(id))
+        .is_some_and(|parent| matches!(parent.inner, ItemEnum::Module(_)))
+}
+
+/// Finds importable items called `name` in the loaded crates and returns `use` candidates,
+/// ranked by how directly the workspace depends on the owning crate, then by path depth.
+/// Candidates also reachable through a `prelude` module carry the glob import for it.
+pub fn suggest_imports(
+    index: &CrateIndex,
+    workspace: &Workspace,
+    name: &str,
+    crate_name: Option<&str>,
+) -> Vec<ImportSuggestion> {
+    let mut candidates = Vec::new();
+
+    for krate in index.loaded_crate_names() {
+        if crate_name.is_some_and(|target| target != krate) {
+            continue;
+        }
+        let Some(loaded) = index.get_crate(&krate) else {
+            continue;
+        };
+
+        let directness = workspace.directness(&krate);
+        let preludes = loaded.prelude_map();
+        for (path, item) in loaded.find_by_name(name) {
+            if !is_importable(&loaded, path, &item.inner) {
+                continue;
+            }
+            candidates.push((
+                directness,
+                ImportSuggestion {
+                    use_statement: format!("use {path};"),
+                    path: path.to_string(),
+                    kind: get_item_kind(item),
+                    crate_name: krate.clone(),
+                    directness: directness.as_str().to_string(),
+                    prelude: preludes
+                        .get(&item.id)
+                        .map(|prelude| format!("use {prelude}::*;")),
+                },
+            ));
+        }
+    }
+
+    candidates.sort_by(|(a_dir, a), (b_dir, b)| {
+        a_dir
+            .cmp(b_dir)
+            .then_with(|| {
+                a.path
+                    .matches("::")
+                    .count()
+                    .cmp(&b.path.matches("::").count())
+            })
+            .then_with(|| a.path.cmp(&b.path))
+    });
+
+    candidates
+        .into_iter()
+        .map(|(_, suggestion)| suggestion)
+        .collect()
+}
+
+/// Lists the paths at which a loaded crate exports `name`: items indexed under it and
+/// `pub use` re-exports of other crates' items, each with the crate that defines it.
+/// Sorted by path depth, then path.
+pub fn exports_of(
+    loaded: &LoadedCrate,
+    crate_name: &str,
+    name: &str,
+    directness: Directness,
+) -> Vec<SymbolExport> {
+    let krate = &loaded.krate;
+    let owner = |crate_id: u32| match crate_id {
+        0 => crate_name.to_string(),
+        id => krate
+            .external_crates
+            .get(&id)
+            .map_or_else(|| crate_name.to_string(), |c| c.name.clone()),
+    };
+    let export = |path: String, kind: String, defined_in: String| SymbolExport {
+        crate_name: crate_name.to_string(),
+        path,
+        kind,
+        defined_in,
+        directness: directness.as_str().to_string(),
+    };
+
+    let mut exports: Vec<SymbolExport> = loaded
+        .find_by_name(name)
+        .into_iter()
+        .filter(|(path, item)| is_importable(loaded, path, &item.inner))
+        .map(|(path, item)| export(path.to_string(), get_item_kind(item), owner(item.crate_id)))
+        .collect();
+
+    // Re-exports of items from other crates are `use` items rather than indexed paths.
+    for (module_path, id) in &loaded.path_to_id {
+        let Some(ItemEnum::Module(module)) = krate.index.get(id).map(|item| &item.inner) else {
+            continue;
+        };
+        for child in module.items.iter().filter_map(|id| krate.index.get(id)) {
+            let ItemEnum::Use(use_item) = &child.inner else {
+                continue;
+            };
+            if use_item.is_glob || use_item.name != name {
+                continue;
+            }
+            let summary = use_item.id.and_then(|id| krate.paths.get(&id));
+            exports.push(export(
+                format!("{module_path}::{name}"),
+                "re-export".to_string(),
+                summary.map_or_else(|| crate_name.to_string(), |s| owner(s.crate_id)),
+            ));
+        }
+    }
+
+    exports.sort_by(|a, b| {
+        a.path
+            .matches("::")
+            .count()
+            .cmp(&b.path.matches("::").count())
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    exports.dedup_by(|a, b| a.path == b.path);
+    exports
+}
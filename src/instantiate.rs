@@ -0,0 +1,582 @@
+use std::collections::HashMap;
+
+use rustdoc_mcp_core::markdown::{format_item_definition, format_type};
+use rustdoc_types::{
+    AssocItemConstraintKind, Crate, Function, GenericArg, GenericArgs, GenericBound,
+    GenericParamDefKind, Id, Impl, Item, ItemEnum, Term, Type, WherePredicate,
+};
+
+use crate::types::{AssocTypeBinding, ResolvedImpl};
+
+/// Splits a type expression like `HashMap<String, Vec<u8>>` into its path and its
+/// top-level generic arguments.
+pub fn parse_type_expr(expr: &str) -> (String, Vec<String>) {
+    let expr = expr.trim();
+    let Some((path, rest)) = expr.split_once('<') else {
+        return (expr.to_string(), Vec::new());
+    };
+    let inner = rest.trim_end().strip_suffix('>').unwrap_or(rest);
+
+    let mut args = Vec::new();
+    let mut depth = 0usize;
+    let mut current = String::new();
+    for c in inner.chars() {
+        match c {
+            '<' | '(' | '[' => depth += 1,
+            '>' | ')' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                args.push(current.trim().to_string());
+                current.clear();
+                continue;
+            }
+            _ => {}
+        }
+        current.push(c);
+    }
+    if !current.trim().is_empty() {
+        args.push(current.trim().to_string());
+    }
+    (path.trim().to_string(), args)
+}
+
+/// Replaces generic parameters named in `map` with their concrete types. The concrete
+/// type is stored as a `Type::Generic` holding its source text, which renders verbatim.
+/// Associated types of `Self` and of other parameters are looked up by their qualified
+/// name, e.g. `Self::Item` or `I::Item`.
+pub fn substitute_type(ty: &Type, map: &HashMap<String, String>) -> Type {
+    let sub = |ty: &Type| Box::new(substitute_type(ty, map));
+    match ty {
+        Type::Generic(name) => Type::Generic(map.get(name).unwrap_or(name).clone()),
+        Type::ResolvedPath(p) => {
+            let mut p = p.clone();
+            if let Some(args) = p.args.as_deref_mut() {
+                substitute_args(args, map);
+            }
+            Type::ResolvedPath(p)
+        }
+        Type::ImplTrait(bounds) => Type::ImplTrait(
+            bounds
+                .iter()
+                .map(|bound| substitute_bound(bound, map))
+                .collect(),
+        ),
+        Type::Tuple(types) => Type::Tuple(types.iter().map(|t| substitute_type(t, map)).collect()),
+        Type::Slice(t) => Type::Slice(sub(t)),
+        Type::Array { type_, len } => Type::Array {
+            type_: sub(type_),
+            len: len.clone(),
+        },
+        Type::BorrowedRef {
+            lifetime,
+            is_mutable,
+            type_,
+        } => Type::BorrowedRef {
+            lifetime: lifetime.clone(),
+            is_mutable: *is_mutable,
+            type_: sub(type_),
+        },
+        Type::RawPointer { is_mutable, type_ } => Type::RawPointer {
+            is_mutable: *is_mutable,
+            type_: sub(type_),
+        },
+        Type::QualifiedPath {
+            name,
+            args,
+            self_type,
+            trait_,
+        } => {
+            if let Type::Generic(s) = self_type.as_ref()
+                && let Some(ty) = map.get(&format!("{s}::{name}"))
+            {
+                return Type::Generic(ty.clone());
+            }
+            Type::QualifiedPath {
+                name: name.clone(),
+                args: args.clone(),
+                self_type: sub(self_type),
+                trait_: trait_.clone(),
+            }
+        }
+        other => other.clone(),
+    }
+}
+
+fn substitute_args(args: &mut GenericArgs, map: &HashMap<String, String>) {
+    match args {
+        GenericArgs::AngleBracketed { args, constraints } => {
+            for arg in args.iter_mut() {
+                if let GenericArg::Type(t) = arg {
+                    *t = substitute_type(t, map);
+                }
+            }
+            for constraint in constraints.iter_mut() {
+                if let AssocItemConstraintKind::Equality(Term::Type(t)) = &mut constraint.binding {
+                    *t = substitute_type(t, map);
+                }
+            }
+        }
+        GenericArgs::Parenthesized { inputs, output } => {
+            for t in inputs.iter_mut() {
+                *t = substitute_type(t, map);
+            }
+            if let Some(t) = output {
+                *t = substitute_type(t, map);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn substitute_bound(bound: &GenericBound, map: &HashMap<String, String>) -> GenericBound {
+    let mut bound = bound.clone();
+    if let GenericBound::TraitBound { trait_, .. } = &mut bound
+        && let Some(args) = trait_.args.as_deref_mut()
+    {
+        substitute_args(args, map);
+    }
+    bound
+}
+
+/// Substitutes `map` into a function's signature and into the bounds of its generic
+/// parameters, so closures like `F: FnMut(Self::Item) -> B` are instantiated too.
+pub fn substitute_function(f: &Function, map: &HashMap<String, String>) -> Function {
+    let mut f = f.clone();
+    for (_, ty) in &mut f.sig.inputs {
+        *ty = substitute_type(ty, map);
+    }
+    f.sig.output = f.sig.output.as_ref().map(|ty| substitute_type(ty, map));
+
+    for param in &mut f.generics.params {
+        if let GenericParamDefKind::Type {
+            bounds, default, ..
+        } = &mut param.kind
+        {
+            *bounds = bounds.iter().map(|b| substitute_bound(b, map)).collect();
+            *default = default.as_ref().map(|ty| substitute_type(ty, map));
+        }
+    }
+    for predicate in &mut f.generics.where_predicates {
+        if let WherePredicate::BoundPredicate { type_, bounds, .. } = predicate {
+            *type_ = substitute_type(type_, map);
+            *bounds = bounds.iter().map(|b| substitute_bound(b, map)).collect();
+        }
+    }
+    f
+}
+
+/// Maps the parameters of an inherent impl to the concrete arguments, positionally through
+/// the impl's self type. Returns `None` when the impl only covers other instantiations,
+/// e.g. `impl Foo<u8>` when instantiating `Foo<String>`.
+fn impl_substitutions(for_: &Type, concrete: &[String]) -> Option<HashMap<String, String>> {
+    let mut map = HashMap::new();
+    let Type::ResolvedPath(p) = for_ else {
+        return Some(map);
+    };
+    let Some(GenericArgs::AngleBracketed { args, .. }) = p.args.as_deref() else {
+        return Some(map);
+    };
+
+    let type_args = args.iter().filter_map(|arg| match arg {
+        GenericArg::Type(t) => Some(t),
+        _ => None,
+    });
+    for (arg, concrete) in type_args.zip(concrete) {
+        match arg {
+            Type::Generic(name) => {
+                map.insert(name.clone(), concrete.clone());
+            }
+            other if format_type(other) != *concrete => return None,
+            _ => {}
+        }
+    }
+    Some(map)
+}
+
+/// Instantiates a struct, enum or union with `concrete` type arguments. Returns the
+/// instantiated type, e.g. `IntoIter<u8>`, the argument of each type parameter and the
+/// type's impls.
+fn instantiate<'a>(item: &'a Item, concrete: &[String]) -> Option<(String, Vec<String>, &'a [Id])> {
+    let (generics, impls) = match &item.inner {
+        ItemEnum::Struct(s) => (&s.generics, &s.impls),
+        ItemEnum::Enum(e) => (&e.generics, &e.impls),
+        ItemEnum::Union(u) => (&u.generics, &u.impls),
+        _ => return None,
+    };
+
+    // Parameters left out keep their names, e.g. a defaulted hasher parameter.
+    let type_params: Vec<String> = generics
+        .params
+        .iter()
+        .filter(|p| matches!(p.kind, GenericParamDefKind::Type { .. }))
+        .map(|p| p.name.clone())
+        .collect();
+    let args: Vec<String> = type_params
+        .iter()
+        .enumerate()
+        .map(|(i, name)| concrete.get(i).unwrap_or(name).clone())
+        .collect();
+    let name = item.name.as_deref().unwrap_or_default();
+    let instantiated = if args.is_empty() {
+        name.to_string()
+    } else {
+        format!("{name}<{}>", args.join(", "))
+    };
+    Some((instantiated, args, impls))
+}
+
+/// Renders the methods of `item`'s inherent impls with its generic parameters replaced by
+/// `concrete`. Returns the instantiated type and the method signatures.
+pub fn instantiate_methods(
+    krate: &Crate,
+    item: &Item,
+    concrete: &[String],
+) -> (String, Vec<String>) {
+    let Some((instantiated, args, impls)) = instantiate(item, concrete) else {
+        return (String::new(), Vec::new());
+    };
+
+    let mut methods = Vec::new();
+    for impl_id in impls {
+        let Some(ItemEnum::Impl(impl_)) = krate.index.get(impl_id).map(|i| &i.inner) else {
+            continue;
+        };
+        if impl_.trait_.is_some() {
+            continue;
+        }
+        let Some(map) = impl_substitutions(&impl_.for_, &args) else {
+            continue;
+        };
+
+        for method_id in &impl_.items {
+            let Some(method) = krate.index.get(method_id) else {
+                continue;
+            };
+            let ItemEnum::Function(f) = &method.inner else {
+                continue;
+            };
+            let mut method = method.clone();
+            method.inner = ItemEnum::Function(substitute_function(f, &map));
+            methods.push(format_item_definition(&method));
+        }
+    }
+
+    (instantiated, methods)
+}
+
+/// A trait impl of an instantiated type, with the substitutions for its parameters.
+struct ImplInstance<'a> {
+    impl_: &'a Impl,
+    map: HashMap<String, String>,
+    /// The impl's own type parameter for blanket impls like `impl<I: Iterator> IntoIterator for I`.
+    blanket_param: Option<&'a str>,
+}
+
+fn trait_name(path: &str) -> &str {
+    let path = path.split('<').next().unwrap_or(path);
+    path.rsplit("::").next().unwrap_or(path).trim()
+}
+
+/// Finds the impls of `trait_path` for `item` instantiated with `concrete` and resolves
+/// their associated types, e.g. `Item = u8` for `IntoIter<u8>: Iterator`. Blanket impls
+/// count, and associated types defined through another impl (`type Item = I::Item`) are
+/// resolved through it. Returns the instantiated type and the impls, or `None` if `item`
+/// is not a struct, enum or union.
+pub fn resolve_assoc_types(
+    krate: &Crate,
+    item: &Item,
+    concrete: &[String],
+    trait_path: &str,
+) -> Option<(String, Vec<ResolvedImpl>)> {
+    let (instantiated, args, impls) = instantiate(item, concrete)?;
+
+    let instances: Vec<ImplInstance> = impls
+        .iter()
+        .filter_map(|id| match krate.index.get(id).map(|i| &i.inner) {
+            Some(ItemEnum::Impl(impl_)) if impl_.trait_.is_some() && !impl_.is_negative => {
+                Some(impl_)
+            }
+            _ => None,
+        })
+        .filter_map(|impl_| match &impl_.for_ {
+            Type::Generic(param) => Some(ImplInstance {
+                impl_,
+                map: HashMap::from([(param.clone(), instantiated.clone())]),
+                blanket_param: Some(param.as_str()),
+            }),
+            for_ => Some(ImplInstance {
+                impl_,
+                map: impl_substitutions(for_, &args)?,
+                blanket_param: None,
+            }),
+        })
+        .collect();
+
+    let assoc_types = |instance: &ImplInstance<'_>, map: &HashMap<String, String>| {
+        instance
+            .impl_
+            .items
+            .iter()
+            .filter_map(|id| krate.index.get(id))
+            .filter_map(|assoc| match &assoc.inner {
+                ItemEnum::AssocType {
+                    type_: Some(ty), ..
+                } => Some((assoc.name.clone()?, format_type(&substitute_type(ty, map)))),
+                _ => None,
+            })
+            .collect::<Vec<(String, String)>>()
+    };
+
+    // Associated types that concrete impls define, for the impls that refer to them.
+    let mut known: HashMap<String, String> = HashMap::new();
+    for instance in instances.iter().filter(|i| i.blanket_param.is_none()) {
+        for (name, ty) in assoc_types(instance, &instance.map) {
+            known.entry(name).or_insert(ty);
+        }
+    }
+
+    let wanted = trait_name(trait_path);
+    let mut resolved = Vec::new();
+    for instance in &instances {
+        let Some(trait_) = &instance.impl_.trait_ else {
+            continue;
+        };
+        if trait_name(&trait_.path) != wanted {
+            continue;
+        }
+        let mut map = instance.map.clone();
+        for (name, ty) in &known {
+            map.insert(format!("Self::{name}"), ty.clone());
+            if let Some(param) = instance.blanket_param {
+                map.insert(format!("{param}::{name}"), ty.clone());
+            }
+        }
+        let mut bindings: Vec<AssocTypeBinding> = assoc_types(instance, &map)
+            .into_iter()
+            .map(|(name, resolved)| AssocTypeBinding { name, resolved })
+            .collect();
+        bindings.sort_by(|a, b| a.name.cmp(&b.name));
+        resolved.push(ResolvedImpl {
+            trait_path: format_type(&substitute_type(
+                &Type::ResolvedPath(trait_.clone()),
+                &instance.map,
+            )),
+            blanket: instance.blanket_param.is_some(),
+            assoc_types: bindings,
+        });
+    }
+    Some((instantiated, resolved))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_type_expr() {
+        assert_eq!(
+            parse_type_expr("HashMap<String, Vec<u8>>"),
+            (
+                "HashMap".to_string(),
+                vec!["String".to_string(), "Vec<u8>".to_string()]
+            )
+        );
+        assert_eq!(
+            parse_type_expr("foo::Bar"),
+            ("foo::Bar".to_string(), vec![])
+        );
+    }
+
+    #[test]
+    fn test_substitute_type() {
+        let map = HashMap::from([("K".to_string(), "String".to_string())]);
+        let ty = Type::BorrowedRef {
+            lifetime: None,
+            is_mutable: false,
+            type_: Box::new(Type::Generic("K".to_string())),
+        };
+        assert_eq!(format_type(&substitute_type(&ty, &map)), "&String");
+
+        let ty = Type::Tuple(vec![
+            Type::Generic("K".to_string()),
+            Type::Generic("V".to_string()),
+        ]);
+        assert_eq!(format_type(&substitute_type(&ty, &map)), "(String, V)");
+    }
+
+    #[test]
+    fn test_substitute_closure_bound() {
+        let map = HashMap::from([("T".to_string(), "u8".to_string())]);
+        let ty = Type::ImplTrait(vec![GenericBound::TraitBound {
+            trait_: rustdoc_types::Path {
+                path: "FnMut".to_string(),
+                id: rustdoc_types::Id(0),
+                args: Some(Box::new(GenericArgs::Parenthesized {
+                    inputs: vec![Type::Generic("T".to_string())],
+                    output: Some(Type::Primitive("bool".to_string())),
+                })),
+            },
+            generic_params: vec![],
+            modifier: rustdoc_types::TraitBoundModifier::None,
+        }]);
+        assert_eq!(
+            format_type(&substitute_type(&ty, &map)),
+            "impl FnMut(u8) -> bool"
+        );
+    }
+
+    #[test]
+    fn test_impl_substitutions() {
+        let for_ = Type::ResolvedPath(rustdoc_types::Path {
+            path: "Foo".to_string(),
+            id: rustdoc_types::Id(0),
+            args: Some(Box::new(GenericArgs::AngleBracketed {
+                args: vec![
+                    GenericArg::Type(Type::Generic("T".to_string())),
+                    GenericArg::Type(Type::Primitive("u8".to_string())),
+                ],
+                constraints: vec![],
+            })),
+        });
+
+        let map = impl_substitutions(&for_, &["String".to_string(), "u8".to_string()]).unwrap();
+        assert_eq!(map.get("T").map(String::as_str), Some("String"));
+        assert!(impl_substitutions(&for_, &["String".to_string(), "u16".to_string()]).is_none());
+    }
+
+    #[test]
+    fn test_resolve_assoc_types() {
+        use rustdoc_types::{GenericParamDef, Generics, Path, Struct, StructKind, Visibility};
+
+        let item = |id: u32, name: Option<&str>, inner: ItemEnum| Item {
+            id: Id(id),
+            crate_id: 0,
+            name: name.map(str::to_string),
+            span: None,
+            visibility: Visibility::Public,
+            docs: None,
+            links: HashMap::new(),
+            attrs: Vec::new(),
+            deprecation: None,
+            inner,
+        };
+        let generics = |params: &[&str]| Generics {
+            params: params
+                .iter()
+                .map(|name| GenericParamDef {
+                    name: name.to_string(),
+                    kind: GenericParamDefKind::Type {
+                        bounds: vec![],
+                        default: None,
+                        is_synthetic: false,
+                    },
+                })
+                .collect(),
+            where_predicates: vec![],
+        };
+        let path = |name: &str, args: Vec<Type>| Path {
+            path: name.to_string(),
+            id: Id(90),
+            args: (!args.is_empty()).then(|| {
+                Box::new(GenericArgs::AngleBracketed {
+                    args: args.into_iter().map(GenericArg::Type).collect(),
+                    constraints: vec![],
+                })
+            }),
+        };
+        let assoc = |id: u32, name: &str, ty: Type| {
+            item(
+                id,
+                Some(name),
+                ItemEnum::AssocType {
+                    generics: generics(&[]),
+                    bounds: vec![],
+                    type_: Some(ty),
+                },
+            )
+        };
+        let trait_impl = |id: u32, params: &[&str], trait_: &str, for_: Type, items: Vec<Id>| {
+            item(
+                id,
+                None,
+                ItemEnum::Impl(Impl {
+                    is_unsafe: false,
+                    generics: generics(params),
+                    provided_trait_methods: vec![],
+                    trait_: Some(path(trait_, vec![])),
+                    blanket_impl: matches!(for_, Type::Generic(_)).then(|| for_.clone()),
+                    for_,
+                    items,
+                    is_negative: false,
+                    is_synthetic: false,
+                }),
+            )
+        };
+
+        // impl<T> Iterator for IntoIter<T> { type Item = T; }
+        // impl<I: Iterator> IntoIterator for I { type Item = I::Item; type IntoIter = I; }
+        let into_iter = Type::ResolvedPath(path("IntoIter", vec![Type::Generic("T".to_string())]));
+        let items = [
+            assoc(10, "Item", Type::Generic("T".to_string())),
+            trait_impl(11, &["T"], "Iterator", into_iter, vec![Id(10)]),
+            assoc(
+                12,
+                "Item",
+                Type::QualifiedPath {
+                    name: "Item".to_string(),
+                    args: None,
+                    self_type: Box::new(Type::Generic("I".to_string())),
+                    trait_: Some(path("Iterator", vec![])),
+                },
+            ),
+            assoc(13, "IntoIter", Type::Generic("I".to_string())),
+            trait_impl(
+                14,
+                &["I"],
+                "IntoIterator",
+                Type::Generic("I".to_string()),
+                vec![Id(12), Id(13)],
+            ),
+        ];
+        let ty = item(
+            1,
+            Some("IntoIter"),
+            ItemEnum::Struct(Struct {
+                kind: StructKind::Unit,
+                generics: generics(&["T"]),
+                impls: vec![Id(11), Id(14)],
+            }),
+        );
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: items.into_iter().map(|i| (i.id, i)).collect(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        krate.index.insert(ty.id, ty.clone());
+
+        let concrete = ["u8".to_string()];
+        let (instantiated, impls) =
+            resolve_assoc_types(&krate, &ty, &concrete, "std::iter::Iterator").unwrap();
+        assert_eq!(instantiated, "IntoIter<u8>");
+        assert_eq!(impls.len(), 1);
+        assert!(!impls[0].blanket);
+        assert_eq!(impls[0].assoc_types[0].name, "Item");
+        assert_eq!(impls[0].assoc_types[0].resolved, "u8");
+
+        let (_, impls) = resolve_assoc_types(&krate, &ty, &concrete, "IntoIterator").unwrap();
+        assert!(impls[0].blanket);
+        let bindings: Vec<(&str, &str)> = impls[0]
+            .assoc_types
+            .iter()
+            .map(|b| (b.name.as_str(), b.resolved.as_str()))
+            .collect();
+        assert_eq!(bindings, vec![("IntoIter", "IntoIter<u8>"), ("Item", "u8")]);
+    }
+}
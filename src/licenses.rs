@@ -0,0 +1,158 @@
+use std::collections::BTreeMap;
+
+use rustdoc_mcp_core::workspace::Workspace;
+
+use crate::types::{LicenseGroup, LicenseReportResult};
+
+/// SPDX identifier prefixes of copyleft licenses, strong and weak.
+const COPYLEFT_PREFIXES: [&str; 10] = [
+    "AGPL", "GPL", "LGPL", "MPL", "EPL", "EUPL", "CDDL", "OSL", "CPL", "CC-BY-SA",
+];
+
+fn is_copyleft_id(id: &str) -> bool {
+    COPYLEFT_PREFIXES
+        .iter()
+        .any(|prefix| id.starts_with(prefix))
+}
+
+/// Evaluates whether an SPDX expression forces copyleft terms: `AND` binds tighter than
+/// `OR`, an `AND` requires any copyleft operand to be honored, and an `OR` only forces
+/// copyleft when every alternative does. `WITH` exceptions are ignored.
+fn requires_copyleft(tokens: &[&str], pos: &mut usize) -> bool {
+    let mut all_alternatives = true;
+    loop {
+        let mut any_operand = false;
+        loop {
+            let operand = match tokens.get(*pos) {
+                Some(&"(") => {
+                    *pos += 1;
+                    let inner = requires_copyleft(tokens, pos);
+                    *pos += 1; // `)`
+                    inner
+                }
+                Some(id) => {
+                    *pos += 1;
+                    is_copyleft_id(id)
+                }
+                None => false,
+            };
+            if tokens.get(*pos) == Some(&"WITH") {
+                *pos += 2;
+            }
+            any_operand |= operand;
+            if tokens.get(*pos) != Some(&"AND") {
+                break;
+            }
+            *pos += 1;
+        }
+        all_alternatives &= any_operand;
+        if tokens.get(*pos) != Some(&"OR") {
+            return all_alternatives;
+        }
+        *pos += 1;
+    }
+}
+
+/// Classifies a package's license as `permissive`, `copyleft`, `custom` (only a license
+/// file) or `unknown`. The legacy `MIT/Apache-2.0` syntax counts as `OR`.
+pub fn classify_license(license: Option<&str>, has_license_file: bool) -> &'static str {
+    let Some(license) = license.map(str::trim).filter(|l| !l.is_empty()) else {
+        return if has_license_file {
+            "custom"
+        } else {
+            "unknown"
+        };
+    };
+    let spaced = license
+        .replace('/', " OR ")
+        .replace('(', " ( ")
+        .replace(')', " ) ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    if requires_copyleft(&tokens, &mut 0) {
+        "copyleft"
+    } else {
+        "permissive"
+    }
+}
+
+/// Groups every dependency of the workspace (all non-member packages in the resolved
+/// metadata) by license, most common first, and lists the copyleft and unknown ones.
+pub fn license_report(workspace: &Workspace) -> LicenseReportResult {
+    let mut groups: BTreeMap<String, LicenseGroup> = BTreeMap::new();
+    let mut total_packages = 0;
+    for package in &workspace.metadata.packages {
+        if workspace.metadata.workspace_members.contains(&package.id) {
+            continue;
+        }
+        total_packages += 1;
+        let category = classify_license(package.license.as_deref(), package.license_file.is_some());
+        let license = match (&package.license, &package.license_file) {
+            (Some(license), _) => license.clone(),
+            (None, Some(file)) => format!("see {file}"),
+            (None, None) => "unknown".to_string(),
+        };
+        groups
+            .entry(license.clone())
+            .or_insert_with(|| LicenseGroup {
+                license,
+                category: category.to_string(),
+                packages: Vec::new(),
+            })
+            .packages
+            .push(format!("{} {}", package.name, package.version));
+    }
+
+    let mut licenses: Vec<LicenseGroup> = groups.into_values().collect();
+    for group in &mut licenses {
+        group.packages.sort();
+    }
+    licenses.sort_by(|a, b| b.packages.len().cmp(&a.packages.len()));
+
+    let flagged = licenses
+        .iter()
+        .filter(|g| g.category == "copyleft" || g.category == "unknown")
+        .flat_map(|g| {
+            g.packages
+                .iter()
+                .map(move |p| format!("{p} ({}: {})", g.category, g.license))
+        })
+        .collect();
+
+    LicenseReportResult {
+        total_packages,
+        licenses,
+        flagged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_license() {
+        assert_eq!(
+            classify_license(Some("MIT OR Apache-2.0"), false),
+            "permissive"
+        );
+        assert_eq!(
+            classify_license(Some("MIT/Apache-2.0"), false),
+            "permissive"
+        );
+        assert_eq!(classify_license(Some("GPL-3.0-only"), false), "copyleft");
+        assert_eq!(
+            classify_license(Some("LGPL-2.1 OR MIT"), false),
+            "permissive"
+        );
+        assert_eq!(
+            classify_license(Some("(MIT OR Apache-2.0) AND MPL-2.0"), false),
+            "copyleft"
+        );
+        assert_eq!(classify_license(None, true), "custom");
+        assert_eq!(classify_license(Some(" "), false), "unknown");
+        assert_eq!(
+            classify_license(Some("GPL-2.0 WITH Classpath-exception-2.0 OR MIT"), false),
+            "permissive"
+        );
+    }
+}
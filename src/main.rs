@@ -1,16 +1,40 @@
+use std::time::Duration;
+
 use clap::Parser;
 use rmcp::{ServiceExt, transport::stdio};
 use tracing_subscriber::{Layer, layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::cmd::{AppCommand, CmdOptions};
+use crate::cmd::{AppCommand, CmdOptions, Transport};
 
+mod adapters;
+mod api_diff;
+mod bench;
+mod bookmarks;
 mod cmd;
-mod doc_gen;
-mod index;
-mod markdown;
+mod compact;
+mod daemon;
+mod dep_cost;
+mod dep_tree;
+mod example_check;
+mod explain;
+mod features;
+mod http;
+mod impl_matrix;
+mod imports;
+mod instantiate;
+mod licenses;
+mod output_limit;
+mod overview;
+mod paths;
+mod redact;
+mod schemas;
+mod sections;
 mod server;
+mod skeleton;
+mod source;
+mod type_tree;
 mod types;
-mod workspace;
+mod version;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -43,9 +67,42 @@ async fn main() -> anyhow::Result<()> {
             Ok(())
         }
 
-        AppCommand::Start { cwd } => {
+        AppCommand::Schemas => {
+            let tools = server::RustDocMCPServer::tool_schemas()
+                .map_err(|e| anyhow::anyhow!("Invalid tool schema: {e}"))?;
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&schemas::schemas_document(&tools))?
+            );
+            Ok(())
+        }
+
+        AppCommand::BenchIndex {
+            crate_name,
+            cwd,
+            config,
+            queries,
+        } => {
+            let cwd = match cwd {
+                Some(dir) => dir,
+                None => std::env::current_dir()?.to_string_lossy().into_owned(),
+            };
+            let report = bench::bench_index(&cwd, config.as_deref(), &crate_name, queries).await?;
+            print!("{report}");
+            Ok(())
+        }
+
+        AppCommand::Start {
+            cwd,
+            config,
+            fixture,
+            transport,
+            port,
+            daemon,
+            idle_secs,
+        } => {
             tracing::info!("Starting RustDoc MCP Server...");
-            let server = match server::RustDocMCPServer::new(cwd) {
+            let server = match server::RustDocMCPServer::new(cwd, config, fixture) {
                 Ok(s) => s,
                 Err(e) => {
                     tracing::error!("Failed to start server: {}", e);
@@ -55,6 +112,13 @@ async fn main() -> anyhow::Result<()> {
 
             tracing::info!("Server initialized successfully");
 
+            if daemon {
+                return daemon::serve(server, port, Duration::from_secs(idle_secs)).await;
+            }
+            if transport == Transport::Http {
+                return http::serve(server, port).await;
+            }
+
             let service = server
                 .serve(stdio())
                 .await
@@ -0,0 +1,151 @@
+use std::sync::Arc;
+
+use rmcp::model::{CallToolResult, Content, JsonObject, RawContent, Tool};
+use serde_json::{Value, json};
+
+/// Argument accepted by every tool to continue a truncated response.
+pub const CURSOR_ARG: &str = "cursor";
+
+/// Advertises the `cursor` argument in a tool's input schema.
+pub fn add_cursor_param(tool: &mut Tool) {
+    let schema = Arc::make_mut(&mut tool.input_schema);
+    let properties = schema
+        .entry("properties")
+        .or_insert_with(|| Value::Object(Default::default()));
+    if let Value::Object(properties) = properties {
+        properties.insert(
+            CURSOR_ARG.to_string(),
+            json!({
+                "type": ["string", "null"],
+                "description": "Continues a truncated response: pass the `next_cursor` it returned."
+            }),
+        );
+    }
+}
+
+/// Removes the `cursor` argument before the call reaches the tool, returning the byte
+/// offset it encodes (0 when absent).
+pub fn take_cursor(arguments: &mut Option<JsonObject>) -> Result<usize, String> {
+    let Some(cursor) = arguments.as_mut().and_then(|args| args.remove(CURSOR_ARG)) else {
+        return Ok(0);
+    };
+    match &cursor {
+        Value::Null => Ok(0),
+        Value::String(s) => s.parse().map_err(|_| format!("Invalid cursor: {s}")),
+        Value::Number(n) => n
+            .as_u64()
+            .and_then(|n| usize::try_from(n).ok())
+            .ok_or(format!("Invalid cursor: {n}")),
+        other => Err(format!("Invalid cursor: {other}")),
+    }
+}
+
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    let mut index = index.min(text.len());
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Cuts the chunk of `text` starting at byte `start` that fits in `max_bytes`, ending at a
+/// line break when one falls in the second half of the chunk. Returns the chunk and the
+/// offset of the next one, if any.
+pub fn page(text: &str, start: usize, max_bytes: usize) -> (&str, Option<usize>) {
+    let start = floor_char_boundary(text, start);
+    let mut end = floor_char_boundary(text, start.saturating_add(max_bytes));
+    if end < text.len()
+        && let Some(newline) = text[start..end].rfind('\n')
+        && newline >= max_bytes / 2
+    {
+        end = start + newline + 1;
+    }
+    if end == start {
+        // A character wider than the limit is still returned whole, so paging progresses.
+        end += text[start..].chars().next().map_or(0, char::len_utf8);
+    }
+    let next = (end < text.len()).then_some(end);
+    (&text[start..end], next)
+}
+
+/// Limits the text of a tool result to `max_bytes`, starting at `cursor`. A partial
+/// response drops its structured content, which cannot be cut without breaking it, and
+/// ends with a JSON trailer: `truncated`, `total_bytes`, `cursor` and `next_cursor`.
+/// A `max_bytes` of 0 disables the limit.
+pub fn limit_tool_result(result: &mut CallToolResult, cursor: usize, max_bytes: usize) {
+    if max_bytes == 0 {
+        return;
+    }
+    let Some(text) = result.content.iter_mut().find_map(|c| match &mut c.raw {
+        RawContent::Text(text) => Some(text),
+        _ => None,
+    }) else {
+        return;
+    };
+    let total_bytes = text.text.len();
+    if cursor == 0 && total_bytes <= max_bytes {
+        return;
+    }
+
+    let (chunk, next) = page(&text.text, cursor, max_bytes);
+    let chunk = chunk.to_string();
+    text.text = chunk;
+    result.structured_content = None;
+    result.content.push(Content::text(
+        json!({
+            "truncated": true,
+            "total_bytes": total_bytes,
+            "cursor": cursor.to_string(),
+            "next_cursor": next.map(|n| n.to_string()),
+        })
+        .to_string(),
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_page() {
+        let text = "line one\nline two\nline three\n";
+        assert_eq!(page(text, 0, 100), (text, None));
+        assert_eq!(page(text, 0, 14), ("line one\n", Some(9)));
+        assert_eq!(page(text, 9, 14), ("line two\n", Some(18)));
+        assert_eq!(page(text, 18, 14), ("line three\n", None));
+        // Never splits a multi-byte character.
+        assert_eq!(page("ééé", 0, 3), ("é", Some(2)));
+    }
+
+    #[test]
+    fn test_take_cursor() {
+        let mut args: Option<JsonObject> = Some(
+            json!({"path": "tokio", "cursor": "120"})
+                .as_object()
+                .unwrap()
+                .clone(),
+        );
+        assert_eq!(take_cursor(&mut args), Ok(120));
+        assert!(!args.as_ref().unwrap().contains_key(CURSOR_ARG));
+        assert_eq!(take_cursor(&mut args), Ok(0));
+        assert_eq!(take_cursor(&mut None), Ok(0));
+    }
+
+    #[test]
+    fn test_limit_tool_result() {
+        let mut result = CallToolResult::success(vec![Content::text("a".repeat(250))]);
+        limit_tool_result(&mut result, 0, 100);
+        assert_eq!(result.content.len(), 2);
+        let RawContent::Text(trailer) = &result.content[1].raw else {
+            panic!("expected a text trailer");
+        };
+        let trailer: Value = serde_json::from_str(&trailer.text).unwrap();
+        assert_eq!(trailer["truncated"], true);
+        assert_eq!(trailer["total_bytes"], 250);
+        assert_eq!(trailer["next_cursor"], "100");
+
+        let mut small = CallToolResult::success(vec![Content::text("short")]);
+        limit_tool_result(&mut small, 0, 100);
+        assert_eq!(small.content.len(), 1);
+    }
+}
@@ -0,0 +1,118 @@
+use std::collections::{BTreeSet, HashSet};
+
+use cargo_metadata::DependencyKind;
+use markdown_builder::{ListBuilder, Markdown};
+use rustdoc_mcp_core::workspace::Workspace;
+
+/// URI of the workspace overview resource.
+pub const WORKSPACE_URI: &str = "rustdoc://workspace";
+/// URI prefix under which the root docs of workspace members are served.
+pub const CRATE_URI_PREFIX: &str = "rustdoc://crate/";
+
+/// Renders an overview of the workspace: each member with its description, targets,
+/// dependencies on other members and the resource URI of its docs.
+pub fn workspace_overview(workspace: &Workspace) -> String {
+    let mut members = workspace.metadata.workspace_packages();
+    members.sort_by(|a, b| a.name.cmp(&b.name));
+    let member_names: HashSet<&str> = members.iter().map(|p| p.name.as_str()).collect();
+
+    let mut doc = Markdown::new();
+    doc.header1("Workspace");
+    doc.paragraph(format!(
+        "Root: `{}` ({} members)",
+        workspace.metadata.workspace_root,
+        members.len()
+    ));
+
+    for package in members {
+        doc.header2(format!("{} {}", package.name, package.version));
+        if let Some(description) = &package.description {
+            doc.paragraph(description.trim());
+        }
+
+        let mut details = ListBuilder::new();
+        let kinds: BTreeSet<String> = package
+            .targets
+            .iter()
+            .flat_map(|t| t.kind.iter().map(ToString::to_string))
+            .collect();
+        let kinds: Vec<String> = kinds.into_iter().collect();
+        details = details.append(format!("Targets: {}", kinds.join(", ")));
+
+        let internal: Vec<String> = package
+            .dependencies
+            .iter()
+            .filter(|dep| member_names.contains(dep.name.as_str()))
+            .map(|dep| match dep.kind {
+                DependencyKind::Development => format!("`{}` (dev)", dep.name),
+                DependencyKind::Build => format!("`{}` (build)", dep.name),
+                _ => format!("`{}`", dep.name),
+            })
+            .collect();
+        if !internal.is_empty() {
+            details = details.append(format!("Depends on members: {}", internal.join(", ")));
+        }
+
+        if package
+            .targets
+            .iter()
+            .any(|t| t.is_lib() || t.is_proc_macro())
+        {
+            details = details.append(format!("Docs: `{CRATE_URI_PREFIX}{}`", package.name));
+        } else {
+            details = details.append("Docs: none (no library target)".to_string());
+        }
+        doc.list(details.unordered());
+    }
+
+    doc.render()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_workspace_overview() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let root = temp_dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"core\", \"app\"]\nresolver = \"2\"\n",
+        )
+        .unwrap();
+
+        std::fs::create_dir_all(root.join("core/src")).unwrap();
+        std::fs::write(
+            root.join("core/Cargo.toml"),
+            "[package]\nname = \"core-lib\"\nversion = \"0.1.0\"\nedition = \"2021\"\ndescription = \"Shared types\"\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("core/src/lib.rs"), "").unwrap();
+
+        std::fs::create_dir_all(root.join("app/src")).unwrap();
+        std::fs::write(
+            root.join("app/Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\ncore-lib = { path = \"../core\" }\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("app/src/main.rs"), "fn main() {}").unwrap();
+        std::fs::create_dir_all(root.join("app/src/bin")).unwrap();
+        std::fs::write(root.join("app/src/bin/tool.rs"), "fn main() {}").unwrap();
+        std::fs::create_dir_all(root.join("app/examples")).unwrap();
+        std::fs::write(root.join("app/examples/demo.rs"), "fn main() {}").unwrap();
+
+        let workspace = Workspace::load(root).expect("Failed to load workspace");
+        let overview = workspace_overview(&workspace);
+
+        assert!(overview.contains("core-lib 0.1.0"));
+        assert!(overview.contains("Shared types"));
+        assert!(overview.contains("Depends on members: `core-lib`"));
+        assert!(overview.contains("rustdoc://crate/core-lib"));
+        assert!(overview.contains("Docs: none (no library target)"));
+        // Each kind is listed once, however the targets are ordered.
+        assert!(overview.contains("Targets: bin, example"));
+        assert!(overview.contains("Targets: lib"));
+    }
+}
@@ -0,0 +1,413 @@
+/// Removes generic arguments from a path, including turbofish ones:
+/// `Vec::<u8>::push` becomes `Vec::push` and `HashMap<String, i32>` becomes `HashMap`.
+/// A leading qualifier like `<Vec<u8> as IntoIterator>::` is kept as written, since it
+/// names an impl rather than passing arguments.
+pub fn strip_generic_args(path: &str) -> String {
+    let path = path.trim();
+    let (qualifier, path) = path.split_at(qualifier_len(path));
+    let mut stripped = String::with_capacity(path.len());
+    let mut depth = 0usize;
+    let mut previous = None;
+    for c in path.chars() {
+        match c {
+            '<' => depth += 1,
+            // The `>` of an `fn() -> T` return type closes no bracket.
+            '>' if previous != Some('-') => depth = depth.saturating_sub(1),
+            _ if depth == 0 => stripped.push(c),
+            _ => {}
+        }
+        previous = Some(c);
+    }
+    // A turbofish leaves `Vec::::push` or a trailing `Vec::` behind.
+    while stripped.contains("::::") {
+        stripped = stripped.replace("::::", "::");
+    }
+    format!("{qualifier}{}", stripped.trim().trim_end_matches("::"))
+}
+
+/// The length of the `<Type as Trait>` qualifier a path starts with, or 0.
+fn qualifier_len(path: &str) -> usize {
+    if !path.starts_with('<') {
+        return 0;
+    }
+    let mut depth = 0usize;
+    for (i, c) in path.char_indices() {
+        match c {
+            '<' => depth += 1,
+            '>' if !path[..i].ends_with('-') => {
+                depth -= 1;
+                if depth == 0 {
+                    return i + 1;
+                }
+            }
+            _ => {}
+        }
+    }
+    0
+}
+
+/// Names of the primitive types std documents, each at a path like `std::str`.
+const PRIMITIVES: [&str; 27] = [
+    "array",
+    "bool",
+    "char",
+    "f16",
+    "f32",
+    "f64",
+    "f128",
+    "fn",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "i128",
+    "isize",
+    "never",
+    "pointer",
+    "reference",
+    "slice",
+    "str",
+    "tuple",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "u128",
+    "unit",
+    "usize",
+];
+
+/// The std path of an item of a primitive type as written in code: `str::split` is
+/// `std::str::split`, `[T]::iter` is `std::slice::iter` and `[u8; 4]::map` is
+/// `std::array::map`.
+fn primitive_path(path: &str) -> Option<String> {
+    let (primitive, rest) = match path.strip_prefix('[') {
+        Some(inner) => {
+            let (element, rest) = inner.rsplit_once("]::")?;
+            let primitive = if element.contains(';') {
+                "array"
+            } else {
+                "slice"
+            };
+            (primitive, rest)
+        }
+        None => {
+            let (primitive, rest) = path.split_once("::")?;
+            (*PRIMITIVES.iter().find(|p| **p == primitive)?, rest)
+        }
+    };
+    Some(format!("std::{primitive}::{rest}"))
+}
+
+/// Turns a path as written in code into the absolute paths it may refer to, most likely
+/// first. Leading `::` is stripped, `crate::` maps to the crate of `current_module` (or to
+/// `focus_crate`), `self::` and `super::` are resolved against `current_module`, and any
+/// other path is tried relative to `current_module` before being taken as absolute, then
+/// as an item of a primitive type (see [`primitive_path`]).
+pub fn path_candidates(
+    path: &str,
+    focus_crate: Option<&str>,
+    current_module: Option<&str>,
+) -> Vec<String> {
+    let path = strip_generic_args(path);
+    if path.starts_with('<') {
+        // A qualified path like `<Name as Trait>::method` is already absolute.
+        return vec![path];
+    }
+    let path = path.trim_start_matches("::");
+    let module = current_module
+        .map(|m| m.trim().trim_start_matches("::"))
+        .filter(|m| !m.is_empty());
+    let own_crate = module
+        .map(|m| m.split("::").next().unwrap_or(m))
+        .or(focus_crate);
+
+    let mut segments = path.split("::");
+    match segments.next() {
+        Some("crate") => {
+            let rest: Vec<&str> = segments.collect();
+            let Some(own_crate) = own_crate else {
+                return vec![path.to_string()];
+            };
+            vec![join(own_crate, &rest)]
+        }
+        Some("self") | Some("super") if module.is_some() => {
+            let mut base: Vec<&str> = module.unwrap_or_default().split("::").collect();
+            let mut rest: Vec<&str> = path.split("::").collect();
+            while let Some(first) = rest.first() {
+                match *first {
+                    "self" => {}
+                    // `super` never climbs above the crate root.
+                    "super" if base.len() > 1 => {
+                        base.pop();
+                    }
+                    "super" => {}
+                    _ => break,
+                }
+                rest.remove(0);
+            }
+            vec![join(&base.join("::"), &rest)]
+        }
+        _ => {
+            let mut candidates = Vec::new();
+            if let Some(module) = module {
+                candidates.push(format!("{module}::{path}"));
+            }
+            candidates.push(path.to_string());
+            candidates.extend(primitive_path(path));
+            candidates
+        }
+    }
+}
+
+/// Anchor kinds of rustdoc pages that name a member of the documented item.
+const MEMBER_ANCHORS: [&str; 7] = [
+    "method",
+    "tymethod",
+    "associatedtype",
+    "associatedconstant",
+    "structfield",
+    "variant",
+    "field",
+];
+
+/// Decodes `%XX` escapes, as in URL-encoded fragments like `%23method.foo`.
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| text.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// The path segments of a rustdoc page URL that follow its docs root: the crate, its
+/// modules and the page file. Understands docs.rs, doc.rust-lang.org and local
+/// `target/doc` URLs; other URLs are taken to be relative to a docs root.
+fn page_segments(url: &str) -> Vec<&str> {
+    let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let segments: Vec<&str> = url.split('/').filter(|s| !s.is_empty()).collect();
+    let skip = match segments.first() {
+        // `docs.rs/<package>/<version>/<crate>/...`
+        Some(&"docs.rs") => 3,
+        // `doc.rust-lang.org/<channel>/<crate>/...`, the channel being optional.
+        Some(&"doc.rust-lang.org") => match segments.get(1) {
+            Some(&("stable" | "beta" | "nightly")) => 2,
+            Some(channel) if channel.starts_with(|c: char| c.is_ascii_digit()) => 2,
+            _ => 1,
+        },
+        _ => segments
+            .iter()
+            .rposition(|&s| s == "doc")
+            .map_or(0, |i| i + 1),
+    };
+    segments.into_iter().skip(skip).collect()
+}
+
+/// Turns a rustdoc page URL, like the ones rust-analyzer opens for external docs, into the
+/// item path it documents: `https://docs.rs/serde/1.0.0/serde/de/trait.Deserialize.html#tymethod.deserialize`
+/// becomes `serde::de::Deserialize::deserialize`. Paths with a `#method.name` style anchor
+/// are accepted too. Returns `None` for anything that is not a URL or anchored path.
+pub fn doc_url_path(input: &str) -> Option<String> {
+    let input = percent_decode(input.trim());
+    if !input.contains(".html") && !input.contains('#') {
+        return None;
+    }
+    let (base, fragment) = input.split_once('#').unwrap_or((&input, ""));
+
+    let mut path: Vec<String> = if base.contains(".html") || base.contains('/') {
+        let segments = page_segments(base);
+        let (page, modules) = segments.split_last()?;
+        let mut path: Vec<String> = modules.iter().map(|s| s.to_string()).collect();
+        let name = page.strip_suffix(".html")?;
+        // `index.html` and `all.html` document the module itself.
+        if let Some((_, name)) = name.split_once('.') {
+            path.push(name.to_string());
+        }
+        path
+    } else {
+        base.split("::").map(str::to_string).collect()
+    };
+    if let Some(first) = path.first_mut() {
+        *first = first.replace('-', "_");
+    }
+
+    // `variant.Some`, `method.map` or `variant.V.field.x`; other anchors, like
+    // `impl-Clone-for-Foo` or headings, point into the page and are dropped.
+    let mut parts = fragment.split('.');
+    while let (Some(kind), Some(name)) = (parts.next(), parts.next()) {
+        if !MEMBER_ANCHORS.contains(&kind) {
+            break;
+        }
+        // Rustdoc numbers repeated anchors on a page, like `method.fmt-1`.
+        let name = name
+            .rsplit_once('-')
+            .filter(|(_, n)| n.parse::<u32>().is_ok())
+            .map_or(name, |(name, _)| name);
+        path.push(name.to_string());
+    }
+    (!path.is_empty()).then(|| path.join("::"))
+}
+
+fn join(base: &str, rest: &[&str]) -> String {
+    if rest.is_empty() {
+        base.to_string()
+    } else {
+        format!("{base}::{}", rest.join("::"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_generic_args() {
+        assert_eq!(strip_generic_args("Vec::<u8>::push"), "Vec::push");
+        assert_eq!(strip_generic_args("HashMap<String, Vec<u8>>"), "HashMap");
+        assert_eq!(
+            strip_generic_args("std::iter::once::<u8>"),
+            "std::iter::once"
+        );
+        assert_eq!(
+            strip_generic_args("tokio::sync::Mutex"),
+            "tokio::sync::Mutex"
+        );
+    }
+
+    #[test]
+    fn test_strip_generic_args_keeps_qualifier() {
+        assert_eq!(
+            strip_generic_args("<demo::Name as fmt::Display>::fmt"),
+            "<demo::Name as fmt::Display>::fmt"
+        );
+        assert_eq!(
+            strip_generic_args("<Vec<u8> as IntoIterator>::into_iter::<u8>"),
+            "<Vec<u8> as IntoIterator>::into_iter"
+        );
+        assert_eq!(strip_generic_args("Foo<fn() -> u8>::bar"), "Foo::bar");
+        assert_eq!(
+            strip_generic_args("<Foo<fn() -> u8> as Tr>::bar::<u8>"),
+            "<Foo<fn() -> u8> as Tr>::bar"
+        );
+        // Without a closing `>` there is no qualifier to keep.
+        assert_eq!(strip_generic_args("<Vec<u8>"), "");
+        assert_eq!(
+            path_candidates(
+                "<demo::Name as ToString>::to_string",
+                None,
+                Some("demo::inner")
+            ),
+            vec!["<demo::Name as ToString>::to_string"]
+        );
+    }
+
+    #[test]
+    fn test_absolute_paths() {
+        assert_eq!(
+            path_candidates("::tokio::sync::Mutex", None, None),
+            vec!["tokio::sync::Mutex"]
+        );
+        assert_eq!(
+            path_candidates("crate::config::Config", Some("my_app"), None),
+            vec!["my_app::config::Config"]
+        );
+        assert_eq!(
+            path_candidates("crate::Foo", Some("my_app"), Some("dep::inner")),
+            vec!["dep::Foo"]
+        );
+        assert_eq!(
+            path_candidates("Vec::<u8>::push", None, None),
+            vec!["Vec::push"]
+        );
+    }
+
+    #[test]
+    fn test_primitive_paths() {
+        assert_eq!(
+            path_candidates("u64::checked_add", None, None),
+            vec!["u64::checked_add", "std::u64::checked_add"]
+        );
+        assert_eq!(
+            path_candidates("[T]::iter", None, None),
+            vec!["[T]::iter", "std::slice::iter"]
+        );
+        assert_eq!(
+            path_candidates("[u8; 4]::map", None, None),
+            vec!["[u8; 4]::map", "std::array::map"]
+        );
+        assert_eq!(path_candidates("Vec::push", None, None), vec!["Vec::push"]);
+    }
+
+    #[test]
+    fn test_relative_paths() {
+        assert_eq!(
+            path_candidates("mpsc::Sender", None, Some("tokio::sync")),
+            vec!["tokio::sync::mpsc::Sender", "mpsc::Sender"]
+        );
+        assert_eq!(
+            path_candidates("super::net::TcpStream", None, Some("tokio::io")),
+            vec!["tokio::net::TcpStream"]
+        );
+        assert_eq!(
+            path_candidates("self::Receiver", None, Some("tokio::sync::mpsc")),
+            vec!["tokio::sync::mpsc::Receiver"]
+        );
+    }
+
+    #[test]
+    fn test_doc_url_path() {
+        assert_eq!(
+            doc_url_path(
+                "https://docs.rs/serde/1.0.219/serde/de/trait.Deserialize.html#tymethod.deserialize"
+            )
+            .as_deref(),
+            Some("serde::de::Deserialize::deserialize")
+        );
+        assert_eq!(
+            doc_url_path(
+                "https://doc.rust-lang.org/nightly/core/option/enum.Option.html#variant.Some"
+            )
+            .as_deref(),
+            Some("core::option::Option::Some")
+        );
+        assert_eq!(
+            doc_url_path("https://doc.rust-lang.org/std/vec/index.html").as_deref(),
+            Some("std::vec")
+        );
+        assert_eq!(
+            doc_url_path(
+                "file:///home/me/app/target/doc/my_app/struct.Config.html%23structfield.name"
+            )
+            .as_deref(),
+            Some("my_app::Config::name")
+        );
+        assert_eq!(
+            doc_url_path("demo/enum.Shape.html#variant.Circle.field.radius").as_deref(),
+            Some("demo::Shape::Circle::radius")
+        );
+        assert_eq!(
+            doc_url_path("demo::Greeter#method.greet-1").as_deref(),
+            Some("demo::Greeter::greet")
+        );
+        assert_eq!(
+            doc_url_path("demo/struct.Greeter.html#impl-Clone-for-Greeter").as_deref(),
+            Some("demo::Greeter")
+        );
+        assert_eq!(doc_url_path("demo::Greeter::greet"), None);
+    }
+}
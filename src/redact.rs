@@ -0,0 +1,18 @@
+use rmcp::model::{CallToolResult, RawContent};
+use rustdoc_mcp_core::config::RedactConfig;
+
+/// Applies the patterns to the text and structured content of a tool result. This also
+/// covers source snippets quoted in compiler output and manifest or README text.
+pub fn redact_tool_result(redact: &RedactConfig, result: &mut CallToolResult) {
+    if redact.patterns.is_empty() {
+        return;
+    }
+    for content in &mut result.content {
+        if let RawContent::Text(text) = &mut content.raw {
+            text.text = redact.redact_text(&text.text);
+        }
+    }
+    if let Some(structured) = &mut result.structured_content {
+        redact.redact_json(structured);
+    }
+}
@@ -0,0 +1,141 @@
+use std::collections::HashSet;
+
+use rmcp::model::Tool;
+use serde_json::{Map, Value, json};
+
+use crate::compact::FORMAT_ARG;
+use crate::output_limit::CURSOR_ARG;
+
+/// Checks the schemas generated for the tools: every tool needs a unique name and a
+/// description, input and output schemas must describe JSON objects, and no tool may
+/// declare the `cursor` or `format` arguments the server adds to all of them.
+pub fn validate_tool_schemas(tools: &[Tool]) -> Result<(), String> {
+    let object = json!("object");
+    let mut names = HashSet::new();
+    for tool in tools {
+        let name = &tool.name;
+        if !names.insert(name) {
+            return Err(format!("Tool `{name}` is defined twice"));
+        }
+        if tool.description.as_deref().is_none_or(str::is_empty) {
+            return Err(format!("Tool `{name}` has no description"));
+        }
+        if tool.input_schema.get("type") != Some(&object) {
+            return Err(format!("Input schema of `{name}` is not an object schema"));
+        }
+        if let Some(Value::Object(properties)) = tool.input_schema.get("properties")
+            && let Some(reserved) = [CURSOR_ARG, FORMAT_ARG]
+                .into_iter()
+                .find(|arg| properties.contains_key(*arg))
+        {
+            return Err(format!(
+                "Tool `{name}` declares the reserved `{reserved}` argument"
+            ));
+        }
+        if let Some(output) = &tool.output_schema
+            && output.get("type") != Some(&object)
+        {
+            return Err(format!("Output schema of `{name}` is not an object schema"));
+        }
+    }
+    Ok(())
+}
+
+/// Renders tools as one JSON object mapping each tool name to its description, input
+/// schema and output schema (if any), sorted by name.
+pub fn schemas_document(tools: &[Tool]) -> Value {
+    let document: Map<String, Value> = tools
+        .iter()
+        .map(|tool| {
+            (
+                tool.name.to_string(),
+                json!({
+                    "description": tool.description,
+                    "input_schema": tool.input_schema,
+                    "output_schema": tool.output_schema,
+                }),
+            )
+        })
+        .collect();
+    Value::Object(document)
+}
+
+/// Summarizes each tool's arguments as one line like `get_module(current_module?, path)`,
+/// sorted, with optional arguments marked by `?`.
+pub fn argument_summary(tools: &[Tool]) -> String {
+    let mut lines: Vec<String> = tools
+        .iter()
+        .map(|tool| {
+            let required: HashSet<&str> = match tool.input_schema.get("required") {
+                Some(Value::Array(required)) => required.iter().filter_map(Value::as_str).collect(),
+                _ => HashSet::new(),
+            };
+            let mut arguments: Vec<String> = match tool.input_schema.get("properties") {
+                Some(Value::Object(properties)) => properties
+                    .keys()
+                    .map(|arg| {
+                        if required.contains(arg.as_str()) {
+                            arg.clone()
+                        } else {
+                            format!("{arg}?")
+                        }
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            };
+            arguments.sort();
+            format!("{}({})", tool.name, arguments.join(", "))
+        })
+        .collect();
+    lines.sort();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::RustDocMCPServer;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_validate_tool_schemas() {
+        let tools = RustDocMCPServer::tool_schemas().unwrap();
+        assert!(!tools.is_empty());
+        // The advertised tools already carry the cursor argument, so validating them again
+        // reports the collision.
+        let error = validate_tool_schemas(&tools).unwrap_err();
+        assert!(error.contains("reserved `cursor` argument"));
+
+        let mut duplicated = tools[..1].to_vec();
+        duplicated.extend_from_slice(&tools[..1]);
+        for tool in &mut duplicated {
+            Arc::make_mut(&mut tool.input_schema).remove("properties");
+        }
+        assert!(
+            validate_tool_schemas(&duplicated)
+                .unwrap_err()
+                .contains("defined twice")
+        );
+    }
+
+    #[test]
+    fn test_tool_arguments_golden() {
+        let golden = include_str!("../testdata/tool_arguments.txt");
+        let actual = argument_summary(&RustDocMCPServer::tool_schemas().unwrap());
+        assert_eq!(
+            actual,
+            golden.trim_end(),
+            "tool arguments changed; if intended, update testdata/tool_arguments.txt"
+        );
+    }
+
+    #[test]
+    fn test_schemas_document() {
+        let document = schemas_document(&RustDocMCPServer::tool_schemas().unwrap());
+        let get_docs = &document["get_docs"];
+        assert!(get_docs["description"].is_string());
+        assert_eq!(get_docs["input_schema"]["type"], "object");
+        assert!(get_docs["output_schema"].is_null());
+        assert_eq!(document["list_deps"]["output_schema"]["type"], "object");
+    }
+}
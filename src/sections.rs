@@ -0,0 +1,173 @@
+use std::collections::HashMap;
+
+use rustdoc_mcp_core::impl_summary::inherent_methods;
+use rustdoc_types::{Crate, Id, Item, ItemEnum, StructKind, VariantKind};
+
+/// Turns a heading title into its anchor: lowercase words joined by `-`, without a trailing
+/// parenthetical, so `Methods (page 2 of 3)` is `methods` on every page.
+pub fn section_anchor(title: &str) -> String {
+    let title = title.split(" (").next().unwrap_or(title);
+    let mut anchor = String::new();
+    for c in title.chars() {
+        if c.is_alphanumeric() || c == '_' {
+            anchor.extend(c.to_lowercase());
+        } else if !anchor.is_empty() && !anchor.ends_with('-') {
+            anchor.push('-');
+        }
+    }
+    anchor.trim_end_matches('-').to_string()
+}
+
+/// The headings of `markdown` as `(line, level, anchor)`, skipping fenced code blocks so
+/// that hidden `# ` doctest lines are not mistaken for headings. Repeated anchors get a
+/// `-1`, `-2` suffix, as on GitHub, so each heading has its own.
+fn heading_anchors(markdown: &str) -> Vec<(usize, usize, String)> {
+    let mut anchors = Vec::new();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut in_fence = false;
+    for (i, line) in markdown.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+        }
+        let level = trimmed.chars().take_while(|c| *c == '#').count();
+        if in_fence || !(1..=6).contains(&level) || !trimmed[level..].starts_with(' ') {
+            continue;
+        }
+        let base = section_anchor(&trimmed[level..]);
+        let repeats = seen.entry(base.clone()).or_default();
+        let anchor = match *repeats {
+            0 => base,
+            n => format!("{base}-{n}"),
+        };
+        *repeats += 1;
+        anchors.push((i, level, anchor));
+    }
+    anchors
+}
+
+/// Appends `{#anchor}` to every heading below the title of rendered docs, so sections can
+/// be requested on their own.
+pub fn add_anchors(markdown: &str) -> String {
+    let anchors: HashMap<usize, String> = heading_anchors(markdown)
+        .into_iter()
+        .skip(1)
+        .map(|(line, _, anchor)| (line, anchor))
+        .collect();
+    let mut anchored: Vec<String> = markdown
+        .lines()
+        .enumerate()
+        .map(|(i, line)| match anchors.get(&i) {
+            Some(anchor) => format!("{} {{#{anchor}}}", line.trim_end()),
+            None => line.to_string(),
+        })
+        .collect();
+    if markdown.ends_with('\n') {
+        anchored.push(String::new());
+    }
+    anchored.join("\n")
+}
+
+/// Cuts the section with the given anchor out of rendered docs: its heading and everything
+/// up to the next heading of the same or a higher level.
+pub fn select_section(markdown: &str, anchor: &str) -> Option<String> {
+    let anchors = heading_anchors(markdown);
+    let position = anchors.iter().position(|(_, _, a)| a == anchor)?;
+    let (start, level, _) = anchors[position];
+    let end = anchors[position + 1..]
+        .iter()
+        .find(|(_, l, _)| *l <= level)
+        .map_or(usize::MAX, |(line, _, _)| *line);
+    let lines: Vec<&str> = markdown.lines().take(end).skip(start).collect();
+    Some(format!(
+        "{} {{#{anchor}}}\n{}\n",
+        lines[0].trim_end(),
+        lines[1..].join("\n").trim_end()
+    ))
+}
+
+/// The anchors of the sections of rendered docs, for error messages.
+pub fn section_anchors(markdown: &str) -> Vec<String> {
+    heading_anchors(markdown)
+        .into_iter()
+        .skip(1)
+        .map(|(_, _, anchor)| anchor)
+        .collect()
+}
+
+/// Finds the member an entry anchor like `fields.len`, `variants.Some` or `methods.push`
+/// names: a field of a struct or struct-like variant, a variant of an enum, or an inherent
+/// method of a type or an item of a trait.
+pub fn member_entry<'a>(
+    krate: &'a Crate,
+    item: &Item,
+    section: &str,
+    name: &str,
+) -> Option<&'a Item> {
+    let named = |ids: &[Id]| {
+        ids.iter()
+            .filter_map(|id| krate.index.get(id))
+            .find(|member| member.name.as_deref() == Some(name))
+    };
+    match (section, &item.inner) {
+        ("fields", ItemEnum::Struct(s)) => match &s.kind {
+            StructKind::Plain { fields, .. } => named(fields),
+            StructKind::Tuple(fields) => {
+                named(&fields.iter().flatten().copied().collect::<Vec<_>>())
+            }
+            StructKind::Unit => None,
+        },
+        ("fields", ItemEnum::Variant(v)) => match &v.kind {
+            VariantKind::Struct { fields, .. } => named(fields),
+            _ => None,
+        },
+        ("fields", ItemEnum::Union(u)) => named(&u.fields),
+        ("variants", ItemEnum::Enum(e)) => named(&e.variants),
+        ("methods", ItemEnum::Trait(t)) => named(&t.items),
+        ("methods", _) => inherent_methods(krate, item)?
+            .into_iter()
+            .find(|method| method.name.as_deref() == Some(name)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOCS: &str = "# struct Greeter\n\n## Documentation\n\nGreets people.\n\n# Examples\n\n```rust\n# use demo::Greeter;\n```\n\n## Implementations\n\n2 inherent methods.\n\n### Methods (page 1 of 1)\n\n```rust\nfn greet(&self)\n```\n\n## Examples\n\nMore.\n";
+
+    #[test]
+    fn test_section_anchor() {
+        assert_eq!(section_anchor("Methods (page 2 of 3)"), "methods");
+        assert_eq!(section_anchor("Methods via Deref"), "methods-via-deref");
+        assert_eq!(section_anchor("From `String`"), "from-string");
+    }
+
+    #[test]
+    fn test_anchors() {
+        assert_eq!(
+            section_anchors(DOCS),
+            vec![
+                "documentation",
+                "examples",
+                "implementations",
+                "methods",
+                "examples-1"
+            ]
+        );
+        let anchored = add_anchors(DOCS);
+        assert!(anchored.starts_with("# struct Greeter\n"));
+        assert!(anchored.contains("\n### Methods (page 1 of 1) {#methods}\n"));
+        assert!(anchored.contains("\n## Examples {#examples-1}\n"));
+        assert!(anchored.contains("\n# use demo::Greeter;\n"));
+
+        assert_eq!(
+            select_section(DOCS, "implementations").as_deref(),
+            Some(
+                "## Implementations {#implementations}\n\n2 inherent methods.\n\n### Methods (page 1 of 1)\n\n```rust\nfn greet(&self)\n```\n"
+            )
+        );
+        assert_eq!(select_section(DOCS, "fields"), None);
+    }
+}
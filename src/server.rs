@@ -1,63 +1,597 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::env::current_dir;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
+use crate::adapters::{chain_impl, ext_trait_adapters};
+use crate::api_diff::{SNAPSHOT_FILE_NAME, diff_api, public_api, required_bump, snapshot_file};
+use crate::bookmarks::{self, bookmarks_path};
+use crate::compact::{add_format_param, compact_tool_result, json_tool_result, take_format};
+use crate::daemon;
+use crate::dep_cost::dep_cost;
+use crate::dep_tree::dependency_tree;
+use crate::example_check::ExampleChecker;
+use crate::explain::{lookup, parse_diagnostic};
+use crate::features::{diff_paths, enabled_features, feature_sets, list_features, select_features};
+use crate::impl_matrix::impl_matrix;
+use crate::imports::{exports_of, suggest_imports};
+use crate::instantiate::{instantiate_methods, parse_type_expr, resolve_assoc_types};
+use crate::licenses::license_report;
+use crate::output_limit::{add_cursor_param, limit_tool_result, take_cursor};
+use crate::overview::{CRATE_URI_PREFIX, WORKSPACE_URI, workspace_overview};
+use crate::paths::{doc_url_path, path_candidates, strip_generic_args};
+use crate::redact::redact_tool_result;
+use crate::schemas::validate_tool_schemas;
+use crate::sections::{add_anchors, member_entry, section_anchors, select_section};
+use crate::skeleton::{DEFAULT_SELF_TYPE, ImplTarget, struct_literal, trait_usage_example};
+use crate::source::{annotated_snippet, snippet_shows, span_text};
+use crate::type_tree::tree_code_blocks;
 use crate::types::{
-    GetDocsArgs, GetModuleArgs, GetModuleResult, ItemSummary, ListCrateItemsArgs,
-    ListCrateItemsResult, ListDepsResult, SearchDocsArgs, SearchDocsResult,
+    ApiReference, ApiSnapshot, AttributedItem, Bookmark, BookmarkItemArgs, BookmarkItemResult,
+    CancellationSafety, CheckExampleArgs, CheckExampleResult, CheckPublicApiResult, CrateFormat,
+    CrateInfo, CrateInfoArgs, DepCost, DepCostArgs, ExplainErrorArgs, ExplainErrorResult,
+    ExplainSearchResult, FeatureItem, GenerateDocsArgs, GenerateDocsResult,
+    GenerateImplSkeletonArgs, GenerateMatchSkeletonArgs, GenerateStructLiteralArgs,
+    GetAssocTypeResolutionArgs, GetAssocTypeResolutionResult, GetAttributesArgs,
+    GetAttributesResult, GetCancellationSafetyArgs, GetCancellationSafetyResult,
+    GetDependencyTreeArgs, GetDependencyTreeResult, GetDocsArgs, GetExamplesArgs,
+    GetExamplesResult, GetMacroArgumentsArgs, GetMacroArgumentsResult, GetModuleArgs,
+    GetModuleResult, GetSignaturesArgs, GetSignaturesResult, GetSourceArgs,
+    GetTraitUsageExampleArgs, GuideSummary, HeadingMatch, ImplMatrixArgs, ImplMatrixResult,
+    InstantiateGenericArgs, InstantiateGenericResult, ItemSignature, ItemSummary,
+    LicenseReportResult, ListBookmarksResult, ListCrateItemsArgs, ListCrateItemsResult,
+    ListDepsResult, ListFeatureItemsArgs, ListFeatureItemsResult, ListFeaturesArgs,
+    ListFeaturesResult, ListGuidesArgs, ListGuidesResult, ListIteratorAdaptersArgs,
+    ListIteratorAdaptersResult, ListMethodsArgs, ListMethodsResult, ListPreludeArgs,
+    ListPreludeResult, ListTraitImplsArgs, ListTraitImplsResult, ListWorkspaceMembersResult,
+    LoadCrateArgs, LoadCrateResult, MethodSummary, PreludeSummary, PublicApiArgs, RenameImpactArgs,
+    RenameImpactResult, SearchBySignatureArgs, SearchDocsArgs, SearchDocsResult, SearchExplanation,
+    SearchHeadingsArgs, SearchHeadingsResult, SearchWorkspaceDocsArgs, SearchWorkspaceDocsResult,
+    ServerStatusArgs, ServerStatusResult, SnapshotPublicApiResult, SuggestImportsArgs,
+    SuggestImportsResult, TargetSummary, TraitUsageExampleResult, UnloadCrateArgs,
+    UnloadCrateResult, WhoExportsArgs, WhoExportsResult, WorkspaceDocMatch, WorkspaceMember,
 };
-use crate::workspace::Workspace;
-use crate::{
-    index::{CrateIndex, get_item_kind},
-    markdown::generate_item_markdown,
+use crate::version::{
+    PACKAGE_NAME, SERVER_VERSION, is_newer, latest_release, nightly_rustc_version,
 };
 
 use anyhow::Result;
+use cargo_metadata::Package;
 use rmcp::{
-    ServerHandler,
+    ErrorData, RoleServer, ServerHandler,
     handler::server::{
-        tool::ToolRouter,
+        tool::{ToolCallContext, ToolRouter},
         wrapper::{Json, Parameters},
     },
-    model::{ServerCapabilities, ServerInfo},
-    tool, tool_handler, tool_router,
+    model::{
+        AnnotateAble, CallToolRequestParams, CallToolResult, Implementation, ListResourcesResult,
+        ListToolsResult, PaginatedRequestParams, ProtocolVersion, RawResource,
+        ReadResourceRequestParams, ReadResourceResult, ResourceContents, ServerCapabilities,
+        ServerInfo, Tool,
+    },
+    service::RequestContext,
+    tool, tool_router,
+};
+use rustdoc_mcp_core::attributes::correctness_attributes;
+use rustdoc_mcp_core::availability::availability;
+use rustdoc_mcp_core::config::{Config, OutputFormat};
+use rustdoc_mcp_core::crate_info::crate_info;
+use rustdoc_mcp_core::doc_examples::extract_examples;
+use rustdoc_mcp_core::headings::{
+    DocHeading, cancellation_safety, heading_score, truncate_section,
+};
+use rustdoc_mcp_core::impl_summary::{
+    doc_summary, implementations_section, inherent_methods, trait_impls,
+};
+use rustdoc_mcp_core::macro_args::extract_macro_arguments;
+use rustdoc_mcp_core::references::{
+    doc_link_references, external_references, find_references, signature_references,
 };
-use rustdoc_types::ItemEnum;
+use rustdoc_mcp_core::sysroot::is_sysroot_crate;
+use rustdoc_mcp_core::workspace::{Directness, Workspace};
+use rustdoc_mcp_core::{
+    index::{
+        AvailableCrates, CrateIndex, CrateRef, DEFAULT_GUIDE_MIN_LENGTH, LoadOutcome, LoadTimings,
+        LoadedCrate, SearchOptions, get_item_kind, guide_title,
+    },
+    markdown::{
+        format_disambiguation, format_item_definition, format_match_skeleton,
+        generate_item_markdown, referenced_types_section, related_items_section,
+    },
+};
+use rustdoc_types::{Attribute, Crate, Id, Item, ItemEnum, MacroKind};
 use tracing::{debug, info};
 
+/// URI prefix under which module-level guides are served as resources.
+const GUIDE_URI_PREFIX: &str = "rustdoc://guide/";
+
 #[derive(Debug, Clone)]
 pub struct RustDocMCPServer {
     workspace: Workspace,
     index: CrateIndex,
+    /// When the last tool call arrived, shared by every session of an HTTP server.
+    last_request: Arc<Mutex<Instant>>,
     tool_router: ToolRouter<Self>,
 }
 
 #[tool_router]
 impl RustDocMCPServer {
-    pub fn new(cwd: Option<String>) -> Result<Self, String> {
-        let cwd = match cwd {
-            Some(dir) => dir,
-            None => current_dir()
-                .map_err(|e| e.to_string())?
-                .to_str()
-                .ok_or("Failed to convert path to string".to_string())?
-                .to_string(),
-        };
+    /// Creates the server for the cargo workspace at `cwd`, or, with `fixture`, for a
+    /// directory of prebuilt rustdoc JSON (see [`Workspace::load_fixture`]).
+    pub fn new(
+        cwd: Option<String>,
+        config_path: Option<String>,
+        fixture: Option<String>,
+    ) -> Result<Self, String> {
+        Self::tool_schemas().map_err(|e| format!("Invalid tool schema: {e}"))?;
 
-        if !Workspace::has_nightly_toolchain() {
-            return Err("Rust nightly toolchain is required but not found. Please install it with `rustup toolchain install nightly`.".to_string());
-        }
+        let workspace = match fixture {
+            Some(dir) => Workspace::load_fixture(&dir)
+                .map_err(|e| format!("Failed to load fixture: {e:#}"))?,
+            None => {
+                let cwd = match cwd {
+                    Some(dir) => dir,
+                    None => current_dir()
+                        .map_err(|e| e.to_string())?
+                        .to_str()
+                        .ok_or("Failed to convert path to string".to_string())?
+                        .to_string(),
+                };
+
+                if !Workspace::has_nightly_toolchain() {
+                    return Err("Rust nightly toolchain is required but not found. Please install it with `rustup toolchain install nightly`.".to_string());
+                }
 
-        let workspace =
-            Workspace::load(&cwd).map_err(|e| format!("Failed to load workspace: {e}"))?;
+                Workspace::load(&cwd).map_err(|e| format!("Failed to load workspace: {e}"))?
+            }
+        };
 
-        let index = CrateIndex::new(workspace.clone());
+        let config = Config::load(config_path.as_deref().map(Path::new), &workspace.root)
+            .map_err(|e| format!("Failed to load config: {e:#}"))?;
+
+        let index = CrateIndex::new(workspace.clone(), config);
 
         Ok(Self {
             workspace,
             index,
+            last_request: Arc::new(Mutex::new(Instant::now())),
             tool_router: Self::tool_router(),
         })
     }
 
+    pub fn config(&self) -> &Config {
+        self.index.config()
+    }
+
+    /// A handle on this server for another client session: it shares the loaded docs, but
+    /// the feature sets it picks with `generate_docs` are its own.
+    pub fn for_session(&self) -> Self {
+        Self {
+            index: self.index.session(),
+            ..self.clone()
+        }
+    }
+
+    /// How long it has been since the last tool call, or since the server started.
+    pub fn idle_time(&self) -> Duration {
+        self.last_request
+            .lock()
+            .map_or(Duration::ZERO, |last| last.elapsed())
+    }
+
+    /// Where the daemon keeps the crates it had loaded, under the target directory like
+    /// bookmarks. Fixtures have none, since their directory is part of the sources.
+    pub fn session_path(&self) -> Option<PathBuf> {
+        (!self.workspace.fixture)
+            .then(|| daemon::session_path(self.workspace.metadata.target_directory.as_std_path()))
+    }
+
+    /// Names of the crates loaded so far, sorted.
+    pub fn loaded_crates(&self) -> Vec<String> {
+        let mut names = self.index.loaded_crate_names();
+        names.sort();
+        names
+    }
+
+    /// Packages loaded with a chosen feature set by any session, with their features.
+    pub fn feature_variants(&self) -> Vec<(String, Vec<String>)> {
+        let mut variants = self.index.feature_variants();
+        variants.sort();
+        variants
+    }
+
+    /// Loads the crates, from the index cache when they were loaded before, and returns
+    /// how many failed.
+    pub async fn restore_crates(&self, crate_names: &[String]) -> usize {
+        let outcomes = self.index.ensure_all_loaded(crate_names).await;
+        outcomes
+            .iter()
+            .filter(|(name, outcome)| match outcome {
+                LoadOutcome::Failed(e) => {
+                    tracing::warn!("Failed to restore `{name}`: {e:#}");
+                    true
+                }
+                _ => false,
+            })
+            .count()
+    }
+
+    /// Loads packages with chosen feature sets again, without selecting them for any
+    /// session, so a later `generate_docs` of one finds it loaded. Returns how many failed.
+    pub async fn restore_feature_variants(&self, variants: &[(String, Vec<String>)]) -> usize {
+        let mut failed = 0;
+        for (crate_name, features) in variants {
+            if let Err(e) = self.index.cache_with_features(crate_name, features).await {
+                tracing::warn!("Failed to restore `{crate_name}` with {features:?}: {e:#}");
+                failed += 1;
+            }
+        }
+        failed
+    }
+
+    /// Returns every tool as advertised by `list_tools`, after checking the schemas
+    /// generated for them with [`validate_tool_schemas`].
+    pub fn tool_schemas() -> Result<Vec<Tool>, String> {
+        let mut tools = Self::tool_router().list_all();
+        validate_tool_schemas(&tools)?;
+        tools.iter_mut().for_each(add_cursor_param);
+        tools.iter_mut().for_each(add_format_param);
+        Ok(tools)
+    }
+
+    /// Resolves the `path`, or `crate_name` and `id`, an item is requested by to its crate,
+    /// loading it, and its path. The id is returned when the item was requested by id.
+    async fn locate_item(
+        &self,
+        path: Option<&str>,
+        crate_name: Option<&str>,
+        id: Option<u32>,
+        current_module: Option<&str>,
+    ) -> Result<(String, String, Option<Id>), String> {
+        // An id names one item exactly; a path is resolved first and may be ambiguous.
+        let (crate_name, path, queried_id) = match (id, path) {
+            (Some(id), _) => {
+                let crate_name = crate_name
+                    .ok_or("`crate_name` is required when querying by `id`".to_string())?;
+                (crate_name.to_string(), None, Some(Id(id)))
+            }
+            (None, Some(path)) => {
+                // `<Type as Trait>::name` picks one trait's item; only the type path is resolved.
+                let path = match path.strip_prefix('<').and_then(|p| p.split_once(" as ")) {
+                    Some((type_path, rest)) => format!(
+                        "<{} as {rest}",
+                        self.resolve_path(type_path, current_module).await
+                    ),
+                    None => self.resolve_path(path, current_module).await,
+                };
+                let crate_name = path
+                    .trim_start_matches('<')
+                    .split("::")
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                (crate_name, Some(path), None)
+            }
+            (None, None) => return Err("Provide `path`, or `crate_name` and `id`".to_string()),
+        };
+
+        self.index
+            .ensure_loaded(&crate_name)
+            .await
+            .map_err(|e| e.to_string())?;
+        let path = match path {
+            Some(path) => path,
+            None => self
+                .index
+                .get_crate(&crate_name)
+                .zip(queried_id)
+                .and_then(|(krate, id)| krate.path_of(id))
+                .unwrap_or_default(),
+        };
+        Ok((crate_name, path, queried_id))
+    }
+
+    /// Loads `crate_name` if needed and borrows its docs.
+    async fn loaded_crate(&self, crate_name: &str) -> Result<CrateRef<'_>, String> {
+        self.index
+            .ensure_loaded(crate_name)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.index
+            .get_crate(crate_name)
+            .ok_or("Failed to load crate".to_string())
+    }
+
+    /// Looks up the item [`locate_item`](Self::locate_item) located in its loaded crate. A
+    /// path several traits provide items under is answered with the candidates instead.
+    fn lookup_item(
+        &self,
+        crate_name: String,
+        path: String,
+        id: Option<Id>,
+    ) -> Result<Lookup<'_>, String> {
+        let krate = self
+            .index
+            .get_crate(&crate_name)
+            .ok_or("Failed to load crate".to_string())?;
+        let id = match id {
+            Some(id) => id,
+            None => {
+                if let Some(candidates) = krate.disambiguation(&path) {
+                    let candidates = format_disambiguation(&path, candidates, &krate.krate);
+                    return Ok(Lookup::Ambiguous(candidates));
+                }
+                krate
+                    .path_to_id
+                    .get(&path)
+                    .copied()
+                    .or_else(|| krate.resolve_qualified(&path))
+                    .ok_or(format!("Item not found: {path}"))?
+            }
+        };
+        if !krate.krate.index.contains_key(&id) {
+            return Err(format!("No item with id {} in crate {crate_name}", id.0));
+        }
+        Ok(Lookup::Found(FoundItem {
+            crate_name,
+            path,
+            krate,
+            id,
+        }))
+    }
+
+    /// Resolves, loads and looks up the item `path` names, for the tools that take an item
+    /// by path alone. An ambiguous path fails with the candidates to choose from.
+    async fn find_item(
+        &self,
+        path: &str,
+        current_module: Option<&str>,
+    ) -> Result<FoundItem<'_>, String> {
+        let (crate_name, path, _) = self
+            .locate_item(Some(path), None, None, current_module)
+            .await?;
+        match self.lookup_item(crate_name, path, None)? {
+            Lookup::Found(found) => Ok(found),
+            Lookup::Ambiguous(candidates) => Err(candidates),
+        }
+    }
+
+    /// Resolves a path as written in code to the first of its candidates (see
+    /// [`path_candidates`]) that names an indexed item. Falls back to the most likely
+    /// candidate, so that lookups report that one as missing.
+    async fn resolve_path(&self, path: &str, current_module: Option<&str>) -> String {
+        let url_path = doc_url_path(path);
+        let path = url_path.as_deref().unwrap_or(path);
+        let focus_crate = self.workspace.focus_crate();
+        let candidates = path_candidates(path, focus_crate.as_deref(), current_module);
+
+        for candidate in &candidates {
+            if self.path_exists(candidate).await {
+                debug!("Resolved path {} to {}", path, candidate);
+                return candidate.clone();
+            }
+        }
+
+        // `Type::method` may name a method reached through `Deref`, like `String::trim`.
+        for candidate in &candidates {
+            let Some((type_path, method)) = candidate.rsplit_once("::") else {
+                continue;
+            };
+            for target in self.deref_chain(type_path).await {
+                let target_method = format!("{target}::{method}");
+                if self.path_exists(&target_method).await {
+                    debug!("Resolved path {} through Deref to {}", path, target_method);
+                    return target_method;
+                }
+            }
+        }
+
+        candidates.into_iter().next().unwrap_or_default()
+    }
+
+    /// The page of ranked search results starting at `offset`, holding `limit` results or
+    /// `search.max_results` by default.
+    fn search_page<T>(
+        &self,
+        results: Vec<T>,
+        offset: Option<usize>,
+        limit: Option<usize>,
+    ) -> Vec<T> {
+        let limit = limit.unwrap_or(self.index.config().search.max_results);
+        results
+            .into_iter()
+            .skip(offset.unwrap_or(0))
+            .take(limit)
+            .collect()
+    }
+
+    /// Checks whether `path` is indexed, loading its crate if needed.
+    async fn path_exists(&self, path: &str) -> bool {
+        let crate_name = path.split("::").next().unwrap_or(path);
+        // Only load crates that exist, rather than generating docs for every guess.
+        let known = self.index.get_crate(crate_name).is_some()
+            || self.workspace.find_package(crate_name).is_some()
+            || (!self.workspace.fixture && is_sysroot_crate(crate_name))
+            || (self.workspace.fixture && self.index.json_path(crate_name).exists());
+        known
+            && self.index.ensure_loaded(crate_name).await.is_ok()
+            && self
+                .index
+                .get_crate(crate_name)
+                .is_some_and(|krate| krate.path_to_id.contains_key(path))
+    }
+
+    /// Follows `Deref` impls from `type_path` up to the configured depth, returning each
+    /// target in turn. Stops at primitives, crates outside the workspace and cycles.
+    async fn deref_chain(&self, type_path: &str) -> Vec<String> {
+        let mut chain: Vec<String> = Vec::new();
+        let mut current = type_path.to_string();
+        for _ in 0..self.index.config().resolve.deref_depth {
+            if !self.path_exists(&current).await {
+                break;
+            }
+            let crate_name = current.split("::").next().unwrap_or(&current);
+            let Some(target) = self
+                .index
+                .get_crate(crate_name)
+                .and_then(|krate| krate.deref_target(&current))
+            else {
+                break;
+            };
+            if target == type_path || chain.contains(&target) {
+                break;
+            }
+            chain.push(target.clone());
+            current = target;
+        }
+        chain
+    }
+
+    /// Points out the items an item's docs link to in crates whose docs are not loaded yet,
+    /// so they can be loaded before following the links. Crates excluded by policy are left
+    /// out since they cannot be loaded, and standard library crates since nearly every
+    /// crate links to them.
+    fn unloaded_links_section(
+        &self,
+        mut linked: BTreeMap<String, BTreeSet<String>>,
+    ) -> Option<String> {
+        linked.retain(|crate_name, _| {
+            !is_sysroot_crate(crate_name)
+                && self.index.config().crates.check(crate_name).is_ok()
+                && self.index.get_crate(crate_name).is_none()
+        });
+        if linked.is_empty() {
+            return None;
+        }
+        let count: usize = linked.values().map(BTreeSet::len).sum();
+        let crates: Vec<String> = linked.keys().map(|name| format!("`{name}`")).collect();
+        let mut section = format!(
+            "\n## Unresolved Links\n\nThe docs reference {count} items from crates {} whose docs are not loaded. Call `load_crate` for them before following the links:\n\n",
+            crates.join(", ")
+        );
+        for (crate_name, paths) in &linked {
+            let paths: Vec<String> = paths.iter().map(|p| format!("`{p}`")).collect();
+            section.push_str(&format!("- `{crate_name}`: {}\n", paths.join(", ")));
+        }
+        Some(section)
+    }
+
+    /// Lists the crates that own the external types an item mentions, with whether the
+    /// workspace can already use them or needs to add them.
+    fn provenance_section(&self, external: &BTreeMap<String, BTreeSet<String>>) -> String {
+        let mut section = String::from("\n## Referenced Crates\n\n");
+        for (crate_name, paths) in external {
+            let status = if is_sysroot_crate(crate_name) {
+                "standard library"
+            } else if self.workspace.find_package(crate_name).is_none() {
+                "not in the dependency graph"
+            } else {
+                match self.workspace.directness(crate_name) {
+                    Directness::Member => "workspace member",
+                    Directness::Direct => "direct dependency",
+                    Directness::Transitive => {
+                        "transitive dependency; add it to Cargo.toml to name its types directly"
+                    }
+                }
+            };
+            let paths: Vec<String> = paths.iter().map(|p| format!("`{p}`")).collect();
+            section.push_str(&format!(
+                "- `{crate_name}` ({status}): {}\n",
+                paths.join(", ")
+            ));
+        }
+        section
+    }
+
+    /// Lists the methods made available on a type by its `Deref` chain.
+    fn deref_methods_section(&self, type_path: &str, chain: &[String]) -> String {
+        let targets: Vec<String> = chain.iter().map(|t| format!("`{t}`")).collect();
+        let mut section = format!(
+            "\n## Methods via Deref\n\n`{type_path}` derefs to {}, so their methods can be called on it too.\n",
+            targets.join(", then ")
+        );
+        for target in chain {
+            let crate_name = target.split("::").next().unwrap_or(target);
+            let Some(krate) = self.index.get_crate(crate_name) else {
+                section.push_str(&format!(
+                    "\nMethods of `{target}` are not indexed; they are documented with its crate.\n"
+                ));
+                continue;
+            };
+            let mut methods: Vec<String> = krate
+                .members_of(target)
+                .into_iter()
+                .filter(|(_, item)| matches!(item.inner, ItemEnum::Function(_)))
+                .map(|(path, _)| format!("- `{path}`"))
+                .collect();
+            let page_size = self.index.config().docs.page_size;
+            if methods.len() > page_size {
+                let more = methods.len() - page_size;
+                methods.truncate(page_size);
+                methods.push(format!(
+                    "- ... and {more} more; use `get_docs` on `{target}` to page through them"
+                ));
+            }
+            if !methods.is_empty() {
+                section.push_str(&format!(
+                    "\n### From `{target}`\n\n{}\n",
+                    methods.join("\n")
+                ));
+            }
+        }
+        section
+    }
+
+    /// Formats where an item is defined as `file:line`, relative to the workspace root when
+    /// possible, if the file exists on disk (e.g. in the registry cache or a vendor directory).
+    fn source_location(&self, item: &Item) -> Option<String> {
+        let span = item.span.as_ref()?;
+        let file = self.workspace.resolve_source_file(&span.filename)?;
+        let file = file
+            .strip_prefix(self.workspace.metadata.workspace_root.as_std_path())
+            .unwrap_or(&file);
+        Some(format!("{}:{}", file.display(), span.begin.0))
+    }
+
+    /// Renders the default bodies of a trait's provided methods, read from the source. Only
+    /// traits of workspace members are shown, whose source is the user's own code.
+    fn default_bodies_section(
+        &self,
+        krate: &Crate,
+        item: &Item,
+        crate_name: &str,
+    ) -> Option<String> {
+        let ItemEnum::Trait(trait_) = &item.inner else {
+            return None;
+        };
+        if self.workspace.directness(crate_name) != Directness::Member {
+            return Some(
+                "\n_Default method bodies are only shown for traits of workspace members._\n"
+                    .to_string(),
+            );
+        }
+
+        let mut section = String::new();
+        for method in trait_.items.iter().filter_map(|id| krate.index.get(id)) {
+            let ItemEnum::Function(function) = &method.inner else {
+                continue;
+            };
+            if !function.has_body {
+                continue;
+            }
+            let Some(body) = method.span.as_ref().and_then(|span| {
+                let file = self.workspace.resolve_source_file(&span.filename)?;
+                span_text(&std::fs::read_to_string(file).ok()?, span)
+            }) else {
+                continue;
+            };
+            let name = method.name.as_deref().unwrap_or("<unnamed>");
+            section.push_str(&format!("\n### `{name}`\n\n```rust\n{body}\n```\n"));
+        }
+        (!section.is_empty()).then(|| format!("\n## Provided Methods\n{section}"))
+    }
+
     #[tool(description = "Returns a list of all dependencies available in the current project.")]
     pub async fn list_deps(&self) -> Result<Json<ListDepsResult>, String> {
         info!("Listing dependencies...");
@@ -71,23 +605,384 @@ impl RustDocMCPServer {
         Ok(Json(ListDepsResult { dependencies: deps }))
     }
 
-    #[tool(description = "Lists the root items of a specific crate.")]
-    pub async fn list_crate_items(
+    #[tool(
+        description = "Lists the workspace members with their targets (lib, bins, examples, tests, benches, build script), showing which packages have a library whose docs can be queried."
+    )]
+    pub async fn list_workspace_members(&self) -> Result<Json<ListWorkspaceMembersResult>, String> {
+        info!("Listing workspace members...");
+        let mut members: Vec<WorkspaceMember> = self
+            .workspace
+            .metadata
+            .workspace_packages()
+            .into_iter()
+            .map(|package| WorkspaceMember {
+                name: package.name.to_string(),
+                version: package.version.to_string(),
+                manifest_path: package.manifest_path.to_string(),
+                has_lib: package
+                    .targets
+                    .iter()
+                    .any(|t| t.is_lib() || t.is_proc_macro()),
+                targets: package
+                    .targets
+                    .iter()
+                    .map(|target| TargetSummary {
+                        name: target.name.clone(),
+                        kinds: target.kind.iter().map(ToString::to_string).collect(),
+                        src_path: target.src_path.to_string(),
+                        required_features: target.required_features.clone(),
+                    })
+                    .collect(),
+            })
+            .collect();
+        members.sort_by(|a, b| a.name.cmp(&b.name));
+
+        debug!("Workspace members: {:?}", members);
+        Ok(Json(ListWorkspaceMembersResult { members }))
+    }
+
+    #[tool(
+        description = "Returns metadata about a package: version, description, license, whether it has a build script and which native library it links (`links` key)."
+    )]
+    pub async fn crate_info(
         &self,
-        args: Parameters<ListCrateItemsArgs>,
-    ) -> Result<Json<ListCrateItemsResult>, String> {
-        let crate_name = &args.0.crate_name;
-        info!("Listing items for crate: {}", crate_name);
+        Parameters(args): Parameters<CrateInfoArgs>,
+    ) -> Result<Json<CrateInfo>, String> {
+        info!("Getting crate info for: {}", args.crate_name);
+        self.index
+            .config()
+            .crates
+            .check(&args.crate_name)
+            .map_err(|e| e.to_string())?;
+        let info = crate_info(&self.workspace, &args.crate_name)
+            .ok_or(format!("Package not found: {}", args.crate_name))?;
+        debug!("Crate info: {:?}", info);
+        Ok(Json(info))
+    }
 
+    #[tool(
+        description = "Estimates the build cost of a dependency: how many packages it pulls in, which of them nothing else needs, the build scripts and proc-macros in its tree, and its public item count when its docs are loaded. Use it to weigh alternative crates."
+    )]
+    pub async fn dep_cost(
+        &self,
+        Parameters(args): Parameters<DepCostArgs>,
+    ) -> Result<Json<DepCost>, String> {
+        info!("Estimating dependency cost of: {}", args.crate_name);
         self.index
-            .ensure_loaded(crate_name)
-            .await
+            .config()
+            .crates
+            .check(&args.crate_name)
+            .map_err(|e| e.to_string())?;
+        let mut cost = dep_cost(&self.workspace, &args.crate_name)
+            .ok_or(format!("Package not found: {}", args.crate_name))?;
+        cost.public_items = self
+            .index
+            .get_crate(&args.crate_name.replace('-', "_"))
+            .map(|loaded| loaded.path_to_id.len());
+        debug!("Dependency cost: {:?}", cost);
+        Ok(Json(cost))
+    }
+
+    #[tool(
+        description = "Returns the resolved dependency graph: every package with its version, whether it is a workspace member, a direct or a transitive dependency, and its dependencies with their kinds (normal, dev, build) and target platforms. Pass `crate_name` for the subtree of one package."
+    )]
+    pub async fn get_dependency_tree(
+        &self,
+        Parameters(args): Parameters<GetDependencyTreeArgs>,
+    ) -> Result<Json<GetDependencyTreeResult>, String> {
+        info!("Getting dependency tree of: {:?}", args.crate_name);
+        if let Some(name) = &args.crate_name {
+            self.index
+                .config()
+                .crates
+                .check(name)
+                .map_err(|e| e.to_string())?;
+        }
+        let tree = dependency_tree(&self.workspace, args.crate_name.as_deref())
+            .ok_or_else(|| format!("Package not found: {}", args.crate_name.unwrap_or_default()))?;
+        info!("Dependency tree has {} packages", tree.packages.len());
+        Ok(Json(tree))
+    }
+
+    #[tool(
+        description = "Lists a package's cargo features with what each enables, the optional dependencies it pulls in, whether it is a default or currently enabled feature, and its documented description."
+    )]
+    pub async fn list_features(
+        &self,
+        Parameters(args): Parameters<ListFeaturesArgs>,
+    ) -> Result<Json<ListFeaturesResult>, String> {
+        info!("Listing features for: {}", args.crate_name);
+        self.index
+            .config()
+            .crates
+            .check(&args.crate_name)
             .map_err(|e| e.to_string())?;
+        let features = list_features(&self.workspace, &args.crate_name)
+            .ok_or(format!("Package not found: {}", args.crate_name))?;
+        info!("Found {} features", features.len());
+        debug!("Features: {:?}", features);
+        Ok(Json(ListFeaturesResult { features }))
+    }
+
+    #[tool(
+        description = "Summarizes the licenses of all dependencies from cargo metadata, grouped by license, and flags copyleft and unknown licenses."
+    )]
+    pub async fn license_report(&self) -> Result<Json<LicenseReportResult>, String> {
+        info!("Building license report...");
+        let report = license_report(&self.workspace);
+        info!(
+            "{} packages, {} licenses, {} flagged",
+            report.total_packages,
+            report.licenses.len(),
+            report.flagged.len()
+        );
+        Ok(Json(report))
+    }
+
+    #[tool(
+        description = "Drops a crate's docs from memory, keeping the generated JSON on disk so loading it again is quick. Use it in long sessions when done with a large dependency. Over HTTP, docs another session still uses stay in memory for it."
+    )]
+    pub async fn unload_crate(
+        &self,
+        Parameters(args): Parameters<UnloadCrateArgs>,
+    ) -> Result<Json<UnloadCrateResult>, String> {
+        let crate_name = args.crate_name.replace('-', "_");
+        info!("Unloading crate {crate_name}");
+        let unloaded = self.index.unload(&crate_name);
+        let mut loaded_crates = self.index.loaded_crate_names();
+        loaded_crates.sort();
+        debug!("Still loaded: {:?}", loaded_crates);
+        Ok(Json(UnloadCrateResult {
+            crate_name,
+            unloaded,
+            loaded_crates,
+        }))
+    }
+
+    #[tool(
+        description = "Reports the server version, the MCP protocol and rustdoc JSON format versions it supports, the installed nightly toolchain and loaded docs in another format version. With `check_updates`, also looks up the latest published server version (needs network access)."
+    )]
+    pub async fn server_status(
+        &self,
+        Parameters(args): Parameters<ServerStatusArgs>,
+    ) -> Result<Json<ServerStatusResult>, String> {
+        info!("Reporting server status...");
+        let rustdoc_format_version = rustdoc_types::FORMAT_VERSION;
+        let mut warnings = Vec::new();
+
+        let nightly_toolchain = nightly_rustc_version().await;
+        if nightly_toolchain.is_none() && !self.workspace.fixture {
+            warnings.push(
+                "No nightly toolchain found; docs cannot be generated. Run `rustup toolchain install nightly`."
+                    .to_string(),
+            );
+        }
+
+        let mut format_mismatches: Vec<CrateFormat> = self
+            .index
+            .loaded_crate_names()
+            .into_iter()
+            .filter_map(|crate_name| {
+                let format_version = self.index.get_crate(&crate_name)?.krate.format_version;
+                (format_version != rustdoc_format_version).then_some(CrateFormat {
+                    crate_name,
+                    format_version,
+                })
+            })
+            .collect();
+        format_mismatches.sort_by(|a, b| a.crate_name.cmp(&b.crate_name));
+        if !format_mismatches.is_empty() {
+            warnings.push(format!(
+                "{} loaded crates use another rustdoc JSON format than version {rustdoc_format_version}; their docs may be incomplete.",
+                format_mismatches.len()
+            ));
+        }
+
+        let mut latest_version = None;
+        if args.check_updates.unwrap_or(false) {
+            match latest_release().await {
+                Ok(Some(version)) => latest_version = Some(version),
+                Ok(None) => warnings.push(format!("`{PACKAGE_NAME}` was not found on crates.io.")),
+                Err(e) => warnings.push(format!("Could not check for updates: {e}")),
+            }
+        }
+        let update_available = latest_version
+            .as_deref()
+            .is_some_and(|latest| is_newer(latest, SERVER_VERSION));
+        if update_available {
+            warnings.push(format!(
+                "A newer server version is available; run `cargo install {PACKAGE_NAME}` to update."
+            ));
+        }
+
+        Ok(Json(ServerStatusResult {
+            server_version: SERVER_VERSION.to_string(),
+            protocol_version: ProtocolVersion::LATEST.to_string(),
+            rustdoc_format_version,
+            nightly_toolchain,
+            format_mismatches,
+            latest_version,
+            update_available,
+            warnings,
+        }))
+    }
+
+    #[tool(
+        description = "Loads a crate's docs ahead of time, generating them if needed, and reports whether they were already loaded, cached on disk or generated, how long that took, how many items were indexed and any warnings. Use it to warm crates (e.g. right after `list_deps`) before asking about them, or pass `git` and `rev` to load the docs of a crate at a git revision instead."
+    )]
+    pub async fn load_crate(
+        &self,
+        Parameters(args): Parameters<LoadCrateArgs>,
+    ) -> Result<Json<LoadCrateResult>, String> {
+        let crate_name = args.crate_name.replace('-', "_");
+        info!("Loading crate {crate_name}");
+        let timings = match (&args.git, &args.rev) {
+            (Some(url), Some(rev)) => Some(
+                self.index
+                    .load_git_revision(&args.crate_name, url, rev)
+                    .await
+                    .map_err(|e| e.to_string())?,
+            ),
+            (None, None) => None,
+            _ => return Err("`git` and `rev` must be given together".to_string()),
+        };
+        let timings = if timings.is_some() {
+            timings
+        } else if self.index.get_crate(&crate_name).is_some() {
+            None
+        } else {
+            Some(
+                self.index
+                    .load(&crate_name)
+                    .await
+                    .map_err(|e| e.to_string())?,
+            )
+        };
 
         let krate_ref = self
             .index
-            .get_crate(crate_name)
+            .get_crate(&crate_name)
+            .ok_or("Failed to load crate".to_string())?;
+        let mut warnings = Vec::new();
+        let format_version = krate_ref.krate.format_version;
+        if format_version != rustdoc_types::FORMAT_VERSION {
+            warnings.push(format!(
+                "The docs use rustdoc JSON format version {format_version}, but the server reads version {}; some items may be missing. See `server_status`.",
+                rustdoc_types::FORMAT_VERSION
+            ));
+        }
+        if self.workspace.find_package(&crate_name).is_some()
+            && self.workspace.directness(&crate_name) == Directness::Transitive
+        {
+            warnings.push(format!(
+                "`{crate_name}` is a transitive dependency; add it to Cargo.toml to use its items directly."
+            ));
+        }
+        if let Some(features) = &krate_ref.features {
+            warnings.push(format!(
+                "The docs were generated with exactly the features [{}] by `generate_docs`; unload the crate to get back to the features the workspace enables.",
+                features.join(", ")
+            ));
+        }
+        if let Some((url, rev)) = &krate_ref.git_revision {
+            warnings.push(format!(
+                "The docs were generated from {url} at {rev} by `load_crate`; unload the crate to get back to the version the workspace resolves."
+            ));
+        }
+        let items = krate_ref.krate.index.len();
+        let paths = krate_ref.path_to_id.len();
+        drop(krate_ref);
+
+        let (status, timings) = match timings {
+            None => ("already_loaded", LoadTimings::default()),
+            Some(timings) if timings.from_index_cache => ("index_cached", timings),
+            Some(timings) if timings.generate.is_zero() => ("cached", timings),
+            Some(timings) => ("generated", timings),
+        };
+        let load = timings.read + timings.parse + timings.path_map + timings.derived_indexes;
+        info!("Crate {crate_name} {status}: {items} items, {paths} paths");
+
+        Ok(Json(LoadCrateResult {
+            crate_name,
+            status: status.to_string(),
+            generate_ms: timings.generate.as_millis() as u64,
+            load_ms: load.as_millis() as u64,
+            json_bytes: timings.json_bytes,
+            items,
+            paths,
+            warnings,
+        }))
+    }
+
+    #[tool(
+        description = "Generates a package's docs with the features you pick instead of those the workspace enables, e.g. `[\"full\"]` to see tokio's feature-gated APIs, and loads them in place of its docs for this session until `unload_crate`. The docs of each feature set are kept and reused."
+    )]
+    pub async fn generate_docs(
+        &self,
+        Parameters(args): Parameters<GenerateDocsArgs>,
+    ) -> Result<Json<GenerateDocsResult>, String> {
+        let package = self
+            .workspace
+            .find_package(&args.crate_name)
+            .ok_or(format!("Package not found: {}", args.crate_name))?;
+        let features = select_features(
+            &package.features,
+            args.features.as_deref().unwrap_or_default(),
+            args.all_features.unwrap_or(false),
+            args.no_default_features.unwrap_or(false),
+        )?;
+        let crate_name = package.name.replace('-', "_");
+        info!("Generating docs of {crate_name} with features {features:?}");
+        let timings = self
+            .index
+            .load_with_features(&crate_name, &features)
+            .await
+            .map_err(|e| format!("{e:#}"))?;
+
+        let krate_ref = self
+            .index
+            .get_crate(&crate_name)
             .ok_or("Failed to load crate".to_string())?;
+        let items = krate_ref.krate.index.len();
+        let paths = krate_ref.path_to_id.len();
+        drop(krate_ref);
+
+        let status = if timings.generate.is_zero() {
+            "cached"
+        } else {
+            "generated"
+        };
+        let load = timings.read + timings.parse + timings.path_map + timings.derived_indexes;
+        Ok(Json(GenerateDocsResult {
+            crate_name,
+            features,
+            status: status.to_string(),
+            generate_ms: timings.generate.as_millis() as u64,
+            load_ms: load.as_millis() as u64,
+            items,
+            paths,
+        }))
+    }
+
+    #[tool(
+        description = "Renders an overview of the workspace: members, their descriptions and targets, dependencies between members, and the resource URI of each member's docs. A good starting point in large workspaces."
+    )]
+    pub async fn list_workspace(&self) -> Result<String, String> {
+        info!("Rendering workspace overview...");
+        Ok(workspace_overview(&self.workspace))
+    }
+
+    #[tool(
+        description = "Lists the root items of a specific crate. With `const_only`, lists every `const fn` and constant in the crate instead."
+    )]
+    pub async fn list_crate_items(
+        &self,
+        args: Parameters<ListCrateItemsArgs>,
+    ) -> Result<Json<ListCrateItemsResult>, String> {
+        let crate_name = &args.0.crate_name;
+        info!("Listing items for crate: {}", crate_name);
+
+        let krate_ref = self.loaded_crate(crate_name).await?;
 
         let root_id = &krate_ref.krate.root;
         let root_item = krate_ref
@@ -99,6 +994,17 @@ impl RustDocMCPServer {
         debug!("Root item: {:?}", root_item);
 
         let mut items = Vec::new();
+        if args.0.const_only.unwrap_or(false) {
+            for (path, item) in krate_ref.const_items() {
+                items.push(ItemSummary::from_item(
+                    path, crate_name, &krate_ref, item.id,
+                ));
+            }
+
+            info!("Found {} const-usable items in crate", items.len());
+            return Ok(Json(ListCrateItemsResult { items }));
+        }
+
         if let ItemEnum::Module(m) = &root_item.inner {
             for item_id in &m.items {
                 if let Some(child) = krate_ref.krate.index.get(item_id) {
@@ -112,10 +1018,9 @@ impl RustDocMCPServer {
                     };
 
                     if let Some(name) = name {
-                        items.push(ItemSummary {
-                            name,
-                            kind: get_item_kind(child),
-                        });
+                        items.push(ItemSummary::from_item(
+                            &name, crate_name, &krate_ref, child.id,
+                        ));
                     }
                 }
             }
@@ -129,39 +1034,125 @@ impl RustDocMCPServer {
 
     #[tool(description = "Returns the documentation for a specific item (e.g., std::vec::Vec).")]
     pub async fn get_docs(&self, args: Parameters<GetDocsArgs>) -> Result<String, String> {
-        let path = &args.0.path;
-        info!("Getting docs for path: {}", path);
+        let (crate_name, path, queried_id) = self
+            .locate_item(
+                args.0.path.as_deref(),
+                args.0.crate_name.as_deref(),
+                args.0.id,
+                args.0.current_module.as_deref(),
+            )
+            .await?;
+        info!("Getting docs for path: {} (id: {:?})", path, queried_id);
+
+        // Resolved before borrowing the crate, since it may load the crates of the targets.
+        let deref_chain = self.deref_chain(&path).await;
+
+        let found = match self.lookup_item(crate_name, path, queried_id)? {
+            Lookup::Found(found) => found,
+            Lookup::Ambiguous(candidates) => return Ok(candidates),
+        };
+        let (crate_name, path, krate_ref, id) = (
+            found.crate_name.as_str(),
+            &found.path,
+            &found.krate,
+            found.id,
+        );
+        debug!("Found item ID: {:?}", id);
+        let item = found.item();
 
-        let parts: Vec<&str> = path.split("::").collect();
-        if parts.is_empty() {
-            return Err("Invalid path".to_string());
+        // An entry like `methods.push` is answered with the docs of that member alone.
+        let section = args.0.section.as_deref();
+        if let Some((kind, name)) = section.and_then(|s| s.split_once('.')) {
+            let member = member_entry(&krate_ref.krate, item, kind, name)
+                .ok_or(format!("{path} has no entry `{kind}.{name}`"))?;
+            let mut docs = generate_item_markdown(member, &krate_ref.krate);
+            if let Some(location) = self.source_location(member) {
+                docs.push_str(&format!("\n_Defined in `{location}`._\n"));
+            }
+            if args.0.type_tree.unwrap_or(false) {
+                docs = tree_code_blocks(&docs);
+            }
+            return Ok(docs);
         }
-        let crate_name = parts[0];
 
-        self.index
-            .ensure_loaded(crate_name)
-            .await
-            .map_err(|e| e.to_string())?;
+        let mut docs = generate_item_markdown(item, &krate_ref.krate);
 
-        let krate_ref = self
-            .index
-            .get_crate(crate_name)
-            .ok_or("Failed to load crate".to_string())?;
+        if let Some(prelude) = krate_ref.prelude_map().get(&id) {
+            docs.push_str(&format!("\n_Available via `use {prelude}::*`._\n"));
+        }
 
-        let id = krate_ref
-            .path_to_id
-            .get(path)
-            .ok_or(format!("Item not found: {path}"))?;
+        let alternatives = match queried_id {
+            Some(_) => &[][..],
+            None => krate_ref.trait_alternatives(path),
+        };
+        if !alternatives.is_empty() {
+            let paths: Vec<String> = alternatives.iter().map(|(p, _)| format!("`{p}`")).collect();
+            let name = path.rsplit("::").next().unwrap_or(path);
+            docs.push_str(&format!(
+                "\n_Traits also provide `{name}`: {}._\n",
+                paths.join(", ")
+            ));
+        }
 
-        debug!("Found item ID: {:?}", id);
+        if let Some(location) = self.source_location(item) {
+            docs.push_str(&format!("\n_Defined in `{location}`._\n"));
+        }
 
-        let item = krate_ref
-            .krate
-            .index
-            .get(id)
-            .ok_or("Item index missing".to_string())?;
+        if args.0.inline_types.unwrap_or(false)
+            && let Some(section) = referenced_types_section(&krate_ref.krate, item)
+        {
+            docs.push_str(&section);
+        }
+
+        if args.0.default_bodies.unwrap_or(false)
+            && let Some(section) = self.default_bodies_section(&krate_ref.krate, item, crate_name)
+        {
+            docs.push_str(&section);
+        }
+
+        let page_size = self.index.config().docs.page_size;
+        if let Some(section) = implementations_section(
+            &krate_ref.krate,
+            item,
+            args.0.page.unwrap_or(1),
+            page_size,
+            args.0.expand_derived.unwrap_or(false),
+        ) {
+            docs.push_str(&section);
+        }
+
+        let external = external_references(&krate_ref.krate, item);
+        if !external.is_empty() {
+            docs.push_str(&self.provenance_section(&external));
+        }
+        let linked = doc_link_references(&krate_ref.krate, item);
+        let related = related_items_section(&krate_ref.krate, item);
+        let path = &path.clone();
+        drop(found);
+
+        if let Some(section) = self.unloaded_links_section(linked) {
+            docs.push_str(&section);
+        }
+
+        if !deref_chain.is_empty() {
+            docs.push_str(&self.deref_methods_section(path, &deref_chain));
+        }
+
+        if let Some(related) = related {
+            docs.push_str(&related);
+        }
+
+        docs = match section {
+            Some(anchor) => select_section(&docs, anchor).ok_or(format!(
+                "{path} has no section `{anchor}`. Its sections are: {}",
+                section_anchors(&docs).join(", ")
+            ))?,
+            None => add_anchors(&docs),
+        };
 
-        let docs = generate_item_markdown(item, &krate_ref.krate);
+        if args.0.type_tree.unwrap_or(false) {
+            docs = tree_code_blocks(&docs);
+        }
 
         Ok(docs)
     }
@@ -175,51 +1166,157 @@ impl RustDocMCPServer {
             "Searching docs for query: '{}' in crate: {:?}",
             args.query, args.crate_name
         );
+        let query = strip_generic_args(&args.query);
         let matches = self
             .index
-            .search(&args.query, args.crate_name.as_deref())
+            .search(&query, &search_options(&args, &query))
             .await
             .map_err(|e| e.to_string())?;
+        let total = matches.len();
+        let matches = self.search_page(matches, args.offset, args.limit);
 
-        info!("Found {} matches", matches.len());
+        info!("Found {} matches, returning {}", total, matches.len());
         debug!("Matches: {:?}", matches);
 
-        Ok(Json(SearchDocsResult { matches }))
+        Ok(Json(SearchDocsResult { matches, total }))
     }
 
-    #[tool(description = "Returns a summary of all public items within a specific module.")]
-    pub async fn get_module(
+    #[tool(
+        description = "Finds functions by their types, e.g. those taking `&str` and returning `Result<PathBuf, _>`. `_` stands for any type, module paths and lifetimes are ignored, and other arguments are allowed. Searches the loaded crates, or `crate_name`."
+    )]
+    pub async fn search_by_signature(
         &self,
-        args: Parameters<GetModuleArgs>,
-    ) -> Result<Json<GetModuleResult>, String> {
-        let path = &args.0.path;
-        info!("Getting module info for path: {}", path);
-
-        let parts: Vec<&str> = path.split("::").collect();
-        if parts.is_empty() {
-            return Err("Invalid path".to_string());
-        }
-        let crate_name = parts[0];
-
-        self.index
-            .ensure_loaded(crate_name)
-            .await
+        Parameters(args): Parameters<SearchBySignatureArgs>,
+    ) -> Result<Json<SearchDocsResult>, String> {
+        info!(
+            "Searching functions taking {:?} and returning {:?} in crate: {:?}",
+            args.inputs, args.output, args.crate_name
+        );
+        let matches = self
+            .index
+            .search_by_signature(
+                &args.inputs.unwrap_or_default(),
+                args.output.as_deref(),
+                args.crate_name.as_deref(),
+            )
+            .await
             .map_err(|e| e.to_string())?;
+        let total = matches.len();
+        let matches = self.search_page(matches, args.offset, args.limit);
+        Ok(Json(SearchDocsResult { matches, total }))
+    }
 
-        let krate_ref = self
-            .index
-            .get_crate(crate_name)
-            .ok_or("Failed to load crate".to_string())?;
+    #[tool(
+        description = "Searches the full text of the doc comments of the workspace's own crates and returns the matching items with the line mentioning the query and their file and line. Use it to find where the project documents a concept."
+    )]
+    pub async fn search_workspace_docs(
+        &self,
+        Parameters(args): Parameters<SearchWorkspaceDocsArgs>,
+    ) -> Result<Json<SearchWorkspaceDocsResult>, String> {
+        info!("Searching workspace docs for: '{}'", args.query);
+        let terms: Vec<String> = args
+            .query
+            .split_whitespace()
+            .map(str::to_lowercase)
+            .collect();
+        if terms.is_empty() {
+            return Err("The query is empty".to_string());
+        }
 
-        let id = krate_ref
-            .path_to_id
-            .get(path)
-            .ok_or(format!("Module not found: {path}"))?;
-        let item = krate_ref
-            .krate
+        let mut members: Vec<String> = self
+            .workspace
+            .metadata
+            .workspace_packages()
+            .into_iter()
+            .filter_map(|p| p.targets.iter().find(|t| t.is_lib() || t.is_proc_macro()))
+            .map(|t| t.name.replace('-', "_"))
+            .collect();
+        if let Some(crate_name) = &args.crate_name {
+            let crate_name = crate_name.replace('-', "_");
+            if !members.contains(&crate_name) {
+                return Err(format!("`{crate_name}` is not a library of the workspace"));
+            }
+            members = vec![crate_name];
+        }
+        members.sort();
+
+        let mut matches = Vec::new();
+        let AvailableCrates {
+            loaded,
+            unavailable,
+            pending,
+        } = self.index.load_available(&members).await;
+        for member in loaded {
+            let Some(krate_ref) = self.index.get_crate(&member) else {
+                continue;
+            };
+            for (path, item, hits) in krate_ref.doc_text_matches(&terms) {
+                let excerpt = LoadedCrate::doc_excerpt(item, &terms).to_string();
+                matches.push(WorkspaceDocMatch {
+                    crate_name: member.clone(),
+                    path: path.to_string(),
+                    kind: get_item_kind(item),
+                    id: item.id.0,
+                    excerpt,
+                    file: item
+                        .span
+                        .as_ref()
+                        .map(|span| span.filename.display().to_string()),
+                    line: item.span.as_ref().map(|span| span.begin.0),
+                    hits,
+                });
+            }
+        }
+        matches.sort_by(|a, b| b.hits.cmp(&a.hits).then_with(|| a.path.cmp(&b.path)));
+        info!("Found {} items in workspace docs", matches.len());
+
+        Ok(Json(SearchWorkspaceDocsResult {
+            matches,
+            unavailable,
+            pending,
+        }))
+    }
+
+    #[tool(
+        description = "Runs the same search as `search_docs` and reports, per result, which matcher fired (exact, prefix, substring or fuzzy), the text it matched and the component scores. Use it to understand or report unexpected rankings."
+    )]
+    pub async fn explain_search(
+        &self,
+        Parameters(args): Parameters<SearchDocsArgs>,
+    ) -> Result<Json<ExplainSearchResult>, String> {
+        info!(
+            "Explaining search for query: '{}' in crate: {:?}",
+            args.query, args.crate_name
+        );
+        let query = strip_generic_args(&args.query);
+        let results: Vec<SearchExplanation> = self
             .index
-            .get(id)
-            .ok_or("Item index missing".to_string())?;
+            .search_explained(&query, &search_options(&args, &query))
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|(_, explanation)| explanation)
+            .collect();
+        let total = results.len();
+        let results = self.search_page(results, args.offset, args.limit);
+
+        info!("Explained {} of {} matches", results.len(), total);
+        debug!("Explanations: {:?}", results);
+
+        Ok(Json(ExplainSearchResult { results, total }))
+    }
+
+    #[tool(description = "Returns a summary of all public items within a specific module.")]
+    pub async fn get_module(
+        &self,
+        args: Parameters<GetModuleArgs>,
+    ) -> Result<Json<GetModuleResult>, String> {
+        info!("Getting module info for path: {}", args.0.path);
+
+        let found = self
+            .find_item(&args.0.path, args.0.current_module.as_deref())
+            .await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
 
         if let rustdoc_types::ItemEnum::Module(m) = &item.inner {
             let mut children = Vec::new();
@@ -234,10 +1331,12 @@ impl RustDocMCPServer {
                     };
 
                     if let Some(name) = name {
-                        children.push(ItemSummary {
-                            name,
-                            kind: get_item_kind(child),
-                        });
+                        children.push(ItemSummary::from_item(
+                            &name,
+                            &found.crate_name,
+                            krate_ref,
+                            child.id,
+                        ));
                     }
                 }
             }
@@ -250,17 +1349,2111 @@ impl RustDocMCPServer {
             Err(format!("Item at {path} is not a module"))
         }
     }
-}
 
-const SERVER_INSTRUCTIONS: &str = include_str!("../server_instructions.md");
+    #[tool(
+        description = "Lists modules of a crate whose docs are long-form guides rather than API reference."
+    )]
+    pub async fn list_guides(
+        &self,
+        Parameters(args): Parameters<ListGuidesArgs>,
+    ) -> Result<Json<ListGuidesResult>, String> {
+        info!("Listing guides for crate: {}", args.crate_name);
 
-#[tool_handler]
-impl ServerHandler for RustDocMCPServer {
-    fn get_info(&self) -> ServerInfo {
-        ServerInfo {
-            instructions: Some(SERVER_INSTRUCTIONS.to_string()),
-            capabilities: ServerCapabilities::builder().enable_tools().build(),
-            ..Default::default()
+        let krate_ref = self.loaded_crate(&args.crate_name).await?;
+
+        let guides: Vec<GuideSummary> = krate_ref
+            .guides(args.min_length.unwrap_or(DEFAULT_GUIDE_MIN_LENGTH))
+            .into_iter()
+            .map(|(path, item)| {
+                let docs = item.docs.as_deref().unwrap_or_default();
+                GuideSummary {
+                    path: path.to_string(),
+                    title: guide_title(docs),
+                    length: docs.len(),
+                    uri: format!("{GUIDE_URI_PREFIX}{path}"),
+                }
+            })
+            .collect();
+
+        info!("Found {} guides", guides.len());
+        debug!("Guides: {:?}", guides);
+
+        Ok(Json(ListGuidesResult { guides }))
+    }
+
+    #[tool(
+        description = "Compiles a code snippet like a doctest against the workspace dependencies and returns compiler diagnostics."
+    )]
+    pub async fn check_example(
+        &self,
+        Parameters(args): Parameters<CheckExampleArgs>,
+    ) -> Result<Json<CheckExampleResult>, String> {
+        info!("Checking example for package: {:?}", args.package);
+
+        let result = ExampleChecker::check(&self.workspace, args.package.as_deref(), &args.code)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        info!(
+            "Example check finished with {} diagnostics",
+            result.diagnostics.len()
+        );
+        debug!("Check result: {:?}", result);
+
+        Ok(Json(result))
+    }
+
+    #[tool(
+        description = "Maps a rustc error (rendered text or JSON diagnostic) to the relevant docs: involved types, similarly named members, traits providing a missing method and imports to add."
+    )]
+    pub async fn explain_error(
+        &self,
+        Parameters(args): Parameters<ExplainErrorArgs>,
+    ) -> Result<Json<ExplainErrorResult>, String> {
+        let query = parse_diagnostic(&args.message);
+        info!("Explaining error {:?}: {}", query.code, query.message);
+        debug!("Parsed error query: {:?}", query);
+
+        if let Some(crate_name) = &args.crate_name {
+            self.index
+                .ensure_loaded(crate_name)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let result = lookup(
+            &self.index,
+            &self.workspace,
+            &query,
+            args.crate_name.as_deref(),
+        );
+        debug!("Explain result: {:?}", result);
+
+        Ok(Json(result))
+    }
+
+    #[tool(
+        description = "Suggests `use` statements for an unresolved name by searching the loaded crates, ranked by dependency directness."
+    )]
+    pub async fn suggest_imports(
+        &self,
+        Parameters(args): Parameters<SuggestImportsArgs>,
+    ) -> Result<Json<SuggestImportsResult>, String> {
+        info!(
+            "Suggesting imports for '{}' in crate: {:?}",
+            args.name, args.crate_name
+        );
+
+        if let Some(crate_name) = &args.crate_name {
+            self.index
+                .ensure_loaded(crate_name)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        let suggestions = suggest_imports(
+            &self.index,
+            &self.workspace,
+            &args.name,
+            args.crate_name.as_deref(),
+        );
+
+        info!("Found {} import candidates", suggestions.len());
+        debug!("Import suggestions: {:?}", suggestions);
+
+        Ok(Json(SuggestImportsResult { suggestions }))
+    }
+
+    #[tool(
+        description = "Finds which dependencies export a type, trait or function given only its name (e.g. `DateTime`), loading each dependency's docs as needed. Reports every path the name is exported at, including re-exports, and the crate that defines it. Transitive dependencies are skipped unless `include_transitive` is set."
+    )]
+    pub async fn who_exports(
+        &self,
+        Parameters(args): Parameters<WhoExportsArgs>,
+    ) -> Result<Json<WhoExportsResult>, String> {
+        info!("Finding crates that export '{}'", args.name);
+        let include_transitive = args.include_transitive.unwrap_or(false);
+
+        let mut crates: Vec<(Directness, String)> = self
+            .workspace
+            .get_dependencies()
+            .into_iter()
+            .filter_map(|package| {
+                let lib = package
+                    .targets
+                    .iter()
+                    .find(|t| t.is_lib() || t.is_proc_macro())?;
+                Some((self.workspace.directness(&lib.name), lib.name.clone()))
+            })
+            .chain(
+                self.index
+                    .loaded_crate_names()
+                    .into_iter()
+                    .map(|name| (self.workspace.directness(&name), name)),
+            )
+            .filter(|(directness, _)| include_transitive || *directness != Directness::Transitive)
+            .filter(|(_, name)| self.index.config().crates.check(name).is_ok())
+            .collect();
+        crates.sort();
+        crates.dedup_by(|a, b| a.1 == b.1);
+
+        let names: Vec<&str> = crates.iter().map(|(_, name)| name.as_str()).collect();
+        let AvailableCrates {
+            loaded,
+            unavailable,
+            pending,
+        } = self.index.load_available(&names).await;
+
+        let mut exports = Vec::new();
+        for (directness, crate_name) in crates {
+            if !loaded.contains(&crate_name) {
+                continue;
+            }
+            if let Some(krate_ref) = self.index.get_crate(&crate_name) {
+                exports.extend(exports_of(&krate_ref, &crate_name, &args.name, directness));
+            }
+        }
+
+        info!(
+            "Found {} exports of '{}' ({} crates unavailable)",
+            exports.len(),
+            args.name,
+            unavailable.len()
+        );
+        debug!("Exports: {:?}", exports);
+
+        Ok(Json(WhoExportsResult {
+            name: args.name,
+            exports,
+            unavailable,
+            pending,
+        }))
+    }
+
+    #[tool(
+        description = "Builds a matrix of which public types of a crate implement each of the given traits (e.g. `Send`, `Sync`, `Clone`, `Serialize`), including auto traits and blanket impls. Cells are `yes`, `no` or `conditional` (implemented only under bounds on the type's parameters). Useful for auditing thread safety or serializability across an API."
+    )]
+    pub async fn impl_matrix(
+        &self,
+        Parameters(args): Parameters<ImplMatrixArgs>,
+    ) -> Result<Json<ImplMatrixResult>, String> {
+        info!(
+            "Building impl matrix of {:?} for crate: {}",
+            args.traits, args.crate_name
+        );
+        if args.traits.is_empty() {
+            return Err("Pass at least one trait".to_string());
+        }
+
+        let krate_ref = self.loaded_crate(&args.crate_name).await?;
+
+        let matrix = impl_matrix(&krate_ref, &args.crate_name, &args.traits);
+        info!("Impl matrix covers {} types", matrix.rows.len());
+        debug!("Trait coverage: {:?}", matrix.coverage);
+
+        Ok(Json(matrix))
+    }
+
+    #[tool(
+        description = "Lists the prelude modules of a crate and the items each brings into scope."
+    )]
+    pub async fn list_prelude(
+        &self,
+        Parameters(args): Parameters<ListPreludeArgs>,
+    ) -> Result<Json<ListPreludeResult>, String> {
+        info!("Listing preludes for crate: {}", args.crate_name);
+
+        let krate_ref = self.loaded_crate(&args.crate_name).await?;
+
+        let mut preludes: Vec<PreludeSummary> = Vec::new();
+        for (prelude, name, id) in krate_ref.prelude_entries() {
+            if preludes.last().is_none_or(|p| p.path != prelude) {
+                preludes.push(PreludeSummary {
+                    use_statement: format!("use {prelude}::*;"),
+                    path: prelude.clone(),
+                    items: Vec::new(),
+                });
+            }
+
+            // Prefer the item's canonical path; re-exports of other crates only live in `paths`.
+            let summary = krate_ref.krate.paths.get(&id);
+            let item = krate_ref.krate.index.get(&id);
+            let path = summary.map_or_else(
+                || format!("{prelude}::{name}"),
+                |summary| summary.path.join("::"),
+            );
+            let kind = item.map_or_else(|| "import".to_string(), get_item_kind);
+
+            if let Some(current) = preludes.last_mut() {
+                current.items.push(ItemSummary {
+                    kind,
+                    ..ItemSummary::from_item(&path, &args.crate_name, &krate_ref, id)
+                });
+            }
+        }
+
+        info!("Found {} prelude modules", preludes.len());
+        debug!(
+            "Preludes: {:?}",
+            preludes.iter().map(|p| &p.path).collect::<Vec<_>>()
+        );
+
+        Ok(Json(ListPreludeResult { preludes }))
+    }
+
+    #[tool(
+        description = "Searches the markdown headings inside a crate's doc comments (e.g. \"Cancel safety\", \"Feature flags\") and returns the owning item and section text."
+    )]
+    pub async fn search_headings(
+        &self,
+        Parameters(args): Parameters<SearchHeadingsArgs>,
+    ) -> Result<Json<SearchHeadingsResult>, String> {
+        info!(
+            "Searching headings for '{}' in crate: {}",
+            args.query, args.crate_name
+        );
+
+        let krate_ref = self.loaded_crate(&args.crate_name).await?;
+
+        let mut scored: Vec<(f64, &DocHeading)> = krate_ref
+            .headings
+            .iter()
+            .map(|heading| (heading_score(&args.query, &heading.title), heading))
+            .filter(|(score, _)| *score > 0.85)
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+        scored.truncate(20);
+
+        let matches: Vec<HeadingMatch> = scored
+            .into_iter()
+            .map(|(_, heading)| HeadingMatch {
+                item_path: heading.item_path.clone(),
+                heading: heading.title.clone(),
+                level: heading.level,
+                section: truncate_section(&heading.section),
+            })
+            .collect();
+
+        info!("Found {} matching headings", matches.len());
+
+        Ok(Json(SearchHeadingsResult { matches }))
+    }
+
+    #[tool(
+        description = "Reports whether async functions are cancellation safe (yes/no/unknown) based on their \"Cancel safety\" docs. Accepts a function, or a type or module to check all of its async functions."
+    )]
+    pub async fn get_cancellation_safety(
+        &self,
+        Parameters(args): Parameters<GetCancellationSafetyArgs>,
+    ) -> Result<Json<GetCancellationSafetyResult>, String> {
+        info!("Getting cancellation safety for path: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let candidates = if matches!(item.inner, ItemEnum::Function(_)) {
+            vec![(path.as_str(), item)]
+        } else {
+            krate_ref.members_of(path)
+        };
+
+        let mut methods = Vec::new();
+        for (fn_path, fn_item) in candidates {
+            let ItemEnum::Function(f) = &fn_item.inner else {
+                continue;
+            };
+            let (verdict, section) =
+                cancellation_safety(fn_item.docs.as_deref().unwrap_or_default());
+            // Plain functions only matter when they document cancellation explicitly.
+            if f.header.is_async || section.is_some() {
+                methods.push(CancellationSafety {
+                    path: fn_path.to_string(),
+                    is_async: f.header.is_async,
+                    cancellation_safe: verdict,
+                    section,
+                });
+            }
+        }
+
+        info!("Found {} async functions", methods.len());
+        debug!("Cancellation safety: {:?}", methods);
+
+        Ok(Json(GetCancellationSafetyResult { methods }))
+    }
+
+    #[tool(
+        description = "List the arguments a procedural attribute macro (e.g. `tokio::main`, `tracing::instrument`) accepts, as documented in its examples and argument lists, plus the helper attributes of derive macros."
+    )]
+    pub async fn get_macro_arguments(
+        &self,
+        Parameters(args): Parameters<GetMacroArgumentsArgs>,
+    ) -> Result<Json<GetMacroArgumentsResult>, String> {
+        info!("Getting macro arguments for path: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let ItemEnum::ProcMacro(pm) = &item.inner else {
+            return Err(format!("{path} is not a procedural macro"));
+        };
+
+        let name = path.rsplit("::").next().unwrap_or(path);
+        let arguments = match (&pm.kind, &item.docs) {
+            (MacroKind::Attr, Some(docs)) => extract_macro_arguments(name, docs),
+            _ => Vec::new(),
+        };
+        let kind = match pm.kind {
+            MacroKind::Bang => "bang",
+            MacroKind::Attr => "attribute",
+            MacroKind::Derive => "derive",
+        };
+
+        info!("Found {} documented arguments", arguments.len());
+        debug!("Macro arguments: {:?}", arguments);
+
+        Ok(Json(GetMacroArgumentsResult {
+            path: path.clone(),
+            kind: kind.to_string(),
+            arguments,
+            helpers: pm.helpers.clone(),
+        }))
+    }
+
+    #[tool(
+        description = "Returns the Rust code examples in an item's docs as a list: the code (hidden doctest lines included), whether it is `no_run`, `ignore`, `should_panic` or `compile_fail`, the heading it is under and the paragraph introducing it."
+    )]
+    pub async fn get_examples(
+        &self,
+        Parameters(args): Parameters<GetExamplesArgs>,
+    ) -> Result<Json<GetExamplesResult>, String> {
+        info!("Getting examples for path: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let examples = item
+            .docs
+            .as_deref()
+            .map(extract_examples)
+            .unwrap_or_default();
+        info!("Found {} examples", examples.len());
+
+        Ok(Json(GetExamplesResult {
+            path: path.clone(),
+            examples,
+        }))
+    }
+
+    #[tool(
+        description = "Lists the attributes that affect calling code (`must_use`, `track_caller`, `deprecated`, `non_exhaustive`) on an item and its direct members, with what each requires of the caller, and where each is available according to `#[doc(cfg(...))]`."
+    )]
+    pub async fn get_attributes(
+        &self,
+        Parameters(args): Parameters<GetAttributesArgs>,
+    ) -> Result<Json<GetAttributesResult>, String> {
+        info!("Getting attributes for path: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let mut candidates = vec![(path.as_str(), item)];
+        if !matches!(item.inner, ItemEnum::Module(_)) {
+            candidates.extend(krate_ref.members_of(path));
+        }
+
+        let items: Vec<AttributedItem> = candidates
+            .into_iter()
+            .filter_map(|(item_path, item)| {
+                let attributes = correctness_attributes(item);
+                let availability = availability(item);
+                (!attributes.is_empty() || availability.is_some()).then(|| AttributedItem {
+                    path: item_path.to_string(),
+                    attributes,
+                    availability,
+                })
+            })
+            .collect();
+
+        info!("Found {} items with attributes", items.len());
+        debug!("Attributes: {:?}", items);
+
+        Ok(Json(GetAttributesResult { items }))
+    }
+
+    #[tool(
+        description = "Returns the signatures of an item and its direct members, with every type they mention as a typed reference (`display`, `crate_name`, `path`, `id`) that can be passed back to `get_docs`."
+    )]
+    pub async fn get_signatures(
+        &self,
+        Parameters(args): Parameters<GetSignaturesArgs>,
+    ) -> Result<Json<GetSignaturesResult>, String> {
+        info!("Getting signatures for path: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let mut candidates = vec![(path.as_str(), item)];
+        if !matches!(item.inner, ItemEnum::Module(_)) {
+            candidates.extend(krate_ref.members_of(path));
+        }
+
+        let items: Vec<ItemSignature> = candidates
+            .into_iter()
+            .map(|(item_path, item)| ItemSignature {
+                path: item_path.to_string(),
+                signature: format_item_definition(item),
+                references: signature_references(&krate_ref.krate, item),
+            })
+            .collect();
+
+        info!("Found {} signatures", items.len());
+        debug!("Signatures: {:?}", items);
+
+        Ok(Json(GetSignaturesResult { items }))
+    }
+
+    #[tool(
+        description = "Lists every inherent method of a struct, enum or union with its full signature and the first line of its docs. Unlike `get_docs`, which pages through methods, returns them all at once."
+    )]
+    pub async fn list_methods(
+        &self,
+        Parameters(args): Parameters<ListMethodsArgs>,
+    ) -> Result<Json<ListMethodsResult>, String> {
+        info!("Listing methods for path: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let methods: Vec<MethodSummary> = inherent_methods(&krate_ref.krate, item)
+            .ok_or(format!("{path} is not a struct, enum or union"))?
+            .into_iter()
+            .map(|method| MethodSummary {
+                name: method.name.clone().unwrap_or_default(),
+                signature: format_item_definition(method),
+                id: method.id.0,
+                summary: doc_summary(method).map(str::to_string),
+            })
+            .collect();
+
+        info!("Found {} methods", methods.len());
+        debug!("Methods: {:?}", methods);
+
+        Ok(Json(ListMethodsResult {
+            path: path.clone(),
+            crate_name: found.crate_name.clone(),
+            methods,
+        }))
+    }
+
+    #[tool(
+        description = "Lists every trait a struct, enum or union implements (e.g. `serde_json::Value`), with each impl's generics and where clause and whether it is explicit, derived, an auto trait impl or a blanket impl."
+    )]
+    pub async fn list_trait_impls(
+        &self,
+        Parameters(args): Parameters<ListTraitImplsArgs>,
+    ) -> Result<Json<ListTraitImplsResult>, String> {
+        info!("Listing trait impls for path: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let impls = trait_impls(&krate_ref.krate, item)
+            .ok_or(format!("{path} is not a struct, enum or union"))?;
+
+        info!("Found {} trait impls", impls.len());
+        debug!("Trait impls: {:?}", impls);
+
+        Ok(Json(ListTraitImplsResult {
+            path: path.clone(),
+            impls,
+        }))
+    }
+
+    #[tool(
+        description = "Returns what implementing a trait takes: required method signatures with `Self` replaced by `self_type`, associated types and constants to define, supertraits to implement too, and a ready-to-fill `impl` skeleton."
+    )]
+    pub async fn get_trait_usage_example(
+        &self,
+        Parameters(args): Parameters<GetTraitUsageExampleArgs>,
+    ) -> Result<Json<TraitUsageExampleResult>, String> {
+        let self_type = args.self_type.as_deref().unwrap_or(DEFAULT_SELF_TYPE);
+        info!(
+            "Building impl skeleton of {} for {}",
+            args.trait_path, self_type
+        );
+
+        let found = self.find_item(&args.trait_path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        let example =
+            trait_usage_example(&krate_ref.krate, path, item, &ImplTarget::named(self_type))
+                .ok_or(format!("{path} is not a trait"))?;
+
+        info!(
+            "Found {} required and {} provided methods",
+            example.required_methods.len(),
+            example.provided_methods.len()
+        );
+        debug!("Trait usage example: {:?}", example);
+
+        Ok(Json(example))
+    }
+
+    #[tool(
+        description = "Generates an `impl Trait for Type` block for a struct, enum or union and a trait, with the type's generic parameters, the trait's associated types and required methods, correct signatures and `todo!()` bodies, derived from the docs."
+    )]
+    pub async fn generate_impl_skeleton(
+        &self,
+        Parameters(args): Parameters<GenerateImplSkeletonArgs>,
+    ) -> Result<String, String> {
+        info!(
+            "Generating impl of {} for {}",
+            args.trait_path, args.type_path
+        );
+
+        // Only the trait's path is kept for now, since looking up the type may load a crate.
+        let trait_path = self.find_item(&args.trait_path, None).await?.path.clone();
+        let trait_name = trait_path.rsplit("::").next().unwrap_or(&trait_path);
+
+        let (type_path, target, already_implemented) = {
+            let found = self.find_item(&args.type_path, None).await?;
+            let (type_path, item) = (&found.path, found.item());
+            let target = ImplTarget::of_type(item)
+                .ok_or(format!("{type_path} is not a struct, enum or union"))?;
+            let already_implemented = trait_impls(&found.krate.krate, item)
+                .unwrap_or_default()
+                .iter()
+                .any(|impl_| impl_.trait_path.rsplit("::").next() == Some(trait_name));
+            (type_path.clone(), target, already_implemented)
+        };
+
+        let found = self.find_item(&trait_path, None).await?;
+        let example = trait_usage_example(&found.krate.krate, &trait_path, found.item(), &target)
+            .ok_or(format!("{trait_path} is not a trait"))?;
+
+        info!(
+            "Generated impl with {} required methods",
+            example.required_methods.len()
+        );
+
+        let mut output = String::new();
+        if already_implemented {
+            output.push_str(&format!(
+                "_`{type_path}` already implements a trait named `{trait_name}`._\n\n"
+            ));
+        }
+        output.push_str(&format!("```rust\n{}\n```\n", example.skeleton));
+        Ok(output)
+    }
+
+    #[tool(
+        description = "Generates a `match value { ... }` over every variant of an enum, with payload fields destructured in the right shape (tuple or struct) and their types noted, plus the `_` arm required when the enum is `#[non_exhaustive]` or has hidden variants."
+    )]
+    pub async fn generate_match_skeleton(
+        &self,
+        Parameters(args): Parameters<GenerateMatchSkeletonArgs>,
+    ) -> Result<String, String> {
+        info!("Generating match skeleton for: {}", args.enum_path);
+
+        let found = self.find_item(&args.enum_path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+        let ItemEnum::Enum(e) = &item.inner else {
+            return Err(format!("{path} is not an enum"));
+        };
+        let name = item.name.as_deref().unwrap_or_default();
+
+        let non_exhaustive = item
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, Attribute::NonExhaustive));
+        let wildcard = non_exhaustive || e.has_stripped_variants;
+        info!(
+            "Generated match over {} variants (wildcard: {})",
+            e.variants.len(),
+            wildcard
+        );
+
+        let skeleton = format_match_skeleton(name, e, &krate_ref.krate, wildcard);
+        Ok(format!("```rust\n{skeleton}\n```\n"))
+    }
+
+    #[tool(
+        description = "Returns the source code of an item with line numbers, read from the workspace, the cargo registry or a vendor directory, e.g. to see how a function is implemented rather than just its signature."
+    )]
+    pub async fn get_source(
+        &self,
+        Parameters(args): Parameters<GetSourceArgs>,
+    ) -> Result<String, String> {
+        let (crate_name, path, queried_id) = self
+            .locate_item(
+                args.path.as_deref(),
+                args.crate_name.as_deref(),
+                args.id,
+                None,
+            )
+            .await?;
+        info!("Getting source of: {} (id: {:?})", path, queried_id);
+
+        let found = match self.lookup_item(crate_name, path, queried_id)? {
+            Lookup::Found(found) => found,
+            Lookup::Ambiguous(candidates) => return Ok(candidates),
+        };
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+        let span = item
+            .span
+            .as_ref()
+            .ok_or(format!("The docs have no source location for {path}"))?;
+        // Withheld docs would show in the source of the item or of what encloses them.
+        let context_lines = args.context_lines.unwrap_or(0);
+        let redact = &self.config().redact;
+        if let Some(withheld) = krate_ref.path_to_id.iter().find_map(|(other, other_id)| {
+            let other_span = krate_ref.krate.index.get(other_id)?.span.as_ref()?;
+            (redact.withholds(other) && snippet_shows(span, context_lines, other_span))
+                .then_some(other)
+        }) {
+            return Err(if withheld == path {
+                format!("The source of {path} is withheld by `redact.paths`")
+            } else {
+                format!(
+                    "The source of {path} shows {withheld}, which is withheld by `redact.paths`"
+                )
+            });
         }
+        let file = self
+            .workspace
+            .resolve_source_file(&span.filename)
+            .ok_or(format!(
+                "Source file not found: {}",
+                span.filename.display()
+            ))?;
+        let source = std::fs::read_to_string(&file)
+            .map_err(|e| format!("Failed to read {}: {e}", file.display()))?;
+        let snippet = annotated_snippet(&source, span, context_lines).ok_or(format!(
+            "{} is shorter than the docs expect; they may be stale",
+            file.display()
+        ))?;
+        debug!("Read {} lines from {:?}", snippet.lines().count(), file);
+
+        let shown = file
+            .strip_prefix(self.workspace.metadata.workspace_root.as_std_path())
+            .unwrap_or(&file);
+        Ok(format!(
+            "`{}:{}-{}`\n\n```rust\n{snippet}```\n",
+            shown.display(),
+            span.begin.0,
+            span.end.0
+        ))
+    }
+
+    #[tool(
+        description = "Bookmarks an item with a note on why it matters, keeping a working set of important items across sessions without searching for them again. Bookmarking an item again replaces its note."
+    )]
+    pub async fn bookmark_item(
+        &self,
+        Parameters(args): Parameters<BookmarkItemArgs>,
+    ) -> Result<Json<BookmarkItemResult>, String> {
+        info!("Bookmarking: {}", args.path);
+
+        let (path, kind) = {
+            let found = self.find_item(&args.path, None).await?;
+            (found.path.clone(), get_item_kind(found.item()))
+        };
+
+        let file = bookmarks_path(self.workspace.metadata.target_directory.as_std_path());
+        let mut bookmarks = bookmarks::read(&file).map_err(|e| format!("{e:#}"))?;
+        let bookmark = Bookmark {
+            path,
+            kind,
+            note: args.note,
+        };
+        let replaced = bookmarks::add(&mut bookmarks, bookmark.clone());
+        bookmarks::write(&file, &bookmarks).map_err(|e| format!("{e:#}"))?;
+        debug!("{} bookmarks in {:?}", bookmarks.len(), file);
+
+        Ok(Json(BookmarkItemResult {
+            bookmark,
+            replaced,
+            bookmark_count: bookmarks.len(),
+        }))
+    }
+
+    #[tool(description = "Lists the items bookmarked with `bookmark_item` and their notes.")]
+    pub async fn list_bookmarks(&self) -> Result<Json<ListBookmarksResult>, String> {
+        let file = bookmarks_path(self.workspace.metadata.target_directory.as_std_path());
+        let bookmarks = bookmarks::read(&file).map_err(|e| format!("{e:#}"))?;
+        info!("Listing {} bookmarks", bookmarks.len());
+        Ok(Json(ListBookmarksResult {
+            bookmarks,
+            file: file.display().to_string(),
+        }))
+    }
+
+    #[tool(
+        description = "Generates code building a struct: a struct literal with every field and a placeholder of its type, or, when private fields or `#[non_exhaustive]` rule literals out, calls to its constructors (associated functions returning `Self`, and `default()`)."
+    )]
+    pub async fn generate_struct_literal(
+        &self,
+        Parameters(args): Parameters<GenerateStructLiteralArgs>,
+    ) -> Result<String, String> {
+        info!("Generating struct literal for: {}", args.path);
+
+        let found = self.find_item(&args.path, None).await?;
+        let (path, krate_ref, item) = (&found.path, &found.krate, found.item());
+
+        struct_literal(&krate_ref.krate, item).ok_or(format!("{path} is not a struct"))
+    }
+
+    #[tool(
+        description = "Lists the items a cargo feature unlocks by building a package's docs with and without it, e.g. the types behind tokio's `net` feature. Slow: runs `cargo rustdoc` twice."
+    )]
+    pub async fn list_feature_items(
+        &self,
+        Parameters(args): Parameters<ListFeatureItemsArgs>,
+    ) -> Result<Json<ListFeatureItemsResult>, String> {
+        info!(
+            "Listing items of feature {} of {}",
+            args.feature, args.crate_name
+        );
+        self.index
+            .config()
+            .crates
+            .check(&args.crate_name)
+            .map_err(|e| e.to_string())?;
+        let package = self
+            .workspace
+            .find_package(&args.crate_name)
+            .ok_or(format!("Package not found: {}", args.crate_name))?;
+        if !package.features.contains_key(&args.feature) {
+            let known: Vec<&str> = package.features.keys().map(String::as_str).collect();
+            return Err(format!(
+                "{} has no feature `{}`. Its features are: {}",
+                package.name,
+                args.feature,
+                known.join(", ")
+            ));
+        }
+
+        let enabled = enabled_features(&self.workspace, package);
+        let (without, with) = feature_sets(&package.features, &enabled, &args.feature);
+        debug!("Comparing features {:?} with {:?}", without, with);
+        let (krate_without, paths_without) = self
+            .index
+            .build_with_features(&package.name, &without)
+            .await
+            .map_err(|e| e.to_string())?;
+        let (krate_with, paths_with) = self
+            .index
+            .build_with_features(&package.name, &with)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let (added, removed) = diff_paths(&paths_without, &paths_with);
+        let items = |paths: Vec<String>, krate: &Crate, map: &HashMap<String, Id>| -> Vec<_> {
+            paths
+                .into_iter()
+                .map(|path| FeatureItem {
+                    kind: krate
+                        .index
+                        .get(&map[&path])
+                        .map(get_item_kind)
+                        .unwrap_or_default(),
+                    path,
+                })
+                .collect()
+        };
+        info!("{} items added, {} removed", added.len(), removed.len());
+
+        Ok(Json(ListFeatureItemsResult {
+            crate_name: args.crate_name,
+            feature: args.feature,
+            compared_features: without,
+            added: items(added, &krate_with, &paths_with),
+            removed: items(removed, &krate_without, &paths_without),
+        }))
+    }
+
+    #[tool(
+        description = "Renders the inherent methods of a generic type with concrete type arguments substituted, e.g. `indexmap::IndexMap<String, Vec<u8>>`."
+    )]
+    pub async fn instantiate_generic(
+        &self,
+        Parameters(args): Parameters<InstantiateGenericArgs>,
+    ) -> Result<Json<InstantiateGenericResult>, String> {
+        info!("Instantiating generic type: {}", args.type_expr);
+
+        let (path, concrete) = parse_type_expr(&args.type_expr);
+        let crate_name = match &args.crate_name {
+            Some(name) => name.clone(),
+            None if path.contains("::") => path.split("::").next().unwrap_or(&path).to_string(),
+            None => {
+                return Err("Provide a full path or `crate_name` to locate the type".to_string());
+            }
+        };
+
+        let krate_ref = self.loaded_crate(&crate_name).await?;
+        let (type_path, item) = find_type(&krate_ref, &path)?;
+
+        let (instantiated, methods) = instantiate_methods(&krate_ref.krate, item, &concrete);
+        if instantiated.is_empty() {
+            return Err(format!("{type_path} is not a struct, enum or union"));
+        }
+
+        info!("Instantiated {} methods", methods.len());
+        debug!("Methods: {:?}", methods);
+
+        Ok(Json(InstantiateGenericResult {
+            path: type_path,
+            instantiated,
+            methods,
+        }))
+    }
+
+    #[tool(
+        description = "Resolves the associated types a concrete type gets from a trait, e.g. `Item = u8` for `std::vec::IntoIter<u8>` and `Iterator`, by locating the impl (including blanket impls) and substituting the type arguments. Answers \"what does iterating this yield\" directly."
+    )]
+    pub async fn get_assoc_type_resolution(
+        &self,
+        Parameters(args): Parameters<GetAssocTypeResolutionArgs>,
+    ) -> Result<Json<GetAssocTypeResolutionResult>, String> {
+        info!(
+            "Resolving associated types of {} for {}",
+            args.trait_path, args.type_expr
+        );
+
+        let (path, concrete) = parse_type_expr(&args.type_expr);
+        let crate_name = match &args.crate_name {
+            Some(name) => name.clone(),
+            None if path.contains("::") => path.split("::").next().unwrap_or(&path).to_string(),
+            None => {
+                return Err("Provide a full path or `crate_name` to locate the type".to_string());
+            }
+        };
+
+        let krate_ref = self.loaded_crate(&crate_name).await?;
+        let (type_path, item) = find_type(&krate_ref, &path)?;
+        let (instantiated, impls) =
+            resolve_assoc_types(&krate_ref.krate, item, &concrete, &args.trait_path)
+                .ok_or(format!("{type_path} is not a struct, enum or union"))?;
+        if impls.is_empty() {
+            return Err(format!(
+                "No impl of {} found for {instantiated}",
+                args.trait_path
+            ));
+        }
+
+        info!("Resolved {} impls", impls.len());
+        debug!("Impls: {:?}", impls);
+
+        Ok(Json(GetAssocTypeResolutionResult {
+            path: type_path,
+            instantiated,
+            impls,
+        }))
+    }
+
+    /// Finds a workspace member and the file its API snapshot is stored in: `snapshot_path`
+    /// relative to the member's directory, which must stay inside the workspace.
+    fn snapshot_location(
+        &self,
+        crate_name: &str,
+        snapshot_path: Option<&str>,
+    ) -> Result<(&Package, PathBuf), String> {
+        let package = self
+            .workspace
+            .find_package(crate_name)
+            .filter(|_| self.workspace.directness(crate_name) == Directness::Member)
+            .ok_or(format!("{crate_name} is not a workspace member"))?;
+        let package_dir = package
+            .manifest_path
+            .parent()
+            .map(|dir| dir.as_std_path())
+            .ok_or("Package has no manifest directory".to_string())?;
+        let path = match snapshot_path {
+            Some(path) => snapshot_file(
+                package_dir,
+                self.workspace.metadata.workspace_root.as_std_path(),
+                path,
+            )?,
+            None => package_dir.join(SNAPSHOT_FILE_NAME),
+        };
+        Ok((package, path))
+    }
+
+    #[tool(
+        description = "Saves the public API of a workspace member to a JSON snapshot (by default `public-api.json` next to its Cargo.toml), as a baseline for `check_public_api`."
+    )]
+    pub async fn snapshot_public_api(
+        &self,
+        Parameters(args): Parameters<PublicApiArgs>,
+    ) -> Result<Json<SnapshotPublicApiResult>, String> {
+        info!("Snapshotting public API of {}", args.crate_name);
+        let (package, path) =
+            self.snapshot_location(&args.crate_name, args.snapshot_path.as_deref())?;
+
+        self.index
+            .reload(&args.crate_name)
+            .await
+            .map_err(|e| e.to_string())?;
+        let items = {
+            let krate_ref = self
+                .index
+                .get_crate(&args.crate_name)
+                .ok_or("Failed to load crate".to_string())?;
+            public_api(&krate_ref)
+        };
+
+        let snapshot = ApiSnapshot {
+            crate_name: args.crate_name.clone(),
+            version: package.version.to_string(),
+            items,
+        };
+        let content = serde_json::to_string_pretty(&snapshot).map_err(|e| e.to_string())?;
+        tokio::fs::write(&path, content)
+            .await
+            .map_err(|e| format!("Failed to write {}: {e}", path.display()))?;
+
+        info!("Wrote {} items to {:?}", snapshot.items.len(), path);
+
+        Ok(Json(SnapshotPublicApiResult {
+            snapshot_path: path.display().to_string(),
+            item_count: snapshot.items.len(),
+        }))
+    }
+
+    #[tool(
+        description = "Diffs the current public API of a workspace member against its snapshot from `snapshot_public_api`, classifying each change as breaking or not per the SemVer guidelines and reporting the required version bump."
+    )]
+    pub async fn check_public_api(
+        &self,
+        Parameters(args): Parameters<PublicApiArgs>,
+    ) -> Result<Json<CheckPublicApiResult>, String> {
+        info!("Checking public API of {}", args.crate_name);
+        let (package, path) =
+            self.snapshot_location(&args.crate_name, args.snapshot_path.as_deref())?;
+
+        let content = tokio::fs::read_to_string(&path).await.map_err(|e| {
+            format!(
+                "Failed to read snapshot {}: {e}. Create one with `snapshot_public_api` first.",
+                path.display()
+            )
+        })?;
+        let snapshot: ApiSnapshot = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid snapshot {}: {e}", path.display()))?;
+
+        self.index
+            .reload(&args.crate_name)
+            .await
+            .map_err(|e| e.to_string())?;
+        let current = {
+            let krate_ref = self
+                .index
+                .get_crate(&args.crate_name)
+                .ok_or("Failed to load crate".to_string())?;
+            public_api(&krate_ref)
+        };
+
+        let changes = diff_api(&snapshot.items, &current);
+        let breaking = changes.iter().any(|c| c.breaking);
+        info!(
+            "Found {} API changes (breaking: {})",
+            changes.len(),
+            breaking
+        );
+
+        Ok(Json(CheckPublicApiResult {
+            crate_name: args.crate_name,
+            snapshot_version: snapshot.version,
+            current_version: package.version.to_string(),
+            breaking,
+            required_bump: required_bump(&changes, &package.version).to_string(),
+            changes,
+        }))
+    }
+
+    #[tool(
+        description = "Reports which other workspace members mention an item in their public API (fields, parameters, return types, bounds), to gauge the impact of renaming or changing it."
+    )]
+    pub async fn rename_impact(
+        &self,
+        Parameters(args): Parameters<RenameImpactArgs>,
+    ) -> Result<Json<RenameImpactResult>, String> {
+        info!("Reporting rename impact of: {}", args.path);
+
+        // Other crates record the item under the path where it is defined.
+        let (crate_name, path, defined_at) = {
+            let found = self.find_item(&args.path, None).await?;
+            let defined_at = found
+                .krate
+                .krate
+                .paths
+                .get(&found.id)
+                .map_or_else(|| found.path.clone(), |summary| summary.path.join("::"));
+            (found.crate_name.clone(), found.path.clone(), defined_at)
+        };
+
+        let mut members: Vec<String> = self
+            .workspace
+            .metadata
+            .workspace_packages()
+            .into_iter()
+            .filter_map(|p| p.targets.iter().find(|t| t.is_lib() || t.is_proc_macro()))
+            .map(|t| t.name.clone())
+            .filter(|name| *name != crate_name)
+            .collect();
+        members.sort();
+
+        let mut references = Vec::new();
+        let AvailableCrates {
+            loaded,
+            unavailable,
+            pending,
+        } = self.index.load_available(&members).await;
+        for member in loaded {
+            let Some(krate_ref) = self.index.get_crate(&member) else {
+                continue;
+            };
+            references.extend(find_references(&krate_ref, &defined_at).into_iter().map(
+                |(path, role)| ApiReference {
+                    crate_name: member.clone(),
+                    path,
+                    role: role.to_string(),
+                },
+            ));
+        }
+
+        info!("Found {} references to {}", references.len(), defined_at);
+
+        Ok(Json(RenameImpactResult {
+            path,
+            defined_at,
+            references,
+            unavailable,
+            pending,
+        }))
+    }
+
+    #[tool(
+        description = "Lists the adapter methods available on a type implementing `Iterator` or `Stream`: the provided trait methods and the methods of extension traits (e.g. `Itertools`, `StreamExt`) in loaded crates, with the `Item` type substituted."
+    )]
+    pub async fn list_iterator_adapters(
+        &self,
+        Parameters(args): Parameters<ListIteratorAdaptersArgs>,
+    ) -> Result<Json<ListIteratorAdaptersResult>, String> {
+        info!("Listing iterator adapters for: {}", args.path);
+
+        let ext_crates = args.ext_crates.unwrap_or_default();
+        for (name, outcome) in self.index.ensure_all_loaded(&ext_crates).await {
+            outcome.required(&name)?;
+        }
+
+        let (path, chain) = {
+            let found = self.find_item(&args.path, None).await?;
+            let chain = chain_impl(&found.krate.krate, found.item()).ok_or(format!(
+                "{} does not implement Iterator or Stream",
+                found.path
+            ))?;
+            (found.path.clone(), chain)
+        };
+
+        let mut crate_names = self.index.loaded_crate_names();
+        crate_names.sort();
+        let mut ext_methods = Vec::new();
+        for name in crate_names {
+            if let Some(krate_ref) = self.index.get_crate(&name) {
+                ext_methods.extend(ext_trait_adapters(
+                    &krate_ref,
+                    &chain.trait_name,
+                    &chain.item_type,
+                ));
+            }
+        }
+
+        info!(
+            "Found {} provided and {} extension adapters",
+            chain.provided_methods.len(),
+            ext_methods.len()
+        );
+
+        Ok(Json(ListIteratorAdaptersResult {
+            path,
+            trait_name: chain.trait_name,
+            item_type: chain.item_type,
+            provided_methods: chain.provided_methods,
+            ext_methods,
+        }))
+    }
+}
+
+/// An item a tool was asked about, with the loaded crate it is in.
+struct FoundItem<'a> {
+    crate_name: String,
+    /// The path the item was resolved to.
+    path: String,
+    krate: CrateRef<'a>,
+    id: Id,
+}
+
+impl FoundItem<'_> {
+    fn item(&self) -> &Item {
+        &self.krate.krate.index[&self.id]
+    }
+}
+
+/// What [`RustDocMCPServer::lookup_item`] finds.
+enum Lookup<'a> {
+    Found(FoundItem<'a>),
+    /// The path is ambiguous; the candidates to choose from, formatted for the client.
+    Ambiguous(String),
+}
+
+/// The search `search_docs` and `explain_search` run for `args`, with generic arguments
+/// already stripped from `query`.
+fn search_options(args: &SearchDocsArgs, query: &str) -> SearchOptions {
+    SearchOptions {
+        crate_name: args.crate_name.clone(),
+        const_only: args.const_only.unwrap_or(false),
+        kinds: args.kinds.clone(),
+        unstable: args.unstable,
+        // Paths never contain spaces, so several words can only match the docs.
+        full_text: args
+            .full_text
+            .unwrap_or_else(|| query.contains(char::is_whitespace)),
+        exclude_deprecated: args.exclude_deprecated.unwrap_or(false),
+    }
+}
+
+/// Looks up a struct, enum or union by path, falling back to the first type of that name in
+/// the crate. Returns the path it was found at.
+fn find_type<'a>(loaded: &'a LoadedCrate, path: &str) -> Result<(String, &'a Item), String> {
+    match loaded.path_to_id.get(path) {
+        Some(id) => Ok((
+            path.to_string(),
+            loaded
+                .krate
+                .index
+                .get(id)
+                .ok_or("Item index missing".to_string())?,
+        )),
+        None => {
+            let name = path.rsplit("::").next().unwrap_or(path);
+            loaded
+                .find_by_name(name)
+                .into_iter()
+                .find(|(_, item)| {
+                    matches!(
+                        item.inner,
+                        ItemEnum::Struct(_) | ItemEnum::Enum(_) | ItemEnum::Union(_)
+                    )
+                })
+                .map(|(found, item)| (found.to_string(), item))
+                .ok_or(format!("Type not found: {path}"))
+        }
+    }
+}
+
+const SERVER_INSTRUCTIONS: &str = include_str!("../server_instructions.md");
+
+impl ServerHandler for RustDocMCPServer {
+    fn get_info(&self) -> ServerInfo {
+        ServerInfo {
+            server_info: Implementation {
+                name: PACKAGE_NAME.to_string(),
+                version: SERVER_VERSION.to_string(),
+                ..Implementation::from_build_env()
+            },
+            instructions: Some(SERVER_INSTRUCTIONS.to_string()),
+            capabilities: ServerCapabilities::builder()
+                .enable_tools()
+                .enable_resources()
+                .build(),
+            ..Default::default()
+        }
+    }
+
+    async fn list_tools(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListToolsResult, ErrorData> {
+        let tools = Self::tool_schemas().map_err(|e| ErrorData::internal_error(e, None))?;
+        Ok(ListToolsResult::with_all_items(tools))
+    }
+
+    /// Dispatches to the tool router, then post-processes every result in one place
+    /// instead of in each tool: redaction first, then the output format, then the output
+    /// size limit.
+    async fn call_tool(
+        &self,
+        mut request: CallToolRequestParams,
+        context: RequestContext<RoleServer>,
+    ) -> Result<CallToolResult, ErrorData> {
+        if let Ok(mut last) = self.last_request.lock() {
+            *last = Instant::now();
+        }
+        let cursor =
+            take_cursor(&mut request.arguments).map_err(|e| ErrorData::invalid_params(e, None))?;
+        let format =
+            take_format(&mut request.arguments).map_err(|e| ErrorData::invalid_params(e, None))?;
+        let context = ToolCallContext::new(self, request, context);
+        let mut result = self.tool_router.call(context).await?;
+
+        let config = self.index.config();
+        redact_tool_result(&config.redact, &mut result);
+        match format.unwrap_or(config.output.format) {
+            OutputFormat::Full => {}
+            OutputFormat::Compact => compact_tool_result(&mut result),
+            OutputFormat::Json => json_tool_result(&mut result),
+        }
+        limit_tool_result(&mut result, cursor, config.output.max_bytes);
+        Ok(result)
+    }
+
+    async fn list_resources(
+        &self,
+        _request: Option<PaginatedRequestParams>,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ListResourcesResult, ErrorData> {
+        let mut overview = RawResource::new(WORKSPACE_URI, "workspace");
+        overview.description = Some("Overview of the workspace members".to_string());
+        overview.mime_type = Some("text/markdown".to_string());
+        let mut resources = vec![overview.no_annotation()];
+
+        for package in self.workspace.metadata.workspace_packages() {
+            if !package
+                .targets
+                .iter()
+                .any(|t| t.is_lib() || t.is_proc_macro())
+            {
+                continue;
+            }
+            let mut resource = RawResource::new(
+                format!("{CRATE_URI_PREFIX}{}", package.name),
+                package.name.to_string(),
+            );
+            resource.description = package.description.clone();
+            resource.mime_type = Some("text/markdown".to_string());
+            resources.push(resource.no_annotation());
+        }
+
+        for crate_name in self.index.loaded_crate_names() {
+            let Some(krate_ref) = self.index.get_crate(&crate_name) else {
+                continue;
+            };
+            for (path, item) in krate_ref.guides(DEFAULT_GUIDE_MIN_LENGTH) {
+                let docs = item.docs.as_deref().unwrap_or_default();
+                let mut resource = RawResource::new(format!("{GUIDE_URI_PREFIX}{path}"), path);
+                resource.description = Some(guide_title(docs));
+                resource.mime_type = Some("text/markdown".to_string());
+                resource.size = u32::try_from(docs.len()).ok();
+                resources.push(resource.no_annotation());
+            }
+        }
+
+        debug!("Listing {} resources", resources.len());
+        Ok(ListResourcesResult::with_all_items(resources))
+    }
+
+    async fn read_resource(
+        &self,
+        request: ReadResourceRequestParams,
+        _context: RequestContext<RoleServer>,
+    ) -> Result<ReadResourceResult, ErrorData> {
+        let uri = request.uri;
+        info!("Reading resource: {}", uri);
+        // A fragment like `rustdoc://crate/demo#implementations` selects one section.
+        let (resource, fragment) = match uri.split_once('#') {
+            Some((resource, fragment)) => (resource.to_string(), Some(fragment.to_string())),
+            None => (uri.clone(), None),
+        };
+        let fragment = fragment.as_deref();
+
+        if resource == WORKSPACE_URI {
+            let overview = self
+                .index
+                .config()
+                .redact
+                .redact_text(&workspace_overview(&self.workspace));
+            return Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text(
+                    resource_section(overview, fragment)?,
+                    uri,
+                )],
+            });
+        }
+
+        if let Some(crate_name) = resource.strip_prefix(CRATE_URI_PREFIX) {
+            self.index
+                .ensure_loaded(crate_name)
+                .await
+                .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+
+            let krate_ref = self
+                .index
+                .get_crate(crate_name)
+                .ok_or_else(|| ErrorData::internal_error("Failed to load crate", None))?;
+            let root = krate_ref
+                .krate
+                .index
+                .get(&krate_ref.krate.root)
+                .ok_or_else(|| ErrorData::internal_error("Root item missing", None))?;
+            let docs = self
+                .index
+                .config()
+                .redact
+                .redact_text(&generate_item_markdown(root, &krate_ref.krate));
+
+            return Ok(ReadResourceResult {
+                contents: vec![ResourceContents::text(
+                    resource_section(docs, fragment)?,
+                    uri,
+                )],
+            });
+        }
+
+        let path = resource.strip_prefix(GUIDE_URI_PREFIX).ok_or_else(|| {
+            ErrorData::resource_not_found(format!("Unknown resource: {uri}"), None)
+        })?;
+        let crate_name = path.split("::").next().unwrap_or(path);
+
+        self.index
+            .ensure_loaded(crate_name)
+            .await
+            .map_err(|e| ErrorData::internal_error(e.to_string(), None))?;
+
+        let krate_ref = self
+            .index
+            .get_crate(crate_name)
+            .ok_or_else(|| ErrorData::internal_error("Failed to load crate", None))?;
+
+        let docs = krate_ref
+            .path_to_id
+            .get(path)
+            .and_then(|id| krate_ref.krate.index.get(id))
+            .filter(|item| matches!(item.inner, ItemEnum::Module(_)))
+            .and_then(|item| item.docs.clone())
+            .ok_or_else(|| ErrorData::resource_not_found(format!("No guide at {path}"), None))?;
+
+        Ok(ReadResourceResult {
+            contents: vec![ResourceContents::text(
+                resource_section(docs, fragment)?,
+                uri,
+            )],
+        })
+    }
+}
+
+/// Narrows the markdown of a resource to the section its URI fragment names, if any.
+fn resource_section(markdown: String, fragment: Option<&str>) -> Result<String, ErrorData> {
+    match fragment {
+        None => Ok(markdown),
+        Some(anchor) => select_section(&markdown, anchor).ok_or_else(|| {
+            ErrorData::resource_not_found(
+                format!(
+                    "No section `{anchor}`. The sections are: {}",
+                    section_anchors(&markdown).join(", ")
+                ),
+                None,
+            )
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_server() -> RustDocMCPServer {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/demo");
+        RustDocMCPServer::new(None, None, Some(dir.to_string_lossy().into_owned()))
+            .expect("Failed to start fixture server")
+    }
+
+    #[tokio::test]
+    async fn test_fixture_get_docs() {
+        let server = fixture_server();
+        let docs = server
+            .get_docs(Parameters(GetDocsArgs {
+                path: Some("demo::Greeter".to_string()),
+                crate_name: None,
+                id: None,
+                current_module: None,
+                type_tree: None,
+                page: None,
+                expand_derived: None,
+                default_bodies: None,
+                inline_types: None,
+                section: None,
+            }))
+            .await
+            .unwrap();
+        assert!(docs.contains("Greeter"));
+        assert!(docs.contains("Greets people by name."));
+        assert!(docs.contains("greet"));
+        assert!(docs.contains("\n## Fields {#fields}\n"));
+        assert!(docs.contains("- `default_greeting` (function)"));
+    }
+
+    #[tokio::test]
+    async fn test_source_location_in_vendor_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        std::fs::write(
+            root.join("Cargo.toml"),
+            "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), "").unwrap();
+        std::fs::create_dir_all(root.join(".cargo")).unwrap();
+        std::fs::write(
+            root.join(".cargo/config.toml"),
+            "[source.crates-io]\nreplace-with = \"vendored-sources\"\n\n[source.vendored-sources]\ndirectory = \"vendor\"\n",
+        )
+        .unwrap();
+        std::fs::create_dir_all(root.join("vendor/dep/src")).unwrap();
+        std::fs::write(root.join("vendor/dep/src/lib.rs"), "").unwrap();
+
+        let server = RustDocMCPServer {
+            workspace: Workspace::load(root).unwrap(),
+            ..fixture_server()
+        };
+        let located = |filename: &str, line: usize| {
+            let mut item = rustdoc_mcp_core::test_support::item(
+                1,
+                "Item",
+                ItemEnum::ExternCrate {
+                    name: "dep".to_string(),
+                    rename: None,
+                },
+            );
+            item.span = Some(rustdoc_types::Span {
+                filename: filename.into(),
+                begin: (line, 0),
+                end: (line, 0),
+            });
+            server.source_location(&item)
+        };
+        let registry = "/home/dev/.cargo/registry/src/index.crates.io-1949cf8c6b5b557f";
+
+        // The registry cache path rustdoc recorded is missing, so the vendored copy is shown.
+        assert_eq!(
+            located(&format!("{registry}/dep-0.1.0/src/lib.rs"), 3).as_deref(),
+            Some("vendor/dep/src/lib.rs:3")
+        );
+        assert_eq!(located("src/lib.rs", 1).as_deref(), Some("src/lib.rs:1"));
+        assert_eq!(
+            located(&format!("{registry}/other-1.0.0/src/lib.rs"), 1),
+            None
+        );
+
+        // get_docs states where the item is defined.
+        let docs = fixture_server()
+            .get_docs(Parameters(GetDocsArgs {
+                path: Some("demo::Greeter".to_string()),
+                crate_name: None,
+                id: None,
+                current_module: None,
+                type_tree: None,
+                page: None,
+                expand_derived: None,
+                default_bodies: None,
+                inline_types: None,
+                section: None,
+            }))
+            .await
+            .unwrap();
+        assert!(docs.contains("_Defined in `src/lib.rs:"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_get_docs_section() {
+        let server = fixture_server();
+        let get_section = |section: &str| {
+            server.get_docs(Parameters(GetDocsArgs {
+                path: Some("demo::Greeter".to_string()),
+                crate_name: None,
+                id: None,
+                current_module: None,
+                type_tree: None,
+                page: None,
+                expand_derived: None,
+                default_bodies: None,
+                inline_types: None,
+                section: Some(section.to_string()),
+            }))
+        };
+
+        let fields = get_section("fields").await.unwrap();
+        assert!(fields.starts_with("## Fields {#fields}\n"));
+        assert!(fields.contains("`greeting: String`"));
+        assert!(!fields.contains("## Implementations"));
+
+        let method = get_section("methods.greet").await.unwrap();
+        assert!(method.contains("Greets `name`."));
+        assert!(!method.contains("Greets people by name."));
+
+        let err = get_section("variants").await.unwrap_err();
+        assert!(err.contains("has no section `variants`"));
+        assert!(err.contains("fields"));
+        let err = get_section("methods.wave").await.unwrap_err();
+        assert_eq!(err, "demo::Greeter has no entry `methods.wave`");
+    }
+
+    #[tokio::test]
+    async fn test_fixture_get_docs_from_doc_url() {
+        let server = fixture_server();
+        let docs = server
+            .get_docs(Parameters(GetDocsArgs {
+                path: Some(
+                    "https://docs.rs/demo/0.1.0/demo/struct.Greeter.html#method.greet".to_string(),
+                ),
+                crate_name: None,
+                id: None,
+                current_module: None,
+                type_tree: None,
+                page: None,
+                expand_derived: None,
+                default_bodies: None,
+                inline_types: None,
+                section: None,
+            }))
+            .await
+            .unwrap();
+        assert!(docs.contains("Greets `name`."));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_search_and_follow_id() {
+        let server = fixture_server();
+        let Json(result) = server
+            .search_docs(Parameters(SearchDocsArgs {
+                query: "default_greeting".to_string(),
+                crate_name: Some("demo".to_string()),
+                const_only: None,
+                kinds: None,
+                unstable: None,
+                full_text: None,
+                exclude_deprecated: None,
+                limit: None,
+                offset: None,
+            }))
+            .await
+            .unwrap();
+        let summary = result
+            .matches
+            .iter()
+            .find(|m| m.name == "demo::default_greeting")
+            .expect("default_greeting not found");
+        assert_eq!(
+            summary.summary.as_deref(),
+            Some("Returns the default greeting.")
+        );
+        assert_eq!(
+            summary.signature.as_deref(),
+            Some("fn default_greeting() -> &'static str")
+        );
+        assert!(!summary.is_deprecated);
+
+        let docs = server
+            .get_docs(Parameters(GetDocsArgs {
+                path: None,
+                crate_name: Some(summary.crate_name.clone()),
+                id: Some(summary.id),
+                current_module: None,
+                type_tree: None,
+                page: None,
+                expand_derived: None,
+                default_bodies: None,
+                inline_types: None,
+                section: None,
+            }))
+            .await
+            .unwrap();
+        assert!(docs.contains("Returns the default greeting."));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_who_exports() {
+        let server = fixture_server();
+        // The fixture has no package metadata, so only already loaded crates are searched.
+        server.index.ensure_loaded("demo").await.unwrap();
+        let Json(result) = server
+            .who_exports(Parameters(WhoExportsArgs {
+                name: "Greeter".to_string(),
+                include_transitive: None,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(result.exports.len(), 1);
+        let export = &result.exports[0];
+        assert_eq!(export.path, "demo::Greeter");
+        assert_eq!(export.kind, "struct");
+        assert_eq!(export.defined_in, "demo");
+        assert!(result.unavailable.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fixture_impl_matrix() {
+        let server = fixture_server();
+        let Json(matrix) = server
+            .impl_matrix(Parameters(ImplMatrixArgs {
+                crate_name: "demo".to_string(),
+                traits: vec!["Send".to_string(), "Clone".to_string(), "Into".to_string()],
+            }))
+            .await
+            .unwrap();
+        assert_eq!(matrix.rows.len(), 1);
+        assert_eq!(matrix.rows[0].path, "demo::Greeter");
+        // `Into` comes from a blanket impl bounded by `U: From<T>`.
+        assert_eq!(matrix.rows[0].cells, vec!["yes", "no", "conditional"]);
+        assert_eq!(matrix.coverage[0].implemented, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fixture_generate_match_skeleton() {
+        let server = fixture_server();
+        let err = server
+            .generate_match_skeleton(Parameters(GenerateMatchSkeletonArgs {
+                enum_path: "demo::Greeter".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err, "demo::Greeter is not an enum");
+    }
+
+    #[tokio::test]
+    async fn test_fixture_generate_struct_literal() {
+        let server = fixture_server();
+        let literal = server
+            .generate_struct_literal(Parameters(GenerateStructLiteralArgs {
+                path: "demo::Greeter".to_string(),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            literal,
+            "```rust\nGreeter {\n    greeting: /* String */,\n}\n```\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sessions_share_loaded_docs() {
+        let server = fixture_server();
+        let (first, second) = (server.for_session(), server.for_session());
+        first.index.ensure_loaded("demo").await.unwrap();
+        assert_eq!(server.loaded_crates(), vec!["demo"]);
+        second.index.ensure_loaded("demo").await.unwrap();
+        let args = || {
+            Parameters(UnloadCrateArgs {
+                crate_name: "demo".to_string(),
+            })
+        };
+
+        // The docs stay in memory while the other session uses them.
+        let Json(unloaded) = first.unload_crate(args()).await.unwrap();
+        assert!(!unloaded.unloaded);
+        assert_eq!(unloaded.loaded_crates, vec!["demo"]);
+        second.index.reload("demo").await.unwrap();
+        assert!(second.index.get_crate("demo").is_some());
+
+        let Json(unloaded) = second.unload_crate(args()).await.unwrap();
+        assert!(unloaded.unloaded);
+        assert!(server.loaded_crates().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_fixture_get_source() {
+        let server = fixture_server();
+        let source = server
+            .get_source(Parameters(GetSourceArgs {
+                path: Some("demo::Greeter::greet".to_string()),
+                crate_name: None,
+                id: None,
+                context_lines: Some(1),
+            }))
+            .await
+            .unwrap();
+        assert_eq!(
+            source,
+            "`src/lib.rs:18-20`\n\n```rust\n17 :     /// Greets `name`.\n18 |     pub fn greet(&self, name: &str) -> String {\n19 |         format!(\"{}, {name}!\", self.greeting)\n20 |     }\n21 : }\n```\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fixture_get_source_by_id_and_redacted() {
+        let dir = tempfile::tempdir().unwrap();
+        let config_path = dir.path().join("rustdoc-mcp.toml");
+        std::fs::write(
+            &config_path,
+            "[redact]\npaths = [\"demo::Greeter::greet\"]\n",
+        )
+        .unwrap();
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/demo");
+        let server = RustDocMCPServer::new(
+            None,
+            Some(config_path.to_string_lossy().into_owned()),
+            Some(fixture.to_string_lossy().into_owned()),
+        )
+        .unwrap();
+        let source = |path: Option<&str>, id: Option<u32>, context_lines: usize| {
+            server.get_source(Parameters(GetSourceArgs {
+                path: path.map(str::to_string),
+                crate_name: id.map(|_| "demo".to_string()),
+                id,
+                context_lines: Some(context_lines),
+            }))
+        };
+
+        let by_id = source(None, Some(47), 0).await.unwrap();
+        assert!(by_id.starts_with("`src/lib.rs:24-26`"));
+        assert!(by_id.contains("pub fn default_greeting()"));
+
+        let err = source(Some("demo::Greeter::greet"), None, 0)
+            .await
+            .unwrap_err();
+        assert!(err.contains("withheld"));
+        assert!(source(Some("demo::Greeter::new"), None, 0).await.is_ok());
+        // So is a snippet whose context lines reach into the method.
+        let err = source(Some("demo::Greeter::new"), None, 3)
+            .await
+            .unwrap_err();
+        assert!(err.contains("shows demo::Greeter::greet"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_search_pages() {
+        let server = fixture_server();
+        let search = |offset| {
+            server.search_docs(Parameters(SearchDocsArgs {
+                query: "Greeter".to_string(),
+                crate_name: Some("demo".to_string()),
+                const_only: None,
+                kinds: None,
+                unstable: None,
+                full_text: None,
+                exclude_deprecated: None,
+                limit: Some(1),
+                offset: Some(offset),
+            }))
+        };
+        let Json(first) = search(0).await.unwrap();
+        let Json(second) = search(1).await.unwrap();
+        assert!(first.total > 1);
+        assert_eq!(first.total, second.total);
+        assert_eq!(first.matches.len(), 1);
+        assert_eq!(first.matches[0].name, "demo::Greeter");
+        assert_ne!(second.matches[0].name, first.matches[0].name);
+    }
+
+    #[tokio::test]
+    async fn test_fixture_search_by_signature() {
+        let server = fixture_server();
+        let search = |inputs: &[&str], output: Option<&str>| {
+            server.search_by_signature(Parameters(SearchBySignatureArgs {
+                inputs: Some(inputs.iter().map(|t| t.to_string()).collect()),
+                output: output.map(str::to_string),
+                crate_name: Some("demo".to_string()),
+                limit: None,
+                offset: None,
+            }))
+        };
+        let names = |result: &SearchDocsResult| -> Vec<String> {
+            result.matches.iter().map(|m| m.name.clone()).collect()
+        };
+
+        // `new` takes nothing else, so it ranks before the method.
+        let Json(found) = search(&["&str"], None).await.unwrap();
+        assert_eq!(
+            names(&found),
+            vec!["demo::Greeter::new", "demo::Greeter::greet"]
+        );
+        let Json(found) = search(&["&str"], Some("String")).await.unwrap();
+        assert_eq!(names(&found), vec!["demo::Greeter::greet"]);
+        assert_eq!(
+            found.matches[0].owner,
+            Some(crate::types::ItemOwner {
+                type_name: Some("Greeter".to_string()),
+                trait_name: None,
+            })
+        );
+        // Lifetimes are ignored.
+        let Json(found) = search(&[], Some("&str")).await.unwrap();
+        assert_eq!(names(&found), vec!["demo::default_greeting"]);
+        assert_eq!(found.matches[0].owner, None);
+
+        assert!(search(&[], None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fixture_get_examples() {
+        let server = fixture_server();
+        let examples = |path: &str| {
+            server.get_examples(Parameters(GetExamplesArgs {
+                path: path.to_string(),
+            }))
+        };
+        // The fixture documents no examples.
+        let Json(found) = examples("demo::Greeter").await.unwrap();
+        assert_eq!(found.path, "demo::Greeter");
+        assert!(found.examples.is_empty());
+        assert_eq!(
+            examples("demo::Missing").await.err().unwrap(),
+            "Item not found: demo::Missing"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fixture_missing_item_errors() {
+        let server = fixture_server();
+        let path = || "demo::Missing".to_string();
+        let errors = [
+            server
+                .get_signatures(Parameters(GetSignaturesArgs { path: path() }))
+                .await
+                .err(),
+            server
+                .list_trait_impls(Parameters(ListTraitImplsArgs { path: path() }))
+                .await
+                .err(),
+            server
+                .get_module(Parameters(GetModuleArgs {
+                    path: path(),
+                    current_module: None,
+                }))
+                .await
+                .err(),
+            server
+                .generate_impl_skeleton(Parameters(GenerateImplSkeletonArgs {
+                    type_path: path(),
+                    trait_path: "demo::Greeter".to_string(),
+                }))
+                .await
+                .err(),
+        ];
+        for error in errors {
+            assert_eq!(error.as_deref(), Some("Item not found: demo::Missing"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fixture_bookmarks() {
+        let server = fixture_server();
+        let err = server
+            .bookmark_item(Parameters(BookmarkItemArgs {
+                path: "demo::Missing".to_string(),
+                note: "not there".to_string(),
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err, "Item not found: demo::Missing");
+
+        let Json(listed) = server.list_bookmarks().await.unwrap();
+        assert!(listed.bookmarks.is_empty());
+        assert!(listed.file.ends_with("bookmarks.json"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_list_feature_items() {
+        let server = fixture_server();
+        // Fixture docs cannot be rebuilt, and the fixture has no package metadata.
+        let err = server
+            .list_feature_items(Parameters(ListFeatureItemsArgs {
+                crate_name: "demo".to_string(),
+                feature: "std".to_string(),
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(err, "Package not found: demo");
+    }
+
+    #[tokio::test]
+    async fn test_fixture_generate_impl_skeleton() {
+        let server = fixture_server();
+        let err = server
+            .generate_impl_skeleton(Parameters(GenerateImplSkeletonArgs {
+                type_path: "demo::Greeter".to_string(),
+                trait_path: "demo::Greeter".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(err, "demo::Greeter is not a trait");
+
+        let err = server
+            .generate_impl_skeleton(Parameters(GenerateImplSkeletonArgs {
+                type_path: "demo::Greeter::greet".to_string(),
+                trait_path: "demo::Greeter".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert!(err.contains("not a struct, enum or union"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_list_methods() {
+        let server = fixture_server();
+        let Json(result) = server
+            .list_methods(Parameters(ListMethodsArgs {
+                path: "demo::Greeter".to_string(),
+            }))
+            .await
+            .unwrap();
+        let names: Vec<&str> = result.methods.iter().map(|m| m.name.as_str()).collect();
+        assert_eq!(names, vec!["greet", "new"]);
+        assert!(result.methods[0].signature.starts_with("fn greet("));
+        assert_eq!(result.crate_name, "demo");
+    }
+
+    #[tokio::test]
+    async fn test_fixture_list_trait_impls() {
+        let server = fixture_server();
+        let Json(result) = server
+            .list_trait_impls(Parameters(ListTraitImplsArgs {
+                path: "demo::Greeter".to_string(),
+            }))
+            .await
+            .unwrap();
+        // Auto trait impls come before blanket impls.
+        assert_eq!(result.impls[0].trait_path, "core::marker::Freeze");
+        assert_eq!(result.impls[0].kind, "auto");
+        let into = result
+            .impls
+            .iter()
+            .find(|impl_| impl_.trait_path == "core::convert::Into")
+            .unwrap();
+        assert_eq!(into.kind, "blanket");
+        assert_eq!(into.header, "impl<T, U> Into<U> for T");
+        assert_eq!(into.where_clause.as_deref(), Some("where U: From<T>"));
+
+        let err = server
+            .list_trait_impls(Parameters(ListTraitImplsArgs {
+                path: "demo::Greeter::greet".to_string(),
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert!(err.contains("not a struct, enum or union"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_generate_docs() {
+        // Fixture docs are prebuilt, and this one has no cargo metadata to find packages in.
+        let server = fixture_server();
+        let result = server
+            .generate_docs(Parameters(GenerateDocsArgs {
+                crate_name: "demo".to_string(),
+                features: Some(vec!["full".to_string()]),
+                all_features: None,
+                no_default_features: None,
+            }))
+            .await;
+        assert_eq!(result.err().as_deref(), Some("Package not found: demo"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_get_dependency_tree() {
+        // Without cargo metadata there is no resolved graph.
+        let server = fixture_server();
+        let Json(tree) = server
+            .get_dependency_tree(Parameters(GetDependencyTreeArgs { crate_name: None }))
+            .await
+            .unwrap();
+        assert!(tree.packages.is_empty());
+        let result = server
+            .get_dependency_tree(Parameters(GetDependencyTreeArgs {
+                crate_name: Some("demo".to_string()),
+            }))
+            .await;
+        assert_eq!(result.err().as_deref(), Some("Package not found: demo"));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_load_crate() {
+        let server = fixture_server();
+        let load = |crate_name: &str| {
+            server.load_crate(Parameters(LoadCrateArgs {
+                crate_name: crate_name.to_string(),
+                git: None,
+                rev: None,
+            }))
+        };
+        let Json(first) = load("demo").await.unwrap();
+        assert_eq!(first.status, "cached");
+        assert!(first.items > 0);
+        assert!(first.paths > 0);
+        let Json(second) = load("demo").await.unwrap();
+        assert_eq!(second.status, "already_loaded");
+        assert_eq!(second.items, first.items);
+        assert!(load("serde").await.is_err());
+
+        let unload = || {
+            server.unload_crate(Parameters(UnloadCrateArgs {
+                crate_name: "demo".to_string(),
+            }))
+        };
+        let Json(unloaded) = unload().await.unwrap();
+        assert!(unloaded.unloaded);
+        assert!(!unloaded.loaded_crates.contains(&"demo".to_string()));
+        let Json(again) = unload().await.unwrap();
+        assert!(!again.unloaded);
+        let Json(reloaded) = load("demo").await.unwrap();
+        assert_eq!(reloaded.status, "cached");
+    }
+
+    #[tokio::test]
+    async fn test_fixture_load_crate_from_git() {
+        let server = fixture_server();
+        let load = |git: Option<&str>, rev: Option<&str>| {
+            server.load_crate(Parameters(LoadCrateArgs {
+                crate_name: "demo".to_string(),
+                git: git.map(str::to_string),
+                rev: rev.map(str::to_string),
+            }))
+        };
+        let url = "https://github.com/example/demo";
+        assert_eq!(
+            load(Some(url), None).await.err().as_deref(),
+            Some("`git` and `rev` must be given together")
+        );
+        assert_eq!(
+            load(Some(url), Some("main")).await.err().as_deref(),
+            Some("Fixture docs are prebuilt and cannot be generated from git")
+        );
+        // The failed load leaves the fixture's docs in place.
+        let Json(loaded) = load(None, None).await.unwrap();
+        assert_eq!(loaded.status, "cached");
+        assert!(!loaded.warnings.iter().any(|w| w.contains(url)));
+    }
+
+    #[tokio::test]
+    async fn test_fixture_server_status() {
+        let server = fixture_server();
+        server.index.ensure_loaded("demo").await.unwrap();
+        let Json(status) = server
+            .server_status(Parameters(ServerStatusArgs {
+                check_updates: None,
+            }))
+            .await
+            .unwrap();
+        assert_eq!(status.server_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(status.rustdoc_format_version, rustdoc_types::FORMAT_VERSION);
+        assert!(!status.protocol_version.is_empty());
+        assert!(status.latest_version.is_none());
+        assert!(!status.update_available);
+    }
+
+    #[tokio::test]
+    async fn test_fixture_never_generates_docs() {
+        let server = fixture_server();
+        let error = server
+            .list_crate_items(Parameters(ListCrateItemsArgs {
+                crate_name: "serde".to_string(),
+                const_only: None,
+            }))
+            .await
+            .err()
+            .unwrap();
+        assert!(error.contains("fixture has no docs"));
     }
 }
@@ -0,0 +1,671 @@
+use std::collections::HashMap;
+
+use rustdoc_mcp_core::impl_summary::{inherent_methods, trait_impls};
+use rustdoc_mcp_core::markdown::{
+    format_generic_bound, format_item_definition, format_type, format_where_clause,
+};
+use rustdoc_types::{
+    Attribute, Crate, Function, GenericArg, GenericArgs, GenericParamDefKind, Id, Item, ItemEnum,
+    StructKind, Type,
+};
+
+use crate::instantiate::{substitute_function, substitute_type};
+use crate::types::TraitUsageExampleResult;
+
+/// The implementing type used when the caller names none.
+pub const DEFAULT_SELF_TYPE: &str = "MyType";
+
+/// The receiver of a method as written in an impl (`self`, `&self`, `&'a mut self`), when
+/// its first parameter is one.
+fn receiver(f: &Function) -> Option<String> {
+    let (name, ty) = f.sig.inputs.first()?;
+    if name != "self" {
+        return None;
+    }
+    let is_self = |ty: &Type| matches!(ty, Type::Generic(name) if name == "Self");
+    match ty {
+        ty if is_self(ty) => Some("self".to_string()),
+        Type::BorrowedRef {
+            lifetime,
+            is_mutable,
+            type_,
+        } if is_self(type_) => {
+            let mut receiver = String::from("&");
+            if let Some(lifetime) = lifetime {
+                receiver.push_str(lifetime);
+                receiver.push(' ');
+            }
+            if *is_mutable {
+                receiver.push_str("mut ");
+            }
+            receiver.push_str("self");
+            Some(receiver)
+        }
+        _ => None,
+    }
+}
+
+/// Renders a trait method as it is written in an impl, with `Self` substituted by `map`
+/// (which keeps `Self::Assoc` for the associated types the impl defines).
+fn method_signature(method: &Item, f: &Function, map: &HashMap<String, String>) -> String {
+    let substituted = substitute_function(f, map);
+    let mut method = method.clone();
+    method.inner = ItemEnum::Function(substituted.clone());
+    let mut signature = format_item_definition(&method);
+    if let Some(receiver) = receiver(f)
+        && let Some((_, ty)) = substituted.sig.inputs.first()
+    {
+        signature = signature.replacen(
+            &format!("(self: {}", format_type(ty)),
+            &format!("({receiver}"),
+            1,
+        );
+    }
+    if let Some(where_clause) = format_where_clause(&substituted.generics) {
+        signature.push(' ');
+        signature.push_str(&where_clause);
+    }
+    signature
+}
+
+/// The type an impl is written for.
+pub struct ImplTarget {
+    /// The type as written after `for`, e.g. `Wrapper<'a, T>`.
+    pub self_type: String,
+    /// The generic parameters the impl declares for the type, e.g. `'a` and `T: Clone`.
+    pub params: Vec<String>,
+    pub where_clause: Option<String>,
+}
+
+impl ImplTarget {
+    /// A type without generic parameters, or one named by the caller as written.
+    pub fn named(self_type: &str) -> Self {
+        ImplTarget {
+            self_type: self_type.to_string(),
+            params: Vec::new(),
+            where_clause: None,
+        }
+    }
+
+    /// A struct, enum or union, with its generic parameters (less their defaults) declared
+    /// on the impl. Returns `None` for other items.
+    pub fn of_type(item: &Item) -> Option<Self> {
+        let generics = match &item.inner {
+            ItemEnum::Struct(s) => &s.generics,
+            ItemEnum::Enum(e) => &e.generics,
+            ItemEnum::Union(u) => &u.generics,
+            _ => return None,
+        };
+        let mut params = Vec::new();
+        let mut args = Vec::new();
+        for param in &generics.params {
+            let name = &param.name;
+            match &param.kind {
+                GenericParamDefKind::Lifetime { outlives } if outlives.is_empty() => {
+                    params.push(name.clone());
+                }
+                GenericParamDefKind::Lifetime { outlives } => {
+                    params.push(format!("{name}: {}", outlives.join(" + ")));
+                }
+                GenericParamDefKind::Type { is_synthetic, .. } if *is_synthetic => continue,
+                GenericParamDefKind::Type { bounds, .. } if bounds.is_empty() => {
+                    params.push(name.clone());
+                }
+                GenericParamDefKind::Type { bounds, .. } => {
+                    let bounds: Vec<String> = bounds.iter().map(format_generic_bound).collect();
+                    params.push(format!("{name}: {}", bounds.join(" + ")));
+                }
+                GenericParamDefKind::Const { type_, .. } => {
+                    params.push(format!("const {name}: {}", format_type(type_)));
+                }
+            }
+            args.push(name.clone());
+        }
+        let name = item.name.as_deref()?;
+        Some(ImplTarget {
+            self_type: if args.is_empty() {
+                name.to_string()
+            } else {
+                format!("{name}<{}>", args.join(", "))
+            },
+            params,
+            where_clause: format_where_clause(generics),
+        })
+    }
+}
+
+/// The `impl` line for `target`. Lifetime parameters of the trait are declared on the
+/// impl; type and const parameters without defaults are left as placeholders to fill in.
+fn impl_header(name: &str, item: &Item, target: &ImplTarget) -> Option<String> {
+    let ItemEnum::Trait(t) = &item.inner else {
+        return None;
+    };
+    let mut params = Vec::new();
+    let mut args = Vec::new();
+    for param in &t.generics.params {
+        match &param.kind {
+            GenericParamDefKind::Lifetime { .. } => {
+                params.push(param.name.clone());
+                args.push(param.name.clone());
+            }
+            GenericParamDefKind::Type {
+                default: None,
+                is_synthetic: false,
+                ..
+            }
+            | GenericParamDefKind::Const { default: None, .. } => {
+                args.push(format!("/* {} */", param.name));
+            }
+            _ => {}
+        }
+    }
+    params.extend(target.params.iter().cloned());
+    let mut header = String::from(if t.is_unsafe { "unsafe impl" } else { "impl" });
+    if !params.is_empty() {
+        header.push_str(&format!("<{}>", params.join(", ")));
+    }
+    header.push(' ');
+    header.push_str(name);
+    if !args.is_empty() {
+        header.push_str(&format!("<{}>", args.join(", ")));
+    }
+    header.push_str(&format!(" for {}", target.self_type));
+    if let Some(where_clause) = &target.where_clause {
+        header.push_str(&format!("\n{where_clause}\n"));
+    }
+    Some(header)
+}
+
+/// Collects what implementing a trait for `target` takes: the required methods with
+/// `Self` substituted, the associated types and constants without defaults and the
+/// supertraits the type must also implement. They are also rendered as an `impl`
+/// skeleton that compiles once trait parameters are filled in: methods and constants are
+/// `todo!()`, and associated types are `()` with a comment naming their bounds. Returns
+/// `None` if `item` is not a trait.
+pub fn trait_usage_example(
+    krate: &Crate,
+    trait_path: &str,
+    item: &Item,
+    target: &ImplTarget,
+) -> Option<TraitUsageExampleResult> {
+    let self_type = target.self_type.as_str();
+    let ItemEnum::Trait(t) = &item.inner else {
+        return None;
+    };
+    let name = item.name.as_deref()?;
+    let members: Vec<&Item> = t
+        .items
+        .iter()
+        .filter_map(|id| krate.index.get(id))
+        .collect();
+
+    let mut map = HashMap::from([("Self".to_string(), self_type.to_string())]);
+    for member in &members {
+        if let (ItemEnum::AssocType { .. }, Some(assoc)) = (&member.inner, &member.name) {
+            let qualified = format!("Self::{assoc}");
+            map.insert(qualified.clone(), qualified);
+        }
+    }
+
+    let mut result = TraitUsageExampleResult {
+        trait_path: trait_path.to_string(),
+        self_type: self_type.to_string(),
+        supertraits: t.bounds.iter().map(format_generic_bound).collect(),
+        assoc_types: Vec::new(),
+        assoc_consts: Vec::new(),
+        required_methods: Vec::new(),
+        provided_methods: Vec::new(),
+        skeleton: String::new(),
+    };
+    let mut type_lines = Vec::new();
+    let mut const_lines = Vec::new();
+    let mut method_blocks = Vec::new();
+    for member in members {
+        let member_name = member.name.as_deref().unwrap_or_default();
+        match &member.inner {
+            ItemEnum::AssocType {
+                bounds,
+                type_: None,
+                ..
+            } => {
+                let bounds: Vec<String> = bounds.iter().map(format_generic_bound).collect();
+                if bounds.is_empty() {
+                    type_lines.push(format!("    type {member_name} = (); // TODO"));
+                    result.assoc_types.push(format!("type {member_name}"));
+                } else {
+                    let bounds = bounds.join(" + ");
+                    type_lines.push(format!(
+                        "    type {member_name} = (); // TODO: a type implementing {bounds}"
+                    ));
+                    result
+                        .assoc_types
+                        .push(format!("type {member_name}: {bounds}"));
+                }
+            }
+            ItemEnum::AssocConst { type_, value: None } => {
+                let ty = format_type(&substitute_type(type_, &map));
+                const_lines.push(format!("    const {member_name}: {ty} = todo!();"));
+                result
+                    .assoc_consts
+                    .push(format!("const {member_name}: {ty}"));
+            }
+            ItemEnum::Function(f) if !f.has_body => {
+                let signature = method_signature(member, f, &map);
+                method_blocks.push(format!("    {signature} {{\n        todo!()\n    }}"));
+                result.required_methods.push(signature);
+            }
+            ItemEnum::Function(_) => result.provided_methods.push(member_name.to_string()),
+            _ => {}
+        }
+    }
+
+    let mut skeleton = String::new();
+    if !result.supertraits.is_empty() {
+        skeleton.push_str(&format!(
+            "// {self_type} must also implement: {}\n",
+            result.supertraits.join(" + ")
+        ));
+    }
+    skeleton.push_str(&impl_header(name, item, target)?);
+    let sections: Vec<String> = [
+        type_lines.join("\n"),
+        const_lines.join("\n"),
+        method_blocks.join("\n\n"),
+    ]
+    .into_iter()
+    .filter(|section| !section.is_empty())
+    .collect();
+    // A `where` clause ends the header with a line break.
+    let open = if skeleton.ends_with('\n') { "{" } else { " {" };
+    if sections.is_empty() {
+        skeleton.push_str(&format!("{open}}}"));
+    } else {
+        skeleton.push_str(&format!("{open}\n{}\n}}", sections.join("\n\n")));
+    }
+    result.skeleton = skeleton;
+    Some(result)
+}
+
+/// Whether a type is `Self` or the type `self_id`, directly or as a generic argument like
+/// in `Result<Self, Error>` or `Option<Box<Self>>`.
+fn returns_self(ty: &Type, self_id: Id) -> bool {
+    match ty {
+        Type::Generic(name) => name == "Self",
+        Type::ResolvedPath(p) if p.id == self_id => true,
+        Type::ResolvedPath(p) => match p.args.as_deref() {
+            Some(GenericArgs::AngleBracketed { args, .. }) => args
+                .iter()
+                .any(|arg| matches!(arg, GenericArg::Type(ty) if returns_self(ty, self_id))),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Renders the value placeholder for a field or argument: `/* Type */`.
+fn placeholder(ty: &Type) -> String {
+    format!("/* {} */", format_type(ty))
+}
+
+/// Renders how to build a struct: a struct literal naming every field with a placeholder
+/// of its type, or, when private fields or `#[non_exhaustive]` rule literals out outside
+/// its crate, calls to the associated functions returning `Self`. Returns `None` for
+/// items other than structs.
+pub fn struct_literal(krate: &Crate, item: &Item) -> Option<String> {
+    let ItemEnum::Struct(s) = &item.inner else {
+        return None;
+    };
+    let name = item.name.as_deref()?;
+    let field_type = |id: &Id| match krate.index.get(id).map(|field| &field.inner) {
+        Some(ItemEnum::StructField(ty)) => Some(ty),
+        _ => None,
+    };
+    let non_exhaustive = item
+        .attrs
+        .iter()
+        .any(|attr| matches!(attr, Attribute::NonExhaustive));
+    let has_default = trait_impls(krate, item)
+        .unwrap_or_default()
+        .iter()
+        .any(|impl_| impl_.kind != "blanket" && impl_.trait_path.ends_with("Default"));
+
+    let literal = match &s.kind {
+        StructKind::Unit => Some(name.to_string()),
+        StructKind::Tuple(fields) if fields.iter().all(Option::is_some) => {
+            let values: Vec<String> = fields
+                .iter()
+                .flatten()
+                .filter_map(field_type)
+                .map(placeholder)
+                .collect();
+            Some(format!("{name}({})", values.join(", ")))
+        }
+        StructKind::Plain {
+            fields,
+            has_stripped_fields: false,
+        } => {
+            let mut literal = format!("{name} {{\n");
+            for id in fields {
+                if let (Some(field), Some(ty)) = (krate.index.get(id), field_type(id)) {
+                    let field_name = field.name.as_deref().unwrap_or("_");
+                    literal.push_str(&format!("    {field_name}: {},\n", placeholder(ty)));
+                }
+            }
+            literal.push('}');
+            Some(literal)
+        }
+        _ => None,
+    };
+    if let Some(literal) = literal
+        && !non_exhaustive
+    {
+        let mut out = format!("```rust\n{literal}\n```\n");
+        if has_default && matches!(s.kind, StructKind::Plain { .. }) {
+            out.push_str(&format!(
+                "\n`{name}` implements `Default`, so fields can be left out by ending the literal with `..Default::default()`.\n"
+            ));
+        }
+        return Some(out);
+    }
+
+    let reason = if non_exhaustive {
+        "is `#[non_exhaustive]`"
+    } else {
+        "has private fields"
+    };
+    let mut calls = Vec::new();
+    if has_default {
+        calls.push(format!("{name}::default()"));
+    }
+    for method in inherent_methods(krate, item).unwrap_or_default() {
+        let ItemEnum::Function(f) = &method.inner else {
+            continue;
+        };
+        let takes_self = f.sig.inputs.first().is_some_and(|(arg, _)| arg == "self");
+        if takes_self
+            || !f
+                .sig
+                .output
+                .as_ref()
+                .is_some_and(|ty| returns_self(ty, item.id))
+        {
+            continue;
+        }
+        let args: Vec<String> = f
+            .sig
+            .inputs
+            .iter()
+            .map(|(arg, ty)| format!("/* {arg}: {} */", format_type(ty)))
+            .collect();
+        let method_name = method.name.as_deref().unwrap_or_default();
+        calls.push(format!("{name}::{method_name}({})", args.join(", ")));
+    }
+    if calls.is_empty() {
+        return Some(format!(
+            "`{name}` {reason}, so it cannot be built with a struct literal outside its crate, and has no associated function returning it. Look for a builder type or a `From` impl (see `list_trait_impls`).\n"
+        ));
+    }
+    Some(format!(
+        "`{name}` {reason}, so it cannot be built with a struct literal outside its crate. Use a constructor:\n\n```rust\n{}\n```\n",
+        calls.join("\n")
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rustdoc_mcp_core::test_support::{generics, item};
+    use rustdoc_types::{
+        Abi, FunctionHeader, FunctionSignature, GenericBound, Generics, Id, Path, Trait,
+        TraitBoundModifier,
+    };
+
+    fn trait_bound(name: &str) -> GenericBound {
+        GenericBound::TraitBound {
+            trait_: Path {
+                path: name.to_string(),
+                id: Id(99),
+                args: None,
+            },
+            generic_params: vec![],
+            modifier: TraitBoundModifier::None,
+        }
+    }
+
+    fn method(
+        id: u32,
+        name: &str,
+        inputs: Vec<(&str, Type)>,
+        output: Type,
+        has_body: bool,
+    ) -> Item {
+        item(
+            id,
+            name,
+            ItemEnum::Function(Function {
+                sig: FunctionSignature {
+                    inputs: inputs
+                        .into_iter()
+                        .map(|(name, ty)| (name.to_string(), ty))
+                        .collect(),
+                    output: Some(output),
+                    is_c_variadic: false,
+                },
+                generics: generics(),
+                header: FunctionHeader {
+                    is_const: false,
+                    is_unsafe: false,
+                    is_async: false,
+                    abi: Abi::Rust,
+                },
+                has_body,
+            }),
+        )
+    }
+
+    #[test]
+    fn test_trait_usage_example() {
+        let self_ty = || Type::Generic("Self".to_string());
+        let output = Type::QualifiedPath {
+            name: "Output".to_string(),
+            args: None,
+            self_type: Box::new(self_ty()),
+            trait_: None,
+        };
+        let members = vec![
+            item(
+                2,
+                "Output",
+                ItemEnum::AssocType {
+                    generics: generics(),
+                    bounds: vec![trait_bound("Debug")],
+                    type_: None,
+                },
+            ),
+            method(
+                3,
+                "parse",
+                vec![
+                    (
+                        "self",
+                        Type::BorrowedRef {
+                            lifetime: None,
+                            is_mutable: true,
+                            type_: Box::new(self_ty()),
+                        },
+                    ),
+                    ("input", Type::Primitive("str".to_string())),
+                ],
+                output,
+                false,
+            ),
+            method(4, "new", vec![], self_ty(), false),
+            method(5, "reset", vec![("self", self_ty())], self_ty(), true),
+        ];
+        let parser = item(
+            1,
+            "Parser",
+            ItemEnum::Trait(Trait {
+                is_auto: false,
+                is_unsafe: false,
+                is_dyn_compatible: false,
+                items: members.iter().map(|member| member.id).collect(),
+                generics: generics(),
+                bounds: vec![trait_bound("Clone")],
+                implementations: vec![],
+            }),
+        );
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        for member in members {
+            krate.index.insert(member.id, member);
+        }
+
+        let example =
+            trait_usage_example(&krate, "demo::Parser", &parser, &ImplTarget::named("Json"))
+                .unwrap();
+        assert_eq!(example.supertraits, vec!["Clone"]);
+        assert_eq!(example.assoc_types, vec!["type Output: Debug"]);
+        assert_eq!(
+            example.required_methods,
+            vec![
+                "fn parse(&mut self, input: str) -> Self::Output",
+                "fn new() -> Json"
+            ]
+        );
+        assert_eq!(example.provided_methods, vec!["reset"]);
+        assert_eq!(
+            example.skeleton,
+            "// Json must also implement: Clone\nimpl Parser for Json {\n    type Output = (); // TODO: a type implementing Debug\n\n    fn parse(&mut self, input: str) -> Self::Output {\n        todo!()\n    }\n\n    fn new() -> Json {\n        todo!()\n    }\n}"
+        );
+    }
+
+    #[test]
+    fn test_impl_target() {
+        let wrapper = item(
+            1,
+            "Wrapper",
+            ItemEnum::Struct(rustdoc_types::Struct {
+                kind: rustdoc_types::StructKind::Unit,
+                generics: Generics {
+                    params: vec![
+                        rustdoc_types::GenericParamDef {
+                            name: "'a".to_string(),
+                            kind: GenericParamDefKind::Lifetime { outlives: vec![] },
+                        },
+                        rustdoc_types::GenericParamDef {
+                            name: "T".to_string(),
+                            kind: GenericParamDefKind::Type {
+                                bounds: vec![trait_bound("Clone")],
+                                default: Some(Type::Primitive("u8".to_string())),
+                                is_synthetic: false,
+                            },
+                        },
+                    ],
+                    where_predicates: vec![],
+                },
+                impls: vec![],
+            }),
+        );
+        let target = ImplTarget::of_type(&wrapper).unwrap();
+        assert_eq!(target.self_type, "Wrapper<'a, T>");
+        assert_eq!(target.params, vec!["'a", "T: Clone"]);
+        assert_eq!(target.where_clause, None);
+
+        let marker = item(
+            2,
+            "Marker",
+            ItemEnum::Trait(Trait {
+                is_auto: false,
+                is_unsafe: true,
+                is_dyn_compatible: true,
+                items: vec![],
+                generics: generics(),
+                bounds: vec![],
+                implementations: vec![],
+            }),
+        );
+        assert_eq!(
+            impl_header("Marker", &marker, &target).as_deref(),
+            Some("unsafe impl<'a, T: Clone> Marker for Wrapper<'a, T>")
+        );
+    }
+
+    #[test]
+    fn test_struct_literal() {
+        let mut krate = Crate {
+            root: Id(0),
+            crate_version: None,
+            includes_private: false,
+            index: HashMap::new(),
+            paths: HashMap::new(),
+            external_crates: HashMap::new(),
+            format_version: 0,
+            target: rustdoc_types::Target {
+                triple: "x86_64-unknown-linux-gnu".to_string(),
+                target_features: vec![],
+            },
+        };
+        let string = || Type::Primitive("String".to_string());
+        let field = item(2, "name", ItemEnum::StructField(string()));
+        let new = method(
+            4,
+            "new",
+            vec![("name", string())],
+            Type::Generic("Self".to_string()),
+            true,
+        );
+        let inherent = item(
+            3,
+            "",
+            ItemEnum::Impl(rustdoc_types::Impl {
+                is_unsafe: false,
+                generics: generics(),
+                provided_trait_methods: vec![],
+                trait_: None,
+                for_: Type::Primitive("unit".to_string()),
+                items: vec![new.id],
+                is_negative: false,
+                is_synthetic: false,
+                blanket_impl: None,
+            }),
+        );
+        let config = |has_stripped_fields| {
+            item(
+                1,
+                "Config",
+                ItemEnum::Struct(rustdoc_types::Struct {
+                    kind: StructKind::Plain {
+                        fields: vec![field.id],
+                        has_stripped_fields,
+                    },
+                    generics: generics(),
+                    impls: vec![inherent.id],
+                }),
+            )
+        };
+        for i in [field.clone(), new, inherent] {
+            krate.index.insert(i.id, i);
+        }
+
+        assert_eq!(
+            struct_literal(&krate, &config(false)).unwrap(),
+            "```rust\nConfig {\n    name: /* String */,\n}\n```\n"
+        );
+        let fallback = struct_literal(&krate, &config(true)).unwrap();
+        assert!(fallback.contains("has private fields"));
+        assert!(fallback.contains("Config::new(/* name: String */)"));
+        assert_eq!(struct_literal(&krate, &field), None);
+    }
+}
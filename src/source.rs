@@ -0,0 +1,112 @@
+use rustdoc_types::Span;
+
+/// Cuts the text a span covers out of its file's contents: whole lines from the span's first
+/// to its last line, with the indentation they share removed.
+pub fn span_text(source: &str, span: &Span) -> Option<String> {
+    let (first, last) = (span.begin.0, span.end.0);
+    if first == 0 || last < first {
+        return None;
+    }
+    let lines: Vec<&str> = source
+        .lines()
+        .skip(first - 1)
+        .take(last - first + 1)
+        .collect();
+    if lines.len() != last - first + 1 {
+        return None;
+    }
+
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let text: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or_else(|| line.trim_start()))
+        .collect();
+    Some(text.join("\n"))
+}
+
+/// Renders the lines a span covers, widened by `context` lines on each side, with their
+/// line numbers in a gutter. Lines outside the span itself are marked with `:` instead of
+/// `|`.
+pub fn annotated_snippet(source: &str, span: &Span, context: usize) -> Option<String> {
+    let (first, last) = (span.begin.0, span.end.0);
+    let lines: Vec<&str> = source.lines().collect();
+    if first == 0 || last < first || last > lines.len() {
+        return None;
+    }
+    let start = first.saturating_sub(context).max(1);
+    let end = (last + context).min(lines.len());
+    let width = end.to_string().len();
+
+    let mut snippet = String::new();
+    for (number, line) in (start..=end).zip(&lines[start - 1..end]) {
+        let gutter = if (first..=last).contains(&number) {
+            '|'
+        } else {
+            ':'
+        };
+        snippet.push_str(format!("{number:>width$} {gutter} {line}").trim_end());
+        snippet.push('\n');
+    }
+    Some(snippet)
+}
+
+/// Whether the snippet [`annotated_snippet`] renders for `span` and `context` shows any
+/// line of `other`.
+pub fn snippet_shows(span: &Span, context: usize, other: &Span) -> bool {
+    span.filename == other.filename
+        && other.begin.0 <= span.end.0 + context
+        && span.begin.0.saturating_sub(context) <= other.end.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn span(begin: usize, end: usize) -> Span {
+        Span {
+            filename: PathBuf::from("src/lib.rs"),
+            begin: (begin, 4),
+            end: (end, 5),
+        }
+    }
+
+    #[test]
+    fn test_span_text() {
+        let source = "pub trait Greet {\n    fn name(&self) -> String;\n\n    fn greet(&self) -> String {\n        format!(\"Hello, {}\", self.name())\n    }\n}\n";
+        assert_eq!(
+            span_text(source, &span(4, 6)).as_deref(),
+            Some("fn greet(&self) -> String {\n    format!(\"Hello, {}\", self.name())\n}")
+        );
+        assert_eq!(span_text(source, &span(7, 9)), None);
+        assert_eq!(span_text(source, &span(0, 1)), None);
+    }
+
+    #[test]
+    fn test_annotated_snippet() {
+        let source = "pub trait Greet {\n    fn name(&self) -> String;\n\n    fn greet(&self) -> String {\n        format!(\"Hello, {}\", self.name())\n    }\n}\n";
+        assert_eq!(
+            annotated_snippet(source, &span(4, 6), 1).as_deref(),
+            Some(
+                "3 :\n4 |     fn greet(&self) -> String {\n5 |         format!(\"Hello, {}\", self.name())\n6 |     }\n7 : }\n"
+            )
+        );
+        assert_eq!(annotated_snippet(source, &span(7, 9), 0), None);
+    }
+
+    #[test]
+    fn test_snippet_shows() {
+        assert!(snippet_shows(&span(4, 6), 0, &span(5, 5)));
+        assert!(snippet_shows(&span(4, 6), 0, &span(1, 7)));
+        assert!(!snippet_shows(&span(4, 6), 0, &span(8, 9)));
+        assert!(snippet_shows(&span(4, 6), 2, &span(8, 9)));
+        let mut elsewhere = span(4, 6);
+        elsewhere.filename = PathBuf::from("src/other.rs");
+        assert!(!snippet_shows(&span(4, 6), 0, &elsewhere));
+    }
+}
@@ -0,0 +1,187 @@
+/// Groups that fit in this many characters stay on one line.
+const INLINE_WIDTH: usize = 40;
+const INDENT: &str = "    ";
+
+enum Node {
+    Text(String),
+    Group {
+        open: char,
+        close: char,
+        args: Vec<Vec<Node>>,
+    },
+}
+
+fn closing(open: char) -> char {
+    match open {
+        '<' => '>',
+        '(' => ')',
+        _ => ']',
+    }
+}
+
+/// Parses `text` up to the closing bracket of the current group (or the end), splitting
+/// the group's arguments at top-level commas. The `>` of `->` is not a bracket.
+fn parse(chars: &[char], pos: &mut usize, close: Option<char>) -> Vec<Vec<Node>> {
+    let mut args = Vec::new();
+    let mut current = Vec::new();
+    let mut text = String::new();
+    while *pos < chars.len() {
+        let c = chars[*pos];
+        *pos += 1;
+        match c {
+            '<' | '(' | '[' => {
+                if !text.is_empty() {
+                    current.push(Node::Text(std::mem::take(&mut text)));
+                }
+                let close = closing(c);
+                let group_args = parse(chars, pos, Some(close));
+                current.push(Node::Group {
+                    open: c,
+                    close,
+                    args: group_args,
+                });
+            }
+            '>' if text.ends_with('-') => text.push(c),
+            c if Some(c) == close => break,
+            ',' if close.is_some() => {
+                if !text.is_empty() {
+                    current.push(Node::Text(std::mem::take(&mut text)));
+                }
+                args.push(std::mem::take(&mut current));
+            }
+            _ => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        current.push(Node::Text(text));
+    }
+    let is_empty_last = current
+        .iter()
+        .all(|n| matches!(n, Node::Text(t) if t.trim().is_empty()));
+    if !(is_empty_last && close.is_some()) {
+        args.push(current);
+    }
+    args
+}
+
+fn render_inline(nodes: &[Node]) -> String {
+    nodes
+        .iter()
+        .map(|node| match node {
+            Node::Text(text) => text.clone(),
+            Node::Group { open, close, args } => {
+                let args: Vec<String> = args
+                    .iter()
+                    .map(|arg| render_inline(arg).trim().to_string())
+                    .collect();
+                format!("{open}{}{close}", args.join(", "))
+            }
+        })
+        .collect()
+}
+
+fn render_tree(nodes: &[Node], indent: &str, out: &mut String) {
+    for node in nodes {
+        let inline = render_inline(std::slice::from_ref(node));
+        let Node::Group { open, close, args } = node else {
+            out.push_str(&inline);
+            continue;
+        };
+        if inline.len() <= INLINE_WIDTH || args.is_empty() {
+            out.push_str(&inline);
+            continue;
+        }
+        let inner = format!("{indent}{INDENT}");
+        out.push(*open);
+        out.push('\n');
+        for arg in args {
+            let mut rendered = String::new();
+            render_tree(arg, &inner, &mut rendered);
+            out.push_str(&inner);
+            out.push_str(rendered.trim());
+            out.push_str(",\n");
+        }
+        out.push_str(indent);
+        out.push(*close);
+    }
+}
+
+/// Lays out a signature with long generic argument lists and parameter lists broken into
+/// an indented tree, one argument per line, so nested types like tower `Service` stacks
+/// stay readable. Short groups are kept on one line.
+pub fn type_tree(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut pos = 0;
+    let nodes: Vec<Node> = parse(&chars, &mut pos, None)
+        .into_iter()
+        .flatten()
+        .collect();
+    let mut out = String::new();
+    render_tree(&nodes, "", &mut out);
+    out
+}
+
+/// Applies [`type_tree`] to every line of the `rust` code blocks in a markdown document.
+pub fn tree_code_blocks(markdown: &str) -> String {
+    let mut in_rust_block = false;
+    let mut lines = Vec::new();
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_rust_block = !in_rust_block && line.trim_start() == "```rust";
+            lines.push(line.to_string());
+        } else if in_rust_block {
+            lines.push(type_tree(line));
+        } else {
+            lines.push(line.to_string());
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_types_stay_inline() {
+        assert_eq!(
+            type_tree("fn len(&self) -> usize"),
+            "fn len(&self) -> usize"
+        );
+        assert_eq!(
+            type_tree("fn get(&self, k: &K) -> Option<&V>"),
+            "fn get(&self, k: &K) -> Option<&V>"
+        );
+    }
+
+    #[test]
+    fn test_nested_types_become_tree() {
+        let tree = type_tree(
+            "type Stack = Timeout<RateLimit<Retry<Policy, Buffer<BoxService<Request, Response, Error>, Request>>>>;",
+        );
+        assert_eq!(
+            tree,
+            "type Stack = Timeout<
+    RateLimit<
+        Retry<
+            Policy,
+            Buffer<
+                BoxService<Request, Response, Error>,
+                Request,
+            >,
+        >,
+    >,
+>;"
+        );
+    }
+
+    #[test]
+    fn test_tree_code_blocks() {
+        let markdown = "# Type Alias Stack\n\n```rust\nfn f(a: Very<Long<Nested<Type<With, Many, Arguments>>>>)\n```\n\nText with Long<Nested<Type<With, Many, Arguments, That, Stays>>>";
+        let laid_out = tree_code_blocks(markdown);
+        assert!(laid_out.contains("fn f(\n    a: Very<"));
+        assert!(
+            laid_out.ends_with("Text with Long<Nested<Type<With, Many, Arguments, That, Stays>>>")
+        );
+    }
+}
@@ -1,25 +1,293 @@
+use std::collections::BTreeMap;
+
+use rustdoc_mcp_core::headings::CancelSafety;
+use rustdoc_mcp_core::stability::UnstableItems;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+// The items the core library reports, which tool results embed.
+pub use rustdoc_mcp_core::types::{
+    Availability, CrateInfo, DocExample, ItemAttribute, ItemOwner, ItemReference, ItemSummary,
+    MacroArgument, SearchExplanation, TraitImpl,
+};
+
 #[derive(Deserialize, JsonSchema)]
 pub struct GetDocsArgs {
-    pub path: String,
+    pub path: Option<String>,
+    pub crate_name: Option<String>,
+    pub id: Option<u32>,
+    pub current_module: Option<String>,
+    pub type_tree: Option<bool>,
+    pub page: Option<usize>,
+    pub expand_derived: Option<bool>,
+    pub default_bodies: Option<bool>,
+    pub inline_types: Option<bool>,
+    /// Return only the section with this anchor, like `implementations`, or one entry of
+    /// it, like `methods.push`, `fields.len` or `variants.Some`.
+    pub section: Option<String>,
 }
 
 #[derive(Deserialize, JsonSchema)]
 pub struct SearchDocsArgs {
     pub query: String,
     pub crate_name: Option<String>,
+    pub const_only: Option<bool>,
+    /// Only return items of these kinds, e.g. `["trait", "macro"]`.
+    pub kinds: Option<Vec<String>>,
+    pub unstable: Option<UnstableItems>,
+    /// Also match items whose docs contain every word of the query. On by default for
+    /// queries of several words.
+    pub full_text: Option<bool>,
+    /// Leave out `#[deprecated]` items.
+    pub exclude_deprecated: Option<bool>,
+    /// How many results to return, `search.max_results` (20) by default.
+    pub limit: Option<usize>,
+    /// How many of the best results to skip, to page through the rest.
+    pub offset: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchBySignatureArgs {
+    /// Argument types the function must take, in any order, e.g. `["&str"]`. `_` stands
+    /// for any type.
+    pub inputs: Option<Vec<String>>,
+    /// The return type, e.g. `Result<PathBuf, _>`.
+    pub output: Option<String>,
+    pub crate_name: Option<String>,
+    /// How many results to return, `search.max_results` (20) by default.
+    pub limit: Option<usize>,
+    /// How many of the best results to skip, to page through the rest.
+    pub offset: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchWorkspaceDocsArgs {
+    /// Words that must all appear in the docs, case-insensitively.
+    pub query: String,
+    /// Only search this workspace member.
+    pub crate_name: Option<String>,
 }
 
 #[derive(Deserialize, JsonSchema)]
 pub struct GetModuleArgs {
     pub path: String,
+    pub current_module: Option<String>,
 }
 
 #[derive(Deserialize, JsonSchema)]
 pub struct ListCrateItemsArgs {
     pub crate_name: String,
+    pub const_only: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListGuidesArgs {
+    pub crate_name: String,
+    pub min_length: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CheckExampleArgs {
+    pub code: String,
+    pub package: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ExplainErrorArgs {
+    pub message: String,
+    pub crate_name: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SuggestImportsArgs {
+    pub name: String,
+    pub crate_name: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListPreludeArgs {
+    pub crate_name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct SearchHeadingsArgs {
+    pub query: String,
+    pub crate_name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetCancellationSafetyArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetMacroArgumentsArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetExamplesArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetAttributesArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetSignaturesArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct InstantiateGenericArgs {
+    pub type_expr: String,
+    pub crate_name: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct CrateInfoArgs {
+    pub crate_name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DepCostArgs {
+    pub crate_name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetDependencyTreeArgs {
+    /// Only return the packages this one depends on, directly or transitively, itself
+    /// included.
+    pub crate_name: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListFeaturesArgs {
+    pub crate_name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListFeatureItemsArgs {
+    pub crate_name: String,
+    /// The feature whose items to list, e.g. `net`.
+    pub feature: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct PublicApiArgs {
+    pub crate_name: String,
+    pub snapshot_path: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct RenameImpactArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListIteratorAdaptersArgs {
+    pub path: String,
+    pub ext_crates: Option<Vec<String>>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct WhoExportsArgs {
+    pub name: String,
+    pub include_transitive: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ImplMatrixArgs {
+    pub crate_name: String,
+    pub traits: Vec<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetAssocTypeResolutionArgs {
+    pub type_expr: String,
+    pub trait_path: String,
+    pub crate_name: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ServerStatusArgs {
+    pub check_updates: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct LoadCrateArgs {
+    pub crate_name: String,
+    /// A git repository to generate the docs from instead of the workspace's version,
+    /// e.g. `https://github.com/tokio-rs/tokio`. Requires `rev`.
+    pub git: Option<String>,
+    /// The commit, tag or branch of `git` to check out.
+    pub rev: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GenerateDocsArgs {
+    pub crate_name: String,
+    /// Features to enable, e.g. `["full"]`.
+    pub features: Option<Vec<String>>,
+    /// Enable every feature of the package.
+    pub all_features: Option<bool>,
+    /// Leave out the `default` feature.
+    pub no_default_features: Option<bool>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct UnloadCrateArgs {
+    pub crate_name: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListTraitImplsArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetTraitUsageExampleArgs {
+    pub trait_path: String,
+    pub self_type: Option<String>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GenerateImplSkeletonArgs {
+    pub type_path: String,
+    pub trait_path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GenerateMatchSkeletonArgs {
+    pub enum_path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GetSourceArgs {
+    pub path: Option<String>,
+    pub crate_name: Option<String>,
+    pub id: Option<u32>,
+    /// Lines of context shown around the item, 0 by default.
+    pub context_lines: Option<usize>,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct BookmarkItemArgs {
+    pub path: String,
+    /// Why the item matters, e.g. `entry point for request handling`.
+    pub note: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct GenerateStructLiteralArgs {
+    pub path: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct ListMethodsArgs {
+    pub path: String,
 }
 
 #[derive(Serialize, JsonSchema)]
@@ -30,20 +298,568 @@ pub struct ListDepsResult {
 #[derive(Serialize, JsonSchema)]
 pub struct SearchDocsResult {
     pub matches: Vec<ItemSummary>,
+    /// How many items matched in all, across every page.
+    pub total: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetModuleResult {
+    pub items: Vec<ItemSummary>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListCrateItemsResult {
+    pub items: Vec<ItemSummary>,
 }
 
 #[derive(Debug, Serialize, JsonSchema)]
-pub struct ItemSummary {
-    pub name: String,
+pub struct GuideSummary {
+    pub path: String,
+    pub title: String,
+    pub length: usize,
+    pub uri: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListGuidesResult {
+    pub guides: Vec<GuideSummary>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CheckExampleResult {
+    pub success: bool,
+    pub diagnostics: Vec<String>,
+    pub source: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ExplainErrorResult {
+    pub code: Option<String>,
+    pub message: String,
+    pub types: Vec<ItemSummary>,
+    pub similar_items: Vec<ItemSummary>,
+    pub providing_traits: Vec<ItemSummary>,
+    pub imports: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ImportSuggestion {
+    pub use_statement: String,
+    pub path: String,
     pub kind: String,
+    pub crate_name: String,
+    pub directness: String,
+    pub prelude: Option<String>,
 }
 
 #[derive(Serialize, JsonSchema)]
-pub struct GetModuleResult {
+pub struct SuggestImportsResult {
+    pub suggestions: Vec<ImportSuggestion>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct PreludeSummary {
+    pub path: String,
+    pub use_statement: String,
     pub items: Vec<ItemSummary>,
 }
 
 #[derive(Serialize, JsonSchema)]
-pub struct ListCrateItemsResult {
-    pub items: Vec<ItemSummary>,
+pub struct ListPreludeResult {
+    pub preludes: Vec<PreludeSummary>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct HeadingMatch {
+    pub item_path: String,
+    pub heading: String,
+    pub level: usize,
+    pub section: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct SearchHeadingsResult {
+    pub matches: Vec<HeadingMatch>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CancellationSafety {
+    pub path: String,
+    pub is_async: bool,
+    pub cancellation_safe: CancelSafety,
+    pub section: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetCancellationSafetyResult {
+    pub methods: Vec<CancellationSafety>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetMacroArgumentsResult {
+    pub path: String,
+    pub kind: String,
+    pub arguments: Vec<MacroArgument>,
+    pub helpers: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AttributedItem {
+    pub path: String,
+    pub attributes: Vec<ItemAttribute>,
+    pub availability: Option<Availability>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetAttributesResult {
+    pub items: Vec<AttributedItem>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct InstantiateGenericResult {
+    pub path: String,
+    pub instantiated: String,
+    pub methods: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ExplainSearchResult {
+    pub results: Vec<SearchExplanation>,
+    /// How many items matched in all, across every page.
+    pub total: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TargetSummary {
+    pub name: String,
+    pub kinds: Vec<String>,
+    pub src_path: String,
+    pub required_features: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WorkspaceMember {
+    pub name: String,
+    pub version: String,
+    pub manifest_path: String,
+    pub has_lib: bool,
+    pub targets: Vec<TargetSummary>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListWorkspaceMembersResult {
+    pub members: Vec<WorkspaceMember>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FeatureInfo {
+    pub name: String,
+    pub enables: Vec<String>,
+    pub default: bool,
+    pub enabled: bool,
+    pub description: Option<String>,
+    /// Optional dependencies the feature turns on, directly or through the features it
+    /// enables, by the name they have in the manifest.
+    pub optional_dependencies: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListFeaturesResult {
+    pub features: Vec<FeatureInfo>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AdapterMethod {
+    pub trait_path: String,
+    pub name: String,
+    pub signature: String,
+    pub references: Vec<ItemReference>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListIteratorAdaptersResult {
+    pub path: String,
+    pub trait_name: String,
+    pub item_type: String,
+    pub provided_methods: Vec<String>,
+    pub ext_methods: Vec<AdapterMethod>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiReference {
+    pub crate_name: String,
+    pub path: String,
+    pub role: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct RenameImpactResult {
+    pub path: String,
+    pub defined_at: String,
+    pub references: Vec<ApiReference>,
+    pub unavailable: Vec<String>,
+    /// Members still loading when the request stopped waiting; ask again shortly.
+    pub pending: Vec<String>,
+}
+
+/// One public item in an API snapshot. `flags` records what decides whether additions
+/// below it are breaking: `non_exhaustive`, `private_fields` and `required`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ApiEntry {
+    pub kind: String,
+    pub signature: String,
+    #[serde(default)]
+    pub flags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiSnapshot {
+    pub crate_name: String,
+    pub version: String,
+    pub items: BTreeMap<String, ApiEntry>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct SnapshotPublicApiResult {
+    pub snapshot_path: String,
+    pub item_count: usize,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ApiChange {
+    pub path: String,
+    pub change: String,
+    pub breaking: bool,
+    pub reason: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct CheckPublicApiResult {
+    pub crate_name: String,
+    pub snapshot_version: String,
+    pub current_version: String,
+    pub breaking: bool,
+    pub required_bump: String,
+    pub changes: Vec<ApiChange>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct LicenseGroup {
+    pub license: String,
+    pub category: String,
+    pub packages: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct LicenseReportResult {
+    pub total_packages: usize,
+    pub licenses: Vec<LicenseGroup>,
+    pub flagged: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ItemSignature {
+    pub path: String,
+    pub signature: String,
+    pub references: Vec<ItemReference>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetSignaturesResult {
+    pub items: Vec<ItemSignature>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct SymbolExport {
+    pub crate_name: String,
+    pub path: String,
+    pub kind: String,
+    /// The crate that defines the item, which differs from `crate_name` for re-exports.
+    pub defined_in: String,
+    pub directness: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct WhoExportsResult {
+    pub name: String,
+    pub exports: Vec<SymbolExport>,
+    /// Crates whose docs could not be generated, so they were not searched.
+    pub unavailable: Vec<String>,
+    /// Crates still loading when the request stopped waiting; ask again shortly.
+    pub pending: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ImplMatrixRow {
+    pub path: String,
+    pub kind: String,
+    /// One of `yes`, `no` or `conditional` per requested trait, in request order.
+    pub cells: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TraitCoverage {
+    pub trait_path: String,
+    pub implemented: usize,
+    pub conditional: usize,
+    pub missing: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ImplMatrixResult {
+    pub crate_name: String,
+    pub traits: Vec<String>,
+    pub rows: Vec<ImplMatrixRow>,
+    pub coverage: Vec<TraitCoverage>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct AssocTypeBinding {
+    pub name: String,
+    pub resolved: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ResolvedImpl {
+    /// The implemented trait with the type's arguments substituted, e.g. `From<u8>`.
+    pub trait_path: String,
+    /// Whether the impl is a blanket impl like `impl<I: Iterator> IntoIterator for I`.
+    pub blanket: bool,
+    pub assoc_types: Vec<AssocTypeBinding>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetAssocTypeResolutionResult {
+    pub path: String,
+    pub instantiated: String,
+    pub impls: Vec<ResolvedImpl>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct CrateFormat {
+    pub crate_name: String,
+    pub format_version: u32,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ServerStatusResult {
+    pub server_version: String,
+    /// The newest MCP protocol version the server speaks.
+    pub protocol_version: String,
+    /// The rustdoc JSON format version the server reads.
+    pub rustdoc_format_version: u32,
+    /// `rustc +nightly --version`, or `None` when no nightly toolchain is installed.
+    pub nightly_toolchain: Option<String>,
+    /// Loaded crates whose docs use another format version than the server reads.
+    pub format_mismatches: Vec<CrateFormat>,
+    /// The latest published server version, when `check_updates` was requested.
+    pub latest_version: Option<String>,
+    pub update_available: bool,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct LoadCrateResult {
+    pub crate_name: String,
+    /// `already_loaded` (in memory), `index_cached` (read from the parsed index cached by
+    /// an earlier run), `cached` (read from previously generated JSON) or `generated`
+    /// (docs were built with `cargo rustdoc`).
+    pub status: String,
+    pub generate_ms: u64,
+    /// Reading, parsing and indexing the JSON.
+    pub load_ms: u64,
+    pub json_bytes: usize,
+    /// Items in the rustdoc JSON, including private and external ones.
+    pub items: usize,
+    /// Indexed item paths, which search and lookups use.
+    pub paths: usize,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GenerateDocsResult {
+    pub crate_name: String,
+    /// The features the docs were built with.
+    pub features: Vec<String>,
+    /// `cached` (read from JSON generated earlier with these features) or `generated`.
+    pub status: String,
+    pub generate_ms: u64,
+    /// Reading, parsing and indexing the JSON.
+    pub load_ms: u64,
+    /// Items in the rustdoc JSON, including private and external ones.
+    pub items: usize,
+    /// Indexed item paths, which search and lookups use.
+    pub paths: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct UnloadCrateResult {
+    pub crate_name: String,
+    /// Whether the crate was dropped from memory. It stays loaded while another session
+    /// uses it, and unloading a crate that was not loaded is not an error.
+    pub unloaded: bool,
+    /// The crates still held in memory, sorted.
+    pub loaded_crates: Vec<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListTraitImplsResult {
+    pub path: String,
+    pub impls: Vec<TraitImpl>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct TraitUsageExampleResult {
+    pub trait_path: String,
+    /// The type the skeleton implements the trait for.
+    pub self_type: String,
+    /// Bounds on `Self` that `self_type` must also satisfy, e.g. `Clone`.
+    pub supertraits: Vec<String>,
+    /// Associated types without a default, with their bounds.
+    pub assoc_types: Vec<String>,
+    /// Associated constants without a default.
+    pub assoc_consts: Vec<String>,
+    /// Signatures of the methods without a default body, with `Self` substituted.
+    pub required_methods: Vec<String>,
+    /// Names of the methods with a default body, which the impl may override.
+    pub provided_methods: Vec<String>,
+    /// An `impl` block to fill in, with `todo!()` bodies.
+    pub skeleton: String,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct MethodSummary {
+    pub name: String,
+    /// The full signature, e.g. `fn push(self: &mut Self, value: T)`.
+    pub signature: String,
+    /// The rustdoc id of the method within `crate_name`; pass both to `get_docs`.
+    pub id: u32,
+    /// The first line of the method's docs.
+    pub summary: Option<String>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListMethodsResult {
+    pub path: String,
+    pub crate_name: String,
+    pub methods: Vec<MethodSummary>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct FeatureItem {
+    pub path: String,
+    pub kind: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListFeatureItemsResult {
+    pub crate_name: String,
+    pub feature: String,
+    /// The features the docs were built with for comparison, without `feature`.
+    pub compared_features: Vec<String>,
+    /// Items that only exist with `feature` enabled.
+    pub added: Vec<FeatureItem>,
+    /// Items that disappear with `feature` enabled, e.g. fallbacks it replaces.
+    pub removed: Vec<FeatureItem>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DepCost {
+    pub name: String,
+    pub version: String,
+    /// Packages built because of this one, directly or transitively.
+    pub transitive_deps: usize,
+    /// Of those, the packages nothing else in the workspace needs, which removing this
+    /// dependency would drop from the build.
+    pub exclusive_deps: Vec<String>,
+    pub has_build_script: bool,
+    pub is_proc_macro: bool,
+    /// Packages in its dependency tree, itself included, that run a build script.
+    pub build_scripts: Vec<String>,
+    /// Proc-macro packages in its dependency tree, itself included.
+    pub proc_macros: Vec<String>,
+    /// Indexed public item paths; unset when its docs are not loaded.
+    pub public_items: Option<usize>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DependencyEdge {
+    /// The package depended on, listed in the tree under the same name and version.
+    pub name: String,
+    pub version: String,
+    /// `normal`, `dev` and/or `build`.
+    pub kinds: Vec<String>,
+    /// The platforms it is limited to, like `cfg(windows)`; empty when it is not.
+    pub targets: Vec<String>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct DependencyNode {
+    pub name: String,
+    pub version: String,
+    /// `member`, `direct` (a dependency of a workspace member) or `transitive`.
+    pub directness: String,
+    pub dependencies: Vec<DependencyEdge>,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetDependencyTreeResult {
+    /// Workspace members first, then direct and transitive dependencies, each by name.
+    pub packages: Vec<DependencyNode>,
+}
+
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct WorkspaceDocMatch {
+    pub crate_name: String,
+    pub path: String,
+    pub kind: String,
+    /// The rustdoc id of the item within `crate_name`.
+    pub id: u32,
+    /// The first line of the docs mentioning a query word.
+    pub excerpt: String,
+    /// Where the documented item is defined, relative to the workspace root.
+    pub file: Option<String>,
+    pub line: Option<usize>,
+    /// How often the query words occur in the docs.
+    pub hits: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct SearchWorkspaceDocsResult {
+    pub matches: Vec<WorkspaceDocMatch>,
+    /// Members whose docs could not be generated.
+    pub unavailable: Vec<String>,
+    /// Members still loading when the request stopped waiting; ask again shortly.
+    pub pending: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Bookmark {
+    pub path: String,
+    pub kind: String,
+    pub note: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct BookmarkItemResult {
+    pub bookmark: Bookmark,
+    /// Whether the item was already bookmarked, with the note now replaced.
+    pub replaced: bool,
+    pub bookmark_count: usize,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ListBookmarksResult {
+    /// In the order they were first added.
+    pub bookmarks: Vec<Bookmark>,
+    /// The file the bookmarks are kept in.
+    pub file: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct GetExamplesResult {
+    pub path: String,
+    pub examples: Vec<DocExample>,
 }
@@ -0,0 +1,92 @@
+use anyhow::{Context, Result};
+use cargo_metadata::semver::Version;
+use tokio::process::Command;
+
+/// Name under which the server is published on crates.io.
+pub const PACKAGE_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Version of this server.
+pub const SERVER_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The version line of the nightly compiler docs are generated with, if installed.
+pub async fn nightly_rustc_version() -> Option<String> {
+    let output = Command::new("rustc")
+        .arg("+nightly")
+        .arg("--version")
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Reads the version of `package` from `cargo search` output, whose lines look like
+/// `name = "1.2.3"    # description`.
+fn parse_search_output(output: &str, package: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let rest = line.strip_prefix(package)?.trim_start().strip_prefix('=')?;
+        let version = rest.trim_start().strip_prefix('"')?;
+        Some(version[..version.find('"')?].to_string())
+    })
+}
+
+/// Looks up the latest published version of the server with `cargo search`, which needs
+/// network access to the crates.io index.
+pub async fn latest_release() -> Result<Option<String>> {
+    let output = Command::new("cargo")
+        .arg("search")
+        .arg(PACKAGE_NAME)
+        .arg("--limit")
+        .arg("1")
+        .output()
+        .await
+        .context("Failed to execute cargo search")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "cargo search failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(parse_search_output(
+        &String::from_utf8_lossy(&output.stdout),
+        PACKAGE_NAME,
+    ))
+}
+
+/// Whether `latest` is a newer version than `current`. Unparsable versions never are.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    match (Version::parse(latest), Version::parse(current)) {
+        (Ok(latest), Ok(current)) => latest > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_output() {
+        let output = "rustdoc-mcp-server = \"0.3.1\"    # MCP server for accessing Rust crate documentation\n... and 2 crates more (use --limit N to see more)\n";
+        assert_eq!(
+            parse_search_output(output, "rustdoc-mcp-server").as_deref(),
+            Some("0.3.1")
+        );
+        assert_eq!(
+            parse_search_output(
+                "rustdoc-mcp-server-extra = \"1.0.0\"\n",
+                "rustdoc-mcp-server"
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("0.2.0", "0.1.9"));
+        assert!(!is_newer("0.1.0", "0.1.0"));
+        assert!(!is_newer("garbage", "0.1.0"));
+    }
+}